@@ -0,0 +1,232 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Print the GPT of a disk image as JSON.
+//!
+//! This is a more complete version of the `reader` example, intended as
+//! a starting point for tools that need structured output. Both headers
+//! and partition entry arrays are read and validated, and the results
+//! are printed as a single JSON object.
+
+#[cfg(feature = "std")]
+use {
+    gpt_disk_io::gpt_disk_types::{
+        BlockSize, GptHeader, GptPartitionEntry, Guid, Lba, PartitionIndex,
+    },
+    gpt_disk_io::{BlockIo, BlockIoAdapter, Disk, DiskError, FilesystemKind},
+    serde::Serialize,
+    std::{env, error, fs, io},
+};
+
+// To create a disk to test this you can use truncate and sgdisk. For example:
+//
+// truncate --size 10MiB disk.bin
+// sgdisk disk.bin --new=1:2048:4096 --change-name=1:'hello world!' --print
+// cargo run --features=std --example inspector disk.bin
+
+/// Results of validating a header and its partition entry array.
+#[cfg(feature = "std")]
+#[derive(Serialize)]
+struct Validation {
+    signature_valid: bool,
+    revision_compatible: bool,
+    header_crc32_valid: bool,
+    my_lba_valid: bool,
+    partition_entry_array_crc32_valid: bool,
+    /// The reserved bytes after the header in its block are zero. Some
+    /// firmware rejects headers with junk there.
+    block_remainder_zero: bool,
+}
+
+#[cfg(feature = "std")]
+#[derive(Serialize)]
+struct Header {
+    revision: String,
+    header_size: u32,
+    header_crc32: u32,
+    my_lba: u64,
+    alternate_lba: u64,
+    first_usable_lba: u64,
+    last_usable_lba: u64,
+    disk_guid: Guid,
+    partition_entry_lba: u64,
+    number_of_partition_entries: u32,
+    size_of_partition_entry: u32,
+    partition_entry_array_crc32: u32,
+    validation: Validation,
+}
+
+#[cfg(feature = "std")]
+#[derive(Serialize)]
+struct Partition {
+    number: u64,
+    partition_type_guid: Guid,
+    unique_partition_guid: Guid,
+    starting_lba: u64,
+    ending_lba: u64,
+    attributes: u64,
+    name: String,
+    /// Filesystem detected from the partition's contents, e.g. `ext4`.
+    filesystem: Option<String>,
+}
+
+#[cfg(feature = "std")]
+#[derive(Serialize)]
+struct Inspection {
+    block_size: BlockSize,
+    num_blocks: u64,
+    primary_header: Header,
+    secondary_header: Header,
+    /// The bytes after the MBR in the first block are zero. Always true
+    /// for 512-byte blocks.
+    mbr_block_remainder_zero: bool,
+    /// Used entries of the primary partition entry array.
+    partitions: Vec<Partition>,
+}
+
+#[cfg(feature = "std")]
+impl Partition {
+    fn new(
+        index: PartitionIndex,
+        entry: &GptPartitionEntry,
+        filesystem: Option<FilesystemKind>,
+    ) -> Self {
+        Self {
+            number: index.to_display_number(),
+            partition_type_guid: entry.partition_type_guid.0,
+            unique_partition_guid: entry.unique_partition_guid,
+            starting_lba: entry.starting_lba.to_u64(),
+            ending_lba: entry.ending_lba.to_u64(),
+            attributes: entry.attributes.0.to_u64(),
+            name: entry.name.to_string(),
+            filesystem: filesystem.map(|fs| fs.to_string()),
+        }
+    }
+}
+
+/// Read the header at `lba` along with its partition entry array.
+#[cfg(feature = "std")]
+fn read_header<Io: BlockIo>(
+    disk: &mut Disk<Io>,
+    block_size: BlockSize,
+    lba: Lba,
+    block_buf: &mut [u8],
+    partitions: Option<&mut Vec<Partition>>,
+) -> Result<Header, DiskError<Io::Error>> {
+    let header: GptHeader = disk.read_gpt_header(lba, block_buf)?;
+    let block_remainder_zero =
+        disk.is_gpt_header_block_remainder_zero(lba, block_buf)?;
+
+    // The partition entry array can only be read if the header
+    // describes a valid layout.
+    let mut array_crc32_valid = false;
+    if let Ok(layout) = header.get_partition_entry_array_layout() {
+        let num_bytes = layout
+            .num_bytes_rounded_to_block_as_usize(block_size)
+            .ok_or(DiskError::Overflow)?;
+        let mut storage = vec![0; num_bytes];
+        let array =
+            disk.read_gpt_partition_entry_array(layout, &mut storage)?;
+        array_crc32_valid =
+            array.calculate_crc32() == header.partition_entry_array_crc32;
+
+        if let Some(partitions) = partitions {
+            let mut probe_buf = vec![0; FilesystemKind::PROBE_NUM_BYTES];
+            for (i, entry) in array.iter().enumerate() {
+                if entry.is_used() {
+                    // OK to unwrap: the number of entries is a `u32`.
+                    let index = PartitionIndex::new(u32::try_from(i).unwrap());
+                    // Partitions with an invalid range are still listed.
+                    let filesystem = disk
+                        .probe_partition_filesystem(entry, &mut probe_buf)
+                        .ok()
+                        .flatten();
+                    partitions.push(Partition::new(index, entry, filesystem));
+                }
+            }
+        }
+    }
+
+    Ok(Header {
+        revision: header.revision.to_string(),
+        header_size: header.header_size.to_u32(),
+        header_crc32: header.header_crc32.0.to_u32(),
+        my_lba: header.my_lba.to_u64(),
+        alternate_lba: header.alternate_lba.to_u64(),
+        first_usable_lba: header.first_usable_lba.to_u64(),
+        last_usable_lba: header.last_usable_lba.to_u64(),
+        disk_guid: header.disk_guid,
+        partition_entry_lba: header.partition_entry_lba.to_u64(),
+        number_of_partition_entries: header
+            .number_of_partition_entries
+            .to_u32(),
+        size_of_partition_entry: header.size_of_partition_entry.to_u32(),
+        partition_entry_array_crc32: header
+            .partition_entry_array_crc32
+            .0
+            .to_u32(),
+        validation: Validation {
+            signature_valid: header.is_signature_valid(),
+            revision_compatible: header.revision.is_compatible(),
+            header_crc32_valid: header.is_header_crc32_valid(),
+            my_lba_valid: header.my_lba.to_u64() == lba.to_u64(),
+            partition_entry_array_crc32_valid: array_crc32_valid,
+            block_remainder_zero,
+        },
+    })
+}
+
+#[cfg(feature = "std")]
+fn main() -> Result<(), Box<dyn error::Error>> {
+    let disk_path = env::args().nth(1).expect("one argument is required");
+    let file = fs::File::open(disk_path)?;
+
+    let block_size = BlockSize::BS_512;
+    let mut block_buf = vec![0u8; 512];
+
+    let mut block_io = BlockIoAdapter::new(file, block_size);
+    let num_blocks = block_io.num_blocks()?;
+    let mut disk = Disk::new(block_io)?;
+
+    let mbr_block_remainder_zero =
+        disk.is_mbr_block_remainder_zero(&mut block_buf)?;
+
+    let mut partitions = Vec::new();
+    let primary_header = read_header(
+        &mut disk,
+        block_size,
+        Lba(1),
+        &mut block_buf,
+        Some(&mut partitions),
+    )?;
+    let secondary_header = read_header(
+        &mut disk,
+        block_size,
+        Lba(num_blocks - 1),
+        &mut block_buf,
+        None,
+    )?;
+
+    let inspection = Inspection {
+        block_size,
+        num_blocks,
+        primary_header,
+        secondary_header,
+        mbr_block_remainder_zero,
+        partitions,
+    };
+    serde_json::to_writer_pretty(io::stdout().lock(), &inspection)?;
+    println!();
+
+    Ok(())
+}
+
+#[cfg(not(feature = "std"))]
+fn main() {
+    panic!("this program must be compiled with the 'std' feature");
+}
@@ -6,17 +6,109 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+pub(crate) mod shared_block_io;
 pub(crate) mod slice_block_io;
+pub(crate) mod sub_range_block_io;
+pub(crate) mod verifying_block_io;
+pub(crate) mod write_protected_block_io;
 
+#[cfg(feature = "alloc")]
+pub(crate) mod recording_block_io;
+#[cfg(feature = "alloc")]
+pub(crate) mod retry_block_io;
+
+#[cfg(feature = "std")]
+pub(crate) mod compressed_block_io;
+#[cfg(feature = "ureq")]
+pub(crate) mod http_block_io;
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+pub(crate) mod io_uring_block_io;
+#[cfg(feature = "qcow2")]
+pub(crate) mod qcow2_block_io;
+#[cfg(feature = "std")]
+pub(crate) mod read_only_block_io;
+#[cfg(feature = "std")]
+pub(crate) mod split_block_io;
 #[cfg(feature = "std")]
 pub(crate) mod std_block_io;
+#[cfg(feature = "std")]
+mod sync_block_io;
+#[cfg(feature = "std")]
+pub(crate) mod vhd_block_io;
+#[cfg(feature = "zstd")]
+pub(crate) mod zstd_seekable_block_io;
 
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
 use core::fmt::{Debug, Display};
-use gpt_disk_types::{BlockSize, Lba};
+use core::mem::MaybeUninit;
+use core::{ptr, slice};
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+
+/// Size of the static buffer used by the default implementation of
+/// [`BlockIo::write_zeroes`]. This is the largest block size accepted
+/// by [`BlockSize::new_strict`].
+const ZERO_BUF_SIZE: usize = 64 * 1024;
+
+static ZERO_BUF: [u8; ZERO_BUF_SIZE] = [0; ZERO_BUF_SIZE];
+
+/// Convert a fully-initialized buffer to `&mut [u8]`.
+///
+/// # Safety
+///
+/// Every element of `buf` must be initialized.
+#[allow(unsafe_code)]
+unsafe fn assume_init_mut(buf: &mut [MaybeUninit<u8>]) -> &mut [u8] {
+    // SAFETY: `MaybeUninit<u8>` has the same layout as `u8`, and the
+    // caller guarantees that the elements are initialized.
+    unsafe {
+        slice::from_raw_parts_mut(buf.as_mut_ptr().cast::<u8>(), buf.len())
+    }
+}
+
+/// Fill `buf` with zeroes and return it as an initialized buffer.
+pub(crate) fn zero_uninit(buf: &mut [MaybeUninit<u8>]) -> &mut [u8] {
+    buf.fill(MaybeUninit::new(0));
+    // SAFETY: every element was just initialized.
+    #[allow(unsafe_code)]
+    unsafe {
+        assume_init_mut(buf)
+    }
+}
+
+/// Copy `src` into `dst` and return `dst` as an initialized buffer.
+///
+/// # Panics
+///
+/// Panics if `src` and `dst` have different lengths.
+pub(crate) fn copy_to_uninit<'buf>(
+    dst: &'buf mut [MaybeUninit<u8>],
+    src: &[u8],
+) -> &'buf mut [u8] {
+    assert_eq!(dst.len(), src.len());
+    // SAFETY: the lengths are equal, `MaybeUninit<u8>` has the same
+    // layout as `u8`, and the buffers can't overlap since `dst` is a
+    // unique reference. After the copy every element of `dst` is
+    // initialized.
+    #[allow(unsafe_code)]
+    unsafe {
+        ptr::copy_nonoverlapping(
+            src.as_ptr(),
+            dst.as_mut_ptr().cast::<u8>(),
+            src.len(),
+        );
+        assume_init_mut(dst)
+    }
+}
 
 /// Trait for reading from and writing to a block device.
 ///
 /// See also [`BlockIoAdapter`].
+///
+/// With the `std` feature, this is also implemented for
+/// `Arc<Mutex<T>>` and `Arc<RwLock<T>>` where `T: BlockIo`, so that
+/// multiple owners can share one device. Each method call holds the
+/// lock for the duration of the call. Poisoned locks are ignored.
 pub trait BlockIo {
     /// IO error type.
     type Error: Debug + Display + Send + Sync + 'static;
@@ -25,6 +117,30 @@ pub trait BlockIo {
     /// change.
     fn block_size(&self) -> BlockSize;
 
+    /// Get the physical block size, if known.
+    ///
+    /// Many devices have a physical block size that is larger than the
+    /// logical [`block_size`], for example "512e" drives that use
+    /// 4096-byte physical sectors but are addressed in 512-byte
+    /// blocks. Writes that are not aligned to the physical block size
+    /// may be slower, so partitions should be aligned to it.
+    ///
+    /// The default implementation returns `None`.
+    ///
+    /// [`block_size`]: Self::block_size
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        None
+    }
+
+    /// Get the optimal IO size in bytes, if known. This is the
+    /// preferred size and alignment for large transfers, such as the
+    /// stripe width of a RAID device.
+    ///
+    /// The default implementation returns `None`.
+    fn optimal_io_size(&self) -> Option<u32> {
+        None
+    }
+
     /// Get the number of logical blocks in the disk.
     ///
     /// If the underlying storage has a number of bytes that are not
@@ -47,6 +163,28 @@ pub trait BlockIo {
         dst: &mut [u8],
     ) -> Result<(), Self::Error>;
 
+    /// Read contiguous blocks from the disk into a buffer that may be
+    /// uninitialized, and return the initialized buffer. This avoids
+    /// the cost of zeroing large buffers that are about to be
+    /// overwritten. The `dst` buffer has the same requirements as in
+    /// [`read_blocks`].
+    ///
+    /// The default implementation zeroes `dst` and calls
+    /// [`read_blocks`]. Implementations that can write directly into
+    /// uninitialized memory, such as the byte slice implementations of
+    /// [`BlockIoAdapter`], override this.
+    ///
+    /// [`read_blocks`]: Self::read_blocks
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        let dst = zero_uninit(dst);
+        self.read_blocks(start_lba, dst)?;
+        Ok(dst)
+    }
+
     /// Write contiguous block to the disk. The `src` buffer size must
     /// be a multiple of [`block_size`]. Implementations are permitted
     /// to panic if this precondition is not met, e.g. by calling
@@ -65,6 +203,208 @@ pub trait BlockIo {
 
     /// Flush any pending writes to the device.
     fn flush(&mut self) -> Result<(), Self::Error>;
+
+    /// Tell the device that the blocks in `range` are no longer in
+    /// use, for example by issuing a TRIM command to an SSD.
+    ///
+    /// This is only a hint; the contents of the blocks after a discard
+    /// are unspecified. Use [`write_zeroes`] if the blocks must read
+    /// back as zeros.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// [`write_zeroes`]: Self::write_zeroes
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        let _ = range;
+        Ok(())
+    }
+
+    /// Set all the blocks in `range` to zero.
+    ///
+    /// Implementations should override this if the device can zero
+    /// blocks more efficiently than writing zero buffers.
+    ///
+    /// The default implementation calls [`write_blocks`] with a static
+    /// 64 KiB zero buffer. Larger block sizes are written one block at
+    /// a time from an allocated buffer if the `alloc` feature is
+    /// enabled, and otherwise cause a panic.
+    ///
+    /// [`write_blocks`]: Self::write_blocks
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        let block_size = self.block_size().to_u64();
+
+        #[cfg(feature = "alloc")]
+        let block_buf;
+        let zeros: &[u8] =
+            if block_size <= u64::try_from(ZERO_BUF_SIZE).unwrap() {
+                &ZERO_BUF
+            } else {
+                #[cfg(feature = "alloc")]
+                {
+                    // OK to unwrap: the block size is a `u32`.
+                    block_buf =
+                        alloc::vec![0; usize::try_from(block_size).unwrap()];
+                    &block_buf
+                }
+                #[cfg(not(feature = "alloc"))]
+                panic!("block size is larger than the zero buffer");
+            };
+        let blocks_per_write = u64::try_from(zeros.len()).unwrap() / block_size;
+
+        let end = range.end().to_u64();
+        let mut lba = range.start().to_u64();
+        loop {
+            let num_blocks =
+                blocks_per_write.min((end - lba).saturating_add(1));
+            // OK to unwrap: the length is at most `zeros.len()`.
+            let len = usize::try_from(num_blocks * block_size).unwrap();
+            self.write_blocks(Lba(lba), &zeros[..len])?;
+
+            match lba.checked_add(num_blocks) {
+                Some(next) if next <= end => lba = next,
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+impl<T> BlockIo for &mut T
+where
+    T: BlockIo + ?Sized,
+{
+    type Error = T::Error;
+
+    fn block_size(&self) -> BlockSize {
+        (**self).block_size()
+    }
+
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        (**self).physical_block_size()
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        (**self).optimal_io_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        (**self).num_blocks()
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        (**self).read_blocks(start_lba, dst)
+    }
+
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        (**self).read_blocks_uninit(start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        (**self).write_blocks(start_lba, src)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        (**self).discard_blocks(range)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        (**self).write_zeroes(range)
+    }
+}
+
+/// Boxed block devices, e.g. `Box<dyn BlockIo<Error = E>>`. Requires the
+/// `alloc` feature.
+#[cfg(feature = "alloc")]
+impl<T> BlockIo for Box<T>
+where
+    T: BlockIo + ?Sized,
+{
+    type Error = T::Error;
+
+    fn block_size(&self) -> BlockSize {
+        (**self).block_size()
+    }
+
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        (**self).physical_block_size()
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        (**self).optimal_io_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        (**self).num_blocks()
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        (**self).read_blocks(start_lba, dst)
+    }
+
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        (**self).read_blocks_uninit(start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        (**self).write_blocks(start_lba, src)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        (**self).discard_blocks(range)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        (**self).write_zeroes(range)
+    }
 }
 
 /// Adapter for types that can act as storage, but don't have a block
@@ -99,6 +439,23 @@ pub trait BlockIo {
 /// assert!(bio.write_blocks(Lba(0), &one_block).is_ok());
 /// ```
 ///
+/// # For byte arrays
+///
+/// An owned fixed-size byte array can be used directly as storage,
+/// without allocation. A borrowed array must be passed as a slice, e.g.
+/// `&mut data[..]`, since `&mut [u8; N]` would overlap with the
+/// `std::io` impl.
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba};
+/// use gpt_disk_io::{BlockIo, BlockIoAdapter};
+///
+/// let mut bio = BlockIoAdapter::new([0; 1024], BlockSize::BS_512);
+/// assert_eq!(bio.num_blocks().unwrap(), 2);
+/// assert!(bio.write_blocks(Lba(1), &[1; 512]).is_ok());
+/// assert_eq!(bio.storage()[512], 1);
+/// ```
+///
 /// # With the `alloc` feature
 ///
 /// Construct a `BlockIoAdapter` that owns a `Vec<u8>`:
@@ -141,10 +498,15 @@ pub trait BlockIo {
 ///     let mut bio = BlockIoAdapter::new(&file, BlockSize::BS_512);
 ///     assert_eq!(bio.num_blocks()?, 1);
 ///
-///     // Construct a `BlockIoAdapter` from another type that
-///     // implements `Read + Write + Seek`, but does not directly implement
-///     // `ReadWriteSeek`. Due to trait constraints, this requires an `&mut`
-///     // borrow; transferring ownership of `cursor` would fail to compile.
+///     // Construct a `BlockIoAdapter` that takes ownership of an
+///     // in-memory cursor.
+///     let cursor = Cursor::new(vec![0; 512]);
+///     let mut bio = BlockIoAdapter::new(cursor, BlockSize::BS_512);
+///     assert_eq!(bio.num_blocks()?, 1);
+///
+///     // Any type that implements `Read + Write + Seek` can also be
+///     // borrowed with `&mut`, even if it does not directly implement
+///     // `ReadWriteSeek`.
 ///     let mut cursor = Cursor::new(vec![0; 512]);
 ///     let mut bio = BlockIoAdapter::new(&mut cursor, BlockSize::BS_512);
 ///     assert_eq!(bio.num_blocks()?, 1);
@@ -157,24 +519,67 @@ pub trait BlockIo {
 pub struct BlockIoAdapter<T> {
     storage: T,
     block_size: BlockSize,
+    physical_block_size: Option<BlockSize>,
+    optimal_io_size: Option<u32>,
 }
 
 impl<T> BlockIoAdapter<T> {
     /// Create a new `BlockIoAdapter`.
+    ///
+    /// The physical block size and optimal IO size are not set; see
+    /// [`with_physical_block_size`] and [`with_optimal_io_size`].
+    ///
+    /// [`with_optimal_io_size`]: Self::with_optimal_io_size
+    /// [`with_physical_block_size`]: Self::with_physical_block_size
     #[must_use]
     pub fn new(storage: T, block_size: BlockSize) -> Self {
         Self {
             storage,
             block_size,
+            physical_block_size: None,
+            optimal_io_size: None,
         }
     }
 
+    /// Set the physical block size returned by
+    /// [`BlockIo::physical_block_size`].
+    #[must_use]
+    pub fn with_physical_block_size(
+        mut self,
+        physical_block_size: Option<BlockSize>,
+    ) -> Self {
+        self.physical_block_size = physical_block_size;
+        self
+    }
+
+    /// Set the optimal IO size returned by [`BlockIo::optimal_io_size`].
+    #[must_use]
+    pub fn with_optimal_io_size(
+        mut self,
+        optimal_io_size: Option<u32>,
+    ) -> Self {
+        self.optimal_io_size = optimal_io_size;
+        self
+    }
+
     /// Get the [`BlockSize`].
     #[must_use]
     pub fn block_size(&self) -> BlockSize {
         self.block_size
     }
 
+    /// Get the physical block size, if set.
+    #[must_use]
+    pub fn physical_block_size(&self) -> Option<BlockSize> {
+        self.physical_block_size
+    }
+
+    /// Get the optimal IO size in bytes, if set.
+    #[must_use]
+    pub fn optimal_io_size(&self) -> Option<u32> {
+        self.optimal_io_size
+    }
+
     /// Get a reference to the underlying storage.
     #[must_use]
     pub fn storage(&self) -> &T {
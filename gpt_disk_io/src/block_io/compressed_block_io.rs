@@ -0,0 +1,183 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::BlockIo;
+use gpt_disk_types::{BlockSize, Lba};
+use std::io::{self, Read};
+
+/// Read-only [`BlockIo`] backed by a compressed disk image.
+///
+/// This type does not depend on any particular compression library.
+/// Instead, it takes an `open` function that returns a new
+/// decompressing reader (for example `flate2::read::GzDecoder` or
+/// `zstd::Decoder`) positioned at the start of the uncompressed data.
+///
+/// Streaming decompressors can't seek, so a read past the current
+/// position skips over the intervening data, and a read before the
+/// current position calls `open` again and skips forward from the
+/// start. Reading the GPT structures at both ends of the disk
+/// therefore decompresses the image a small number of times, but never
+/// requires the decompressed image to be stored.
+///
+/// The number of blocks is found by decompressing the entire image the
+/// first time [`BlockIo::num_blocks`] is called. The result is cached.
+///
+/// Since the whole stream is treated as one sequence of bytes, any
+/// index or seek table in the compressed data is ignored, and the cost
+/// of a backward read grows with the distance from the start of the
+/// image. For images in the zstd seekable format, use
+/// [`ZstdSeekableBlockIo`] instead, which only decompresses the frames
+/// that a read overlaps.
+///
+/// Writing is not supported; [`BlockIo::write_blocks`] always fails
+/// with [`io::ErrorKind::Unsupported`].
+///
+/// [`ZstdSeekableBlockIo`]: crate::ZstdSeekableBlockIo
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba};
+/// use gpt_disk_io::{BlockIo, CompressedBlockIo};
+/// use std::io::Cursor;
+///
+/// // A real program would return a decompressing reader here.
+/// let image = vec![0u8; 4096];
+/// let open = || Ok(Cursor::new(image.as_slice()));
+///
+/// let mut bio = CompressedBlockIo::new(open, BlockSize::BS_512);
+/// assert_eq!(bio.num_blocks()?, 8);
+///
+/// let mut block = vec![0; 512];
+/// bio.read_blocks(Lba(1), &mut block)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct CompressedBlockIo<F, R> {
+    open: F,
+    reader: Option<R>,
+    position: u64,
+    num_bytes: Option<u64>,
+    block_size: BlockSize,
+}
+
+impl<F, R> CompressedBlockIo<F, R>
+where
+    F: FnMut() -> Result<R, io::Error>,
+    R: Read,
+{
+    /// Create a new `CompressedBlockIo`. The `open` function is not
+    /// called until the first read.
+    #[must_use]
+    pub fn new(open: F, block_size: BlockSize) -> Self {
+        Self {
+            open,
+            reader: None,
+            position: 0,
+            num_bytes: None,
+            block_size,
+        }
+    }
+
+    /// Get a reader positioned at `offset` bytes into the uncompressed
+    /// data, reopening the stream if needed.
+    fn reader_at(&mut self, offset: u64) -> Result<&mut R, io::Error> {
+        if self.reader.is_none() || self.position > offset {
+            self.reader = Some((self.open)()?);
+            self.position = 0;
+        }
+
+        // OK to unwrap since the reader was set above.
+        let reader = self.reader.as_mut().unwrap();
+
+        let to_skip = offset - self.position;
+        let skipped =
+            io::copy(&mut reader.by_ref().take(to_skip), &mut io::sink())?;
+        self.position += skipped;
+        if skipped != to_skip {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        Ok(reader)
+    }
+
+    fn read_at(
+        &mut self,
+        offset: u64,
+        dst: &mut [u8],
+    ) -> Result<(), io::Error> {
+        self.reader_at(offset)?.read_exact(dst)?;
+        self.position += u64::try_from(dst.len())
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        Ok(())
+    }
+}
+
+impl<F, R> BlockIo for CompressedBlockIo<F, R>
+where
+    F: FnMut() -> Result<R, io::Error>,
+    R: Read,
+{
+    type Error = io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        let num_bytes = if let Some(num_bytes) = self.num_bytes {
+            num_bytes
+        } else {
+            // Decompress the rest of the stream to find the end.
+            let reader = self.reader_at(self.position)?;
+            let remaining = match io::copy(reader, &mut io::sink()) {
+                Ok(remaining) => remaining,
+                Err(err) => {
+                    self.reader = None;
+                    return Err(err);
+                }
+            };
+            let num_bytes = self.position + remaining;
+            self.position = num_bytes;
+            self.num_bytes = Some(num_bytes);
+            num_bytes
+        };
+        Ok(num_bytes / self.block_size.to_u64())
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.block_size.assert_valid_block_buffer(dst);
+
+        let offset = start_lba.to_u64() * self.block_size.to_u64();
+        let result = self.read_at(offset, dst);
+        if result.is_err() {
+            // The state of the stream is unknown after an error, so
+            // start over on the next read.
+            self.reader = None;
+        }
+        result
+    }
+
+    fn write_blocks(
+        &mut self,
+        _start_lba: Lba,
+        _src: &[u8],
+    ) -> Result<(), Self::Error> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "compressed disk images are read-only",
+        ))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::std_block_io::byte_offset;
+use crate::BlockIo;
+use gpt_disk_types::{BlockSize, Lba};
+use std::fmt::{self, Debug, Formatter};
+use std::io::{self, Read};
+
+/// Size of each range request, and of each entry in the cache.
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Maximum number of chunks in the cache.
+const MAX_CACHED_CHUNKS: usize = 16;
+
+fn other_error<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+/// Parse the total size from a `Content-Range` header, e.g.
+/// `bytes 0-0/1234`.
+fn parse_content_range_size(header: &str) -> Option<u64> {
+    let (_, size) = header.strip_prefix("bytes ")?.split_once('/')?;
+    size.trim().parse().ok()
+}
+
+/// A cached chunk of the remote image.
+struct CachedChunk {
+    index: u64,
+    data: Vec<u8>,
+}
+
+/// Read-only [`BlockIo`] for a remote disk image accessed with HTTP
+/// range requests.
+///
+/// This allows inspecting the partition table of a large remote image
+/// without downloading it. The server must support `Range` requests;
+/// [`HttpBlockIo::new`] fails if it does not.
+///
+/// Data is fetched in 64 KiB chunks, and the most recently used 16
+/// chunks are cached, so reading the headers and partition entry
+/// arrays of a GPT disk only takes a few requests.
+///
+/// Writing is not supported; [`BlockIo::write_blocks`] always fails
+/// with [`io::ErrorKind::Unsupported`].
+///
+/// Requires the `ureq` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use gpt_disk_io::gpt_disk_types::BlockSize;
+/// use gpt_disk_io::{Disk, HttpBlockIo};
+///
+/// let bio = HttpBlockIo::new(
+///     "https://example.com/images/disk.img",
+///     BlockSize::BS_512,
+/// )?;
+/// let mut disk = Disk::new(bio)?;
+/// let mut block_buf = vec![0; 512];
+/// let header = disk.read_primary_gpt_header(&mut block_buf)?;
+/// println!("{header}");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct HttpBlockIo {
+    agent: ureq::Agent,
+    url: String,
+    block_size: BlockSize,
+    num_bytes: u64,
+    /// Cached chunks, from least to most recently used.
+    cache: Vec<CachedChunk>,
+}
+
+impl HttpBlockIo {
+    /// Create an `HttpBlockIo` for `url` using a default
+    /// [`ureq::Agent`].
+    ///
+    /// This makes a request to get the size of the image and to check
+    /// that the server supports range requests.
+    pub fn new(url: &str, block_size: BlockSize) -> Result<Self, io::Error> {
+        Self::with_agent(ureq::agent(), url, block_size)
+    }
+
+    /// Create an `HttpBlockIo` for `url` using `agent`, for example to
+    /// configure timeouts or a proxy. See [`HttpBlockIo::new`].
+    pub fn with_agent(
+        agent: ureq::Agent,
+        url: &str,
+        block_size: BlockSize,
+    ) -> Result<Self, io::Error> {
+        let response = agent
+            .get(url)
+            .set("Range", "bytes=0-0")
+            .call()
+            .map_err(other_error)?;
+        if response.status() != 206 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "server does not support range requests",
+            ));
+        }
+        let num_bytes = response
+            .header("Content-Range")
+            .and_then(parse_content_range_size)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "missing or invalid Content-Range header",
+                )
+            })?;
+
+        Ok(Self {
+            agent,
+            url: url.to_owned(),
+            block_size,
+            num_bytes,
+            cache: Vec::new(),
+        })
+    }
+
+    /// Get the URL of the image.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get chunk `index`, fetching it if it is not cached.
+    fn chunk(&mut self, index: u64) -> Result<&[u8], io::Error> {
+        if let Some(pos) = self.cache.iter().position(|c| c.index == index) {
+            // Move to the most recently used position.
+            let chunk = self.cache.remove(pos);
+            self.cache.push(chunk);
+        } else {
+            let data = self.fetch_chunk(index)?;
+            if self.cache.len() == MAX_CACHED_CHUNKS {
+                self.cache.remove(0);
+            }
+            self.cache.push(CachedChunk { index, data });
+        }
+        // OK to unwrap: a chunk was just pushed.
+        Ok(&self.cache.last().unwrap().data)
+    }
+
+    fn fetch_chunk(&self, index: u64) -> Result<Vec<u8>, io::Error> {
+        let start = index * CHUNK_SIZE;
+        let end = (start + CHUNK_SIZE).min(self.num_bytes);
+        let len = end - start;
+
+        let response = self
+            .agent
+            .get(&self.url)
+            .set("Range", &format!("bytes={start}-{}", end - 1))
+            .call()
+            .map_err(other_error)?;
+        if response.status() != 206 {
+            return Err(other_error(format!(
+                "unexpected HTTP status {} for range request",
+                response.status()
+            )));
+        }
+
+        let mut data = Vec::new();
+        response.into_reader().take(len).read_to_end(&mut data)?;
+        if u64::try_from(data.len()).ok() != Some(len) {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "range response is too short",
+            ));
+        }
+        Ok(data)
+    }
+}
+
+impl Debug for HttpBlockIo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpBlockIo")
+            .field("url", &self.url)
+            .field("block_size", &self.block_size)
+            .field("num_bytes", &self.num_bytes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BlockIo for HttpBlockIo {
+    type Error = io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.num_bytes / self.block_size.to_u64())
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.block_size.assert_valid_block_buffer(dst);
+
+        let mut offset = byte_offset(self.block_size, start_lba)?;
+        let end = u64::try_from(dst.len())
+            .ok()
+            .and_then(|len| offset.checked_add(len));
+        if end.map_or(true, |end| end > self.num_bytes) {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "read past the end of the image",
+            ));
+        }
+
+        let mut dst = dst;
+        while !dst.is_empty() {
+            let chunk = self.chunk(offset / CHUNK_SIZE)?;
+            // OK to unwrap: the offset within a chunk is less than
+            // `CHUNK_SIZE`.
+            let chunk = &chunk[usize::try_from(offset % CHUNK_SIZE).unwrap()..];
+            let len = chunk.len().min(dst.len());
+            let (left, right) = dst.split_at_mut(len);
+            left.copy_from_slice(&chunk[..len]);
+            dst = right;
+            offset += u64::try_from(len).unwrap();
+        }
+        Ok(())
+    }
+
+    fn write_blocks(
+        &mut self,
+        _start_lba: Lba,
+        _src: &[u8],
+    ) -> Result<(), Self::Error> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "remote images are read-only",
+        ))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
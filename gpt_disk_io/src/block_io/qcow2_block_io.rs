@@ -0,0 +1,304 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::BlockIo;
+use gpt_disk_types::{BlockSize, Lba};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Magic value at the start of the header: "QFI\xfb".
+const MAGIC: [u8; 4] = *b"QFI\xfb";
+
+/// Bits 9-55 of L1 and L2 entries contain a host offset.
+const OFFSET_MASK: u64 = 0x00ff_ffff_ffff_fe00;
+
+/// Bit 62 of an L2 entry indicates a compressed cluster.
+const L2_COMPRESSED_FLAG: u64 = 1 << 62;
+
+/// Bit 0 of an L2 entry indicates the cluster reads as all zeros
+/// (version 3 only).
+const L2_ZERO_FLAG: u64 = 1;
+
+/// Incompatible feature bits that don't affect reading uncompressed
+/// data: the dirty bit (0) and the compression type bit (3).
+const SUPPORTED_INCOMPATIBLE_FEATURES: u64 = 0b1001;
+
+/// Same limit on the L1 table size that QEMU uses.
+const MAX_L1_TABLE_BYTES: u64 = 32 * 1024 * 1024;
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn unsupported(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, msg)
+}
+
+fn be_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn be_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_be_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Read a table of big-endian `u64` values.
+fn read_table<T: Read + Seek>(
+    storage: &mut T,
+    offset: u64,
+    num_entries: usize,
+) -> io::Result<Vec<u64>> {
+    let mut bytes = vec![0; num_entries * 8];
+    storage.seek(SeekFrom::Start(offset))?;
+    storage.read_exact(&mut bytes)?;
+    Ok(bytes
+        .chunks_exact(8)
+        .map(|entry| be_u64(entry, 0))
+        .collect())
+}
+
+/// Read-only [`BlockIo`] for qcow2 images.
+///
+/// This allows the partition tables inside a qcow2 virtual machine
+/// disk to be read without first converting it to a raw image. Only
+/// the features needed for reading typical images are supported:
+///
+/// * Unallocated clusters and zero clusters read as zeros.
+/// * Images with a backing file, encrypted images, and images with
+///   compressed clusters are not supported. Opening an image with a
+///   backing file or encryption fails; reading a compressed cluster
+///   fails with [`io::ErrorKind::Unsupported`].
+/// * Snapshots are ignored; reads always see the active image.
+///
+/// Writing is not supported; [`BlockIo::write_blocks`] always fails
+/// with [`io::ErrorKind::Unsupported`].
+///
+/// Requires the `qcow2` feature.
+#[derive(Debug)]
+pub struct Qcow2BlockIo<T> {
+    storage: T,
+    block_size: BlockSize,
+    cluster_bits: u32,
+    virtual_size: u64,
+    l1_table: Vec<u64>,
+    /// Most recently used L2 table, along with its host offset.
+    l2_cache: Option<(u64, Vec<u64>)>,
+}
+
+impl<T> Qcow2BlockIo<T>
+where
+    T: Read + Seek,
+{
+    /// Open a qcow2 image. The header is validated and the L1 table is
+    /// read into memory.
+    pub fn open(mut storage: T, block_size: BlockSize) -> io::Result<Self> {
+        // Large enough for the version 3 header fields used here.
+        let mut header = [0; 104];
+        storage.seek(SeekFrom::Start(0))?;
+        storage.read_exact(&mut header[..72])?;
+
+        if header[0..4] != MAGIC {
+            return Err(invalid_data("qcow2 magic not found"));
+        }
+
+        let version = be_u32(&header, 4);
+        match version {
+            2 => {}
+            3 => {
+                storage.read_exact(&mut header[72..])?;
+                let incompatible_features = be_u64(&header, 72);
+                if incompatible_features & !SUPPORTED_INCOMPATIBLE_FEATURES != 0
+                {
+                    return Err(unsupported(
+                        "qcow2 image uses unsupported incompatible features",
+                    ));
+                }
+            }
+            _ => return Err(unsupported("unsupported qcow2 version")),
+        }
+
+        if be_u64(&header, 8) != 0 {
+            return Err(unsupported("qcow2 backing files are not supported"));
+        }
+        if be_u32(&header, 32) != 0 {
+            return Err(unsupported(
+                "encrypted qcow2 images are not supported",
+            ));
+        }
+
+        let cluster_bits = be_u32(&header, 20);
+        if !(9..=21).contains(&cluster_bits) {
+            return Err(invalid_data("invalid qcow2 cluster size"));
+        }
+
+        let virtual_size = be_u64(&header, 24);
+        let l1_size = be_u32(&header, 36);
+        let l1_table_offset = be_u64(&header, 40);
+
+        // Check that the L1 table is large enough to cover the whole
+        // disk, but not unreasonably large.
+        let cluster_size = 1u64 << cluster_bits;
+        let bytes_per_l1_entry = cluster_size * (cluster_size / 8);
+        let required_l1_size = virtual_size / bytes_per_l1_entry
+            + u64::from(virtual_size % bytes_per_l1_entry != 0);
+        if u64::from(l1_size) < required_l1_size
+            || u64::from(l1_size) * 8 > MAX_L1_TABLE_BYTES
+        {
+            return Err(invalid_data("invalid qcow2 L1 table size"));
+        }
+
+        let l1_table = read_table(
+            &mut storage,
+            l1_table_offset,
+            usize::try_from(l1_size)
+                .map_err(|_| invalid_data("invalid qcow2 L1 table size"))?,
+        )?;
+
+        Ok(Self {
+            storage,
+            block_size,
+            cluster_bits,
+            virtual_size,
+            l1_table,
+            l2_cache: None,
+        })
+    }
+
+    /// Consume the `Qcow2BlockIo` and return the underlying storage.
+    #[must_use]
+    pub fn take_storage(self) -> T {
+        self.storage
+    }
+
+    fn cluster_size(&self) -> u64 {
+        1 << self.cluster_bits
+    }
+
+    /// Get the entry at `index` in the L2 table at `l2_offset`.
+    fn l2_entry(&mut self, l2_offset: u64, index: usize) -> io::Result<u64> {
+        let is_cached =
+            matches!(&self.l2_cache, Some((offset, _)) if *offset == l2_offset);
+        if !is_cached {
+            let num_entries = usize::try_from(self.cluster_size() / 8)
+                .map_err(|_| invalid_data("invalid qcow2 cluster size"))?;
+            let table = read_table(&mut self.storage, l2_offset, num_entries)?;
+            self.l2_cache = Some((l2_offset, table));
+        }
+
+        // OK to unwrap since the cache was filled above, and the index
+        // is always less than the number of entries per table.
+        let (_, table) = self.l2_cache.as_ref().unwrap();
+        Ok(table[index])
+    }
+
+    /// Map a guest offset to the host offset of its cluster. Returns
+    /// `None` if the cluster reads as zeros.
+    fn host_cluster_offset(
+        &mut self,
+        guest_offset: u64,
+    ) -> io::Result<Option<u64>> {
+        let entries_per_l2 = self.cluster_size() / 8;
+        let cluster_index = guest_offset >> self.cluster_bits;
+
+        let l1_index = usize::try_from(cluster_index / entries_per_l2)
+            .map_err(|_| invalid_data("qcow2 L1 index out of range"))?;
+        let l2_index = usize::try_from(cluster_index % entries_per_l2)
+            .map_err(|_| invalid_data("qcow2 L2 index out of range"))?;
+
+        let l1_entry = *self
+            .l1_table
+            .get(l1_index)
+            .ok_or_else(|| invalid_data("qcow2 L1 index out of range"))?;
+        let l2_offset = l1_entry & OFFSET_MASK;
+        if l2_offset == 0 {
+            return Ok(None);
+        }
+
+        let l2_entry = self.l2_entry(l2_offset, l2_index)?;
+        if l2_entry & L2_COMPRESSED_FLAG != 0 {
+            return Err(unsupported(
+                "compressed qcow2 clusters are not supported",
+            ));
+        }
+        if l2_entry & L2_ZERO_FLAG != 0 {
+            return Ok(None);
+        }
+
+        let host_offset = l2_entry & OFFSET_MASK;
+        if host_offset == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(host_offset))
+        }
+    }
+}
+
+impl<T> BlockIo for Qcow2BlockIo<T>
+where
+    T: Read + Seek,
+{
+    type Error = io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.virtual_size / self.block_size.to_u64())
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        mut dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.block_size.assert_valid_block_buffer(dst);
+
+        let mut offset = start_lba.to_u64() * self.block_size.to_u64();
+        let end = offset + u64::try_from(dst.len()).unwrap();
+        if end > self.virtual_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "read extends past the end of the qcow2 image",
+            ));
+        }
+
+        // Read one cluster (or part of a cluster) at a time.
+        while !dst.is_empty() {
+            let offset_in_cluster = offset & (self.cluster_size() - 1);
+            let len = usize::try_from(self.cluster_size() - offset_in_cluster)
+                .unwrap_or(usize::MAX)
+                .min(dst.len());
+            let (chunk, rest) = dst.split_at_mut(len);
+
+            if let Some(host_offset) = self.host_cluster_offset(offset)? {
+                self.storage
+                    .seek(SeekFrom::Start(host_offset + offset_in_cluster))?;
+                self.storage.read_exact(chunk)?;
+            } else {
+                chunk.fill(0);
+            }
+
+            offset += u64::try_from(len).unwrap();
+            dst = rest;
+        }
+
+        Ok(())
+    }
+
+    fn write_blocks(
+        &mut self,
+        _start_lba: Lba,
+        _src: &[u8],
+    ) -> Result<(), Self::Error> {
+        Err(unsupported("writing to qcow2 images is not supported"))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
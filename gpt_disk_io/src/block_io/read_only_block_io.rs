@@ -0,0 +1,138 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::std_block_io::byte_offset;
+use crate::BlockIo;
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+use std::io::{self, Read, Seek, SeekFrom};
+
+fn read_only_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "storage is read-only")
+}
+
+/// Read-only [`BlockIo`] for any [`Read`] + [`Seek`] type.
+///
+/// [`BlockIoAdapter`] requires storage that is also [`Write`], so
+/// read-only sources such as a [`File`] opened without write access or
+/// a seekable wrapper around a network stream would otherwise have to
+/// pretend to be writable. `ReadOnlyBlockIo` only requires `Read +
+/// Seek`, and [`BlockIo::write_blocks`], [`BlockIo::write_zeroes`],
+/// and [`BlockIo::discard_blocks`] always fail with
+/// [`io::ErrorKind::Unsupported`].
+///
+/// [`BlockIoAdapter`]: crate::BlockIoAdapter
+/// [`File`]: std::fs::File
+/// [`Write`]: std::io::Write
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba};
+/// use gpt_disk_io::{BlockIo, ReadOnlyBlockIo};
+/// use std::io::{Cursor, ErrorKind};
+///
+/// let data = vec![1u8; 1024];
+/// let mut bio = ReadOnlyBlockIo::new(Cursor::new(data), BlockSize::BS_512);
+/// assert_eq!(bio.num_blocks()?, 2);
+///
+/// let mut block = vec![0; 512];
+/// bio.read_blocks(Lba(1), &mut block)?;
+/// assert_eq!(block, [1; 512]);
+///
+/// let err = bio.write_blocks(Lba(0), &block).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::Unsupported);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReadOnlyBlockIo<T> {
+    reader: T,
+    block_size: BlockSize,
+}
+
+impl<T> ReadOnlyBlockIo<T>
+where
+    T: Read + Seek,
+{
+    /// Create a new `ReadOnlyBlockIo`.
+    #[must_use]
+    pub fn new(reader: T, block_size: BlockSize) -> Self {
+        Self { reader, block_size }
+    }
+
+    /// Get a reference to the underlying reader.
+    #[must_use]
+    pub fn reader(&self) -> &T {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    #[must_use]
+    pub fn reader_mut(&mut self) -> &mut T {
+        &mut self.reader
+    }
+
+    /// Consume the `ReadOnlyBlockIo` and return the underlying reader.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.reader
+    }
+}
+
+impl<T> BlockIo for ReadOnlyBlockIo<T>
+where
+    T: Read + Seek,
+{
+    type Error = io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        let num_bytes = self.reader.seek(SeekFrom::End(0))?;
+        Ok(num_bytes / self.block_size.to_u64())
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.block_size.assert_valid_block_buffer(dst);
+
+        let offset = byte_offset(self.block_size, start_lba)?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.reader.read_exact(dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        _start_lba: Lba,
+        _src: &[u8],
+    ) -> Result<(), Self::Error> {
+        Err(read_only_error())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn discard_blocks(
+        &mut self,
+        _range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        Err(read_only_error())
+    }
+
+    fn write_zeroes(
+        &mut self,
+        _range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        Err(read_only_error())
+    }
+}
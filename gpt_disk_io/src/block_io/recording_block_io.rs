@@ -0,0 +1,472 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::BlockIo;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use gpt_disk_types::{BlockSize, Crc32, Lba, LbaRangeInclusive};
+
+/// Kind of operation in an [`IoTraceEntry`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum IoOp {
+    /// [`BlockIo::read_blocks`].
+    Read,
+
+    /// [`BlockIo::write_blocks`].
+    Write,
+
+    /// [`BlockIo::flush`].
+    Flush,
+
+    /// [`BlockIo::discard_blocks`].
+    Discard,
+
+    /// [`BlockIo::write_zeroes`].
+    WriteZeroes,
+}
+
+impl Display for IoOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Flush => "flush",
+            Self::Discard => "discard",
+            Self::WriteZeroes => "write_zeroes",
+        })
+    }
+}
+
+/// One operation recorded by [`RecordingBlockIo`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct IoTraceEntry {
+    /// Kind of operation.
+    pub op: IoOp,
+
+    /// First block of the operation. Zero for [`IoOp::Flush`].
+    pub lba: Lba,
+
+    /// Number of blocks in the operation. Zero for [`IoOp::Flush`].
+    pub num_blocks: u64,
+
+    /// Checksum of the data written, if data hashing is enabled and the
+    /// operation is an [`IoOp::Write`].
+    pub data_crc32: Option<Crc32>,
+}
+
+impl IoTraceEntry {
+    fn new(op: IoOp, lba: Lba, num_blocks: u64) -> Self {
+        Self {
+            op,
+            lba,
+            num_blocks,
+            data_crc32: None,
+        }
+    }
+
+    fn flush() -> Self {
+        Self::new(IoOp::Flush, Lba(0), 0)
+    }
+
+    fn for_range(op: IoOp, range: LbaRangeInclusive) -> Self {
+        Self::new(op, range.start(), range.num_blocks())
+    }
+}
+
+impl Display for IoTraceEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.op == IoOp::Flush {
+            return f.write_str("flush");
+        }
+        write!(f, "{} {}+{}", self.op, self.lba, self.num_blocks)?;
+        if let Some(crc) = self.data_crc32 {
+            write!(f, " crc32={crc}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Get the number of whole blocks in `buf`.
+fn buf_num_blocks(block_size: BlockSize, buf: &[u8]) -> u64 {
+    // OK to unwrap: a buffer length always fits in a `u64`.
+    u64::try_from(buf.len()).unwrap() / block_size.to_u64()
+}
+
+/// [`BlockIo`] wrapper that records every operation into a trace.
+///
+/// The trace can be compared with a later run using [`ReplayBlockIo`],
+/// for example to check that a refactor does not change the order of
+/// writes to the disk.
+///
+/// Reads, writes, flushes, discards, and zero writes are recorded.
+/// Queries such as [`BlockIo::num_blocks`] are not. Operations are
+/// recorded before they are passed to the underlying `BlockIo`, so
+/// failed operations are included.
+///
+/// Requires the `alloc` feature.
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba};
+/// use gpt_disk_io::{
+///     BlockIo, BlockIoAdapter, IoOp, RecordingBlockIo, ReplayBlockIo,
+/// };
+///
+/// let mut data = vec![0; 2048];
+/// let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+/// let mut bio = RecordingBlockIo::with_data_hashes(bio);
+/// bio.write_blocks(Lba(1), &[1; 1024]).unwrap();
+/// bio.flush().unwrap();
+/// let trace = bio.take_trace();
+/// assert_eq!(trace[0].op, IoOp::Write);
+/// assert_eq!(trace[1].op, IoOp::Flush);
+///
+/// // Check that a second run does the same IO.
+/// let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+/// let mut bio = ReplayBlockIo::new(bio, &trace);
+/// bio.write_blocks(Lba(1), &[1; 1024]).unwrap();
+/// bio.flush().unwrap();
+/// bio.finish().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct RecordingBlockIo<Io> {
+    io: Io,
+    trace: Vec<IoTraceEntry>,
+    hash_data: bool,
+}
+
+impl<Io: BlockIo> RecordingBlockIo<Io> {
+    /// Create a `RecordingBlockIo` that records the operation, LBA, and
+    /// length of each operation.
+    pub fn new(io: Io) -> Self {
+        Self {
+            io,
+            trace: Vec::new(),
+            hash_data: false,
+        }
+    }
+
+    /// Create a `RecordingBlockIo` that also records a CRC32 of the
+    /// data of each write, so that a replay checks that the same data
+    /// is written.
+    pub fn with_data_hashes(io: Io) -> Self {
+        Self {
+            hash_data: true,
+            ..Self::new(io)
+        }
+    }
+
+    /// Get the operations recorded so far.
+    #[must_use]
+    pub fn trace(&self) -> &[IoTraceEntry] {
+        &self.trace
+    }
+
+    /// Take the operations recorded so far, leaving the trace empty.
+    pub fn take_trace(&mut self) -> Vec<IoTraceEntry> {
+        core::mem::take(&mut self.trace)
+    }
+
+    /// Get a reference to the underlying `BlockIo`.
+    #[must_use]
+    pub fn io(&self) -> &Io {
+        &self.io
+    }
+
+    /// Get a mutable reference to the underlying `BlockIo`. Operations
+    /// performed through this reference are not recorded.
+    pub fn io_mut(&mut self) -> &mut Io {
+        &mut self.io
+    }
+
+    /// Consume the `RecordingBlockIo` and return the underlying
+    /// `BlockIo` and the trace.
+    #[must_use]
+    pub fn into_parts(self) -> (Io, Vec<IoTraceEntry>) {
+        (self.io, self.trace)
+    }
+}
+
+impl<Io: BlockIo> BlockIo for RecordingBlockIo<Io> {
+    type Error = Io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.io.block_size()
+    }
+
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.io.physical_block_size()
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.io.optimal_io_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        self.io.num_blocks()
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let num_blocks = buf_num_blocks(self.io.block_size(), dst);
+        self.trace
+            .push(IoTraceEntry::new(IoOp::Read, start_lba, num_blocks));
+        self.io.read_blocks(start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        let num_blocks = buf_num_blocks(self.io.block_size(), src);
+        let mut entry = IoTraceEntry::new(IoOp::Write, start_lba, num_blocks);
+        if self.hash_data {
+            entry.data_crc32 = Some(Crc32::of(src));
+        }
+        self.trace.push(entry);
+        self.io.write_blocks(start_lba, src)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.trace.push(IoTraceEntry::flush());
+        self.io.flush()
+    }
+
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.trace
+            .push(IoTraceEntry::for_range(IoOp::Discard, range));
+        self.io.discard_blocks(range)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.trace
+            .push(IoTraceEntry::for_range(IoOp::WriteZeroes, range));
+        self.io.write_zeroes(range)
+    }
+}
+
+/// Error type used by [`ReplayBlockIo`].
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum ReplayBlockIoError<IoError> {
+    /// An operation did not match the trace.
+    Mismatch {
+        /// Index of the operation in the trace.
+        index: usize,
+
+        /// Operation expected by the trace, or `None` if the trace has
+        /// already ended.
+        expected: Option<IoTraceEntry>,
+
+        /// Operation that was performed.
+        actual: IoTraceEntry,
+    },
+
+    /// [`ReplayBlockIo::finish`] was called before all operations in
+    /// the trace were performed.
+    Incomplete {
+        /// Number of operations in the trace that were not performed.
+        remaining: usize,
+    },
+
+    /// Error from the underlying [`BlockIo`].
+    Io(IoError),
+}
+
+impl<IoError> From<IoError> for ReplayBlockIoError<IoError> {
+    fn from(err: IoError) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl<IoError> Display for ReplayBlockIoError<IoError>
+where
+    IoError: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mismatch {
+                index,
+                expected: Some(expected),
+                actual,
+            } => write!(
+                f,
+                "operation {index} does not match trace: expected \
+                 {expected}, got {actual}"
+            ),
+            Self::Mismatch {
+                index,
+                expected: None,
+                actual,
+            } => write!(
+                f,
+                "operation {index} is past the end of the trace: {actual}"
+            ),
+            Self::Incomplete { remaining } => {
+                write!(
+                    f,
+                    "{remaining} operations in the trace were not performed"
+                )
+            }
+            Self::Io(io) => Display::fmt(io, f),
+        }
+    }
+}
+
+/// [`BlockIo`] wrapper that checks that every operation matches a trace
+/// recorded by [`RecordingBlockIo`].
+///
+/// Each operation is compared with the next entry in the trace before
+/// it is passed to the underlying `BlockIo`. If it does not match,
+/// [`ReplayBlockIoError::Mismatch`] is returned and the operation is
+/// not performed. Data checksums are only compared if the trace entry
+/// has one. Call [`finish`] at the end to check that no operations are
+/// missing.
+///
+/// See [`RecordingBlockIo`] for an example.
+///
+/// Requires the `alloc` feature.
+///
+/// [`finish`]: Self::finish
+#[derive(Debug)]
+pub struct ReplayBlockIo<'trace, Io> {
+    io: Io,
+    trace: &'trace [IoTraceEntry],
+    next_index: usize,
+}
+
+impl<'trace, Io: BlockIo> ReplayBlockIo<'trace, Io> {
+    /// Create a `ReplayBlockIo` that expects the operations in `trace`.
+    pub fn new(io: Io, trace: &'trace [IoTraceEntry]) -> Self {
+        Self {
+            io,
+            trace,
+            next_index: 0,
+        }
+    }
+
+    /// Get a reference to the underlying `BlockIo`.
+    #[must_use]
+    pub fn io(&self) -> &Io {
+        &self.io
+    }
+
+    /// Check that every operation in the trace was performed, and
+    /// return the underlying `BlockIo`.
+    pub fn finish(self) -> Result<Io, ReplayBlockIoError<Io::Error>> {
+        let remaining = self.trace.len() - self.next_index;
+        if remaining == 0 {
+            Ok(self.io)
+        } else {
+            Err(ReplayBlockIoError::Incomplete { remaining })
+        }
+    }
+
+    /// Compare `actual` with the next entry in the trace.
+    fn check(
+        &mut self,
+        mut actual: IoTraceEntry,
+        data: Option<&[u8]>,
+    ) -> Result<(), ReplayBlockIoError<Io::Error>> {
+        let index = self.next_index;
+        let expected = self.trace.get(index).copied();
+        if let (Some(expected), Some(data)) = (expected, data) {
+            if expected.data_crc32.is_some() {
+                actual.data_crc32 = Some(Crc32::of(data));
+            }
+        }
+        if expected != Some(actual) {
+            return Err(ReplayBlockIoError::Mismatch {
+                index,
+                expected,
+                actual,
+            });
+        }
+        self.next_index += 1;
+        Ok(())
+    }
+}
+
+impl<Io: BlockIo> BlockIo for ReplayBlockIo<'_, Io> {
+    type Error = ReplayBlockIoError<Io::Error>;
+
+    fn block_size(&self) -> BlockSize {
+        self.io.block_size()
+    }
+
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.io.physical_block_size()
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.io.optimal_io_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.io.num_blocks()?)
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let num_blocks = buf_num_blocks(self.io.block_size(), dst);
+        self.check(IoTraceEntry::new(IoOp::Read, start_lba, num_blocks), None)?;
+        Ok(self.io.read_blocks(start_lba, dst)?)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        let num_blocks = buf_num_blocks(self.io.block_size(), src);
+        self.check(
+            IoTraceEntry::new(IoOp::Write, start_lba, num_blocks),
+            Some(src),
+        )?;
+        Ok(self.io.write_blocks(start_lba, src)?)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.check(IoTraceEntry::flush(), None)?;
+        Ok(self.io.flush()?)
+    }
+
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.check(IoTraceEntry::for_range(IoOp::Discard, range), None)?;
+        Ok(self.io.discard_blocks(range)?)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.check(IoTraceEntry::for_range(IoOp::WriteZeroes, range), None)?;
+        Ok(self.io.write_zeroes(range)?)
+    }
+}
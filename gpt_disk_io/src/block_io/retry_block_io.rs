@@ -0,0 +1,235 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::BlockIo;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+
+/// Error type used by [`RetryBlockIo`].
+///
+/// Contains the error from every failed attempt, in order.
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RetryBlockIoError<IoError> {
+    errors: Vec<IoError>,
+}
+
+impl<IoError> RetryBlockIoError<IoError> {
+    /// Get the errors from each attempt, in order. This is never
+    /// empty.
+    #[must_use]
+    pub fn errors(&self) -> &[IoError] {
+        &self.errors
+    }
+
+    /// Get the error from the last attempt.
+    #[must_use]
+    pub fn last(&self) -> &IoError {
+        // OK to unwrap: an error is only created after at least one
+        // failed attempt.
+        self.errors.last().unwrap()
+    }
+
+    /// Consume the error and return the errors from each attempt.
+    #[must_use]
+    pub fn into_errors(self) -> Vec<IoError> {
+        self.errors
+    }
+}
+
+impl<IoError> Display for RetryBlockIoError<IoError>
+where
+    IoError: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "failed after {} attempts", self.errors.len())?;
+        for (i, err) in self.errors.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { "; " };
+            write!(f, "{sep}{err}")?;
+        }
+        Ok(())
+    }
+}
+
+/// [`BlockIo`] wrapper that retries failed operations.
+///
+/// Each operation on the underlying `BlockIo` is attempted up to
+/// `max_retries + 1` times. If every attempt fails, a
+/// [`RetryBlockIoError`] containing all of the errors is returned.
+///
+/// An optional backoff function can be set with [`with_backoff`]. It is
+/// called before each retry with the retry number, starting at one.
+///
+/// [`with_backoff`]: Self::with_backoff
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba};
+/// use gpt_disk_io::{BlockIo, BlockIoAdapter, RetryBlockIo};
+///
+/// let mut data = vec![0; 1024];
+/// let mut bio = RetryBlockIo::new(
+///     BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512),
+///     3,
+/// )
+/// .with_backoff(|retry| {
+///     // A real program might sleep here, e.g. for `10ms << retry`.
+///     assert!(retry <= 3);
+/// });
+///
+/// let mut block = vec![0; 512];
+/// bio.read_blocks(Lba(1), &mut block).unwrap();
+///
+/// // Out-of-bounds reads fail every attempt.
+/// let err = bio.read_blocks(Lba(2), &mut block).unwrap_err();
+/// assert_eq!(err.errors().len(), 4);
+/// ```
+#[derive(Debug)]
+pub struct RetryBlockIo<Io, F = fn(u32)> {
+    io: Io,
+    max_retries: u32,
+    backoff: F,
+}
+
+impl<Io: BlockIo> RetryBlockIo<Io> {
+    /// Create a new `RetryBlockIo` with no backoff.
+    pub fn new(io: Io, max_retries: u32) -> Self {
+        Self {
+            io,
+            max_retries,
+            backoff: |_| {},
+        }
+    }
+}
+
+impl<Io, F> RetryBlockIo<Io, F>
+where
+    Io: BlockIo,
+    F: FnMut(u32),
+{
+    /// Set the function to call before each retry. The argument is the
+    /// retry number, starting at one.
+    pub fn with_backoff<G>(self, backoff: G) -> RetryBlockIo<Io, G>
+    where
+        G: FnMut(u32),
+    {
+        RetryBlockIo {
+            io: self.io,
+            max_retries: self.max_retries,
+            backoff,
+        }
+    }
+
+    /// Get the maximum number of retries.
+    #[must_use]
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Get a reference to the underlying `BlockIo`.
+    #[must_use]
+    pub fn io(&self) -> &Io {
+        &self.io
+    }
+
+    /// Get a mutable reference to the underlying `BlockIo`.
+    pub fn io_mut(&mut self) -> &mut Io {
+        &mut self.io
+    }
+
+    /// Consume the `RetryBlockIo` and return the underlying `BlockIo`.
+    #[must_use]
+    pub fn take_io(self) -> Io {
+        self.io
+    }
+
+    fn retry<T, Op>(
+        &mut self,
+        mut op: Op,
+    ) -> Result<T, RetryBlockIoError<Io::Error>>
+    where
+        Op: FnMut(&mut Io) -> Result<T, Io::Error>,
+    {
+        let mut errors = Vec::new();
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                (self.backoff)(attempt);
+            }
+            match op(&mut self.io) {
+                Ok(val) => return Ok(val),
+                Err(err) => errors.push(err),
+            }
+        }
+        Err(RetryBlockIoError { errors })
+    }
+}
+
+impl<Io, F> BlockIo for RetryBlockIo<Io, F>
+where
+    Io: BlockIo,
+    F: FnMut(u32),
+{
+    type Error = RetryBlockIoError<Io::Error>;
+
+    fn block_size(&self) -> BlockSize {
+        self.io.block_size()
+    }
+
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.io.physical_block_size()
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.io.optimal_io_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        self.retry(BlockIo::num_blocks)
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.retry(|io| io.read_blocks(start_lba, dst))
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.retry(|io| io.write_blocks(start_lba, src))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.retry(BlockIo::flush)
+    }
+
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.retry(|io| io.discard_blocks(range))
+    }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.retry(|io| io.write_zeroes(range))
+    }
+}
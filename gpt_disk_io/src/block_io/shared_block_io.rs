@@ -0,0 +1,63 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::fmt::{Debug, Display};
+use gpt_disk_types::{BlockSize, Lba};
+
+/// Trait for block devices that can be read and written through a
+/// shared reference.
+///
+/// This is like [`BlockIo`], except that all methods take `&self`. Types
+/// that also implement [`Sync`] can be accessed from multiple threads at
+/// once, for example by [`copy_blocks_parallel`]. Accesses to
+/// non-overlapping ranges of blocks must not interfere with each other.
+///
+/// With the `std` feature, this is implemented for
+/// `BlockIoAdapter<File>` and `BlockIoAdapter<&File>` on Unix and
+/// Windows, which use positioned IO rather than the shared file
+/// position.
+///
+/// [`BlockIo`]: crate::BlockIo
+/// [`copy_blocks_parallel`]: crate::copy_blocks_parallel
+pub trait SharedBlockIo {
+    /// IO error type.
+    type Error: Debug + Display + Send + Sync + 'static;
+
+    /// Get the [`BlockSize`]. The return value is not allowed to
+    /// change.
+    fn block_size(&self) -> BlockSize;
+
+    /// Get the number of logical blocks in the disk. See
+    /// [`BlockIo::num_blocks`].
+    ///
+    /// [`BlockIo::num_blocks`]: crate::BlockIo::num_blocks
+    fn num_blocks(&self) -> Result<u64, Self::Error>;
+
+    /// Read contiguous blocks from the disk. See
+    /// [`BlockIo::read_blocks`].
+    ///
+    /// [`BlockIo::read_blocks`]: crate::BlockIo::read_blocks
+    fn read_blocks(
+        &self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error>;
+
+    /// Write contiguous blocks to the disk. See
+    /// [`BlockIo::write_blocks`].
+    ///
+    /// [`BlockIo::write_blocks`]: crate::BlockIo::write_blocks
+    fn write_blocks(
+        &self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error>;
+
+    /// Flush any pending writes to the device.
+    fn flush(&self) -> Result<(), Self::Error>;
+}
@@ -6,15 +6,18 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::copy_to_uninit;
 use crate::{BlockIo, BlockIoAdapter};
 use core::fmt::{self, Debug, Display, Formatter};
+use core::mem::MaybeUninit;
 use core::ops::Range;
-use gpt_disk_types::{BlockSize, Lba};
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
 
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
-/// Error type used for `&[u8]` and `&mut [u8]` versions of [`BlockIoAdapter`].
+/// Error type used for byte slice and byte array versions of
+/// [`BlockIoAdapter`].
 ///
 /// If the `std` feature is enabled, this type implements the [`Error`]
 /// trait.
@@ -64,11 +67,11 @@ impl Display for SliceBlockIoError {
 fn buffer_byte_range_opt(
     block_size: BlockSize,
     start_lba: Lba,
-    buf: &[u8],
+    buf_len: usize,
 ) -> Option<Range<usize>> {
     let start_lba = usize::try_from(start_lba).ok()?;
     let start_byte = start_lba.checked_mul(block_size.to_usize()?)?;
-    let end_byte = start_byte.checked_add(buf.len())?;
+    let end_byte = start_byte.checked_add(buf_len)?;
     Some(start_byte..end_byte)
 }
 
@@ -76,9 +79,9 @@ fn buffer_byte_range_opt(
 fn buffer_byte_range(
     block_size: BlockSize,
     start_lba: Lba,
-    buf: &[u8],
+    buf_len: usize,
 ) -> Result<Range<usize>, SliceBlockIoError> {
-    buffer_byte_range_opt(block_size, start_lba, buf)
+    buffer_byte_range_opt(block_size, start_lba, buf_len)
         .ok_or(SliceBlockIoError::Overflow)
 }
 
@@ -103,7 +106,7 @@ fn read_blocks(
     block_size.assert_valid_block_buffer(dst);
 
     let src = storage
-        .get(buffer_byte_range(block_size, start_lba, dst)?)
+        .get(buffer_byte_range(block_size, start_lba, dst.len())?)
         .ok_or(SliceBlockIoError::OutOfBounds {
             start_lba,
             length_in_bytes: dst.len(),
@@ -112,6 +115,24 @@ fn read_blocks(
     Ok(())
 }
 
+#[track_caller]
+fn read_blocks_uninit<'buf>(
+    storage: &[u8],
+    block_size: BlockSize,
+    start_lba: Lba,
+    dst: &'buf mut [MaybeUninit<u8>],
+) -> Result<&'buf mut [u8], SliceBlockIoError> {
+    assert!(block_size.is_multiple_of_block_size(dst.len()));
+
+    let src = storage
+        .get(buffer_byte_range(block_size, start_lba, dst.len())?)
+        .ok_or(SliceBlockIoError::OutOfBounds {
+            start_lba,
+            length_in_bytes: dst.len(),
+        })?;
+    Ok(copy_to_uninit(dst, src))
+}
+
 fn write_blocks(
     storage: &mut [u8],
     block_size: BlockSize,
@@ -121,7 +142,7 @@ fn write_blocks(
     block_size.assert_valid_block_buffer(src);
 
     let dst = storage
-        .get_mut(buffer_byte_range(block_size, start_lba, src)?)
+        .get_mut(buffer_byte_range(block_size, start_lba, src.len())?)
         .ok_or(SliceBlockIoError::OutOfBounds {
             start_lba,
             length_in_bytes: src.len(),
@@ -130,6 +151,29 @@ fn write_blocks(
     Ok(())
 }
 
+fn write_zeroes(
+    storage: &mut [u8],
+    block_size: BlockSize,
+    range: LbaRangeInclusive,
+) -> Result<(), SliceBlockIoError> {
+    let byte_range = range
+        .to_byte_range(block_size)
+        .ok_or(SliceBlockIoError::Overflow)?;
+    let start_byte = usize::try_from(*byte_range.start())
+        .map_err(|_| SliceBlockIoError::Overflow)?;
+    let end_byte = usize::try_from(*byte_range.end())
+        .map_err(|_| SliceBlockIoError::Overflow)?;
+
+    let dst = storage.get_mut(start_byte..=end_byte).ok_or(
+        SliceBlockIoError::OutOfBounds {
+            start_lba: range.start(),
+            length_in_bytes: end_byte - start_byte + 1,
+        },
+    )?;
+    dst.fill(0);
+    Ok(())
+}
+
 impl BlockIo for BlockIoAdapter<&[u8]> {
     type Error = SliceBlockIoError;
 
@@ -137,6 +181,14 @@ impl BlockIo for BlockIoAdapter<&[u8]> {
         self.block_size
     }
 
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.physical_block_size
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.optimal_io_size
+    }
+
     fn num_blocks(&mut self) -> Result<u64, Self::Error> {
         num_blocks(self.storage, self.block_size)
     }
@@ -149,6 +201,14 @@ impl BlockIo for BlockIoAdapter<&[u8]> {
         read_blocks(self.storage, self.block_size, start_lba, dst)
     }
 
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        read_blocks_uninit(self.storage, self.block_size, start_lba, dst)
+    }
+
     fn write_blocks(
         &mut self,
         _start_lba: Lba,
@@ -169,6 +229,14 @@ impl BlockIo for BlockIoAdapter<&mut [u8]> {
         self.block_size
     }
 
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.physical_block_size
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.optimal_io_size
+    }
+
     fn num_blocks(&mut self) -> Result<u64, Self::Error> {
         num_blocks(self.storage, self.block_size)
     }
@@ -181,6 +249,14 @@ impl BlockIo for BlockIoAdapter<&mut [u8]> {
         read_blocks(self.storage, self.block_size, start_lba, dst)
     }
 
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        read_blocks_uninit(self.storage, self.block_size, start_lba, dst)
+    }
+
     fn write_blocks(
         &mut self,
         start_lba: Lba,
@@ -192,6 +268,68 @@ impl BlockIo for BlockIoAdapter<&mut [u8]> {
     fn flush(&mut self) -> Result<(), Self::Error> {
         Ok(())
     }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        write_zeroes(self.storage, self.block_size, range)
+    }
+}
+
+impl<const N: usize> BlockIo for BlockIoAdapter<[u8; N]> {
+    type Error = SliceBlockIoError;
+
+    fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.physical_block_size
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.optimal_io_size
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        num_blocks(&self.storage[..], self.block_size)
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        read_blocks(&self.storage[..], self.block_size, start_lba, dst)
+    }
+
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        read_blocks_uninit(&self.storage[..], self.block_size, start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        write_blocks(&mut self.storage[..], self.block_size, start_lba, src)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        write_zeroes(&mut self.storage[..], self.block_size, range)
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -202,6 +340,14 @@ impl BlockIo for BlockIoAdapter<Vec<u8>> {
         self.block_size
     }
 
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.physical_block_size
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.optimal_io_size
+    }
+
     fn num_blocks(&mut self) -> Result<u64, Self::Error> {
         num_blocks(&self.storage, self.block_size)
     }
@@ -214,6 +360,14 @@ impl BlockIo for BlockIoAdapter<Vec<u8>> {
         read_blocks(&self.storage, self.block_size, start_lba, dst)
     }
 
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        read_blocks_uninit(&self.storage, self.block_size, start_lba, dst)
+    }
+
     fn write_blocks(
         &mut self,
         start_lba: Lba,
@@ -225,4 +379,11 @@ impl BlockIo for BlockIoAdapter<Vec<u8>> {
     fn flush(&mut self) -> Result<(), Self::Error> {
         Ok(())
     }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        write_zeroes(&mut self.storage, self.block_size, range)
+    }
 }
@@ -0,0 +1,183 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{BlockIo, ReadWriteSeek};
+use gpt_disk_types::{BlockSize, Lba};
+use std::io::{self, SeekFrom};
+
+/// [`BlockIo`] that concatenates multiple chunks into one disk.
+///
+/// Disk images are sometimes split into multiple files, for example
+/// `disk.img.000`, `disk.img.001`, and so on. This type presents the
+/// chunks as a single disk, in the order given. Chunks do not need to
+/// be the same size, and a block may span the boundary between two
+/// chunks.
+///
+/// The size of each chunk is determined when the `SplitBlockIo` is
+/// created. Writes cannot extend the disk past the end of the last
+/// chunk.
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba};
+/// use gpt_disk_io::{BlockIo, SplitBlockIo};
+/// use std::io::Cursor;
+///
+/// let mut chunks = [Cursor::new(vec![1; 1024]), Cursor::new(vec![2; 1024])];
+/// let mut bio =
+///     SplitBlockIo::new(chunks.iter_mut().collect(), BlockSize::BS_512)?;
+/// assert_eq!(bio.num_blocks()?, 4);
+///
+/// let mut block = vec![0; 512];
+/// bio.read_blocks(Lba(2), &mut block)?;
+/// assert_eq!(block, [2; 512]);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SplitBlockIo<T> {
+    chunks: Vec<T>,
+    chunk_sizes: Vec<u64>,
+    block_size: BlockSize,
+}
+
+impl<T> SplitBlockIo<T>
+where
+    T: ReadWriteSeek,
+{
+    /// Create a `SplitBlockIo` from `chunks`. The size of each chunk is
+    /// found by seeking to its end.
+    pub fn new(mut chunks: Vec<T>, block_size: BlockSize) -> io::Result<Self> {
+        let chunk_sizes = chunks
+            .iter_mut()
+            .map(|chunk| chunk.seek(SeekFrom::End(0)))
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            chunks,
+            chunk_sizes,
+            block_size,
+        })
+    }
+
+    /// Get the chunks.
+    #[must_use]
+    pub fn chunks(&self) -> &[T] {
+        &self.chunks
+    }
+
+    /// Consume the `SplitBlockIo` and return the chunks.
+    #[must_use]
+    pub fn take_chunks(self) -> Vec<T> {
+        self.chunks
+    }
+
+    fn num_bytes(&self) -> u64 {
+        self.chunk_sizes.iter().sum()
+    }
+
+    /// Get the start offset in bytes of the access at `start_lba` with a
+    /// length of `len` bytes. An error is returned if the access
+    /// extends past the end of the last chunk.
+    fn check_bounds(&self, start_lba: Lba, len: usize) -> io::Result<u64> {
+        let start = start_lba.to_u64().checked_mul(self.block_size.to_u64());
+        let end =
+            start.and_then(|start| start.checked_add(u64::try_from(len).ok()?));
+        match (start, end) {
+            (Some(start), Some(end)) if end <= self.num_bytes() => Ok(start),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "access extends past the end of the last chunk",
+            )),
+        }
+    }
+
+    /// Call `f` for each piece of the byte range starting at `offset`
+    /// with length `len`, split at chunk boundaries. The arguments to
+    /// `f` are the chunk, the offset within the chunk, and the range
+    /// within the buffer.
+    fn for_each_piece<F>(
+        &mut self,
+        mut offset: u64,
+        len: usize,
+        mut f: F,
+    ) -> io::Result<()>
+    where
+        F: FnMut(&mut T, u64, core::ops::Range<usize>) -> io::Result<()>,
+    {
+        let mut buf_pos = 0;
+        let mut chunk_start = 0;
+        for (chunk, chunk_size) in
+            self.chunks.iter_mut().zip(self.chunk_sizes.iter())
+        {
+            if buf_pos == len {
+                break;
+            }
+
+            let chunk_end = chunk_start + chunk_size;
+            if offset < chunk_end {
+                let offset_in_chunk = offset - chunk_start;
+                let piece_len = usize::try_from(chunk_end - offset)
+                    .unwrap_or(usize::MAX)
+                    .min(len - buf_pos);
+                f(chunk, offset_in_chunk, buf_pos..buf_pos + piece_len)?;
+                buf_pos += piece_len;
+                offset += u64::try_from(piece_len).unwrap();
+            }
+            chunk_start = chunk_end;
+        }
+        Ok(())
+    }
+}
+
+impl<T> BlockIo for SplitBlockIo<T>
+where
+    T: ReadWriteSeek,
+{
+    type Error = io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.num_bytes() / self.block_size.to_u64())
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.block_size.assert_valid_block_buffer(dst);
+        let offset = self.check_bounds(start_lba, dst.len())?;
+        self.for_each_piece(offset, dst.len(), |chunk, offset, range| {
+            chunk.seek(SeekFrom::Start(offset))?;
+            chunk.read_exact(&mut dst[range])
+        })
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.block_size.assert_valid_block_buffer(src);
+        let offset = self.check_bounds(start_lba, src.len())?;
+        self.for_each_piece(offset, src.len(), |chunk, offset, range| {
+            chunk.seek(SeekFrom::Start(offset))?;
+            chunk.write_all(&src[range])
+        })
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        for chunk in &mut self.chunks {
+            chunk.flush()?;
+        }
+        Ok(())
+    }
+}
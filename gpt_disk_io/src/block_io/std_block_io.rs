@@ -6,10 +6,27 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{BlockIo, BlockIoAdapter};
-use gpt_disk_types::{BlockSize, Lba};
+use crate::{BlockIo, BlockIoAdapter, SharedBlockIo};
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+use std::borrow::Borrow;
 use std::fs::File;
-use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+
+/// Maximum size of the buffer used by the default implementation of
+/// [`ReadWriteSeek::write_zeroes`].
+const MAX_ZERO_BUF_SIZE: u64 = 1024 * 1024;
+
+/// Get the byte offset and length of `range`.
+fn byte_offset_and_len(
+    block_size: BlockSize,
+    range: LbaRangeInclusive,
+) -> Result<(u64, u64), io::Error> {
+    let offset = range.start().to_u64().checked_mul(block_size.to_u64());
+    let len = range.num_bytes(block_size);
+    offset.zip(len).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "numeric overflow")
+    })
+}
 
 /// Combination trait for types that impl [`Read`], [`Write`], and [`Seek`].
 pub trait ReadWriteSeek: Read + Write + Seek {
@@ -50,12 +67,529 @@ pub trait ReadWriteSeek: Read + Write + Seek {
         self.write_all(src)?;
         Ok(())
     }
+
+    /// Hint that the blocks in `range` are no longer in use.
+    ///
+    /// The default implementation does nothing.
+    fn discard_blocks(
+        &mut self,
+        block_size: BlockSize,
+        range: LbaRangeInclusive,
+    ) -> Result<(), io::Error> {
+        let _ = (block_size, range);
+        Ok(())
+    }
+
+    /// Set all the blocks in `range` to zero.
+    ///
+    /// The default implementation writes zero buffers.
+    fn write_zeroes(
+        &mut self,
+        block_size: BlockSize,
+        range: LbaRangeInclusive,
+    ) -> Result<(), io::Error> {
+        write_zero_buffers(self, block_size, range)
+    }
+}
+
+/// Set all the blocks in `range` to zero by writing zero buffers.
+fn write_zero_buffers<T: Write + Seek + ?Sized>(
+    storage: &mut T,
+    block_size: BlockSize,
+    range: LbaRangeInclusive,
+) -> Result<(), io::Error> {
+    let (offset, mut remaining) = byte_offset_and_len(block_size, range)?;
+
+    // OK to unwrap: the size is at most `MAX_ZERO_BUF_SIZE`.
+    let zeroes =
+        vec![0; usize::try_from(remaining.min(MAX_ZERO_BUF_SIZE)).unwrap()];
+
+    storage.seek(SeekFrom::Start(offset))?;
+    while remaining > 0 {
+        // OK to unwrap: the length is at most `zeroes.len()`.
+        let len = usize::try_from(remaining.min(MAX_ZERO_BUF_SIZE)).unwrap();
+        storage.write_all(&zeroes[..len])?;
+        remaining -= u64::try_from(len).unwrap();
+    }
+    Ok(())
+}
+
+/// Get the byte offset of `lba`.
+pub(crate) fn byte_offset(
+    block_size: BlockSize,
+    lba: Lba,
+) -> Result<u64, io::Error> {
+    lba.to_u64()
+        .checked_mul(block_size.to_u64())
+        .ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "numeric overflow")
+        })
+}
+
+/// Read contiguous blocks from `file` with positioned IO. This avoids a
+/// separate `seek` call, and on Unix it does not change the file
+/// position, so a shared `&File` can be used safely from multiple
+/// places.
+pub(crate) fn read_file_blocks(
+    file: &File,
+    block_size: BlockSize,
+    start_lba: Lba,
+    dst: &mut [u8],
+) -> Result<(), io::Error> {
+    block_size.assert_valid_block_buffer(dst);
+    let offset = byte_offset(block_size, start_lba)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileExt;
+
+        file.read_exact_at(dst, offset)
+    }
+    #[cfg(windows)]
+    {
+        use std::mem;
+        use std::os::windows::fs::FileExt;
+
+        let mut dst = dst;
+        let mut offset = offset;
+        while !dst.is_empty() {
+            match file.seek_read(dst, offset) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    ))
+                }
+                Ok(n) => {
+                    dst = &mut mem::take(&mut dst)[n..];
+                    // OK to unwrap: `n` is at most the buffer length.
+                    offset += u64::try_from(n).unwrap();
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let mut file = file;
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(dst)
+    }
+}
+
+/// Write contiguous blocks to `file` with positioned IO. See
+/// [`read_file_blocks`].
+fn write_file_blocks(
+    file: &File,
+    block_size: BlockSize,
+    start_lba: Lba,
+    src: &[u8],
+) -> Result<(), io::Error> {
+    block_size.assert_valid_block_buffer(src);
+    let offset = byte_offset(block_size, start_lba)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileExt;
+
+        file.write_all_at(src, offset)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::FileExt;
+
+        let mut src = src;
+        let mut offset = offset;
+        while !src.is_empty() {
+            match file.seek_write(src, offset) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ))
+                }
+                Ok(n) => {
+                    src = &src[n..];
+                    // OK to unwrap: `n` is at most the buffer length.
+                    offset += u64::try_from(n).unwrap();
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let mut file = file;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(src)
+    }
+}
+
+/// Discard `range` in `file`. On Linux this uses `BLKDISCARD` for block
+/// devices and punches a hole in regular files. Errors indicating that
+/// discard is not supported are ignored.
+#[cfg_attr(not(target_os = "linux"), allow(clippy::unnecessary_wraps))]
+pub(crate) fn discard_file_blocks(
+    file: &File,
+    block_size: BlockSize,
+    range: LbaRangeInclusive,
+) -> Result<(), io::Error> {
+    #[cfg(target_os = "linux")]
+    {
+        let (offset, len) = byte_offset_and_len(block_size, range)?;
+        linux::apply(file, linux::Op::Discard, offset, len)?;
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (file, block_size, range);
+    Ok(())
+}
+
+/// Zero `range` in `file`. On Linux this uses `BLKZEROOUT` for block
+/// devices and punches a hole in regular files, falling back to
+/// writing zero buffers if that is not supported.
+pub(crate) fn write_file_zeroes(
+    mut file: &File,
+    block_size: BlockSize,
+    range: LbaRangeInclusive,
+) -> Result<(), io::Error> {
+    #[cfg(target_os = "linux")]
+    {
+        let (offset, len) = byte_offset_and_len(block_size, range)?;
+        if linux::apply(file, linux::Op::ZeroOut, offset, len)? {
+            return Ok(());
+        }
+    }
+    write_zero_buffers(&mut file, block_size, range)
+}
+
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+mod linux {
+    use std::fs::File;
+    use std::io;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::io::AsRawFd;
+
+    // From linux/fs.h.
+    const BLKDISCARD: libc::Ioctl = 0x1277;
+    const BLKZEROOUT: libc::Ioctl = 0x127f;
+
+    #[derive(Clone, Copy)]
+    pub(super) enum Op {
+        Discard,
+        ZeroOut,
+    }
+
+    fn invalid_input() -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, "numeric overflow")
+    }
+
+    /// Apply `op` to a byte range of `file`. Returns `Ok(false)` if the
+    /// file does not support the operation.
+    pub(super) fn apply(
+        file: &File,
+        op: Op,
+        offset: u64,
+        len: u64,
+    ) -> Result<bool, io::Error> {
+        let metadata = file.metadata()?;
+        let file_type = metadata.file_type();
+
+        let ret = if file_type.is_block_device() {
+            let request = match op {
+                Op::Discard => BLKDISCARD,
+                Op::ZeroOut => BLKZEROOUT,
+            };
+            let range: [u64; 2] = [offset, len];
+            // SAFETY: both ioctls take a pointer to two `u64` values:
+            // the start and length of the range in bytes.
+            unsafe { libc::ioctl(file.as_raw_fd(), request, range.as_ptr()) }
+        } else if file_type.is_file() {
+            // A hole punched past the end of the file would not extend
+            // it the way a write does, so let the caller fall back to
+            // writing in that case.
+            if matches!(op, Op::ZeroOut)
+                && offset.checked_add(len).ok_or_else(invalid_input)?
+                    > metadata.len()
+            {
+                return Ok(false);
+            }
+
+            let offset =
+                libc::off_t::try_from(offset).map_err(|_| invalid_input())?;
+            let len =
+                libc::off_t::try_from(len).map_err(|_| invalid_input())?;
+            // SAFETY: no memory is passed to `fallocate`. Holes read
+            // back as zeros.
+            unsafe {
+                libc::fallocate(
+                    file.as_raw_fd(),
+                    libc::FALLOC_FL_PUNCH_HOLE | libc::FALLOC_FL_KEEP_SIZE,
+                    offset,
+                    len,
+                )
+            }
+        } else {
+            return Ok(false);
+        };
+
+        if ret == 0 {
+            return Ok(true);
+        }
+        let err = io::Error::last_os_error();
+        match err.raw_os_error() {
+            Some(libc::EOPNOTSUPP | libc::ENOTTY) => Ok(false),
+            _ => Err(err),
+        }
+    }
 }
 
-impl ReadWriteSeek for File {}
-impl ReadWriteSeek for &File {}
+impl ReadWriteSeek for File {
+    fn read_blocks(
+        &mut self,
+        block_size: BlockSize,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), io::Error> {
+        read_file_blocks(self, block_size, start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        block_size: BlockSize,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), io::Error> {
+        write_file_blocks(self, block_size, start_lba, src)
+    }
+
+    fn discard_blocks(
+        &mut self,
+        block_size: BlockSize,
+        range: LbaRangeInclusive,
+    ) -> Result<(), io::Error> {
+        discard_file_blocks(self, block_size, range)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        block_size: BlockSize,
+        range: LbaRangeInclusive,
+    ) -> Result<(), io::Error> {
+        write_file_zeroes(self, block_size, range)
+    }
+}
+
+impl ReadWriteSeek for &File {
+    fn read_blocks(
+        &mut self,
+        block_size: BlockSize,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), io::Error> {
+        read_file_blocks(self, block_size, start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        block_size: BlockSize,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), io::Error> {
+        write_file_blocks(self, block_size, start_lba, src)
+    }
+
+    fn discard_blocks(
+        &mut self,
+        block_size: BlockSize,
+        range: LbaRangeInclusive,
+    ) -> Result<(), io::Error> {
+        discard_file_blocks(self, block_size, range)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        block_size: BlockSize,
+        range: LbaRangeInclusive,
+    ) -> Result<(), io::Error> {
+        write_file_zeroes(self, block_size, range)
+    }
+}
 impl<T> ReadWriteSeek for &mut T where T: Read + Write + Seek {}
 
+/// Owned in-memory storage, e.g. `Cursor<Vec<u8>>` or
+/// `Cursor<Box<[u8]>>`.
+impl<T> ReadWriteSeek for Cursor<T> where Cursor<T>: Read + Write + Seek {}
+
+/// Owned boxed storage, e.g. `Box<dyn ReadWriteSeek>`. All methods are
+/// forwarded, so specialized implementations such as the one for
+/// [`File`] are still used.
+impl<T> ReadWriteSeek for Box<T>
+where
+    T: ReadWriteSeek + ?Sized,
+{
+    fn num_blocks(&mut self, block_size: BlockSize) -> Result<u64, io::Error> {
+        (**self).num_blocks(block_size)
+    }
+
+    fn read_blocks(
+        &mut self,
+        block_size: BlockSize,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), io::Error> {
+        (**self).read_blocks(block_size, start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        block_size: BlockSize,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), io::Error> {
+        (**self).write_blocks(block_size, start_lba, src)
+    }
+
+    fn discard_blocks(
+        &mut self,
+        block_size: BlockSize,
+        range: LbaRangeInclusive,
+    ) -> Result<(), io::Error> {
+        (**self).discard_blocks(block_size, range)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        block_size: BlockSize,
+        range: LbaRangeInclusive,
+    ) -> Result<(), io::Error> {
+        (**self).write_zeroes(block_size, range)
+    }
+}
+
+/// Wrapper for [`Read`] + [`Write`] + [`Seek`] types that tracks the
+/// current position and skips seeks that would not change it.
+///
+/// The default [`ReadWriteSeek`] methods seek before every read and
+/// write. For storage where each seek is a system call (e.g. a pipe-like
+/// device or a network stream that does not support positioned IO),
+/// wrapping it in `SeekCache` means sequential operations only seek
+/// once. Reading N consecutive blocks one at a time takes N + 1 calls
+/// instead of 2N.
+///
+/// `File` already uses positioned IO, so it does not benefit from this
+/// wrapper.
+///
+/// The cached position is cleared if an operation fails or if the
+/// inner value is accessed mutably via [`get_mut`], so the next seek
+/// always goes through in those cases.
+///
+/// [`get_mut`]: Self::get_mut
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba};
+/// use gpt_disk_io::{BlockIo, BlockIoAdapter, SeekCache};
+/// use std::io::Cursor;
+///
+/// let cursor = Cursor::new(vec![0; 512 * 4]);
+/// let mut bio = BlockIoAdapter::new(SeekCache::new(cursor), BlockSize::BS_512);
+///
+/// let mut buf = [0; 512];
+/// for lba in 0..4 {
+///     bio.read_blocks(Lba(lba), &mut buf)?;
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct SeekCache<T> {
+    inner: T,
+    position: Option<u64>,
+}
+
+impl<T> SeekCache<T> {
+    /// Create a new `SeekCache`. The position is initially unknown, so
+    /// the first seek always goes through.
+    #[must_use]
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            position: None,
+        }
+    }
+
+    /// Get a reference to the inner value.
+    #[must_use]
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner value. This clears the
+    /// cached position, since the caller may change it.
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.position = None;
+        &mut self.inner
+    }
+
+    /// Consume the `SeekCache` and return the inner value.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Update the cached position after a read or write of `result`.
+    fn advance(&mut self, result: &Result<usize, io::Error>) {
+        self.position = match result {
+            Ok(n) => self
+                .position
+                .and_then(|pos| pos.checked_add(u64::try_from(*n).ok()?)),
+            Err(_) => None,
+        };
+    }
+}
+
+impl<T: Read> Read for SeekCache<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        let result = self.inner.read(buf);
+        self.advance(&result);
+        result
+    }
+}
+
+impl<T: Write> Write for SeekCache<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        let result = self.inner.write(buf);
+        self.advance(&result);
+        result
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Seek> Seek for SeekCache<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, io::Error> {
+        if let (SeekFrom::Start(target), Some(current)) = (pos, self.position) {
+            if target == current {
+                return Ok(current);
+            }
+        }
+
+        let result = self.inner.seek(pos);
+        self.position = result.as_ref().ok().copied();
+        result
+    }
+}
+
+impl<T: Read + Write + Seek> ReadWriteSeek for SeekCache<T> {}
+
 impl<T> BlockIo for BlockIoAdapter<T>
 where
     T: ReadWriteSeek,
@@ -66,6 +600,14 @@ where
         self.block_size
     }
 
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.physical_block_size
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.optimal_io_size
+    }
+
     fn num_blocks(&mut self) -> Result<u64, Self::Error> {
         self.storage.num_blocks(self.block_size)
     }
@@ -89,6 +631,66 @@ where
     fn flush(&mut self) -> Result<(), Self::Error> {
         self.storage.flush()
     }
+
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.storage.discard_blocks(self.block_size, range)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.storage.write_zeroes(self.block_size, range)
+    }
+}
+
+/// Shared access to a file, e.g. `File`, `&File`, or `Arc<File>`. This
+/// uses positioned IO, so it does not depend on the file position.
+#[cfg(any(unix, windows))]
+impl<T> SharedBlockIo for BlockIoAdapter<T>
+where
+    T: Borrow<File>,
+{
+    type Error = io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    fn num_blocks(&self) -> Result<u64, Self::Error> {
+        let mut file: &File = self.storage.borrow();
+        let num_bytes = file.seek(SeekFrom::End(0))?;
+        Ok(num_bytes / self.block_size.to_u64())
+    }
+
+    fn read_blocks(
+        &self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        read_file_blocks(self.storage.borrow(), self.block_size, start_lba, dst)
+    }
+
+    fn write_blocks(
+        &self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        write_file_blocks(
+            self.storage.borrow(),
+            self.block_size,
+            start_lba,
+            src,
+        )
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        let mut file: &File = self.storage.borrow();
+        file.flush()
+    }
 }
 
 impl BlockIo for BlockIoAdapter<&mut dyn ReadWriteSeek> {
@@ -98,6 +700,14 @@ impl BlockIo for BlockIoAdapter<&mut dyn ReadWriteSeek> {
         self.block_size
     }
 
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.physical_block_size
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.optimal_io_size
+    }
+
     fn num_blocks(&mut self) -> Result<u64, Self::Error> {
         self.storage.num_blocks(self.block_size)
     }
@@ -121,4 +731,18 @@ impl BlockIo for BlockIoAdapter<&mut dyn ReadWriteSeek> {
     fn flush(&mut self) -> Result<(), Self::Error> {
         self.storage.flush()
     }
+
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.storage.discard_blocks(self.block_size, range)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        self.storage.write_zeroes(self.block_size, range)
+    }
 }
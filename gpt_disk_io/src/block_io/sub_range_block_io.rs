@@ -0,0 +1,236 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::BlockIo;
+use core::fmt::{self, Display, Formatter};
+use core::mem::MaybeUninit;
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+
+/// Error type used by [`SubRangeBlockIo`].
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum SubRangeBlockIoError<IoError> {
+    /// A read or write is outside the range. The LBA is relative to
+    /// the start of the range.
+    OutOfBounds {
+        /// Start LBA.
+        start_lba: Lba,
+
+        /// Length in bytes.
+        length_in_bytes: usize,
+    },
+
+    /// Error from the underlying [`BlockIo`].
+    Io(IoError),
+}
+
+impl<IoError> From<IoError> for SubRangeBlockIoError<IoError> {
+    fn from(err: IoError) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl<IoError> Display for SubRangeBlockIoError<IoError>
+where
+    IoError: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds {
+                start_lba,
+                length_in_bytes,
+            } => {
+                write!(
+                    f,
+                    "out of bounds: start_lba={start_lba}, length_in_bytes={length_in_bytes}"
+                )
+            }
+            Self::Io(io) => Display::fmt(io, f),
+        }
+    }
+}
+
+/// [`SubRangeBlockIo`] limited to the blocks of one partition of a
+/// [`Disk`], returned by [`Disk::partition_block_io`].
+///
+/// [`Disk`]: crate::Disk
+/// [`Disk::partition_block_io`]: crate::Disk::partition_block_io
+pub type PartitionBlockIo<'disk, Io> = SubRangeBlockIo<&'disk mut Io>;
+
+/// [`BlockIo`] that exposes a range of blocks of another `BlockIo`.
+///
+/// LBA zero of the `SubRangeBlockIo` is the first block of the range,
+/// and accesses outside the range fail with
+/// [`SubRangeBlockIoError::OutOfBounds`]. This can be used to access
+/// the contents of a single partition as if it were a whole disk, for
+/// example to read a nested partition table. See also
+/// [`Disk::partition_block_io`] and [`Disk::open_partition_as_disk`].
+///
+/// [`Disk::open_partition_as_disk`]: crate::Disk::open_partition_as_disk
+/// [`Disk::partition_block_io`]: crate::Disk::partition_block_io
+#[derive(Debug)]
+pub struct SubRangeBlockIo<Io> {
+    io: Io,
+    range: LbaRangeInclusive,
+}
+
+impl<Io: BlockIo> SubRangeBlockIo<Io> {
+    /// Create a new `SubRangeBlockIo`. The `range` is not checked
+    /// against the size of `io`; accesses past the end of `io` return
+    /// whatever error `io` returns.
+    pub fn new(io: Io, range: LbaRangeInclusive) -> Self {
+        Self { io, range }
+    }
+
+    /// Get the range of blocks in the underlying `BlockIo`.
+    #[must_use]
+    pub fn range(&self) -> LbaRangeInclusive {
+        self.range
+    }
+
+    /// Get a reference to the underlying `BlockIo`.
+    #[must_use]
+    pub fn io(&self) -> &Io {
+        &self.io
+    }
+
+    /// Get a mutable reference to the underlying `BlockIo`.
+    pub fn io_mut(&mut self) -> &mut Io {
+        &mut self.io
+    }
+
+    /// Consume the `SubRangeBlockIo` and return the underlying
+    /// `BlockIo`.
+    #[must_use]
+    pub fn take_io(self) -> Io {
+        self.io
+    }
+
+    /// Convert the relative range starting at `start_lba` with `len`
+    /// bytes to an absolute LBA in the underlying `BlockIo`.
+    fn translate(
+        &self,
+        start_lba: Lba,
+        len: usize,
+    ) -> Result<Lba, SubRangeBlockIoError<Io::Error>> {
+        let out_of_bounds = || SubRangeBlockIoError::OutOfBounds {
+            start_lba,
+            length_in_bytes: len,
+        };
+
+        let num_blocks = u64::try_from(len).map_err(|_| out_of_bounds())?
+            / self.io.block_size().to_u64();
+        let end_lba = start_lba
+            .to_u64()
+            .checked_add(num_blocks)
+            .ok_or_else(out_of_bounds)?;
+        if end_lba > self.range.num_blocks() {
+            return Err(out_of_bounds());
+        }
+
+        Ok(Lba(self.range.start().to_u64() + start_lba.to_u64()))
+    }
+
+    /// Convert a relative LBA range to an absolute range in the
+    /// underlying `BlockIo`.
+    fn translate_range(
+        &self,
+        range: LbaRangeInclusive,
+    ) -> Result<LbaRangeInclusive, SubRangeBlockIoError<Io::Error>> {
+        if range.end().to_u64() >= self.range.num_blocks() {
+            return Err(SubRangeBlockIoError::OutOfBounds {
+                start_lba: range.start(),
+                length_in_bytes: range
+                    .num_bytes(self.io.block_size())
+                    .and_then(|n| usize::try_from(n).ok())
+                    .unwrap_or(usize::MAX),
+            });
+        }
+
+        let start = self.range.start().to_u64();
+        // OK to unwrap: the range is within `self.range`, so the
+        // addition can't overflow and the end is not before the start.
+        Ok(LbaRangeInclusive::new(
+            Lba(start + range.start().to_u64()),
+            Lba(start + range.end().to_u64()),
+        )
+        .unwrap())
+    }
+}
+
+impl<Io: BlockIo> BlockIo for SubRangeBlockIo<Io> {
+    type Error = SubRangeBlockIoError<Io::Error>;
+
+    fn block_size(&self) -> BlockSize {
+        self.io.block_size()
+    }
+
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.io.physical_block_size()
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.io.optimal_io_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.range.num_blocks())
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let lba = self.translate(start_lba, dst.len())?;
+        Ok(self.io.read_blocks(lba, dst)?)
+    }
+
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        let lba = self.translate(start_lba, dst.len())?;
+        Ok(self.io.read_blocks_uninit(lba, dst)?)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        let lba = self.translate(start_lba, src.len())?;
+        Ok(self.io.write_blocks(lba, src)?)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(self.io.flush()?)
+    }
+
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        let range = self.translate_range(range)?;
+        Ok(self.io.discard_blocks(range)?)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        let range = self.translate_range(range)?;
+        Ok(self.io.write_zeroes(range)?)
+    }
+}
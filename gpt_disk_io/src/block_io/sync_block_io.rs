@@ -0,0 +1,109 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! [`BlockIo`] impls for block devices shared behind a lock.
+//!
+//! If a thread panics while holding the lock, the lock is poisoned. The
+//! impls below ignore poisoning and continue to use the underlying
+//! `BlockIo`: each call is a self-contained operation on the device, so
+//! there is no partially-updated state in the wrapper to protect.
+
+use crate::BlockIo;
+use core::mem::MaybeUninit;
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+use std::sync::{
+    Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard,
+    RwLockWriteGuard,
+};
+
+fn lock<T: ?Sized>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn read<T: ?Sized>(m: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    m.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write<T: ?Sized>(m: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    m.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Implement `BlockIo` for a lock type. `$shared` takes the lock for
+/// methods of `BlockIo` that take `&self`, and `$exclusive` takes the
+/// lock for methods that take `&mut self`.
+macro_rules! impl_block_io_for_lock {
+    ($ty:ty, $shared:ident, $exclusive:ident) => {
+        impl<T> BlockIo for $ty
+        where
+            T: BlockIo + ?Sized,
+        {
+            type Error = T::Error;
+
+            fn block_size(&self) -> BlockSize {
+                $shared(self).block_size()
+            }
+
+            fn physical_block_size(&self) -> Option<BlockSize> {
+                $shared(self).physical_block_size()
+            }
+
+            fn optimal_io_size(&self) -> Option<u32> {
+                $shared(self).optimal_io_size()
+            }
+
+            fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+                $exclusive(self).num_blocks()
+            }
+
+            fn read_blocks(
+                &mut self,
+                start_lba: Lba,
+                dst: &mut [u8],
+            ) -> Result<(), Self::Error> {
+                $exclusive(self).read_blocks(start_lba, dst)
+            }
+
+            fn read_blocks_uninit<'buf>(
+                &mut self,
+                start_lba: Lba,
+                dst: &'buf mut [MaybeUninit<u8>],
+            ) -> Result<&'buf mut [u8], Self::Error> {
+                $exclusive(self).read_blocks_uninit(start_lba, dst)
+            }
+
+            fn write_blocks(
+                &mut self,
+                start_lba: Lba,
+                src: &[u8],
+            ) -> Result<(), Self::Error> {
+                $exclusive(self).write_blocks(start_lba, src)
+            }
+
+            fn flush(&mut self) -> Result<(), Self::Error> {
+                $exclusive(self).flush()
+            }
+
+            fn discard_blocks(
+                &mut self,
+                range: LbaRangeInclusive,
+            ) -> Result<(), Self::Error> {
+                $exclusive(self).discard_blocks(range)
+            }
+
+            fn write_zeroes(
+                &mut self,
+                range: LbaRangeInclusive,
+            ) -> Result<(), Self::Error> {
+                $exclusive(self).write_zeroes(range)
+            }
+        }
+    };
+}
+
+impl_block_io_for_lock!(Arc<Mutex<T>>, lock, lock);
+impl_block_io_for_lock!(Arc<RwLock<T>>, read, write);
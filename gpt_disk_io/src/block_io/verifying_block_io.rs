@@ -0,0 +1,227 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::BlockIo;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::mem::MaybeUninit;
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+
+/// Error type used by [`VerifyingBlockIo`].
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum VerifyingBlockIoError<IoError> {
+    /// The verification buffer is smaller than one block.
+    BufferTooSmall,
+
+    /// A block read back after writing did not match the data that was
+    /// written.
+    VerificationFailed {
+        /// The first block that did not match.
+        lba: Lba,
+    },
+
+    /// Error from the underlying [`BlockIo`].
+    Io(IoError),
+}
+
+impl<IoError> From<IoError> for VerifyingBlockIoError<IoError> {
+    fn from(err: IoError) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl<IoError> Display for VerifyingBlockIoError<IoError>
+where
+    IoError: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BufferTooSmall => {
+                f.write_str("verification buffer is too small")
+            }
+            Self::VerificationFailed { lba } => {
+                write!(f, "verification failed at LBA {lba}")
+            }
+            Self::Io(io) => Display::fmt(io, f),
+        }
+    }
+}
+
+/// [`BlockIo`] wrapper that verifies every write.
+///
+/// After each call to [`write_blocks`], the underlying `BlockIo` is
+/// flushed and the written blocks are read back and compared to the
+/// source data. If any block differs,
+/// [`VerifyingBlockIoError::VerificationFailed`] is returned. This
+/// catches media that silently drops writes, at the cost of a flush
+/// and a read for every write.
+///
+/// Note that if the underlying storage is cached (for example a file
+/// opened without direct IO), the read may be served from the cache
+/// rather than the media.
+///
+/// Blocks are read back into `verify_buf`, which must be at least one
+/// block in size. A larger buffer allows more blocks to be verified
+/// with each read.
+///
+/// [`write_blocks`]: BlockIo::write_blocks
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba};
+/// use gpt_disk_io::{
+///     BlockIo, BlockIoAdapter, SliceBlockIoError, VerifyingBlockIo,
+///     VerifyingBlockIoError,
+/// };
+///
+/// let mut data = vec![0; 1024];
+/// let mut verify_buf = vec![0; 512];
+/// let mut bio = VerifyingBlockIo::new(
+///     BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512),
+///     &mut verify_buf,
+/// );
+/// bio.write_blocks(Lba(1), &[1; 512])?;
+/// # Ok::<(), VerifyingBlockIoError<SliceBlockIoError>>(())
+/// ```
+#[derive(Debug)]
+pub struct VerifyingBlockIo<'buf, Io> {
+    io: Io,
+    verify_buf: &'buf mut [u8],
+}
+
+impl<'buf, Io: BlockIo> VerifyingBlockIo<'buf, Io> {
+    /// Create a new `VerifyingBlockIo`.
+    pub fn new(io: Io, verify_buf: &'buf mut [u8]) -> Self {
+        Self { io, verify_buf }
+    }
+
+    /// Get a reference to the underlying `BlockIo`.
+    #[must_use]
+    pub fn io(&self) -> &Io {
+        &self.io
+    }
+
+    /// Get a mutable reference to the underlying `BlockIo`.
+    pub fn io_mut(&mut self) -> &mut Io {
+        &mut self.io
+    }
+
+    /// Consume the `VerifyingBlockIo` and return the underlying
+    /// `BlockIo`.
+    #[must_use]
+    pub fn take_io(self) -> Io {
+        self.io
+    }
+
+    /// Get the number of bytes that can be verified with each read.
+    fn verify_len(&self) -> Result<usize, VerifyingBlockIoError<Io::Error>> {
+        let block_size = self
+            .io
+            .block_size()
+            .to_usize()
+            .ok_or(VerifyingBlockIoError::BufferTooSmall)?;
+        let verify_len =
+            self.verify_buf.len() - self.verify_buf.len() % block_size;
+        if verify_len == 0 {
+            Err(VerifyingBlockIoError::BufferTooSmall)
+        } else {
+            Ok(verify_len)
+        }
+    }
+}
+
+impl<Io: BlockIo> BlockIo for VerifyingBlockIo<'_, Io> {
+    type Error = VerifyingBlockIoError<Io::Error>;
+
+    fn block_size(&self) -> BlockSize {
+        self.io.block_size()
+    }
+
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.io.physical_block_size()
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.io.optimal_io_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.io.num_blocks()?)
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        Ok(self.io.read_blocks(start_lba, dst)?)
+    }
+
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        Ok(self.io.read_blocks_uninit(start_lba, dst)?)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        // Check the buffer size before writing anything, so that
+        // unverified writes are never made.
+        let verify_len = self.verify_len()?;
+        // OK to unwrap: `verify_len` checks that the block size fits in
+        // a `usize`.
+        let block_size = self.io.block_size().to_usize().unwrap();
+
+        self.io.write_blocks(start_lba, src)?;
+        self.io.flush()?;
+
+        let mut lba = start_lba.to_u64();
+        for expected in src.chunks(verify_len) {
+            let actual = &mut self.verify_buf[..expected.len()];
+            self.io.read_blocks(Lba(lba), actual)?;
+
+            if actual != expected {
+                // Find the first block that differs.
+                let bad_block = actual
+                    .chunks(block_size)
+                    .zip(expected.chunks(block_size))
+                    .position(|(a, e)| a != e)
+                    .unwrap_or(0);
+                return Err(VerifyingBlockIoError::VerificationFailed {
+                    lba: Lba(lba + u64::try_from(bad_block).unwrap()),
+                });
+            }
+
+            lba += u64::try_from(expected.len() / block_size).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(self.io.flush()?)
+    }
+
+    fn discard_blocks(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        Ok(self.io.discard_blocks(range)?)
+    }
+}
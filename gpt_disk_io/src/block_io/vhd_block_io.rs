@@ -0,0 +1,307 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{BlockIo, ReadWriteSeek};
+use gpt_disk_types::{BlockSize, Guid, Lba};
+use std::io::{self, SeekFrom};
+use std::time::{Duration, SystemTime};
+
+/// Size in bytes of the footer at the end of a VHD file.
+const FOOTER_SIZE: u64 = 512;
+
+/// Magic value at the start of the footer.
+const COOKIE: &[u8; 8] = b"conectix";
+
+/// Byte offset of the checksum field within the footer.
+const CHECKSUM_OFFSET: usize = 64;
+
+/// Disk type field value for a fixed-size disk.
+const DISK_TYPE_FIXED: u32 = 2;
+
+/// VHD timestamps count seconds since 2000-01-01 00:00:00 UTC.
+const VHD_EPOCH_UNIX_SECONDS: u64 = 946_684_800;
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Calculate the footer checksum: the one's complement of the sum of
+/// all bytes in the footer, excluding the checksum field itself.
+fn footer_checksum(footer: &[u8; 512]) -> u32 {
+    let sum = footer
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !(CHECKSUM_OFFSET..CHECKSUM_OFFSET + 4).contains(i))
+        .fold(0u32, |sum, (_, byte)| sum.wrapping_add(u32::from(*byte)));
+    !sum
+}
+
+/// Calculate the CHS geometry field using the algorithm in the VHD
+/// specification.
+fn disk_geometry(num_bytes: u64) -> [u8; 4] {
+    let total_sectors = (num_bytes / 512).min(65535 * 16 * 255);
+
+    let (sectors_per_track, heads, cylinder_times_heads) =
+        if total_sectors >= 65535 * 16 * 63 {
+            (255, 16, total_sectors / 255)
+        } else {
+            let mut sectors_per_track = 17;
+            let mut cylinder_times_heads = total_sectors / sectors_per_track;
+            let mut heads = ((cylinder_times_heads + 1023) / 1024).max(4);
+
+            if cylinder_times_heads >= heads * 1024 || heads > 16 {
+                sectors_per_track = 31;
+                heads = 16;
+                cylinder_times_heads = total_sectors / sectors_per_track;
+            }
+
+            if cylinder_times_heads >= heads * 1024 {
+                sectors_per_track = 63;
+                heads = 16;
+                cylinder_times_heads = total_sectors / sectors_per_track;
+            }
+
+            (sectors_per_track, heads, cylinder_times_heads)
+        };
+
+    // The values are bounded by the calculation above, so these
+    // conversions cannot fail.
+    let cylinders = u16::try_from(cylinder_times_heads / heads).unwrap();
+    let [c0, c1] = cylinders.to_be_bytes();
+    [
+        c0,
+        c1,
+        u8::try_from(heads).unwrap(),
+        u8::try_from(sectors_per_track).unwrap(),
+    ]
+}
+
+/// Create a fixed-disk footer for a disk of `num_bytes` bytes.
+fn create_footer(num_bytes: u64, unique_id: Guid) -> [u8; 512] {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+        .saturating_sub(VHD_EPOCH_UNIX_SECONDS);
+    let timestamp = u32::try_from(timestamp).unwrap_or(u32::MAX);
+
+    let mut footer = [0; 512];
+    footer[0..8].copy_from_slice(COOKIE);
+    // Features: reserved bit, must always be set.
+    footer[8..12].copy_from_slice(&2u32.to_be_bytes());
+    // File format version 1.0.
+    footer[12..16].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    // Data offset: unused for fixed disks.
+    footer[16..24].copy_from_slice(&u64::MAX.to_be_bytes());
+    footer[24..28].copy_from_slice(&timestamp.to_be_bytes());
+    // Creator application and version.
+    footer[28..32].copy_from_slice(b"gdio");
+    footer[32..36].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    // Creator host OS: "Wi2k" is used for compatibility with Hyper-V.
+    footer[36..40].copy_from_slice(b"Wi2k");
+    // Original size and current size.
+    footer[40..48].copy_from_slice(&num_bytes.to_be_bytes());
+    footer[48..56].copy_from_slice(&num_bytes.to_be_bytes());
+    footer[56..60].copy_from_slice(&disk_geometry(num_bytes));
+    footer[60..64].copy_from_slice(&DISK_TYPE_FIXED.to_be_bytes());
+    footer[68..84].copy_from_slice(&unique_id.to_bytes());
+
+    let checksum = footer_checksum(&footer);
+    footer[CHECKSUM_OFFSET..CHECKSUM_OFFSET + 4]
+        .copy_from_slice(&checksum.to_be_bytes());
+    footer
+}
+
+/// Check that `footer` is a valid fixed-disk footer.
+fn validate_footer(footer: &[u8; 512]) -> Result<(), io::Error> {
+    if &footer[0..8] != COOKIE {
+        return Err(invalid_data("VHD footer cookie not found"));
+    }
+
+    let checksum = u32::from_be_bytes(
+        footer[CHECKSUM_OFFSET..CHECKSUM_OFFSET + 4]
+            .try_into()
+            .unwrap(),
+    );
+    if checksum != footer_checksum(footer) {
+        return Err(invalid_data("VHD footer checksum is invalid"));
+    }
+
+    let disk_type = u32::from_be_bytes(footer[60..64].try_into().unwrap());
+    if disk_type != DISK_TYPE_FIXED {
+        return Err(invalid_data("VHD is not a fixed-size disk"));
+    }
+
+    Ok(())
+}
+
+/// [`BlockIo`] for fixed-size VHD images.
+///
+/// A fixed VHD is a raw disk image followed by a 512-byte footer. This
+/// type excludes the footer from [`num_blocks`], and rejects reads and
+/// writes that would overlap it.
+///
+/// Use [`open`] for an existing image, or [`create`] to write the
+/// footer for a new image.
+///
+/// [`create`]: Self::create
+/// [`num_blocks`]: BlockIo::num_blocks
+/// [`open`]: Self::open
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{guid, BlockSize};
+/// use gpt_disk_io::{BlockIo, Disk, FixedVhdBlockIo};
+/// use std::io::Cursor;
+///
+/// let mut image = Cursor::new(Vec::new());
+///
+/// // Create a new 4MiB fixed VHD.
+/// let bio = FixedVhdBlockIo::create(
+///     &mut image,
+///     BlockSize::BS_512,
+///     4 * 1024 * 1024,
+///     guid!("a8f62c3b-d95e-4a47-b6d9-70a8e52a1d3d"),
+/// )?;
+/// let mut disk = Disk::new(bio)?;
+/// let mut block_buf = vec![0; 512];
+/// disk.write_protective_mbr(&mut block_buf)?;
+/// drop(disk);
+///
+/// // The footer is at the end of the file.
+/// assert_eq!(image.get_ref().len(), 4 * 1024 * 1024 + 512);
+///
+/// // Open the image again.
+/// let mut bio = FixedVhdBlockIo::open(&mut image, BlockSize::BS_512)?;
+/// assert_eq!(bio.num_blocks()?, 8192);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct FixedVhdBlockIo<T> {
+    storage: T,
+    block_size: BlockSize,
+    num_bytes: u64,
+}
+
+impl<T> FixedVhdBlockIo<T>
+where
+    T: ReadWriteSeek,
+{
+    /// Open an existing fixed VHD. An error is returned if the footer
+    /// is missing or invalid, or if the image is not a fixed VHD.
+    pub fn open(mut storage: T, block_size: BlockSize) -> io::Result<Self> {
+        let file_size = storage.seek(SeekFrom::End(0))?;
+        let num_bytes = file_size
+            .checked_sub(FOOTER_SIZE)
+            .ok_or_else(|| invalid_data("file is too small to be a VHD"))?;
+
+        let mut footer = [0; 512];
+        storage.seek(SeekFrom::Start(num_bytes))?;
+        storage.read_exact(&mut footer)?;
+        validate_footer(&footer)?;
+
+        Ok(Self {
+            storage,
+            block_size,
+            num_bytes,
+        })
+    }
+
+    /// Create a new fixed VHD with `num_bytes` of disk data. The footer
+    /// is written immediately after the disk data; any existing data
+    /// before the footer is left as-is.
+    ///
+    /// `num_bytes` must be a multiple of 512, as required by the VHD
+    /// format. The `unique_id` is stored in the footer to identify the
+    /// image; it is unrelated to the GPT disk GUID.
+    pub fn create(
+        mut storage: T,
+        block_size: BlockSize,
+        num_bytes: u64,
+        unique_id: Guid,
+    ) -> io::Result<Self> {
+        if num_bytes % FOOTER_SIZE != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "VHD size must be a multiple of 512",
+            ));
+        }
+
+        storage.seek(SeekFrom::Start(num_bytes))?;
+        storage.write_all(&create_footer(num_bytes, unique_id))?;
+
+        Ok(Self {
+            storage,
+            block_size,
+            num_bytes,
+        })
+    }
+
+    /// Consume the `FixedVhdBlockIo` and return the underlying storage.
+    #[must_use]
+    pub fn take_storage(self) -> T {
+        self.storage
+    }
+
+    /// Check that the byte range for `start_lba` and `buf` does not
+    /// extend into the footer.
+    fn check_bounds(&self, start_lba: Lba, buf: &[u8]) -> io::Result<()> {
+        let end = start_lba
+            .to_u64()
+            .checked_mul(self.block_size.to_u64())
+            .and_then(|start| {
+                start.checked_add(u64::try_from(buf.len()).ok()?)
+            });
+        if end.map_or(true, |end| end > self.num_bytes) {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "access extends past the end of the VHD data",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T> BlockIo for FixedVhdBlockIo<T>
+where
+    T: ReadWriteSeek,
+{
+    type Error = io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.num_bytes / self.block_size.to_u64())
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.check_bounds(start_lba, dst)?;
+        self.storage.read_blocks(self.block_size, start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.check_bounds(start_lba, src)?;
+        self.storage.write_blocks(self.block_size, start_lba, src)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.storage.flush()
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::BlockIo;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::mem::MaybeUninit;
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+
+/// Error type used by [`WriteProtectedBlockIo`].
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum WriteProtectedBlockIoError<IoError> {
+    /// An operation that modifies the storage was attempted.
+    WriteProtected,
+
+    /// Error from the underlying [`BlockIo`].
+    Io(IoError),
+}
+
+impl<IoError> From<IoError> for WriteProtectedBlockIoError<IoError> {
+    fn from(err: IoError) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl<IoError> Display for WriteProtectedBlockIoError<IoError>
+where
+    IoError: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WriteProtected => f.write_str("storage is write protected"),
+            Self::Io(io) => Display::fmt(io, f),
+        }
+    }
+}
+
+/// [`BlockIo`] wrapper that rejects all modifications.
+///
+/// Reads are passed through to the underlying `BlockIo`.
+/// [`write_blocks`], [`write_zeroes`], and [`discard_blocks`] always
+/// fail with [`WriteProtectedBlockIoError::WriteProtected`] without
+/// calling the underlying `BlockIo`. Since nothing is ever written
+/// through the wrapper, [`flush`] has nothing to do and always
+/// succeeds without flushing the underlying `BlockIo`.
+///
+/// Unlike [`ReadOnlyBlockIo`], which adapts a `Read + Seek` source,
+/// this can wrap any `BlockIo`, including a file opened for writing or
+/// another wrapper such as [`SubRangeBlockIo`].
+///
+/// [`discard_blocks`]: BlockIo::discard_blocks
+/// [`flush`]: BlockIo::flush
+/// [`write_blocks`]: BlockIo::write_blocks
+/// [`write_zeroes`]: BlockIo::write_zeroes
+/// [`ReadOnlyBlockIo`]: crate::ReadOnlyBlockIo
+/// [`SubRangeBlockIo`]: crate::SubRangeBlockIo
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba};
+/// use gpt_disk_io::{
+///     BlockIo, BlockIoAdapter, WriteProtectedBlockIo, WriteProtectedBlockIoError,
+/// };
+///
+/// let mut data = vec![1; 1024];
+/// let mut bio = WriteProtectedBlockIo::new(BlockIoAdapter::new(
+///     data.as_mut_slice(),
+///     BlockSize::BS_512,
+/// ));
+///
+/// let mut block = [0; 512];
+/// bio.read_blocks(Lba(1), &mut block).unwrap();
+/// assert_eq!(block, [1; 512]);
+///
+/// assert_eq!(
+///     bio.write_blocks(Lba(0), &[0; 512]),
+///     Err(WriteProtectedBlockIoError::WriteProtected)
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WriteProtectedBlockIo<Io> {
+    io: Io,
+}
+
+impl<Io: BlockIo> WriteProtectedBlockIo<Io> {
+    /// Create a new `WriteProtectedBlockIo`.
+    pub fn new(io: Io) -> Self {
+        Self { io }
+    }
+
+    /// Get a reference to the underlying `BlockIo`.
+    #[must_use]
+    pub fn io(&self) -> &Io {
+        &self.io
+    }
+
+    /// Consume the `WriteProtectedBlockIo` and return the underlying
+    /// `BlockIo`.
+    #[must_use]
+    pub fn take_io(self) -> Io {
+        self.io
+    }
+}
+
+impl<Io: BlockIo> BlockIo for WriteProtectedBlockIo<Io> {
+    type Error = WriteProtectedBlockIoError<Io::Error>;
+
+    fn block_size(&self) -> BlockSize {
+        self.io.block_size()
+    }
+
+    fn physical_block_size(&self) -> Option<BlockSize> {
+        self.io.physical_block_size()
+    }
+
+    fn optimal_io_size(&self) -> Option<u32> {
+        self.io.optimal_io_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.io.num_blocks()?)
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        Ok(self.io.read_blocks(start_lba, dst)?)
+    }
+
+    fn read_blocks_uninit<'buf>(
+        &mut self,
+        start_lba: Lba,
+        dst: &'buf mut [MaybeUninit<u8>],
+    ) -> Result<&'buf mut [u8], Self::Error> {
+        Ok(self.io.read_blocks_uninit(start_lba, dst)?)
+    }
+
+    fn write_blocks(
+        &mut self,
+        _start_lba: Lba,
+        _src: &[u8],
+    ) -> Result<(), Self::Error> {
+        Err(WriteProtectedBlockIoError::WriteProtected)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn discard_blocks(
+        &mut self,
+        _range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        Err(WriteProtectedBlockIoError::WriteProtected)
+    }
+
+    fn write_zeroes(
+        &mut self,
+        _range: LbaRangeInclusive,
+    ) -> Result<(), Self::Error> {
+        Err(WriteProtectedBlockIoError::WriteProtected)
+    }
+}
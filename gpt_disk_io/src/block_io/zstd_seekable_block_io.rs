@@ -0,0 +1,354 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::BlockIo;
+use gpt_disk_types::{BlockSize, Lba};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Take};
+use zstd::stream::{raw, zio};
+
+/// Magic value of the skippable frame that holds the seek table.
+const SKIPPABLE_MAGIC: u32 = 0x184d_2a5e;
+
+/// Magic value at the end of the seek table.
+const SEEKABLE_MAGIC: u32 = 0x8f92_eab1;
+
+/// Size in bytes of the skippable frame header (magic and frame size).
+const SKIPPABLE_HEADER_SIZE: u64 = 8;
+
+/// Size in bytes of the seek table footer (number of frames,
+/// descriptor, and magic).
+const FOOTER_SIZE: u64 = 9;
+
+/// Bit in the seek table descriptor indicating that each entry has a
+/// checksum.
+const DESCRIPTOR_CHECKSUM_FLAG: u8 = 0x80;
+
+/// Reserved bits in the seek table descriptor, which must be zero.
+const DESCRIPTOR_RESERVED_BITS: u8 = 0x7c;
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn read_u32_le(bytes: &[u8]) -> u32 {
+    // OK to unwrap since callers always pass at least four bytes.
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+/// Location of one zstd frame in both the compressed and decompressed
+/// data.
+struct Frame {
+    compressed_offset: u64,
+    compressed_size: u64,
+    decompressed_offset: u64,
+    decompressed_size: u64,
+}
+
+impl Frame {
+    fn decompressed_end(&self) -> u64 {
+        self.decompressed_offset + self.decompressed_size
+    }
+}
+
+type FrameDecoder<R> = zio::Reader<BufReader<Take<R>>, raw::Decoder<'static>>;
+
+enum Source<R> {
+    /// No frame is being decompressed.
+    Idle(R),
+
+    /// Frame `index` is being decompressed, and `position` bytes of its
+    /// decompressed data have been read so far.
+    Frame {
+        index: usize,
+        position: u64,
+        decoder: FrameDecoder<R>,
+    },
+}
+
+impl<R> Source<R> {
+    fn into_inner(self) -> R {
+        match self {
+            Self::Idle(reader) => reader,
+            Self::Frame { decoder, .. } => {
+                decoder.into_inner().into_inner().into_inner()
+            }
+        }
+    }
+}
+
+/// Parse the seek table at the end of `reader`.
+fn read_seek_table<R: Read + Seek>(
+    reader: &mut R,
+) -> Result<Vec<Frame>, io::Error> {
+    let stream_len = reader.seek(SeekFrom::End(0))?;
+    if stream_len < SKIPPABLE_HEADER_SIZE + FOOTER_SIZE {
+        return Err(invalid_data("missing zstd seek table"));
+    }
+
+    let mut footer = [0; 9];
+    reader.seek(SeekFrom::Start(stream_len - FOOTER_SIZE))?;
+    reader.read_exact(&mut footer)?;
+    if read_u32_le(&footer[5..]) != SEEKABLE_MAGIC {
+        return Err(invalid_data("missing zstd seek table"));
+    }
+    let descriptor = footer[4];
+    if descriptor & DESCRIPTOR_RESERVED_BITS != 0 {
+        return Err(invalid_data("invalid zstd seek table descriptor"));
+    }
+    let entry_size: u64 = if descriptor & DESCRIPTOR_CHECKSUM_FLAG != 0 {
+        12
+    } else {
+        8
+    };
+
+    // Check that the table fits in the stream before allocating it.
+    let num_frames = u64::from(read_u32_le(&footer));
+    let table_size = num_frames * entry_size;
+    let seek_table_frame_size =
+        SKIPPABLE_HEADER_SIZE + table_size + FOOTER_SIZE;
+    if seek_table_frame_size > stream_len {
+        return Err(invalid_data("zstd seek table is larger than the stream"));
+    }
+
+    let mut header = [0; 8];
+    reader.seek(SeekFrom::Start(stream_len - seek_table_frame_size))?;
+    reader.read_exact(&mut header)?;
+    if read_u32_le(&header) != SKIPPABLE_MAGIC
+        || u64::from(read_u32_le(&header[4..])) != table_size + FOOTER_SIZE
+    {
+        return Err(invalid_data("invalid zstd seek table frame header"));
+    }
+
+    let mut table = vec![
+        0;
+        usize::try_from(table_size).map_err(|_| {
+            invalid_data("zstd seek table is too large")
+        })?
+    ];
+    reader.read_exact(&mut table)?;
+
+    let compressed_len = stream_len - seek_table_frame_size;
+    let mut compressed_offset = 0;
+    let mut decompressed_offset: u64 = 0;
+    let mut frames = Vec::new();
+    // OK to unwrap since the entry size is either 8 or 12.
+    for entry in table.chunks_exact(usize::try_from(entry_size).unwrap()) {
+        let frame = Frame {
+            compressed_offset,
+            compressed_size: u64::from(read_u32_le(entry)),
+            decompressed_offset,
+            decompressed_size: u64::from(read_u32_le(&entry[4..])),
+        };
+        compressed_offset += frame.compressed_size;
+        if compressed_offset > compressed_len {
+            return Err(invalid_data(
+                "zstd seek table frames are larger than the stream",
+            ));
+        }
+        decompressed_offset = frame.decompressed_end();
+        frames.push(frame);
+    }
+
+    Ok(frames)
+}
+
+/// Read-only [`BlockIo`] backed by a disk image in the zstd [seekable
+/// format].
+///
+/// The seekable format splits the data into independently compressed
+/// zstd frames and appends a seek table that records the size of each
+/// frame. A read only has to decompress the frames that overlap it, so
+/// unlike [`CompressedBlockIo`], reading the GPT structures at the end
+/// of the disk does not require decompressing the whole image. Images
+/// in this format can be created with the `zstd_seekable` tool in the
+/// zstd repository, or by any library that implements the format.
+///
+/// The decompressor for the current frame is kept between reads, so
+/// sequential reads within a frame are cheap. A read before the current
+/// position within a frame starts decompressing that frame again.
+///
+/// The optional per-frame checksums in the seek table are not
+/// verified; use zstd's own frame checksums to detect corruption.
+/// Writing is not supported; [`BlockIo::write_blocks`] always fails
+/// with [`io::ErrorKind::Unsupported`].
+///
+/// There is no equivalent seekable format for gzip. Gzip images can be
+/// read with [`CompressedBlockIo`].
+///
+/// Requires the `zstd` feature.
+///
+/// [seekable format]: https://github.com/facebook/zstd/blob/dev/contrib/seekable_format/zstd_seekable_compression_format.md
+/// [`CompressedBlockIo`]: crate::CompressedBlockIo
+pub struct ZstdSeekableBlockIo<R> {
+    source: Option<Source<R>>,
+    frames: Vec<Frame>,
+    block_size: BlockSize,
+}
+
+impl<R: Read + Seek> ZstdSeekableBlockIo<R> {
+    /// Create a new `ZstdSeekableBlockIo` by reading the seek table at
+    /// the end of `reader`.
+    ///
+    /// Returns [`io::ErrorKind::InvalidData`] if the seek table is
+    /// missing or invalid.
+    pub fn open(
+        mut reader: R,
+        block_size: BlockSize,
+    ) -> Result<Self, io::Error> {
+        let frames = read_seek_table(&mut reader)?;
+        Ok(Self {
+            source: Some(Source::Idle(reader)),
+            frames,
+            block_size,
+        })
+    }
+
+    /// Number of bytes of decompressed data.
+    fn num_bytes(&self) -> u64 {
+        self.frames.last().map_or(0, Frame::decompressed_end)
+    }
+
+    /// Take the reader out of `source`, discarding any decoder.
+    fn take_reader(&mut self) -> R {
+        // OK to unwrap since `source` is only `None` while a new
+        // decoder is being set up.
+        self.source.take().unwrap().into_inner()
+    }
+
+    /// Get a decoder for frame `index`, positioned `offset` bytes into
+    /// its decompressed data.
+    fn decoder_at(
+        &mut self,
+        index: usize,
+        offset: u64,
+    ) -> Result<&mut FrameDecoder<R>, io::Error> {
+        let reusable = matches!(
+            &self.source,
+            Some(Source::Frame { index: current, position, .. })
+                if *current == index && *position <= offset
+        );
+        if !reusable {
+            let operation = raw::Decoder::new()?;
+            let mut reader = self.take_reader();
+            let frame = &self.frames[index];
+            if let Err(err) =
+                reader.seek(SeekFrom::Start(frame.compressed_offset))
+            {
+                self.source = Some(Source::Idle(reader));
+                return Err(err);
+            }
+            let mut decoder = zio::Reader::new(
+                BufReader::new(reader.take(frame.compressed_size)),
+                operation,
+            );
+            decoder.set_single_frame();
+            self.source = Some(Source::Frame {
+                index,
+                position: 0,
+                decoder,
+            });
+        }
+
+        let Some(Source::Frame {
+            position, decoder, ..
+        }) = &mut self.source
+        else {
+            unreachable!("decoder was set above");
+        };
+        let to_skip = offset - *position;
+        let skipped =
+            io::copy(&mut decoder.by_ref().take(to_skip), &mut io::sink())?;
+        *position += skipped;
+        if skipped != to_skip {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        Ok(decoder)
+    }
+
+    fn read_at(
+        &mut self,
+        mut offset: u64,
+        mut dst: &mut [u8],
+    ) -> Result<(), io::Error> {
+        while !dst.is_empty() {
+            let index = self
+                .frames
+                .partition_point(|frame| frame.decompressed_end() <= offset);
+            let frame =
+                self.frames.get(index).ok_or(io::ErrorKind::UnexpectedEof)?;
+            let offset_in_frame = offset - frame.decompressed_offset;
+            let len =
+                usize::try_from(frame.decompressed_size - offset_in_frame)
+                    .unwrap_or(usize::MAX)
+                    .min(dst.len());
+
+            let (chunk, rest) = dst.split_at_mut(len);
+            self.decoder_at(index, offset_in_frame)?.read_exact(chunk)?;
+            if let Some(Source::Frame { position, .. }) = &mut self.source {
+                *position += u64::try_from(len).unwrap();
+            }
+
+            offset += u64::try_from(len).unwrap();
+            dst = rest;
+        }
+        Ok(())
+    }
+
+    /// Consume the `ZstdSeekableBlockIo`, returning the underlying
+    /// reader.
+    #[must_use]
+    pub fn into_inner(mut self) -> R {
+        self.take_reader()
+    }
+}
+
+impl<R: Read + Seek> BlockIo for ZstdSeekableBlockIo<R> {
+    type Error = io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.num_bytes() / self.block_size.to_u64())
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.block_size.assert_valid_block_buffer(dst);
+
+        let offset = start_lba.to_u64() * self.block_size.to_u64();
+        let result = self.read_at(offset, dst);
+        if result.is_err() {
+            // The state of the decoder is unknown after an error, so
+            // start the frame over on the next read.
+            let reader = self.take_reader();
+            self.source = Some(Source::Idle(reader));
+        }
+        result
+    }
+
+    fn write_blocks(
+        &mut self,
+        _start_lba: Lba,
+        _src: &[u8],
+    ) -> Result<(), Self::Error> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "compressed disk images are read-only",
+        ))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::BlockIo;
+use core::fmt::{self, Display, Formatter};
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+
+#[cfg(feature = "rayon")]
+use {crate::SharedBlockIo, rayon::prelude::*, std::vec};
+
+/// Error type for [`copy_blocks`] and [`copy_blocks_parallel`].
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+/// [`copy_blocks_parallel`]: crate::copy_blocks_parallel
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum CopyBlocksError<SrcError, DstError> {
+    /// The source and destination have different block sizes.
+    BlockSizeMismatch,
+
+    /// The buffer (or chunk size) is smaller than one block.
+    BufferTooSmall,
+
+    /// Numeric overflow occurred.
+    Overflow,
+
+    /// Error reading from the source.
+    Read(SrcError),
+
+    /// Error writing to the destination.
+    Write(DstError),
+}
+
+impl<SrcError, DstError> Display for CopyBlocksError<SrcError, DstError>
+where
+    SrcError: Display,
+    DstError: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlockSizeMismatch => {
+                f.write_str("source and destination block sizes differ")
+            }
+            Self::BufferTooSmall => f.write_str("copy buffer is too small"),
+            Self::Overflow => f.write_str("numeric overflow occurred"),
+            Self::Read(err) => write!(f, "read failed: {err}"),
+            Self::Write(err) => write!(f, "write failed: {err}"),
+        }
+    }
+}
+
+/// Split a copy into chunks of at most `chunk_num_blocks` blocks.
+struct CopyPlan {
+    src_start: u64,
+    dst_start: u64,
+    num_blocks: u64,
+    chunk_num_blocks: u64,
+}
+
+impl CopyPlan {
+    fn new<SrcError, DstError>(
+        src_block_size: BlockSize,
+        dst_block_size: BlockSize,
+        src_range: LbaRangeInclusive,
+        dst_start_lba: Lba,
+        chunk_num_bytes: usize,
+    ) -> Result<Self, CopyBlocksError<SrcError, DstError>> {
+        if src_block_size != dst_block_size {
+            return Err(CopyBlocksError::BlockSizeMismatch);
+        }
+        let chunk_num_blocks = u64::try_from(chunk_num_bytes)
+            .map_err(|_| CopyBlocksError::Overflow)?
+            / src_block_size.to_u64();
+        if chunk_num_blocks == 0 {
+            return Err(CopyBlocksError::BufferTooSmall);
+        }
+
+        let num_blocks = range_num_blocks(src_range)?;
+        // Check that the destination range does not overflow.
+        dst_start_lba
+            .to_u64()
+            .checked_add(num_blocks - 1)
+            .ok_or(CopyBlocksError::Overflow)?;
+
+        Ok(Self {
+            src_start: src_range.start().to_u64(),
+            dst_start: dst_start_lba.to_u64(),
+            num_blocks,
+            chunk_num_blocks,
+        })
+    }
+
+    fn num_chunks(&self) -> u64 {
+        let mut num_chunks = self.num_blocks / self.chunk_num_blocks;
+        if self.num_blocks % self.chunk_num_blocks != 0 {
+            num_chunks += 1;
+        }
+        num_chunks
+    }
+
+    /// Get the source LBA, destination LBA, and number of blocks of
+    /// chunk `index`.
+    fn chunk(&self, index: u64) -> (Lba, Lba, u64) {
+        let offset = index * self.chunk_num_blocks;
+        let num_blocks = self.chunk_num_blocks.min(self.num_blocks - offset);
+        (
+            Lba(self.src_start + offset),
+            Lba(self.dst_start + offset),
+            num_blocks,
+        )
+    }
+}
+
+/// Get the number of blocks in `range`.
+fn range_num_blocks<SrcError, DstError>(
+    range: LbaRangeInclusive,
+) -> Result<u64, CopyBlocksError<SrcError, DstError>> {
+    (range.end().to_u64() - range.start().to_u64())
+        .checked_add(1)
+        .ok_or(CopyBlocksError::Overflow)
+}
+
+/// Copy the blocks in `src_range` from `src` to `dst`, starting at
+/// `dst_start_lba`.
+///
+/// The copy is done in chunks the size of `buf`, which must be a
+/// multiple of the block size and at least one block. The source and
+/// destination must have the same block size.
+///
+/// If `src` and `dst` are the same storage, the ranges must not
+/// overlap.
+///
+/// `dst` is not flushed.
+///
+/// # Panics
+///
+/// Panics if the length of `buf` is not a multiple of the block size.
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+/// use gpt_disk_io::{copy_blocks, BlockIoAdapter};
+///
+/// let src_data = [1; 2048];
+/// let mut dst_data = [0; 4096];
+/// let mut src = BlockIoAdapter::new(&src_data[..], BlockSize::BS_512);
+/// let mut dst = BlockIoAdapter::new(&mut dst_data[..], BlockSize::BS_512);
+///
+/// let range = LbaRangeInclusive::new(Lba(1), Lba(3)).unwrap();
+/// let mut buf = [0; 1024];
+/// copy_blocks(&mut src, &mut dst, range, Lba(4), &mut buf).unwrap();
+/// assert_eq!(dst_data[..2048], [0; 2048]);
+/// assert_eq!(dst_data[2048..3584], [1; 1536]);
+/// ```
+pub fn copy_blocks<Src, Dst>(
+    src: &mut Src,
+    dst: &mut Dst,
+    src_range: LbaRangeInclusive,
+    dst_start_lba: Lba,
+    buf: &mut [u8],
+) -> Result<(), CopyBlocksError<Src::Error, Dst::Error>>
+where
+    Src: BlockIo,
+    Dst: BlockIo,
+{
+    src.block_size().assert_valid_block_buffer(buf);
+    let plan = CopyPlan::new(
+        src.block_size(),
+        dst.block_size(),
+        src_range,
+        dst_start_lba,
+        buf.len(),
+    )?;
+
+    let block_size = src.block_size().to_u64();
+    for index in 0..plan.num_chunks() {
+        let (src_lba, dst_lba, num_blocks) = plan.chunk(index);
+        // OK to unwrap: the chunk is no larger than `buf`.
+        let buf = &mut buf[..usize::try_from(num_blocks * block_size).unwrap()];
+        src.read_blocks(src_lba, buf)
+            .map_err(CopyBlocksError::Read)?;
+        dst.write_blocks(dst_lba, buf)
+            .map_err(CopyBlocksError::Write)?;
+    }
+    Ok(())
+}
+
+/// Copy the blocks in `src_range` from `src` to `dst` using multiple
+/// threads, starting at `dst_start_lba`.
+///
+/// The range is split into non-overlapping chunks of `chunk_num_bytes`,
+/// which are copied in parallel on the [`rayon`] global thread pool.
+/// Each thread allocates one chunk-sized buffer. `chunk_num_bytes` must
+/// be a multiple of the block size and at least one block. The source
+/// and destination must have the same block size.
+///
+/// If `src` and `dst` are the same storage, the ranges must not
+/// overlap.
+///
+/// If an error occurs, the remaining chunks may or may not have been
+/// copied. `dst` is not flushed.
+///
+/// Requires the `rayon` feature.
+///
+/// # Panics
+///
+/// Panics if `chunk_num_bytes` is not a multiple of the block size.
+#[cfg(feature = "rayon")]
+pub fn copy_blocks_parallel<Src, Dst>(
+    src: &Src,
+    dst: &Dst,
+    src_range: LbaRangeInclusive,
+    dst_start_lba: Lba,
+    chunk_num_bytes: usize,
+) -> Result<(), CopyBlocksError<Src::Error, Dst::Error>>
+where
+    Src: SharedBlockIo + Sync,
+    Dst: SharedBlockIo + Sync,
+{
+    assert_eq!(
+        u64::try_from(chunk_num_bytes).unwrap_or(u64::MAX)
+            % src.block_size().to_u64(),
+        0,
+        "chunk size must be a multiple of the block size"
+    );
+    let plan = CopyPlan::new(
+        src.block_size(),
+        dst.block_size(),
+        src_range,
+        dst_start_lba,
+        chunk_num_bytes,
+    )?;
+
+    let block_size = src.block_size().to_u64();
+    (0..plan.num_chunks()).into_par_iter().try_for_each_init(
+        || vec![0; chunk_num_bytes],
+        |buf, index| {
+            let (src_lba, dst_lba, num_blocks) = plan.chunk(index);
+            // OK to unwrap: the chunk is no larger than `buf`.
+            let buf =
+                &mut buf[..usize::try_from(num_blocks * block_size).unwrap()];
+            src.read_blocks(src_lba, buf)
+                .map_err(CopyBlocksError::Read)?;
+            dst.write_blocks(dst_lba, buf)
+                .map_err(CopyBlocksError::Write)
+        },
+    )
+}
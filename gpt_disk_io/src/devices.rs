@@ -0,0 +1,1170 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Enumerate the block devices attached to the system.
+//!
+//! This is intended for tools that need to let the user pick a disk.
+//! The amount of information available depends on the platform:
+//!
+//! * Linux: devices are found in `/sys/block`. All fields are filled in
+//!   from sysfs, so no special permissions are needed. Devices with a
+//!   size of zero (such as unused loop devices or empty card readers)
+//!   are skipped.
+//! * macOS: whole disks are found in `/dev` and returned as raw
+//!   `/dev/rdiskN` paths. The other fields are read from the I/O Kit
+//!   registry, so no special permissions are needed.
+//! * Windows: `\\.\PhysicalDriveN` paths are probed. The other fields
+//!   are queried from the device, which does not require administrator
+//!   privileges.
+//!
+//! Use [`DeviceOpenOptions`] to open a device. When opening for
+//! writing, it refuses to open a device that is in use, to prevent
+//! accidentally overwriting the partition table of a disk with mounted
+//! filesystems.
+//!
+//! Requires the `devices` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use gpt_disk_io::devices;
+//!
+//! # #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+//! for device in devices::list_block_devices()? {
+//!     println!("{}", device.path.display());
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::{BlockIoAdapter, DiskError};
+use gpt_disk_types::BlockSize;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Information about a block device.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BlockDeviceInfo {
+    /// Path that can be opened to access the device.
+    pub path: PathBuf,
+
+    /// Size of the device in bytes, if known.
+    pub num_bytes: Option<u64>,
+
+    /// Logical block size of the device, if known.
+    pub block_size: Option<BlockSize>,
+
+    /// Whether the device is removable, if known.
+    pub removable: Option<bool>,
+}
+
+/// Block sizes of a device, as reported by the operating system. See
+/// [`query_block_sizes`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct DeviceBlockSizes {
+    /// Logical block size, the unit in which the device is addressed.
+    pub logical: Option<BlockSize>,
+
+    /// Physical block size. This is often larger than the logical
+    /// block size, e.g. 4096 bytes for a "512e" drive.
+    pub physical: Option<BlockSize>,
+
+    /// Optimal IO size in bytes, such as the stripe width of a RAID
+    /// device.
+    pub optimal_io_size: Option<u32>,
+}
+
+/// Get a list of block devices attached to the system, sorted by path.
+///
+/// See the [module documentation](self) for platform details. On
+/// unsupported platforms an error of kind
+/// [`io::ErrorKind::Unsupported`] is returned.
+pub fn list_block_devices() -> Result<Vec<BlockDeviceInfo>, io::Error> {
+    let mut devices = platform::list_block_devices()?;
+    devices.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(devices)
+}
+
+/// Check if the block device at `path` is in use.
+///
+/// On Linux, a device is in use if it or any of its partitions is
+/// mounted, or is held by another device (for example, an LVM or RAID
+/// device built on top of it).
+///
+/// On macOS, a device is in use if a mounted filesystem is on it,
+/// including on a partition of it or on an APFS container stored in one
+/// of its partitions. Both `/dev/diskN` and `/dev/rdiskN` paths are
+/// accepted.
+///
+/// On Windows, a device is in use if it holds part of a volume that is
+/// mounted at a drive letter or folder (see
+/// `GetVolumePathNamesForVolumeName`). The path may be a
+/// `\\.\PhysicalDriveN` path or a volume path such as `\\.\C:`.
+///
+/// On all of these platforms, paths that are not devices, such as disk
+/// image files, are never in use. On other platforms, only regular
+/// files can be checked, and an error of kind
+/// [`io::ErrorKind::Unsupported`] is returned for any other path.
+pub fn is_device_in_use<P: AsRef<Path>>(path: P) -> Result<bool, io::Error> {
+    platform::is_device_in_use(path.as_ref())
+}
+
+/// Query the block sizes of an open block device.
+///
+/// On Linux, all the sizes are queried. On macOS and Windows, the
+/// optimal IO size is not available. On Windows, the physical block
+/// size is only available if the storage driver supports
+/// `StorageAccessAlignmentProperty`. On other platforms, nothing is
+/// available. If `file` is not a block device, for example a disk image
+/// file, all the fields are `None`.
+#[must_use]
+pub fn query_block_sizes(file: &File) -> DeviceBlockSizes {
+    platform::query_block_sizes(file)
+}
+
+impl BlockIoAdapter<File> {
+    /// Create a `BlockIoAdapter` for a block device, with the sizes
+    /// from [`query_block_sizes`]. If the logical block size is not
+    /// known, for example because `file` is a disk image file, 512 is
+    /// used.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use gpt_disk_io::devices::DeviceOpenOptions;
+    /// use gpt_disk_io::{BlockIo, BlockIoAdapter};
+    ///
+    /// let file = DeviceOpenOptions::new().open("/dev/sdb")?;
+    /// let bio = BlockIoAdapter::from_device(file);
+    /// println!("physical block size: {:?}", bio.physical_block_size());
+    /// # Ok::<(), gpt_disk_io::DiskError<std::io::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn from_device(file: File) -> Self {
+        let sizes = query_block_sizes(&file);
+        Self::new(file, sizes.logical.unwrap_or(BlockSize::BS_512))
+            .with_physical_block_size(sizes.physical)
+            .with_optimal_io_size(sizes.optimal_io_size)
+    }
+}
+
+/// Options for opening a block device or disk image file.
+///
+/// This is similar to [`std::fs::OpenOptions`], but when opening for
+/// writing, the device is first checked with [`is_device_in_use`]. If
+/// the device is in use, [`DiskError::TargetInUse`] is returned unless
+/// [`force`] is set. If the check fails, for example because it is not
+/// supported on the platform, the error is returned unless [`force`] is
+/// set.
+///
+/// [`force`]: Self::force
+///
+/// # Examples
+///
+/// ```no_run
+/// use gpt_disk_io::devices::DeviceOpenOptions;
+/// use gpt_disk_io::gpt_disk_types::BlockSize;
+/// use gpt_disk_io::{BlockIoAdapter, Disk};
+///
+/// let file = DeviceOpenOptions::new().write(true).open("/dev/sdb")?;
+/// let mut disk = Disk::new(BlockIoAdapter::new(file, BlockSize::BS_512))?;
+/// # Ok::<(), gpt_disk_io::DiskError<std::io::Error>>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DeviceOpenOptions {
+    write: bool,
+    force: bool,
+}
+
+impl DeviceOpenOptions {
+    /// Create options for opening a device read-only.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the device is opened for writing.
+    pub fn write(&mut self, write: bool) -> &mut Self {
+        self.write = write;
+        self
+    }
+
+    /// Set whether to open the device for writing even if it is in use.
+    pub fn force(&mut self, force: bool) -> &mut Self {
+        self.force = force;
+        self
+    }
+
+    /// Open the device at `path`. The device is always opened for
+    /// reading.
+    pub fn open<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<File, DiskError<io::Error>> {
+        let path = path.as_ref();
+        if self.write && !self.force && is_device_in_use(path)? {
+            return Err(DiskError::TargetInUse);
+        }
+        Ok(fs::OpenOptions::new()
+            .read(true)
+            .write(self.write)
+            .open(path)?)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{BlockDeviceInfo, DeviceBlockSizes};
+    use gpt_disk_types::BlockSize;
+    use std::fs::{self, File};
+    use std::io;
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+    use std::os::unix::io::AsRawFd;
+    use std::path::{Path, PathBuf};
+    use std::ptr;
+
+    /// The sysfs `size` file is always in units of 512-byte sectors.
+    const SYSFS_SECTOR_SIZE: u64 = 512;
+
+    // From linux/fs.h.
+    const BLKSSZGET: libc::Ioctl = 0x1268;
+    const BLKIOOPT: libc::Ioctl = 0x1279;
+    const BLKPBSZGET: libc::Ioctl = 0x127b;
+
+    /// Call an ioctl that writes an `int` or `unsigned int`.
+    #[allow(unsafe_code)]
+    fn ioctl_u32(file: &File, request: libc::Ioctl) -> Option<u32> {
+        let mut val: libc::c_uint = 0;
+        // SAFETY: the requests passed to this function write a value
+        // the size of an `unsigned int`.
+        let ret = unsafe {
+            libc::ioctl(file.as_raw_fd(), request, ptr::addr_of_mut!(val))
+        };
+        (ret == 0).then_some(val)
+    }
+
+    pub(super) fn query_block_sizes(file: &File) -> DeviceBlockSizes {
+        let is_block_device = file
+            .metadata()
+            .map_or(false, |m| m.file_type().is_block_device());
+        if !is_block_device {
+            return DeviceBlockSizes::default();
+        }
+
+        let block_size =
+            |request| ioctl_u32(file, request).and_then(BlockSize::new);
+        DeviceBlockSizes {
+            logical: block_size(BLKSSZGET),
+            physical: block_size(BLKPBSZGET),
+            // Zero means the device does not report an optimal size.
+            optimal_io_size: ioctl_u32(file, BLKIOOPT).filter(|n| *n != 0),
+        }
+    }
+
+    fn read_sysfs_u64(path: &Path) -> Option<u64> {
+        fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    pub(super) fn list_block_devices() -> Result<Vec<BlockDeviceInfo>, io::Error>
+    {
+        let mut devices = Vec::new();
+        for entry in fs::read_dir("/sys/block")? {
+            let entry = entry?;
+            let sys_path = entry.path();
+
+            let num_bytes = read_sysfs_u64(&sys_path.join("size"))
+                .and_then(|sectors| sectors.checked_mul(SYSFS_SECTOR_SIZE));
+            if num_bytes.unwrap_or(0) == 0 {
+                continue;
+            }
+
+            let block_size =
+                read_sysfs_u64(&sys_path.join("queue/logical_block_size"))
+                    .and_then(|size| u32::try_from(size).ok())
+                    .and_then(BlockSize::new);
+            let removable = read_sysfs_u64(&sys_path.join("removable"))
+                .map(|removable| removable != 0);
+
+            devices.push(BlockDeviceInfo {
+                path: PathBuf::from("/dev").join(entry.file_name()),
+                num_bytes,
+                block_size,
+                removable,
+            });
+        }
+        Ok(devices)
+    }
+
+    /// Get the canonical sysfs directory of the block device at `path`,
+    /// or `None` if `path` is not a block device.
+    fn block_device_sys_path(path: &Path) -> Option<PathBuf> {
+        let metadata = fs::metadata(path).ok()?;
+        if !metadata.file_type().is_block_device() {
+            return None;
+        }
+
+        let rdev = metadata.rdev();
+        let major = libc::major(rdev);
+        let minor = libc::minor(rdev);
+        fs::canonicalize(format!("/sys/dev/block/{major}:{minor}")).ok()
+    }
+
+    /// Check if the device in `sys_path` has any holders.
+    fn has_holders(sys_path: &Path) -> bool {
+        fs::read_dir(sys_path.join("holders"))
+            .map_or(false, |mut holders| holders.next().is_some())
+    }
+
+    pub(super) fn is_device_in_use(path: &Path) -> Result<bool, io::Error> {
+        let Some(sys_path) = block_device_sys_path(path) else {
+            return Ok(false);
+        };
+
+        // Check the device and its partitions for holders. Partitions
+        // are subdirectories containing a `partition` file.
+        if has_holders(&sys_path) {
+            return Ok(true);
+        }
+        for entry in fs::read_dir(&sys_path)? {
+            let child = entry?.path();
+            if child.join("partition").exists() && has_holders(&child) {
+                return Ok(true);
+            }
+        }
+
+        // Check for mounts of the device or its partitions. Each line
+        // of mountinfo contains the device number in the third field,
+        // and the mount source after a "-" separator field. The source
+        // is also checked since some filesystems (e.g. btrfs) report an
+        // anonymous device number.
+        let mountinfo = fs::read_to_string("/proc/self/mountinfo")?;
+        for line in mountinfo.lines() {
+            let mut fields = line.split(' ');
+            let dev_sys_path = fields.nth(2).and_then(|dev| {
+                fs::canonicalize(format!("/sys/dev/block/{dev}")).ok()
+            });
+            let source_sys_path = fields
+                .skip_while(|field| *field != "-")
+                .nth(2)
+                .and_then(|source| block_device_sys_path(Path::new(source)));
+
+            if [dev_sys_path, source_sys_path]
+                .iter()
+                .flatten()
+                .any(|mounted| mounted.starts_with(&sys_path))
+            {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[allow(unsafe_code)]
+mod platform {
+    use super::{BlockDeviceInfo, DeviceBlockSizes};
+    use gpt_disk_types::BlockSize;
+    use std::ffi::{c_char, CStr, CString};
+    use std::fs::{self, File};
+    use std::io;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::io::AsRawFd;
+    use std::path::{Path, PathBuf};
+    use std::{ptr, slice};
+
+    // From sys/disk.h.
+    const DKIOCGETBLOCKSIZE: libc::c_ulong = 0x4004_6418;
+    const DKIOCGETBLOCKCOUNT: libc::c_ulong = 0x4008_6419;
+    const DKIOCGETPHYSICALBLOCKSIZE: libc::c_ulong = 0x4004_644d;
+
+    pub(super) fn query_block_sizes(file: &File) -> DeviceBlockSizes {
+        let block_size = |request| {
+            let mut val: u32 = 0;
+            // SAFETY: the requests passed to this closure write a
+            // `u32`.
+            let ret = unsafe {
+                libc::ioctl(file.as_raw_fd(), request, ptr::addr_of_mut!(val))
+            };
+            if ret == 0 {
+                BlockSize::new(val)
+            } else {
+                None
+            }
+        };
+        DeviceBlockSizes {
+            logical: block_size(DKIOCGETBLOCKSIZE),
+            physical: block_size(DKIOCGETPHYSICALBLOCKSIZE),
+            optimal_io_size: None,
+        }
+    }
+
+    /// Get the block size and number of bytes of an open disk.
+    fn disk_size(file: &File) -> Option<(BlockSize, u64)> {
+        let mut block_size: u32 = 0;
+        let mut block_count: u64 = 0;
+        // SAFETY: these ioctls write a `u32` and a `u64` respectively.
+        unsafe {
+            if libc::ioctl(
+                file.as_raw_fd(),
+                DKIOCGETBLOCKSIZE,
+                ptr::addr_of_mut!(block_size),
+            ) != 0
+            {
+                return None;
+            }
+            if libc::ioctl(
+                file.as_raw_fd(),
+                DKIOCGETBLOCKCOUNT,
+                ptr::addr_of_mut!(block_count),
+            ) != 0
+            {
+                return None;
+            }
+        }
+        let num_bytes = block_count.checked_mul(u64::from(block_size))?;
+        Some((BlockSize::new(block_size)?, num_bytes))
+    }
+
+    /// Check if `name` is a whole disk, e.g. "disk0" but not "disk0s1".
+    fn is_whole_disk(name: &str) -> bool {
+        name.strip_prefix("disk").map_or(false, |num| {
+            !num.is_empty() && num.bytes().all(|b| b.is_ascii_digit())
+        })
+    }
+
+    pub(super) fn list_block_devices() -> Result<Vec<BlockDeviceInfo>, io::Error>
+    {
+        let mut devices = Vec::new();
+        for entry in fs::read_dir("/dev")? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let Some(name) = file_name.to_str() else {
+                continue;
+            };
+            if !is_whole_disk(name) {
+                continue;
+            }
+
+            // The `IOMedia` properties can be read without opening the
+            // device, which usually requires root.
+            let path = PathBuf::from(format!("/dev/r{name}"));
+            let media = RegistryEntry::for_bsd_name(name);
+            let size = media
+                .as_ref()
+                .and_then(RegistryEntry::media_size)
+                .or_else(|| File::open(&path).ok().and_then(|f| disk_size(&f)));
+
+            devices.push(BlockDeviceInfo {
+                path,
+                num_bytes: size.map(|(_, num_bytes)| num_bytes),
+                block_size: size.map(|(block_size, _)| block_size),
+                removable: media
+                    .and_then(|media| media.bool_property(b"Removable\0")),
+            });
+        }
+        Ok(devices)
+    }
+
+    /// Minimal `IOKit` and Core Foundation bindings for reading the
+    /// properties of a disk's `IOMedia` object and walking the registry
+    /// to the media it is built on.
+    #[allow(non_camel_case_types, non_upper_case_globals)]
+    mod iokit {
+        use std::ffi::{c_char, c_void};
+
+        pub(super) type io_object_t = u32;
+        pub(super) type CFTypeRef = *const c_void;
+
+        pub(super) const kIOMainPortDefault: u32 = 0;
+        pub(super) const kCFStringEncodingUTF8: u32 = 0x0800_0100;
+        pub(super) const kCFNumberSInt64Type: isize = 4;
+        pub(super) const kIOServicePlane: &[u8] = b"IOService\0";
+
+        #[link(name = "IOKit", kind = "framework")]
+        extern "C" {
+            pub(super) fn IOBSDNameMatching(
+                main_port: u32,
+                options: u32,
+                bsd_name: *const c_char,
+            ) -> *mut c_void;
+            pub(super) fn IOServiceGetMatchingService(
+                main_port: u32,
+                matching: *mut c_void,
+            ) -> io_object_t;
+            pub(super) fn IORegistryEntryGetParentEntry(
+                entry: io_object_t,
+                plane: *const c_char,
+                parent: *mut io_object_t,
+            ) -> i32;
+            pub(super) fn IORegistryEntryCreateCFProperty(
+                entry: io_object_t,
+                key: CFTypeRef,
+                allocator: CFTypeRef,
+                options: u32,
+            ) -> CFTypeRef;
+            pub(super) fn IOObjectRelease(object: io_object_t) -> i32;
+        }
+
+        #[link(name = "CoreFoundation", kind = "framework")]
+        extern "C" {
+            pub(super) fn CFStringCreateWithCString(
+                allocator: CFTypeRef,
+                c_str: *const c_char,
+                encoding: u32,
+            ) -> CFTypeRef;
+            pub(super) fn CFStringGetCString(
+                string: CFTypeRef,
+                buffer: *mut c_char,
+                buffer_size: isize,
+                encoding: u32,
+            ) -> u8;
+            pub(super) fn CFNumberGetValue(
+                number: CFTypeRef,
+                number_type: isize,
+                value: *mut c_void,
+            ) -> u8;
+            pub(super) fn CFBooleanGetValue(boolean: CFTypeRef) -> u8;
+            pub(super) fn CFGetTypeID(cf: CFTypeRef) -> usize;
+            pub(super) fn CFStringGetTypeID() -> usize;
+            pub(super) fn CFNumberGetTypeID() -> usize;
+            pub(super) fn CFBooleanGetTypeID() -> usize;
+            pub(super) fn CFRelease(cf: CFTypeRef);
+        }
+    }
+
+    /// An `IOKit` registry entry, released on drop.
+    struct RegistryEntry(iokit::io_object_t);
+
+    impl RegistryEntry {
+        /// Get the `IOMedia` entry for the BSD device `name`, e.g.
+        /// "disk0s1".
+        fn for_bsd_name(name: &str) -> Option<Self> {
+            let name = CString::new(name).ok()?;
+            // SAFETY: `name` is null terminated. The matching
+            // dictionary is consumed by `IOServiceGetMatchingService`.
+            let entry = unsafe {
+                let matching = iokit::IOBSDNameMatching(
+                    iokit::kIOMainPortDefault,
+                    0,
+                    name.as_ptr(),
+                );
+                if matching.is_null() {
+                    return None;
+                }
+                iokit::IOServiceGetMatchingService(
+                    iokit::kIOMainPortDefault,
+                    matching,
+                )
+            };
+            (entry != 0).then_some(Self(entry))
+        }
+
+        /// Get the entry's parent in the service plane.
+        fn parent(&self) -> Option<Self> {
+            let mut parent = 0;
+            // SAFETY: `self.0` is a valid entry, and the plane name is
+            // null terminated.
+            let ret = unsafe {
+                iokit::IORegistryEntryGetParentEntry(
+                    self.0,
+                    iokit::kIOServicePlane.as_ptr().cast(),
+                    &mut parent,
+                )
+            };
+            (ret == 0).then_some(Self(parent))
+        }
+
+        /// Get the value of the property `key`, which must be null
+        /// terminated, or `None` if it is not set. The value must be
+        /// released with `CFRelease`.
+        fn property(&self, key: &[u8]) -> Option<iokit::CFTypeRef> {
+            assert_eq!(key.last(), Some(&0));
+            // SAFETY: `key` is null terminated. The key string is
+            // released after use.
+            unsafe {
+                let key = iokit::CFStringCreateWithCString(
+                    ptr::null(),
+                    key.as_ptr().cast(),
+                    iokit::kCFStringEncodingUTF8,
+                );
+                if key.is_null() {
+                    return None;
+                }
+                let value = iokit::IORegistryEntryCreateCFProperty(
+                    self.0,
+                    key,
+                    ptr::null(),
+                    0,
+                );
+                iokit::CFRelease(key);
+                (!value.is_null()).then_some(value)
+            }
+        }
+
+        /// Get the value of the boolean property `key`, which must be
+        /// null terminated.
+        fn bool_property(&self, key: &[u8]) -> Option<bool> {
+            let value = self.property(key)?;
+            // SAFETY: `value` is a valid object, and is released after
+            // use.
+            unsafe {
+                let result = (iokit::CFGetTypeID(value)
+                    == iokit::CFBooleanGetTypeID())
+                .then(|| iokit::CFBooleanGetValue(value) != 0);
+                iokit::CFRelease(value);
+                result
+            }
+        }
+
+        /// Get the value of the integer property `key`, which must be
+        /// null terminated.
+        fn u64_property(&self, key: &[u8]) -> Option<u64> {
+            let value = self.property(key)?;
+            let mut num: i64 = 0;
+            // SAFETY: `value` is a valid object, and is released after
+            // use. `num` is valid for writes of the requested type.
+            let ok = unsafe {
+                let ok = iokit::CFGetTypeID(value)
+                    == iokit::CFNumberGetTypeID()
+                    && iokit::CFNumberGetValue(
+                        value,
+                        iokit::kCFNumberSInt64Type,
+                        ptr::addr_of_mut!(num).cast(),
+                    ) != 0;
+                iokit::CFRelease(value);
+                ok
+            };
+            ok.then(|| u64::try_from(num).ok()).flatten()
+        }
+
+        /// Get the block size and number of bytes of an `IOMedia`
+        /// entry.
+        fn media_size(&self) -> Option<(BlockSize, u64)> {
+            let block_size = self.u64_property(b"Preferred Block Size\0")?;
+            Some((
+                BlockSize::new(u32::try_from(block_size).ok()?)?,
+                self.u64_property(b"Size\0")?,
+            ))
+        }
+
+        /// Get the BSD device name of the entry, if it has one.
+        fn bsd_name(&self) -> Option<String> {
+            let value = self.property(b"BSD Name\0")?;
+            let mut buf: [c_char; 128] = [0; 128];
+            // SAFETY: `value` is a valid object, and `buf` is valid for
+            // writes of its length. `value` is released after use. On
+            // success, `CFStringGetCString` writes a null-terminated
+            // string to `buf`.
+            unsafe {
+                let ok = iokit::CFGetTypeID(value)
+                    == iokit::CFStringGetTypeID()
+                    && iokit::CFStringGetCString(
+                        value,
+                        buf.as_mut_ptr(),
+                        isize::try_from(buf.len()).unwrap(),
+                        iokit::kCFStringEncodingUTF8,
+                    ) != 0;
+                iokit::CFRelease(value);
+                if !ok {
+                    return None;
+                }
+                CStr::from_ptr(buf.as_ptr())
+                    .to_str()
+                    .ok()
+                    .map(str::to_owned)
+            }
+        }
+    }
+
+    impl Drop for RegistryEntry {
+        fn drop(&mut self) {
+            // SAFETY: `self.0` is a valid entry that is owned by `self`.
+            unsafe {
+                iokit::IOObjectRelease(self.0);
+            }
+        }
+    }
+
+    /// Check if the media named `name` is `disk_name` or is built on
+    /// it, for example a partition of it or an APFS volume in a
+    /// container stored on it.
+    fn is_media_on_disk(name: &str, disk_name: &str) -> bool {
+        let mut entry = RegistryEntry::for_bsd_name(name);
+        while let Some(current) = entry {
+            if current.bsd_name().as_deref() == Some(disk_name) {
+                return true;
+            }
+            entry = current.parent();
+        }
+        false
+    }
+
+    /// Get the BSD names of the devices that mounted filesystems are
+    /// on, e.g. "disk3s1".
+    fn mounted_device_names() -> Result<Vec<String>, io::Error> {
+        let mut mounts: *mut libc::statfs = ptr::null_mut();
+        // SAFETY: on success, `mounts` points to `count` entries owned
+        // by libc.
+        let count = unsafe { libc::getmntinfo(&mut mounts, libc::MNT_NOWAIT) };
+        if count <= 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: see above.
+        let mounts = unsafe {
+            slice::from_raw_parts(mounts, usize::try_from(count).unwrap())
+        };
+        Ok(mounts
+            .iter()
+            .filter_map(|mount| {
+                // SAFETY: `f_mntfromname` is null terminated.
+                let source =
+                    unsafe { CStr::from_ptr(mount.f_mntfromname.as_ptr()) };
+                source
+                    .to_str()
+                    .ok()?
+                    .strip_prefix("/dev/")
+                    .map(str::to_owned)
+            })
+            .collect())
+    }
+
+    pub(super) fn is_device_in_use(path: &Path) -> Result<bool, io::Error> {
+        let Ok(metadata) = fs::metadata(path) else {
+            return Ok(false);
+        };
+        let file_type = metadata.file_type();
+        if !file_type.is_block_device() && !file_type.is_char_device() {
+            return Ok(false);
+        }
+
+        // Both `/dev/diskN` and `/dev/rdiskN` refer to the media named
+        // "diskN".
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            return Ok(false);
+        };
+        let name = name.strip_prefix('r').unwrap_or(name);
+
+        Ok(mounted_device_names()?
+            .iter()
+            .any(|mounted| is_media_on_disk(mounted, name)))
+    }
+}
+
+#[cfg(windows)]
+#[allow(unsafe_code)]
+mod platform {
+    use super::{BlockDeviceInfo, DeviceBlockSizes};
+    use gpt_disk_types::BlockSize;
+    use std::ffi::{c_void, OsString};
+    use std::fs::{File, OpenOptions};
+    use std::io;
+    use std::mem;
+    use std::os::windows::ffi::OsStringExt;
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use std::path::{Path, PathBuf};
+    use std::ptr;
+    use windows_sys::Win32::Foundation::{
+        GetLastError, ERROR_MORE_DATA, ERROR_NO_MORE_FILES, HANDLE,
+        INVALID_HANDLE_VALUE, MAX_PATH,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        FindFirstVolumeW, FindNextVolumeW, FindVolumeClose,
+        GetVolumePathNamesForVolumeNameW, IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS,
+    };
+    use windows_sys::Win32::System::Ioctl::{
+        PropertyStandardQuery, StorageAccessAlignmentProperty,
+        StorageDeviceProperty, DISK_EXTENT, DISK_GEOMETRY_EX,
+        IOCTL_DISK_GET_DRIVE_GEOMETRY_EX, IOCTL_STORAGE_GET_DEVICE_NUMBER,
+        IOCTL_STORAGE_QUERY_PROPERTY, STORAGE_ACCESS_ALIGNMENT_DESCRIPTOR,
+        STORAGE_DEVICE_DESCRIPTOR, STORAGE_DEVICE_NUMBER, STORAGE_PROPERTY_ID,
+        STORAGE_PROPERTY_QUERY, VOLUME_DISK_EXTENTS,
+    };
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+
+    /// Number of `PhysicalDriveN` paths to probe. Drive numbers are not
+    /// necessarily contiguous, so probing stops at a fixed limit rather
+    /// than the first missing drive.
+    const MAX_PHYSICAL_DRIVES: u32 = 64;
+
+    /// Maximum number of extents read for a volume. Only volumes
+    /// spanning multiple disks have more than one.
+    const MAX_VOLUME_EXTENTS: usize = 32;
+
+    /// Open `path` with no access rights. This is enough for the
+    /// queries below and does not require administrator privileges.
+    fn open_for_query(path: &Path) -> io::Result<File> {
+        OpenOptions::new().access_mode(0).open(path)
+    }
+
+    /// Call `DeviceIoControl` on `file` with the given input and output
+    /// buffers. Returns the number of bytes written to `output`.
+    ///
+    /// # Safety
+    ///
+    /// `input` and `output` must be valid for reads and writes of
+    /// `input_len` and `output_len` bytes, and must have the types
+    /// expected by `code`.
+    unsafe fn device_io_control(
+        file: &File,
+        code: u32,
+        input: *const c_void,
+        input_len: usize,
+        output: *mut c_void,
+        output_len: usize,
+    ) -> io::Result<u32> {
+        let len = |len| {
+            u32::try_from(len).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, "buffer too large")
+            })
+        };
+        let mut bytes_returned = 0;
+        if DeviceIoControl(
+            file.as_raw_handle(),
+            code,
+            input,
+            len(input_len)?,
+            output,
+            len(output_len)?,
+            &mut bytes_returned,
+            ptr::null_mut(),
+        ) == 0
+        {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(bytes_returned)
+        }
+    }
+
+    /// Call an ioctl that reads an `I` and writes a fixed-size `O`.
+    ///
+    /// # Safety
+    ///
+    /// `code` must take an `I` as input and write an `O`, and all zeros
+    /// must be a valid `O`.
+    unsafe fn ioctl<I, O: Copy>(
+        file: &File,
+        code: u32,
+        input: &I,
+    ) -> io::Result<O> {
+        let mut output: O = mem::zeroed();
+        device_io_control(
+            file,
+            code,
+            ptr::addr_of!(*input).cast(),
+            mem::size_of::<I>(),
+            ptr::addr_of_mut!(output).cast(),
+            mem::size_of::<O>(),
+        )?;
+        Ok(output)
+    }
+
+    /// Get the number of the disk containing the device opened as
+    /// `file`, i.e. the `N` in `PhysicalDriveN`. Fails if `file` is not
+    /// a disk or a partition, e.g. if it is a disk image file.
+    fn disk_number(file: &File) -> io::Result<u32> {
+        // SAFETY: this ioctl writes a `STORAGE_DEVICE_NUMBER`.
+        let number: STORAGE_DEVICE_NUMBER =
+            unsafe { ioctl(file, IOCTL_STORAGE_GET_DEVICE_NUMBER, &())? };
+        Ok(number.DeviceNumber)
+    }
+
+    /// Get the logical block size and number of bytes of the disk opened
+    /// as `file`.
+    fn disk_size(file: &File) -> Option<(BlockSize, u64)> {
+        // SAFETY: this ioctl writes a `DISK_GEOMETRY_EX`, followed by
+        // optional data that is left out if the buffer is too small.
+        let geometry: DISK_GEOMETRY_EX =
+            unsafe { ioctl(file, IOCTL_DISK_GET_DRIVE_GEOMETRY_EX, &()).ok()? };
+        Some((
+            BlockSize::new(geometry.Geometry.BytesPerSector)?,
+            u64::try_from(geometry.DiskSize).ok()?,
+        ))
+    }
+
+    /// Create a standard query for `property_id`, to pass to
+    /// `IOCTL_STORAGE_QUERY_PROPERTY`.
+    fn property_query(
+        property_id: STORAGE_PROPERTY_ID,
+    ) -> STORAGE_PROPERTY_QUERY {
+        STORAGE_PROPERTY_QUERY {
+            PropertyId: property_id,
+            QueryType: PropertyStandardQuery,
+            AdditionalParameters: [0],
+        }
+    }
+
+    /// Check if the disk opened as `file` has removable media.
+    fn is_removable(file: &File) -> Option<bool> {
+        let query = property_query(StorageDeviceProperty);
+        // SAFETY: this ioctl takes a `STORAGE_PROPERTY_QUERY`. For the
+        // device property, it writes a `STORAGE_DEVICE_DESCRIPTOR`
+        // followed by optional strings that are left out if the buffer
+        // is too small.
+        let descriptor: STORAGE_DEVICE_DESCRIPTOR =
+            unsafe { ioctl(file, IOCTL_STORAGE_QUERY_PROPERTY, &query).ok()? };
+        Some(descriptor.RemovableMedia != 0)
+    }
+
+    /// Get the numbers of the disks that the volume opened as `file` is
+    /// stored on.
+    fn volume_disk_numbers(file: &File) -> io::Result<Vec<u32>> {
+        /// `VOLUME_DISK_EXTENTS` with room for more than one extent.
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        struct Extents {
+            header: VOLUME_DISK_EXTENTS,
+            more: [DISK_EXTENT; MAX_VOLUME_EXTENTS - 1],
+        }
+
+        // SAFETY: this ioctl writes a `VOLUME_DISK_EXTENTS` with a
+        // variable number of extents, which fits in `Extents` as long
+        // as there are at most `MAX_VOLUME_EXTENTS`. If there are more,
+        // the call fails with `ERROR_MORE_DATA`.
+        let extents: Extents =
+            unsafe { ioctl(file, IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS, &())? };
+        let num_extents = usize::try_from(extents.header.NumberOfDiskExtents)
+            .unwrap_or(usize::MAX)
+            .min(MAX_VOLUME_EXTENTS);
+        Ok(extents
+            .header
+            .Extents
+            .iter()
+            .chain(&extents.more)
+            .take(num_extents)
+            .map(|extent| extent.DiskNumber)
+            .collect())
+    }
+
+    /// Get the part of `buf` before the first null character.
+    fn until_null(buf: &[u16]) -> Vec<u16> {
+        buf.iter().copied().take_while(|c| *c != 0).collect()
+    }
+
+    /// Get the paths (drive letters and mounted folders) at which the
+    /// volume named `volume_name` is mounted. `volume_name` is a
+    /// `\\?\Volume{GUID}\` path.
+    fn volume_mount_paths(volume_name: &[u16]) -> io::Result<Vec<OsString>> {
+        let volume_name: Vec<u16> =
+            volume_name.iter().copied().chain([0]).collect();
+        let mut buf = vec![0u16; usize::try_from(MAX_PATH).unwrap()];
+        loop {
+            let mut len = 0;
+            // SAFETY: `volume_name` is null terminated, and `buf` is
+            // valid for writes of its length.
+            let ok = unsafe {
+                GetVolumePathNamesForVolumeNameW(
+                    volume_name.as_ptr(),
+                    buf.as_mut_ptr(),
+                    u32::try_from(buf.len()).unwrap_or(u32::MAX),
+                    &mut len,
+                )
+            };
+            if ok != 0 {
+                break;
+            }
+            // SAFETY: no preconditions.
+            if unsafe { GetLastError() } != ERROR_MORE_DATA {
+                return Err(io::Error::last_os_error());
+            }
+            buf.resize(usize::try_from(len).unwrap_or(usize::MAX), 0);
+        }
+
+        // The paths are a list of null-terminated strings, ending with
+        // an empty string.
+        Ok(buf
+            .split(|c| *c == 0)
+            .take_while(|path| !path.is_empty())
+            .map(OsString::from_wide)
+            .collect())
+    }
+
+    /// Create a buffer of `MAX_PATH` elements for a volume name.
+    fn volume_name_buf() -> Vec<u16> {
+        vec![0; usize::try_from(MAX_PATH).unwrap()]
+    }
+
+    /// Iterator over the names of the volumes on the system, as
+    /// `\\?\Volume{GUID}\` paths without a null terminator.
+    struct Volumes {
+        handle: HANDLE,
+        first: Option<Vec<u16>>,
+    }
+
+    impl Volumes {
+        fn new() -> io::Result<Self> {
+            let mut buf = volume_name_buf();
+            // SAFETY: `buf` is valid for writes of `MAX_PATH` elements.
+            let handle =
+                unsafe { FindFirstVolumeW(buf.as_mut_ptr(), MAX_PATH) };
+            if handle == INVALID_HANDLE_VALUE {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self {
+                handle,
+                first: Some(until_null(&buf)),
+            })
+        }
+    }
+
+    impl Iterator for Volumes {
+        type Item = io::Result<Vec<u16>>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if let Some(first) = self.first.take() {
+                return Some(Ok(first));
+            }
+            let mut buf = volume_name_buf();
+            // SAFETY: `self.handle` is a valid volume search handle, and
+            // `buf` is valid for writes of `MAX_PATH` elements.
+            let ok = unsafe {
+                FindNextVolumeW(self.handle, buf.as_mut_ptr(), MAX_PATH)
+            };
+            if ok != 0 {
+                Some(Ok(until_null(&buf)))
+            // SAFETY: no preconditions.
+            } else if unsafe { GetLastError() } == ERROR_NO_MORE_FILES {
+                None
+            } else {
+                Some(Err(io::Error::last_os_error()))
+            }
+        }
+    }
+
+    impl Drop for Volumes {
+        fn drop(&mut self) {
+            // SAFETY: `self.handle` is a valid volume search handle that
+            // has not been closed.
+            unsafe {
+                FindVolumeClose(self.handle);
+            }
+        }
+    }
+
+    pub(super) fn query_block_sizes(file: &File) -> DeviceBlockSizes {
+        let query = property_query(StorageAccessAlignmentProperty);
+        // SAFETY: this ioctl takes a `STORAGE_PROPERTY_QUERY`. For the
+        // access alignment property, it writes a
+        // `STORAGE_ACCESS_ALIGNMENT_DESCRIPTOR`.
+        let alignment: Option<STORAGE_ACCESS_ALIGNMENT_DESCRIPTOR> =
+            unsafe { ioctl(file, IOCTL_STORAGE_QUERY_PROPERTY, &query).ok() };
+
+        // Not all drivers support the access alignment property, so
+        // fall back to the drive geometry for the logical block size.
+        let logical = alignment
+            .and_then(|a| BlockSize::new(a.BytesPerLogicalSector))
+            .or_else(|| disk_size(file).map(|(block_size, _)| block_size));
+        DeviceBlockSizes {
+            logical,
+            physical: alignment
+                .and_then(|a| BlockSize::new(a.BytesPerPhysicalSector)),
+            // Windows does not report an optimal IO size.
+            optimal_io_size: None,
+        }
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    pub(super) fn list_block_devices() -> Result<Vec<BlockDeviceInfo>, io::Error>
+    {
+        let mut devices = Vec::new();
+        for i in 0..MAX_PHYSICAL_DRIVES {
+            let path = PathBuf::from(format!(r"\\.\PhysicalDrive{i}"));
+            let Ok(file) = open_for_query(&path) else {
+                continue;
+            };
+
+            let size = disk_size(&file);
+            devices.push(BlockDeviceInfo {
+                path,
+                num_bytes: size.map(|(_, num_bytes)| num_bytes),
+                block_size: size.map(|(block_size, _)| block_size),
+                removable: is_removable(&file),
+            });
+        }
+        Ok(devices)
+    }
+
+    pub(super) fn is_device_in_use(path: &Path) -> Result<bool, io::Error> {
+        let Ok(file) = open_for_query(path) else {
+            return Ok(false);
+        };
+        let Ok(disk_number) = disk_number(&file) else {
+            return Ok(false);
+        };
+
+        // Check each mounted volume for an extent on the disk.
+        for volume_name in Volumes::new()? {
+            let volume_name = volume_name?;
+            if volume_mount_paths(&volume_name)?.is_empty() {
+                continue;
+            }
+
+            // The volume can only be opened without the trailing
+            // backslash.
+            let device_path = OsString::from_wide(
+                volume_name
+                    .strip_suffix(&[u16::from(b'\\')])
+                    .unwrap_or(&volume_name),
+            );
+            let Ok(volume) = open_for_query(Path::new(&device_path)) else {
+                continue;
+            };
+            match volume_disk_numbers(&volume) {
+                Ok(disks) if disks.contains(&disk_number) => return Ok(true),
+                // Too many extents to check, so assume the volume may
+                // be on the disk.
+                Err(err)
+                    if err.raw_os_error()
+                        == i32::try_from(ERROR_MORE_DATA).ok() =>
+                {
+                    return Ok(true);
+                }
+                // Other volumes, such as optical discs, don't have
+                // extents.
+                _ => {}
+            }
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::{BlockDeviceInfo, DeviceBlockSizes};
+    use std::fs::{self, File};
+    use std::io;
+    use std::path::Path;
+
+    pub(super) fn query_block_sizes(_file: &File) -> DeviceBlockSizes {
+        DeviceBlockSizes::default()
+    }
+
+    pub(super) fn list_block_devices() -> Result<Vec<BlockDeviceInfo>, io::Error>
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "block device enumeration is not supported on this platform",
+        ))
+    }
+
+    pub(super) fn is_device_in_use(path: &Path) -> Result<bool, io::Error> {
+        // Regular files, such as disk images, are never in use. Anything
+        // else may be a device that can't be checked.
+        if fs::metadata(path)?.is_file() {
+            Ok(false)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "checking whether a device is in use is not supported on \
+                 this platform",
+            ))
+        }
+    }
+}
@@ -6,24 +6,52 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::BlockIo;
+use crate::{
+    hash_lba_range, BlockIo, FilesystemKind, PartitionBlockIo, SubRangeBlockIo,
+};
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
 use bytemuck::{bytes_of, from_bytes};
 use core::fmt::{self, Debug, Display, Formatter};
 use core::mem;
+use core::ops::{Deref, DerefMut};
 use gpt_disk_types::{
-    GptHeader, GptPartitionEntry, GptPartitionEntryArray,
-    GptPartitionEntryArrayError, GptPartitionEntryArrayLayout, Lba,
-    MasterBootRecord,
+    crc, ApplyLayoutPolicy, BlockSize, Crc32, GptDiskLayout, GptHeader,
+    GptLayoutChange, GptPartitionDescription, GptPartitionEntry,
+    GptPartitionEntryArray, GptPartitionEntryArrayError,
+    GptPartitionEntryArrayLayout, GptPartitionTable, GptPartitionTableError,
+    Guid, Lba, LbaRangeInclusive, MasterBootRecord, PartitionIndex, U32Le,
 };
 
+/// Number of bytes zeroed at each end of a range by
+/// [`Disk::wipe_filesystem_signatures`].
+const SIGNATURE_WIPE_SIZE: u64 = 1024 * 1024;
+
+/// Block sizes checked by [`Disk::probe_block_size`].
+const PROBE_BLOCK_SIZES: [BlockSize; 5] = [
+    BlockSize::BS_512,
+    BlockSize::BS_1024,
+    BlockSize::BS_2048,
+    BlockSize::BS_4096,
+    BlockSize::BS_8192,
+];
+
 /// Iterator over entries in a partition entry array.
+///
+/// As many blocks of the array as fit in `block_buf` are read at once.
 struct GptPartitionEntryIter<'disk, 'buf, Io: BlockIo> {
     disk: &'disk mut Disk<Io>,
     block_buf: &'buf mut [u8],
     layout: GptPartitionEntryArrayLayout,
     next_index: u32,
     current_lba: Lba,
-    byte_offset_within_lba: usize,
+    /// Number of blocks currently loaded into `block_buf`.
+    num_loaded_blocks: u64,
+    /// Number of bytes currently loaded into `block_buf`.
+    num_loaded_bytes: usize,
+    /// LBA just past the end of the array.
+    end_lba: u64,
+    byte_offset: usize,
     entry_size: usize,
 }
 
@@ -33,12 +61,22 @@ impl<'disk, 'buf, Io: BlockIo> GptPartitionEntryIter<'disk, 'buf, Io> {
         layout: GptPartitionEntryArrayLayout,
         block_buf: &'buf mut [u8],
     ) -> Result<Self, DiskError<Io::Error>> {
+        let array_num_blocks = layout
+            .num_blocks(disk.io.block_size())
+            .ok_or(DiskError::Overflow)?;
         let mut iter = Self {
             disk,
             block_buf,
             next_index: 0,
             current_lba: layout.start_lba,
-            byte_offset_within_lba: 0,
+            num_loaded_blocks: 0,
+            num_loaded_bytes: 0,
+            end_lba: layout
+                .start_lba
+                .to_u64()
+                .checked_add(array_num_blocks)
+                .ok_or(DiskError::Overflow)?,
+            byte_offset: 0,
             layout,
             entry_size: layout
                 .entry_size
@@ -49,22 +87,39 @@ impl<'disk, 'buf, Io: BlockIo> GptPartitionEntryIter<'disk, 'buf, Io> {
         Ok(iter)
     }
 
+    /// Read as many blocks starting at `lba` as fit in `block_buf`,
+    /// without reading past the end of the array.
     fn set_current_lba(
         &mut self,
         lba: Lba,
     ) -> Result<(), DiskError<Io::Error>> {
+        let block_size = self.disk.io.block_size().to_u64();
+        let buf_num_blocks = u64::try_from(self.block_buf.len())
+            .map_err(|_| DiskError::Overflow)?
+            / block_size;
+        let num_blocks =
+            buf_num_blocks.min(self.end_lba.saturating_sub(lba.to_u64()));
+        let num_bytes = usize::try_from(num_blocks * block_size)
+            .map_err(|_| DiskError::Overflow)?;
+
         self.current_lba = lba;
-        self.byte_offset_within_lba = 0;
-        Ok(self.disk.io.read_blocks(self.current_lba, self.block_buf)?)
+        self.num_loaded_blocks = num_blocks;
+        self.num_loaded_bytes = num_bytes;
+        self.byte_offset = 0;
+        if num_blocks == 0 {
+            return Ok(());
+        }
+        Ok(self
+            .disk
+            .io
+            .read_blocks(self.current_lba, &mut self.block_buf[..num_bytes])?)
     }
 
     fn read_current_entry(&mut self) -> Option<<Self as Iterator>::Item> {
-        let entry_bytes = self.block_buf.get(
-            self.byte_offset_within_lba
-                ..self.byte_offset_within_lba + self.entry_size,
-        )?;
+        let entry_bytes = self.block_buf[..self.num_loaded_bytes]
+            .get(self.byte_offset..self.byte_offset + self.entry_size)?;
 
-        self.byte_offset_within_lba += self.entry_size;
+        self.byte_offset += self.entry_size;
 
         self.next_index += 1;
 
@@ -85,7 +140,8 @@ impl<Io: BlockIo> Iterator for GptPartitionEntryIter<'_, '_, Io> {
         if let Some(entry) = self.read_current_entry() {
             Some(entry)
         } else {
-            let next_lba = Lba(self.current_lba.to_u64() + 1);
+            let next_lba =
+                Lba(self.current_lba.to_u64() + self.num_loaded_blocks);
             if let Err(err) = self.set_current_lba(next_lba) {
                 Some(Err(err))
             } else {
@@ -114,6 +170,76 @@ pub enum DiskError<IoError: Debug + Display> {
     /// The partition entry size is larger than a single block.
     BlockSizeSmallerThanPartitionEntry,
 
+    /// The block size is not supported. See [`Disk::new_strict`].
+    UnsupportedBlockSize,
+
+    /// The partition's LBA range is invalid or extends past the end of
+    /// the disk.
+    InvalidPartitionRange,
+
+    /// The iterator passed to
+    /// [`Disk::write_gpt_partition_entries_from_iter`] produced more
+    /// entries than fit in the layout, or the array read by
+    /// [`Disk::read_gpt`] has used entries past the table's capacity.
+    TooManyPartitionEntries,
+
+    /// The target device is in use, for example because it has mounted
+    /// partitions. See [`DeviceOpenOptions`].
+    ///
+    /// [`DeviceOpenOptions`]: crate::devices::DeviceOpenOptions
+    TargetInUse,
+
+    /// An LBA range extends past the end of the disk.
+    OutOfBounds,
+
+    /// Data read back after [`Disk::move_partition`] does not match the
+    /// data that was copied.
+    VerificationFailed,
+
+    /// Error from a [`GptPartitionTable`] operation.
+    PartitionTable(GptPartitionTableError),
+
+    /// The data passed to [`Disk::write_partition_image`] is larger
+    /// than the partition.
+    ImageTooLarge,
+
+    /// The disk has an MBR with partitions other than a protective
+    /// partition. See [`Disk::check_existing_partitioning`].
+    ExistingMbrPartitions,
+
+    /// The disk has a GPT with a different disk GUID. See
+    /// [`Disk::check_existing_partitioning`].
+    ExistingGpt {
+        /// Disk GUID of the existing GPT.
+        disk_guid: Guid,
+    },
+
+    /// There is no primary GPT header at LBA 1, but there is one where
+    /// LBA 1 would be with a different block size. This usually means
+    /// a disk image was opened with the wrong block size, for example
+    /// a 4Kn image opened with 512-byte blocks. See
+    /// [`Disk::check_block_size`].
+    ProbableBlockSizeMismatch {
+        /// Block size at which the primary header was found.
+        suggested: BlockSize,
+    },
+
+    /// The first block does not contain a protective MBR. See
+    /// [`Disk::validate_gpt`].
+    MissingProtectiveMbr,
+
+    /// The GPT header is not valid. See [`Disk::validate_gpt`] and
+    /// [`Disk::read_gpt`].
+    InvalidGptHeader,
+
+    /// The disk does not have room for the secondary partition entry
+    /// array and header after the primary array and the usable range.
+    /// See [`Disk::update_and_write_gpt`].
+    DiskTooSmall {
+        /// Smallest number of blocks that the disk needs.
+        min_num_blocks: u64,
+    },
+
     /// Error from a [`BlockIo`] implementation (see [`BlockIo::Error`]).
     ///
     /// [`BlockIo`]: crate::BlockIo
@@ -141,11 +267,95 @@ where
             Self::BlockSizeSmallerThanPartitionEntry => {
                 f.write_str("partition entries are larger than a single block")
             }
+            Self::UnsupportedBlockSize => {
+                f.write_str("block size is not supported")
+            }
+            Self::InvalidPartitionRange => {
+                f.write_str("partition range is invalid")
+            }
+            Self::TooManyPartitionEntries => {
+                f.write_str("too many entries for the partition entry array")
+            }
+            Self::TargetInUse => f.write_str("target device is in use"),
+            Self::OutOfBounds => {
+                f.write_str("LBA range extends past the end of the disk")
+            }
+            Self::VerificationFailed => {
+                f.write_str("copied data does not match the source")
+            }
+            Self::PartitionTable(err) => Display::fmt(err, f),
+            Self::ImageTooLarge => {
+                f.write_str("image is larger than the partition")
+            }
+            Self::ExistingMbrPartitions => {
+                f.write_str("disk has existing MBR partitions")
+            }
+            Self::ExistingGpt { disk_guid } => {
+                write!(f, "disk has an existing GPT with disk GUID {disk_guid}")
+            }
+            Self::ProbableBlockSizeMismatch { suggested } => write!(
+                f,
+                "GPT header not found at LBA 1, block size is probably \
+                 {suggested} bytes"
+            ),
+            Self::MissingProtectiveMbr => {
+                f.write_str("disk does not have a protective MBR")
+            }
+            Self::InvalidGptHeader => f.write_str("GPT header is not valid"),
+            Self::DiskTooSmall { min_num_blocks } => write!(
+                f,
+                "disk is too small for the GPT (needs at least \
+                 {min_num_blocks} blocks)"
+            ),
             Self::Io(io) => Display::fmt(io, f),
         }
     }
 }
 
+/// GPT header returned by [`Disk::read_gpt_header_checked`], along with
+/// the results of its CRC32 checks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct CheckedGptHeader {
+    /// The header as read from the disk, whether or not the checks
+    /// passed.
+    pub header: GptHeader,
+
+    /// Whether [`GptHeader::header_crc32`] matches the header.
+    pub header_crc32_valid: bool,
+
+    /// Whether [`GptHeader::partition_entry_array_crc32`] matches the
+    /// partition entry array.
+    pub partition_entry_array_crc32_valid: bool,
+}
+
+impl CheckedGptHeader {
+    /// Whether both CRC32 checks passed.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.header_crc32_valid && self.partition_entry_array_crc32_valid
+    }
+}
+
+/// Options for [`Disk::validate_gpt`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct GptValidationOptions {
+    /// Whether to require a protective MBR in the first block.
+    ///
+    /// Some firmware places a GPT at the start of a partition or file
+    /// without an MBR. Set this to false to validate such a table;
+    /// LBA 0 is then treated as reserved, and its contents are
+    /// ignored.
+    pub check_protective_mbr: bool,
+}
+
+impl Default for GptValidationOptions {
+    fn default() -> Self {
+        Self {
+            check_protective_mbr: true,
+        }
+    }
+}
+
 /// Read and write GPT disk data.
 ///
 /// The disk is accessed via an object implementing the [`BlockIo`]
@@ -153,7 +363,9 @@ where
 /// not guaranteed to be completed until [`flush`] is called. This
 /// happens automatically when the `Disk` is dropped, but if an error
 /// occurs at that point it will be silently ignored so it is
-/// recommended to call [`flush`] directly before dropping the disk.
+/// recommended to call [`flush`] or [`close`] directly before dropping
+/// the disk. The flush on drop can be disabled with
+/// [`with_flush_on_drop`].
 ///
 /// Many of the methods on `Disk` take a `block_buf` argument, which is
 /// a mutable byte buffer with a length of at least one block. (The
@@ -170,22 +382,193 @@ where
 /// amount of memory usage bounded to the block size, while the latter
 /// may be more efficient since all the blocks can be read at once.
 ///
-/// Writing the array can currently only be done all at once via
-/// [`write_gpt_partition_entry_array`]; a block-at-a-time method may be
-/// added in the future.
+/// Similarly, the array can be written all at once with
+/// [`write_gpt_partition_entry_array`], or one block at a time from an
+/// iterator with [`write_gpt_partition_entries_from_iter`].
 ///
+/// [`close`]: Self::close
 /// [`flush`]: Self::flush
 /// [`gpt_partition_entry_array_iter`]: Self::gpt_partition_entry_array_iter
 /// [`read_gpt_partition_entry_array`]: Self::read_gpt_partition_entry_array
+/// [`write_gpt_partition_entries_from_iter`]: Self::write_gpt_partition_entries_from_iter
 /// [`write_gpt_partition_entry_array`]: Self::write_gpt_partition_entry_array
+/// [`with_flush_on_drop`]: Self::with_flush_on_drop
 pub struct Disk<Io: BlockIo> {
-    io: Io,
+    io: FlushOnDrop<Io>,
+    /// Cached result of [`BlockIo::num_blocks`].
+    num_blocks: Option<u64>,
+}
+
+/// Owner of a [`Disk`]'s [`BlockIo`] that flushes it when dropped, if
+/// `enabled` is true.
+///
+/// Keeping the `Drop` impl here rather than on `Disk` allows the
+/// `BlockIo` to be moved out of a `Disk` with [`into_inner`].
+///
+/// [`into_inner`]: Self::into_inner
+struct FlushOnDrop<Io: BlockIo> {
+    /// Always `Some`, except during [`into_inner`].
+    ///
+    /// [`into_inner`]: Self::into_inner
+    io: Option<Io>,
+    enabled: bool,
+}
+
+impl<Io: BlockIo> FlushOnDrop<Io> {
+    fn new(io: Io, enabled: bool) -> Self {
+        Self {
+            io: Some(io),
+            enabled,
+        }
+    }
+
+    /// Return the `BlockIo` without flushing it.
+    fn into_inner(mut self) -> Io {
+        // OK to unwrap: the `BlockIo` is only taken here, and this
+        // consumes `self`.
+        self.io.take().unwrap()
+    }
+}
+
+impl<Io: BlockIo> Deref for FlushOnDrop<Io> {
+    type Target = Io;
+
+    fn deref(&self) -> &Io {
+        // OK to unwrap: see the `io` field.
+        self.io.as_ref().unwrap()
+    }
+}
+
+impl<Io: BlockIo> DerefMut for FlushOnDrop<Io> {
+    fn deref_mut(&mut self) -> &mut Io {
+        // OK to unwrap: see the `io` field.
+        self.io.as_mut().unwrap()
+    }
+}
+
+impl<Io: BlockIo> Drop for FlushOnDrop<Io> {
+    fn drop(&mut self) {
+        if let (true, Some(io)) = (self.enabled, &mut self.io) {
+            // Throw away any errors.
+            let _r = io.flush();
+        }
+    }
+}
+
+impl<'io, Io: BlockIo> Disk<&'io mut Io> {
+    /// Create a `Disk` that borrows `io` rather than taking ownership
+    /// of it.
+    ///
+    /// This allows a long-lived [`BlockIo`] to be temporarily used as a
+    /// `Disk`. The `Disk` is flushed when it is dropped, after which
+    /// `io` can be used directly again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_io::gpt_disk_types::{BlockSize, GptHeader, Lba};
+    /// use gpt_disk_io::{BlockIo, BlockIoAdapter, Disk, DiskError};
+    ///
+    /// let mut data = vec![0; 512 * 8];
+    /// let mut io = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    /// let mut block_buf = vec![0; 512];
+    ///
+    /// {
+    ///     let mut disk = Disk::from_mut(&mut io)?;
+    ///     disk.write_primary_gpt_header(&GptHeader::default(), &mut block_buf)?;
+    ///     disk.flush()?;
+    /// }
+    ///
+    /// // The backend is still available for other IO.
+    /// io.read_blocks(Lba(1), &mut block_buf)?;
+    /// assert_eq!(&block_buf[..8], b"EFI PART");
+    /// # Ok::<(), DiskError<gpt_disk_io::SliceBlockIoError>>(())
+    /// ```
+    pub fn from_mut(io: &'io mut Io) -> Result<Self, DiskError<Io::Error>> {
+        Self::new(io)
+    }
 }
 
 impl<Io: BlockIo> Disk<Io> {
     /// Create a `Disk`.
     pub fn new(io: Io) -> Result<Self, DiskError<Io::Error>> {
-        Ok(Self { io })
+        Ok(Self {
+            io: FlushOnDrop::new(io, true),
+            num_blocks: None,
+        })
+    }
+
+    /// Create a `Disk`, rejecting unusual block sizes.
+    ///
+    /// This is the same as [`new`], except that
+    /// [`DiskError::UnsupportedBlockSize`] is returned if the block size
+    /// is not accepted by [`BlockSize::new_strict`]; that is, if it is
+    /// not a power of two or is larger than 64 KiB.
+    ///
+    /// [`new`]: Self::new
+    /// [`BlockSize::new_strict`]: gpt_disk_types::BlockSize::new_strict
+    pub fn new_strict(io: Io) -> Result<Self, DiskError<Io::Error>> {
+        if BlockSize::new_strict(io.block_size().to_u32()).is_none() {
+            return Err(DiskError::UnsupportedBlockSize);
+        }
+        Self::new(io)
+    }
+
+    /// Set whether the disk is flushed when it is dropped. The default
+    /// is `true`.
+    ///
+    /// Disabling this avoids a redundant flush for backends where
+    /// flushing is meaningless or has already been done explicitly.
+    /// See also [`close`].
+    ///
+    /// [`close`]: Self::close
+    #[must_use]
+    pub fn with_flush_on_drop(mut self, flush_on_drop: bool) -> Self {
+        self.io.enabled = flush_on_drop;
+        self
+    }
+
+    /// Get the physical block size of the underlying [`BlockIo`], if
+    /// known. Partitions should be aligned to this size for best
+    /// performance. See [`BlockIo::physical_block_size`].
+    #[must_use]
+    pub fn physical_block_size(&self) -> Option<BlockSize> {
+        self.io.physical_block_size()
+    }
+
+    /// Get the optimal IO size in bytes of the underlying [`BlockIo`],
+    /// if known. See [`BlockIo::optimal_io_size`].
+    #[must_use]
+    pub fn optimal_io_size(&self) -> Option<u32> {
+        self.io.optimal_io_size()
+    }
+
+    /// Get the number of blocks in the underlying [`BlockIo`].
+    ///
+    /// The value is queried from [`BlockIo::num_blocks`] on first use
+    /// and then cached, since the query may be slow; for example, the
+    /// `std` backend seeks to the end of the file. If the size of the
+    /// storage can change, e.g. a growable `Vec` or a file that is
+    /// resized, call [`refresh_num_blocks`] after it changes.
+    ///
+    /// [`refresh_num_blocks`]: Self::refresh_num_blocks
+    pub fn num_blocks(&mut self) -> Result<u64, DiskError<Io::Error>> {
+        if let Some(num_blocks) = self.num_blocks {
+            Ok(num_blocks)
+        } else {
+            self.refresh_num_blocks()
+        }
+    }
+
+    /// Query the number of blocks in the underlying [`BlockIo`] again,
+    /// replacing the value cached by [`num_blocks`].
+    ///
+    /// [`num_blocks`]: Self::num_blocks
+    pub fn refresh_num_blocks(&mut self) -> Result<u64, DiskError<Io::Error>> {
+        self.num_blocks = None;
+        let num_blocks = self.io.num_blocks()?;
+        self.num_blocks = Some(num_blocks);
+        Ok(num_blocks)
     }
 
     /// Clip the size of `block_buf` to a single block. Return
@@ -205,6 +588,12 @@ impl<Io: BlockIo> Disk<Io> {
 
     /// Read the primary GPT header from the second block. No validation
     /// of the header is performed.
+    ///
+    /// If the header's signature is not valid, [`check_block_size`] can
+    /// be used to check whether the disk was opened with the wrong
+    /// block size.
+    ///
+    /// [`check_block_size`]: Self::check_block_size
     pub fn read_primary_gpt_header(
         &mut self,
         block_buf: &mut [u8],
@@ -212,6 +601,129 @@ impl<Io: BlockIo> Disk<Io> {
         self.read_gpt_header(Lba(1), block_buf)
     }
 
+    /// Find the block size of the GPT on the disk, for example to open
+    /// an image of unknown geometry.
+    ///
+    /// The primary GPT header is always in the second block, so its
+    /// byte offset is the block size the GPT was created with. The
+    /// disk's own block size is tried first, followed by the other
+    /// common block sizes from 512 through 8192 bytes. Returns the
+    /// first block size at which a header with a valid signature and a
+    /// [`my_lba`] of 1 is found, or `None` if there is no header.
+    ///
+    /// Any block size can be used to probe, since block sizes smaller
+    /// than the disk's are found at an offset within the first block.
+    /// The disk can then be reopened with the returned block size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_io::gpt_disk_types::{guid, BlockSize, GptDiskLayout};
+    /// use gpt_disk_io::{BlockIoAdapter, Disk};
+    ///
+    /// let mut data = vec![0; 4096 * 64];
+    /// let mut block_buf = vec![0; 4096];
+    ///
+    /// // Create a GPT with 4096-byte blocks.
+    /// let mut disk =
+    ///     Disk::new(BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_4096)).unwrap();
+    /// let layout = GptDiskLayout::new(BlockSize::BS_4096, 64).unwrap();
+    /// let header = layout.primary_header(guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870"));
+    /// disk.write_primary_gpt_header(&header, &mut block_buf).unwrap();
+    /// drop(disk);
+    ///
+    /// // Probe with 512-byte blocks.
+    /// let mut disk =
+    ///     Disk::new(BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512)).unwrap();
+    /// assert_eq!(disk.probe_block_size(&mut block_buf).unwrap(), Some(BlockSize::BS_4096));
+    /// ```
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`my_lba`]: GptHeader::my_lba
+    pub fn probe_block_size(
+        &mut self,
+        block_buf: &mut [u8],
+    ) -> Result<Option<BlockSize>, DiskError<Io::Error>> {
+        let block_size = self.io.block_size();
+        let candidates = core::iter::once(block_size).chain(
+            PROBE_BLOCK_SIZES.into_iter().filter(|bs| *bs != block_size),
+        );
+        for candidate in candidates {
+            if self.has_primary_gpt_header_at(candidate.to_u64(), block_buf)? {
+                log_debug!(
+                    "found primary GPT header with block size {candidate}"
+                );
+                return Ok(Some(candidate));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Check that the disk's block size matches the GPT on it.
+    ///
+    /// If there is no valid header signature at LBA 1, but
+    /// [`probe_block_size`] finds a primary header with a different
+    /// block size, returns [`DiskError::ProbableBlockSizeMismatch`]
+    /// with that block size.
+    ///
+    /// Returns `Ok` if the header at LBA 1 is valid, and also if no
+    /// header is found at all, e.g. on a blank disk.
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`probe_block_size`]: Self::probe_block_size
+    pub fn check_block_size(
+        &mut self,
+        block_buf: &mut [u8],
+    ) -> Result<(), DiskError<Io::Error>> {
+        let block_size = self.io.block_size();
+        if self.num_blocks()? < 2
+            || self
+                .read_primary_gpt_header(block_buf)?
+                .is_signature_valid()
+        {
+            return Ok(());
+        }
+
+        match self.probe_block_size(block_buf)? {
+            Some(suggested) if suggested != block_size => {
+                log_debug!(
+                    "block size mismatch: disk uses {block_size}, GPT \
+                     suggests {suggested}"
+                );
+                Err(DiskError::ProbableBlockSizeMismatch { suggested })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check whether there is a primary GPT header (a header with a
+    /// valid signature and a `my_lba` of 1) at `byte_offset`.
+    fn has_primary_gpt_header_at(
+        &mut self,
+        byte_offset: u64,
+        block_buf: &mut [u8],
+    ) -> Result<bool, DiskError<Io::Error>> {
+        let block_buf = self.clip_block_buf_size(block_buf)?;
+        let block_size = self.io.block_size().to_u64();
+        let lba = byte_offset / block_size;
+        if lba >= self.num_blocks()? {
+            return Ok(false);
+        }
+        let offset = usize::try_from(byte_offset % block_size)
+            .map_err(|_| DiskError::Overflow)?;
+
+        self.io.read_blocks(Lba(lba), block_buf)?;
+        let Some(bytes) =
+            block_buf.get(offset..offset + mem::size_of::<GptHeader>())
+        else {
+            return Ok(false);
+        };
+        let header: &GptHeader = from_bytes(bytes);
+        Ok(header.is_signature_valid() && header.my_lba.to_u64() == 1)
+    }
+
     /// Read the secondary GPT header from the last block. No validation
     /// of the header is performed.
     ///
@@ -220,7 +732,7 @@ impl<Io: BlockIo> Disk<Io> {
         &mut self,
         block_buf: &mut [u8],
     ) -> Result<GptHeader, DiskError<Io::Error>> {
-        let num_blocks = self.io.num_blocks()?;
+        let num_blocks = self.num_blocks()?;
         let last_block =
             Lba(num_blocks.checked_sub(1).ok_or(DiskError::Overflow)?);
         self.read_gpt_header(last_block, block_buf)
@@ -236,6 +748,7 @@ impl<Io: BlockIo> Disk<Io> {
         mut block_buf: &mut [u8],
     ) -> Result<GptHeader, DiskError<Io::Error>> {
         block_buf = self.clip_block_buf_size(block_buf)?;
+        log_debug!("reading GPT header at LBA {lba}");
         self.io.read_blocks(lba, block_buf)?;
         let bytes = block_buf
             .get(..mem::size_of::<GptHeader>())
@@ -245,6 +758,401 @@ impl<Io: BlockIo> Disk<Io> {
         Ok(*from_bytes(bytes))
     }
 
+    /// Read a GPT header at the given [`Lba`], along with the bytes
+    /// after the [`GptHeader`] struct that are covered by the header's
+    /// [`header_size`]. No validation of the header is performed.
+    ///
+    /// This crate does not model the extra bytes of a header larger
+    /// than the struct. Keeping the returned tail and passing it to
+    /// [`write_gpt_header_with_tail`] preserves them when rewriting a
+    /// header created by another tool. The tail is clipped to the end
+    /// of the block, and is empty if `header_size` is not larger than
+    /// the struct.
+    ///
+    /// The returned tail is a slice of `block_buf`, which is a mutable
+    /// byte buffer with a length of at least one block.
+    ///
+    /// [`header_size`]: GptHeader::header_size
+    /// [`write_gpt_header_with_tail`]: Self::write_gpt_header_with_tail
+    pub fn read_gpt_header_with_tail<'buf>(
+        &mut self,
+        lba: Lba,
+        block_buf: &'buf mut [u8],
+    ) -> Result<(GptHeader, &'buf [u8]), DiskError<Io::Error>> {
+        let block_buf = self.clip_block_buf_size(block_buf)?;
+        let header = self.read_gpt_header(lba, block_buf)?;
+
+        let struct_size = mem::size_of::<GptHeader>();
+        let header_size = usize::try_from(header.header_size.to_u32())
+            .unwrap_or(usize::MAX)
+            .clamp(struct_size, block_buf.len());
+        Ok((header, &block_buf[struct_size..header_size]))
+    }
+
+    /// Read a GPT header at the given [`Lba`] and check its CRC32 and the
+    /// CRC32 of the partition entry array it points to.
+    ///
+    /// A failed check is not an error: the header is returned along with
+    /// the result of each check in a [`CheckedGptHeader`], so that
+    /// recovery tools can inspect a corrupted header. The array check
+    /// fails if the header's partition entry size is invalid or the
+    /// array extends past the end of the disk.
+    ///
+    /// The header CRC32 covers the header's [`header_size`] bytes, so
+    /// headers larger than the [`GptHeader`] struct are checked
+    /// correctly.
+    ///
+    /// `block_buf` must be a non-empty multiple of the block size. A
+    /// larger buffer allows more blocks of the array to be read at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `block_buf` is not a multiple of the
+    /// block size.
+    ///
+    /// [`header_size`]: GptHeader::header_size
+    pub fn read_gpt_header_checked(
+        &mut self,
+        lba: Lba,
+        block_buf: &mut [u8],
+    ) -> Result<CheckedGptHeader, DiskError<Io::Error>> {
+        let (header, tail) = self.read_gpt_header_with_tail(lba, block_buf)?;
+        let header_crc32_valid = header.calculate_header_crc32_with_tail(tail)
+            == header.header_crc32;
+
+        let num_blocks = self.num_blocks()?;
+        let mut partition_entry_array_crc32_valid = false;
+        if let Ok(layout) = header.get_partition_entry_array_layout() {
+            let end_lba = layout
+                .num_blocks(self.io.block_size())
+                .and_then(|n| layout.start_lba.to_u64().checked_add(n));
+            if end_lba.map_or(false, |end| end <= num_blocks) {
+                let crc32 = self.calculate_gpt_partition_entry_array_crc32(
+                    layout, block_buf,
+                )?;
+                partition_entry_array_crc32_valid =
+                    crc32 == header.partition_entry_array_crc32;
+            }
+        }
+
+        if !header_crc32_valid {
+            log_debug!("invalid header CRC32 in GPT header at LBA {lba}");
+        }
+        if !partition_entry_array_crc32_valid {
+            log_debug!(
+                "invalid partition entry array CRC32 in GPT header at LBA \
+                 {lba}"
+            );
+        }
+
+        Ok(CheckedGptHeader {
+            header,
+            header_crc32_valid,
+            partition_entry_array_crc32_valid,
+        })
+    }
+
+    /// Check that the disk has a valid GPT, and return the primary
+    /// header.
+    ///
+    /// The following checks are made:
+    ///
+    /// * Unless disabled in `options`, the first block must contain an
+    ///   MBR with a valid signature and a protective (`0xee`) partition
+    ///   record. Otherwise, returns [`DiskError::MissingProtectiveMbr`].
+    /// * The primary header at LBA 1 must have a valid signature, a
+    ///   `my_lba` of 1, valid CRC32s (see
+    ///   [`read_gpt_header_checked`]), and a partition entry array in a
+    ///   valid location (see
+    ///   [`GptHeader::check_partition_entry_array_location`]).
+    ///   Otherwise, returns [`DiskError::InvalidGptHeader`].
+    ///
+    /// `block_buf` must be a non-empty multiple of the block size. A
+    /// larger buffer allows more blocks of the array to be read at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `block_buf` is not a multiple of the
+    /// block size.
+    ///
+    /// [`read_gpt_header_checked`]: Self::read_gpt_header_checked
+    pub fn validate_gpt(
+        &mut self,
+        options: GptValidationOptions,
+        block_buf: &mut [u8],
+    ) -> Result<GptHeader, DiskError<Io::Error>> {
+        if options.check_protective_mbr {
+            let mbr_block = self.clip_block_buf_size(block_buf)?;
+            self.io.read_blocks(Lba(0), mbr_block)?;
+            let mbr: &MasterBootRecord =
+                from_bytes(&mbr_block[..mem::size_of::<MasterBootRecord>()]);
+            let has_protective_record = mbr
+                .partitions
+                .iter()
+                .any(|record| record.os_indicator == 0xee);
+            if mbr.signature != [0x55, 0xaa] || !has_protective_record {
+                log_debug!("missing protective MBR");
+                return Err(DiskError::MissingProtectiveMbr);
+            }
+        }
+
+        self.read_valid_gpt_header(Lba(1), block_buf)
+    }
+
+    /// Read the GPT header at `header_lba` and its partition entry
+    /// array, and return the header along with a [`GptPartitionTable`]
+    /// containing the first `N` entries of the array.
+    ///
+    /// Unlike [`read_primary_gpt_header`] and
+    /// [`read_secondary_gpt_header`], the header may be at any
+    /// location. This is useful for recovering from a damaged primary
+    /// header by reading the secondary one, or for disks where the
+    /// header is not at the conventional location.
+    ///
+    /// The header must have a valid signature, a `my_lba` matching
+    /// `header_lba`, valid CRC32s (see [`read_gpt_header_checked`]),
+    /// and a partition entry array in a valid location (see
+    /// [`GptHeader::check_partition_entry_array_location`]). Otherwise,
+    /// returns [`DiskError::InvalidGptHeader`].
+    ///
+    /// The table's [`GptDiskLayout`] is calculated from the header's
+    /// entry count and entry size, and from the disk size implied by
+    /// the header's `my_lba` and `alternate_lba`. The layout reserves
+    /// the spec's minimum array size unless the header's
+    /// `first_usable_lba` is too early for it (see
+    /// [`GptDiskLayout::new_with_options`]), and any remaining space
+    /// between the arrays and the header's usable range is reserved
+    /// (see [`GptDiskLayout::reserve_leading_bytes`]). If the header
+    /// uses a nonstandard array location, the layout describes the
+    /// conventional locations instead; use the returned header for the
+    /// actual ones.
+    ///
+    /// If `N` is larger than the number of entries in the array,
+    /// returns [`GptPartitionTableError::CapacityTooLarge`]. If it is
+    /// smaller and any of the entries past the first `N` are used,
+    /// returns [`DiskError::TooManyPartitionEntries`].
+    ///
+    /// `block_buf` must be a non-empty multiple of the block size. A
+    /// larger buffer allows more blocks of the array to be read at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `block_buf` is not a multiple of the
+    /// block size.
+    ///
+    /// [`read_gpt_header_checked`]: Self::read_gpt_header_checked
+    /// [`read_primary_gpt_header`]: Self::read_primary_gpt_header
+    /// [`read_secondary_gpt_header`]: Self::read_secondary_gpt_header
+    pub fn read_gpt<const N: usize>(
+        &mut self,
+        header_lba: Lba,
+        block_buf: &mut [u8],
+    ) -> Result<(GptHeader, GptPartitionTable<N>), DiskError<Io::Error>> {
+        let header = self.read_valid_gpt_header(header_lba, block_buf)?;
+        let array_layout = header
+            .get_partition_entry_array_layout()
+            .map_err(|_| DiskError::InvalidGptHeader)?;
+
+        let last_lba =
+            header.my_lba.to_u64().max(header.alternate_lba.to_u64());
+        let num_blocks = last_lba.checked_add(1).ok_or(DiskError::Overflow)?;
+        let new_layout = |reserve_min_array_size| {
+            GptDiskLayout::new_with_options(
+                self.io.block_size(),
+                num_blocks,
+                array_layout.entry_size,
+                array_layout.num_entries,
+                reserve_min_array_size,
+            )
+        };
+        // Use the spec-compliant layout unless the header's usable range
+        // starts before it, which means the arrays are smaller than the
+        // minimum size. Any other difference between the layout's
+        // usable range and the header's is reserved space.
+        let first_usable_lba = header.first_usable_lba.to_u64();
+        let last_usable_lba = header.last_usable_lba.to_u64();
+        let layout = match new_layout(true) {
+            Ok(layout)
+                if layout.first_usable_lba().to_u64() <= first_usable_lba =>
+            {
+                layout
+            }
+            _ => new_layout(false).map_err(|_| DiskError::InvalidGptHeader)?,
+        };
+        let block_size = self.io.block_size().to_u64();
+        let reserved_bytes = |num_blocks: u64| {
+            num_blocks
+                .checked_mul(block_size)
+                .ok_or(DiskError::Overflow)
+        };
+        let leading = reserved_bytes(
+            first_usable_lba.saturating_sub(layout.first_usable_lba().to_u64()),
+        )?;
+        let trailing = reserved_bytes(
+            layout
+                .last_usable_lba()
+                .to_u64()
+                .saturating_sub(last_usable_lba),
+        )?;
+        let layout = layout
+            .reserve_leading_bytes(leading)
+            .and_then(|l| l.reserve_trailing_bytes(trailing))
+            .map_err(|_| DiskError::InvalidGptHeader)?;
+
+        let mut entries = [GptPartitionEntry::default(); N];
+        let mut iter =
+            self.gpt_partition_entry_array_iter(array_layout, block_buf)?;
+        for (dst, src) in entries.iter_mut().zip(&mut iter) {
+            *dst = src?;
+        }
+        // Dropping used entries would lose those partitions if the
+        // table is written back, so they must all fit.
+        for entry in iter {
+            if entry?.is_used() {
+                return Err(DiskError::TooManyPartitionEntries);
+            }
+        }
+
+        let table =
+            GptPartitionTable::from_entries(layout, header.disk_guid, entries)
+                .map_err(DiskError::PartitionTable)?;
+        Ok((header, table))
+    }
+
+    /// Check whether the primary GPT header on the disk matches the one
+    /// that `table` would produce (see
+    /// [`GptPartitionTable::primary_header`]).
+    ///
+    /// This is a quick way to confirm that a cached table, for example
+    /// one deserialized with the `serde` feature of `gpt_disk_types`,
+    /// still matches the disk. Only the header block is read: the
+    /// header contains the CRC32 of the partition entry array, and its
+    /// own CRC32 covers that, so any change to the partitions also
+    /// changes the header.
+    ///
+    /// Returns `false` if the header differs in any way, including if
+    /// the disk's GPT was written with a layout other than the table's
+    /// layout. In that case the table should be read again, for example
+    /// with [`read_gpt`].
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`read_gpt`]: Self::read_gpt
+    pub fn verify_matches<const N: usize>(
+        &mut self,
+        table: &GptPartitionTable<N>,
+        block_buf: &mut [u8],
+    ) -> Result<bool, DiskError<Io::Error>> {
+        if table.layout().block_size() != self.io.block_size() {
+            return Ok(false);
+        }
+        let header = self.read_primary_gpt_header(block_buf)?;
+        Ok(header == table.primary_header())
+    }
+
+    /// Get the header CRC32 and partition entry array CRC32 from the
+    /// primary GPT header, in that order.
+    ///
+    /// This is a cheap fingerprint of the partition table: only the
+    /// header block is read, but the header CRC32 changes whenever the
+    /// header or the partition entries change. A poller can compare it
+    /// with the previous value to detect changes without parsing the
+    /// table. See also [`verify_matches`].
+    ///
+    /// The header is not validated. If the disk has no GPT, the values
+    /// are whatever bytes are in the CRC32 fields' locations.
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`verify_matches`]: Self::verify_matches
+    pub fn gpt_generation(
+        &mut self,
+        block_buf: &mut [u8],
+    ) -> Result<(Crc32, Crc32), DiskError<Io::Error>> {
+        let header = self.read_primary_gpt_header(block_buf)?;
+        Ok((header.header_crc32, header.partition_entry_array_crc32))
+    }
+
+    /// Read the GPT header at `lba` and check that it is valid. See
+    /// [`read_gpt`] for the checks.
+    ///
+    /// [`read_gpt`]: Self::read_gpt
+    fn read_valid_gpt_header(
+        &mut self,
+        lba: Lba,
+        block_buf: &mut [u8],
+    ) -> Result<GptHeader, DiskError<Io::Error>> {
+        let checked = self.read_gpt_header_checked(lba, block_buf)?;
+        let header = checked.header;
+        if !header.is_signature_valid()
+            || header.my_lba.to_u64() != lba.to_u64()
+            || !checked.is_valid()
+        {
+            log_debug!("invalid GPT header at LBA {lba}: {header}");
+            return Err(DiskError::InvalidGptHeader);
+        }
+        header
+            .check_partition_entry_array_location(self.io.block_size())
+            .map_err(|_| {
+                log_debug!(
+                    "invalid partition entry array location in GPT header \
+                     at LBA {lba}"
+                );
+                DiskError::InvalidGptHeader
+            })?;
+        Ok(header)
+    }
+
+    /// Check that the bytes following the GPT header at the given
+    /// [`Lba`] are zero.
+    ///
+    /// The UEFI Specification reserves the rest of the header's block
+    /// after [`header_size`] bytes, and requires it to be zero. Some
+    /// firmware fails to boot from a disk with other data there, even
+    /// though the header itself is valid.
+    ///
+    /// If `header_size` is smaller than the [`GptHeader`] struct, the
+    /// check starts after the struct instead.
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`header_size`]: GptHeader::header_size
+    pub fn is_gpt_header_block_remainder_zero(
+        &mut self,
+        lba: Lba,
+        block_buf: &mut [u8],
+    ) -> Result<bool, DiskError<Io::Error>> {
+        let header = self.read_gpt_header(lba, block_buf)?;
+        let block_buf = self.clip_block_buf_size(block_buf)?;
+        let header_size = usize::try_from(header.header_size.to_u32())
+            .unwrap_or(usize::MAX)
+            .max(mem::size_of::<GptHeader>());
+        let remainder = block_buf.get(header_size..).unwrap_or_default();
+        Ok(remainder.iter().all(|b| *b == 0))
+    }
+
+    /// Check that the bytes following the MBR in the first block are
+    /// zero.
+    ///
+    /// This only applies to disks with a block size larger than 512
+    /// bytes. The MBR only occupies the first 512 bytes of the block;
+    /// the rest should be zero, as written by [`write_mbr`].
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`write_mbr`]: Self::write_mbr
+    pub fn is_mbr_block_remainder_zero(
+        &mut self,
+        block_buf: &mut [u8],
+    ) -> Result<bool, DiskError<Io::Error>> {
+        let block_buf = self.clip_block_buf_size(block_buf)?;
+        self.io.read_blocks(Lba(0), block_buf)?;
+        let remainder = block_buf
+            .get(mem::size_of::<MasterBootRecord>()..)
+            .unwrap_or_default();
+        Ok(remainder.iter().all(|b| *b == 0))
+    }
+
     /// Read the entire partition entry array. The `storage` buffer must
     /// be at least [`layout.num_bytes_rounded_to_block`] in size.
     ///
@@ -264,6 +1172,7 @@ impl<Io: BlockIo> Disk<Io> {
                         DiskError::Overflow
                     }
                 })?;
+        log_debug!("reading GPT partition entry array: {layout}");
         self.io
             .read_blocks(layout.start_lba, entry_array.storage_mut())?;
         Ok(entry_array)
@@ -274,32 +1183,167 @@ impl<Io: BlockIo> Disk<Io> {
         &mut self,
         entry_array: &GptPartitionEntryArray,
     ) -> Result<(), DiskError<Io::Error>> {
+        log_debug!(
+            "writing GPT partition entry array: {}",
+            entry_array.layout()
+        );
         Ok(self.io.write_blocks(
             entry_array.layout().start_lba,
             entry_array.storage(),
         )?)
     }
 
-    /// Get an iterator over partition entries. The `layout` parameter
-    /// indicates where to read the entries from; see
-    /// [`GptPartitionEntryArrayLayout`] for more.
+    /// Calculate the CRC32 of the partition entry array at `layout` by
+    /// streaming it from disk, without a buffer for the whole array.
+    /// The result can be compared with or set in the
+    /// [`GptHeader::partition_entry_array_crc32`] field.
+    ///
+    /// `buf` must be a non-empty multiple of the block size. A larger
+    /// buffer allows more blocks to be read at once.
+    ///
+    /// See also [`GptPartitionEntryArrayLayout::calculate_crc32`] for
+    /// calculating the CRC32 from an iterator of entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` is not a multiple of the block
+    /// size.
+    pub fn calculate_gpt_partition_entry_array_crc32(
+        &mut self,
+        layout: GptPartitionEntryArrayLayout,
+        buf: &mut [u8],
+    ) -> Result<Crc32, DiskError<Io::Error>> {
+        let block_size = self.io.block_size();
+        let num_bytes_exact =
+            layout.num_bytes_exact().ok_or(DiskError::Overflow)?;
+        let num_blocks =
+            layout.num_blocks(block_size).ok_or(DiskError::Overflow)?;
+
+        let crc = crc::Crc::<u32>::new(&Crc32::ALGORITHM);
+        let mut digest = crc.digest();
+        if num_blocks > 0 {
+            let end_lba = layout
+                .start_lba
+                .to_u64()
+                .checked_add(num_blocks - 1)
+                .ok_or(DiskError::Overflow)?;
+            // OK to unwrap: `end_lba` is not less than `start_lba`.
+            let range =
+                LbaRangeInclusive::new(layout.start_lba, Lba(end_lba)).unwrap();
+
+            // The CRC only covers the entries, not the padding at the
+            // end of the last block.
+            let mut bytes_left = num_bytes_exact;
+            hash_lba_range(
+                &mut *self.io,
+                range,
+                buf,
+                |chunk| {
+                    let n = usize::try_from(bytes_left)
+                        .map_or(chunk.len(), |n| n.min(chunk.len()));
+                    digest.update(&chunk[..n]);
+                    // OK to unwrap: `n` is no larger than `bytes_left`.
+                    bytes_left -= u64::try_from(n).unwrap();
+                },
+                |_| {},
+            )?;
+        }
+        Ok(Crc32(U32Le::from_u32(digest.finalize())))
+    }
+
+    /// Write a partition entry array one block at a time, taking the
+    /// entries from `entries`. This is the write counterpart of
+    /// [`gpt_partition_entry_array_iter`], and avoids holding the whole
+    /// array in memory.
+    ///
+    /// If `entries` produces fewer than `layout.num_entries` entries,
+    /// the remaining slots are filled with unused (zeroed) entries. If
+    /// it produces more, the array is still written in full, and then
+    /// [`DiskError::TooManyPartitionEntries`] is returned.
+    ///
+    /// Returns the CRC32 of the array, which can be set in the
+    /// [`GptHeader::partition_entry_array_crc32`] field.
     ///
     /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`gpt_partition_entry_array_iter`]: Self::gpt_partition_entry_array_iter
+    pub fn write_gpt_partition_entries_from_iter<I>(
+        &mut self,
+        layout: GptPartitionEntryArrayLayout,
+        entries: I,
+        mut block_buf: &mut [u8],
+    ) -> Result<Crc32, DiskError<Io::Error>>
+    where
+        I: IntoIterator<Item = GptPartitionEntry>,
+    {
+        block_buf = self.clip_block_buf_size(block_buf)?;
+
+        let entry_size =
+            layout.entry_size.to_usize().ok_or(DiskError::Overflow)?;
+        if entry_size > block_buf.len() {
+            return Err(DiskError::BlockSizeSmallerThanPartitionEntry);
+        }
+        let num_blocks = layout
+            .num_blocks(self.io.block_size())
+            .ok_or(DiskError::Overflow)?;
+
+        log_debug!("writing GPT partition entry array from iterator: {layout}");
+
+        let crc = crc::Crc::<u32>::new(&Crc32::ALGORITHM);
+        let mut digest = crc.digest();
+        let mut entries = entries.into_iter();
+        let mut num_entries_left = layout.num_entries;
+        for i in 0..num_blocks {
+            block_buf.fill(0);
+            for slot in block_buf.chunks_exact_mut(entry_size) {
+                if num_entries_left == 0 {
+                    break;
+                }
+                num_entries_left -= 1;
+
+                let entry = entries.next().unwrap_or_default();
+                slot[..mem::size_of::<GptPartitionEntry>()]
+                    .copy_from_slice(bytes_of(&entry));
+                digest.update(slot);
+            }
+            let lba = layout
+                .start_lba
+                .to_u64()
+                .checked_add(i)
+                .ok_or(DiskError::Overflow)?;
+            self.io.write_blocks(Lba(lba), block_buf)?;
+        }
+
+        if entries.next().is_some() {
+            return Err(DiskError::TooManyPartitionEntries);
+        }
+        Ok(Crc32(U32Le::from_u32(digest.finalize())))
+    }
+
+    /// Get an iterator over partition entries. The `layout` parameter
+    /// indicates where to read the entries from; see
+    /// [`GptPartitionEntryArrayLayout`] for more.
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one
+    /// block. If it is larger, as many whole blocks of the array as fit
+    /// are read at once, reducing the number of reads. For example, a
+    /// buffer of 32 blocks reads the default 128-entry array (with
+    /// 512-byte blocks) in a single read.
     #[allow(clippy::type_complexity)]
     pub fn gpt_partition_entry_array_iter<'disk, 'buf>(
         &'disk mut self,
         layout: GptPartitionEntryArrayLayout,
-        mut block_buf: &'buf mut [u8],
+        block_buf: &'buf mut [u8],
     ) -> Result<
         impl Iterator<Item = Result<GptPartitionEntry, DiskError<Io::Error>>>
             + Captures<'disk, 'buf>,
         DiskError<Io::Error>,
     > {
-        block_buf = self.clip_block_buf_size(block_buf)?;
+        let block_size = self.clip_block_buf_size(block_buf)?.len();
 
         let entry_size =
             layout.entry_size.to_usize().ok_or(DiskError::Overflow)?;
-        if entry_size > block_buf.len() {
+        if entry_size > block_size {
             return Err(DiskError::BlockSizeSmallerThanPartitionEntry);
         }
 
@@ -315,7 +1359,24 @@ impl<Io: BlockIo> Disk<Io> {
         &mut self,
         block_buf: &mut [u8],
     ) -> Result<(), DiskError<Io::Error>> {
-        let mbr = MasterBootRecord::protective_mbr(self.io.num_blocks()?);
+        let mbr = MasterBootRecord::protective_mbr(self.num_blocks()?);
+        self.write_mbr(&mbr, block_buf)
+    }
+
+    /// Write a protective MBR containing `boot_strap_code` to the first
+    /// block. See [`write_protective_mbr`] and
+    /// [`MasterBootRecord::with_boot_strap_code`].
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`write_protective_mbr`]: Self::write_protective_mbr
+    pub fn write_protective_mbr_with_bootcode(
+        &mut self,
+        boot_strap_code: &[u8; 440],
+        block_buf: &mut [u8],
+    ) -> Result<(), DiskError<Io::Error>> {
+        let mbr = MasterBootRecord::protective_mbr(self.num_blocks()?)
+            .with_boot_strap_code(boot_strap_code);
         self.write_mbr(&mbr, block_buf)
     }
 
@@ -329,6 +1390,7 @@ impl<Io: BlockIo> Disk<Io> {
         mut block_buf: &mut [u8],
     ) -> Result<(), DiskError<Io::Error>> {
         block_buf = self.clip_block_buf_size(block_buf)?;
+        log_debug!("writing MBR: {mbr}");
 
         let mbr_bytes = bytes_of(mbr);
 
@@ -377,7 +1439,7 @@ impl<Io: BlockIo> Disk<Io> {
         header: &GptHeader,
         block_buf: &mut [u8],
     ) -> Result<(), DiskError<Io::Error>> {
-        let num_blocks = self.io.num_blocks()?;
+        let num_blocks = self.num_blocks()?;
         let last_block =
             Lba(num_blocks.checked_sub(1).ok_or(DiskError::Overflow)?);
         self.write_gpt_header(last_block, header, block_buf)
@@ -395,9 +1457,34 @@ impl<Io: BlockIo> Disk<Io> {
         &mut self,
         lba: Lba,
         header: &GptHeader,
+        block_buf: &mut [u8],
+    ) -> Result<(), DiskError<Io::Error>> {
+        self.write_gpt_header_with_tail(lba, header, &[], block_buf)
+    }
+
+    /// Write a [`GptHeader`] to the specified [`Lba`], followed by
+    /// `tail`. All remaining bytes in the block are set to zero.
+    ///
+    /// This is used to preserve the bytes returned by
+    /// [`read_gpt_header_with_tail`]. The header's CRC32 should be
+    /// calculated with [`GptHeader::calculate_header_crc32_with_tail`]
+    /// so that it covers `tail`.
+    ///
+    /// Returns [`DiskError::BufferTooSmall`] if the header and `tail`
+    /// do not fit in one block.
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`read_gpt_header_with_tail`]: Self::read_gpt_header_with_tail
+    pub fn write_gpt_header_with_tail(
+        &mut self,
+        lba: Lba,
+        header: &GptHeader,
+        tail: &[u8],
         mut block_buf: &mut [u8],
     ) -> Result<(), DiskError<Io::Error>> {
         block_buf = self.clip_block_buf_size(block_buf)?;
+        log_debug!("writing GPT header at LBA {lba}: {header}");
 
         let header_bytes = bytes_of(header);
 
@@ -406,17 +1493,696 @@ impl<Io: BlockIo> Disk<Io> {
         // enforced to be at least 512 bytes which is much larger than
         // the size of the GptHeader struct.
         assert!(block_buf.len() >= header_bytes.len());
+        if block_buf.len() - header_bytes.len() < tail.len() {
+            return Err(DiskError::BufferTooSmall);
+        }
 
         {
             let (left, right) = block_buf.split_at_mut(header_bytes.len());
             left.copy_from_slice(header_bytes);
-            right.fill(0);
+            let (tail_dst, rest) = right.split_at_mut(tail.len());
+            tail_dst.copy_from_slice(tail);
+            rest.fill(0);
         }
 
         self.io.write_blocks(lba, block_buf)?;
         Ok(())
     }
 
+    /// Write a modified partition entry array along with both GPT
+    /// headers.
+    ///
+    /// This is the last step of a read-modify-write workflow: read the
+    /// primary header and partition entry array, change the entries,
+    /// then call this method. The `primary_header` is used as a
+    /// template for both headers:
+    ///
+    /// * The primary header is placed at LBA 1, with the partition entry
+    ///   array at `entry_array`'s starting LBA.
+    /// * The secondary header is placed at the last block of the disk,
+    ///   with its partition entry array directly before it.
+    /// * The partition entry array fields and the CRC32 fields of both
+    ///   headers are regenerated (see [`GptHeader::update_crcs`]).
+    ///
+    /// The secondary array and header are written and flushed before
+    /// the primary array and header. If the write is interrupted, at
+    /// least one complete and valid GPT remains on the disk.
+    ///
+    /// If the secondary array and header would overlap the primary
+    /// array or the header's usable range, nothing is written and
+    /// [`DiskError::DiskTooSmall`] is returned.
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    pub fn update_and_write_gpt(
+        &mut self,
+        primary_header: &GptHeader,
+        entry_array: &mut GptPartitionEntryArray,
+        block_buf: &mut [u8],
+    ) -> Result<(), DiskError<Io::Error>> {
+        let layout = *entry_array.layout();
+        let array_num_blocks = layout
+            .num_blocks(self.io.block_size())
+            .ok_or(DiskError::Overflow)?;
+        // Everything before the secondary array: the primary array and
+        // the usable range, whichever ends later.
+        let used_num_blocks = layout
+            .start_lba
+            .to_u64()
+            .checked_add(array_num_blocks)
+            .ok_or(DiskError::Overflow)?
+            .max(
+                primary_header
+                    .last_usable_lba
+                    .to_u64()
+                    .checked_add(1)
+                    .ok_or(DiskError::Overflow)?,
+            );
+        let min_num_blocks = used_num_blocks
+            .checked_add(array_num_blocks)
+            .and_then(|n| n.checked_add(1))
+            .ok_or(DiskError::Overflow)?;
+        let num_blocks = self.num_blocks()?;
+        if num_blocks < min_num_blocks {
+            return Err(DiskError::DiskTooSmall { min_num_blocks });
+        }
+        let secondary_header_lba = Lba(num_blocks - 1);
+        let secondary_array_lba = Lba(secondary_header_lba
+            .to_u64()
+            .checked_sub(array_num_blocks)
+            .ok_or(DiskError::Overflow)?);
+
+        log_debug!(
+            "updating GPT: primary array at LBA {}, secondary array at LBA \
+             {secondary_array_lba}, secondary header at LBA \
+             {secondary_header_lba}",
+            layout.start_lba
+        );
+
+        let mut primary_header = *primary_header;
+        primary_header.my_lba = Lba(1).into();
+        primary_header.alternate_lba = secondary_header_lba.into();
+        primary_header.partition_entry_lba = layout.start_lba.into();
+        primary_header.number_of_partition_entries =
+            U32Le::from_u32(layout.num_entries);
+        primary_header.size_of_partition_entry =
+            U32Le::from_u32(layout.entry_size.to_u32());
+        primary_header.update_crcs(entry_array);
+
+        let mut secondary_header = primary_header;
+        secondary_header.my_lba = secondary_header_lba.into();
+        secondary_header.alternate_lba = Lba(1).into();
+        secondary_header.partition_entry_lba = secondary_array_lba.into();
+        secondary_header.update_header_crc32();
+
+        entry_array.set_start_lba(secondary_array_lba);
+        let result = self.write_gpt_partition_entry_array(entry_array);
+        entry_array.set_start_lba(layout.start_lba);
+        result?;
+        self.write_gpt_header(
+            secondary_header_lba,
+            &secondary_header,
+            block_buf,
+        )?;
+        self.flush()?;
+
+        self.write_gpt_partition_entry_array(entry_array)?;
+        self.write_gpt_header(Lba(1), &primary_header, block_buf)?;
+        self.flush()
+    }
+
+    /// Same as [`update_and_write_gpt`], but first checks that the disk
+    /// does not already contain a different partition table. See
+    /// [`check_existing_partitioning`].
+    ///
+    /// This protects against destroying data when the wrong disk is
+    /// opened by mistake. Use [`update_and_write_gpt`] directly to
+    /// overwrite the disk regardless.
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`check_existing_partitioning`]: Self::check_existing_partitioning
+    /// [`update_and_write_gpt`]: Self::update_and_write_gpt
+    pub fn update_and_write_gpt_checked(
+        &mut self,
+        primary_header: &GptHeader,
+        entry_array: &mut GptPartitionEntryArray,
+        block_buf: &mut [u8],
+    ) -> Result<(), DiskError<Io::Error>> {
+        self.check_existing_partitioning(primary_header.disk_guid, block_buf)?;
+        self.update_and_write_gpt(primary_header, entry_array, block_buf)
+    }
+
+    /// Check that writing a GPT with `disk_guid` will not destroy an
+    /// existing partition table.
+    ///
+    /// The GPT headers in the second and last blocks and the MBR in the
+    /// first block are read:
+    ///
+    /// * If either header has a valid signature and a disk GUID other
+    ///   than `disk_guid`, returns [`DiskError::ExistingGpt`].
+    /// * If either header has a valid signature and a matching disk
+    ///   GUID, the disk already belongs to this GPT and the check
+    ///   passes, even if the MBR is a hybrid MBR.
+    /// * Otherwise, if the MBR has a valid signature and any partition
+    ///   record other than an empty or protective (`0xee`) record,
+    ///   returns [`DiskError::ExistingMbrPartitions`].
+    ///
+    /// Blank disks and disks with only a protective MBR pass the check.
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    pub fn check_existing_partitioning(
+        &mut self,
+        disk_guid: Guid,
+        block_buf: &mut [u8],
+    ) -> Result<(), DiskError<Io::Error>> {
+        let num_blocks = self.num_blocks()?;
+        if num_blocks >= 2 {
+            let primary = self.read_primary_gpt_header(block_buf)?;
+            let secondary = self.read_secondary_gpt_header(block_buf)?;
+            let mut found = false;
+            for header in [primary, secondary] {
+                if !header.is_signature_valid() {
+                    continue;
+                }
+                let existing_guid = header.disk_guid;
+                if existing_guid != disk_guid {
+                    log_debug!(
+                        "found existing GPT with disk GUID {existing_guid}"
+                    );
+                    return Err(DiskError::ExistingGpt {
+                        disk_guid: existing_guid,
+                    });
+                }
+                found = true;
+            }
+            if found {
+                return Ok(());
+            }
+        }
+
+        if num_blocks >= 1 {
+            let block_buf = self.clip_block_buf_size(block_buf)?;
+            self.io.read_blocks(Lba(0), block_buf)?;
+            let mbr: &MasterBootRecord =
+                from_bytes(&block_buf[..mem::size_of::<MasterBootRecord>()]);
+            let has_partitions = mbr.partitions.iter().any(|record| {
+                record.os_indicator != 0 && record.os_indicator != 0xee
+            });
+            if mbr.signature == [0x55, 0xaa] && has_partitions {
+                log_debug!("found existing MBR partitions");
+                return Err(DiskError::ExistingMbrPartitions);
+            }
+        }
+        Ok(())
+    }
+
+    /// Get a [`BlockIo`] for the data range of the partition described
+    /// by `entry`. LBA zero of the returned `BlockIo` is the first block
+    /// of the partition, and accesses past the end of the partition
+    /// fail.
+    ///
+    /// This allows code written against `BlockIo`, such as a
+    /// filesystem driver, to operate on a partition without knowing
+    /// about GPT. See [`PartitionBlockIo`].
+    ///
+    /// Returns [`DiskError::InvalidPartitionRange`] if the partition's
+    /// ending LBA is before its starting LBA, or past the end of the
+    /// disk.
+    pub fn partition_block_io(
+        &mut self,
+        entry: &GptPartitionEntry,
+    ) -> Result<PartitionBlockIo<'_, Io>, DiskError<Io::Error>> {
+        let range =
+            entry.lba_range().ok_or(DiskError::InvalidPartitionRange)?;
+        if range.end().to_u64() >= self.num_blocks()? {
+            return Err(DiskError::InvalidPartitionRange);
+        }
+
+        log_debug!("opening partition as block IO: {range}");
+        Ok(SubRangeBlockIo::new(&mut self.io, range))
+    }
+
+    /// Get a `Disk` for the data range of the partition described by
+    /// `entry`. LBA zero of the returned disk is the first block of the
+    /// partition.
+    ///
+    /// This allows a partition table nested inside a partition (e.g. a
+    /// GPT inside a VM disk image stored in a partition) to be read
+    /// and written with the same API. See [`partition_block_io`].
+    ///
+    /// Returns [`DiskError::InvalidPartitionRange`] if the partition's
+    /// ending LBA is before its starting LBA, or past the end of the
+    /// disk.
+    ///
+    /// [`partition_block_io`]: Self::partition_block_io
+    pub fn open_partition_as_disk(
+        &mut self,
+        entry: &GptPartitionEntry,
+    ) -> Result<Disk<PartitionBlockIo<'_, Io>>, DiskError<Io::Error>> {
+        let flush_on_drop = self.io.enabled;
+        let io = self.partition_block_io(entry)?;
+        Ok(Disk {
+            num_blocks: Some(io.range().num_blocks()),
+            io: FlushOnDrop::new(io, flush_on_drop),
+        })
+    }
+
+    /// Write `image` to the start of the partition described by `entry`,
+    /// for example a GRUB core image into a [`BIOS_BOOT`] partition.
+    ///
+    /// If `image` is not a multiple of the block size, the rest of the
+    /// last block is filled with zeroes. Blocks of the partition past
+    /// the end of the image are not modified.
+    ///
+    /// Returns [`DiskError::InvalidPartitionRange`] if the partition's
+    /// ending LBA is before its starting LBA, or past the end of the
+    /// disk, and [`DiskError::ImageTooLarge`] if `image` doesn't fit in
+    /// the partition.
+    ///
+    /// `block_buf` is a mutable byte buffer with a length of at least one block.
+    ///
+    /// [`BIOS_BOOT`]: gpt_disk_types::GptPartitionType::BIOS_BOOT
+    pub fn write_partition_image(
+        &mut self,
+        entry: &GptPartitionEntry,
+        image: &[u8],
+        mut block_buf: &mut [u8],
+    ) -> Result<(), DiskError<Io::Error>> {
+        block_buf = self.clip_block_buf_size(block_buf)?;
+        let range =
+            entry.lba_range().ok_or(DiskError::InvalidPartitionRange)?;
+        if range.end().to_u64() >= self.num_blocks()? {
+            return Err(DiskError::InvalidPartitionRange);
+        }
+
+        let block_size = block_buf.len();
+        let full_len = image.len() - image.len() % block_size;
+        let (full, partial) = image.split_at(full_len);
+        let mut image_blocks = u64::try_from(full_len / block_size)
+            .map_err(|_| DiskError::Overflow)?;
+        if !partial.is_empty() {
+            image_blocks += 1;
+        }
+        if image_blocks > range.num_blocks() {
+            return Err(DiskError::ImageTooLarge);
+        }
+
+        log_debug!("writing {} byte image to {range}", image.len());
+        if !full.is_empty() {
+            self.io.write_blocks(range.start(), full)?;
+        }
+        if !partial.is_empty() {
+            let (left, right) = block_buf.split_at_mut(partial.len());
+            left.copy_from_slice(partial);
+            right.fill(0);
+            let lba = range.start().to_u64() + image_blocks - 1;
+            self.io.write_blocks(Lba(lba), block_buf)?;
+        }
+        Ok(())
+    }
+
+    /// Identify the filesystem in the partition described by `entry`
+    /// by reading its first blocks. See [`FilesystemKind::identify`].
+    ///
+    /// `buf` must be at least [`FilesystemKind::PROBE_NUM_BYTES`]
+    /// rounded up to a whole number of blocks, unless the partition is
+    /// smaller than that.
+    ///
+    /// Returns [`DiskError::InvalidPartitionRange`] if the partition's
+    /// ending LBA is before its starting LBA, or past the end of the
+    /// disk.
+    pub fn probe_partition_filesystem(
+        &mut self,
+        entry: &GptPartitionEntry,
+        buf: &mut [u8],
+    ) -> Result<Option<FilesystemKind>, DiskError<Io::Error>> {
+        let range =
+            entry.lba_range().ok_or(DiskError::InvalidPartitionRange)?;
+        if range.end().to_u64() >= self.num_blocks()? {
+            return Err(DiskError::InvalidPartitionRange);
+        }
+
+        let block_size = self.io.block_size().to_u64();
+        // OK to unwrap: `PROBE_NUM_BYTES` is a small constant.
+        let probe_num_bytes =
+            u64::try_from(FilesystemKind::PROBE_NUM_BYTES).unwrap();
+        let num_blocks = ((probe_num_bytes + block_size - 1) / block_size)
+            .min(range.num_blocks());
+        let num_bytes = usize::try_from(num_blocks * block_size)
+            .map_err(|_| DiskError::Overflow)?;
+        let buf = buf.get_mut(..num_bytes).ok_or(DiskError::BufferTooSmall)?;
+
+        self.io.read_blocks(range.start(), buf)?;
+        let kind = FilesystemKind::identify(buf);
+        log_debug!("probed partition {range}: {kind:?}");
+        Ok(kind)
+    }
+
+    /// Zero the first and last 1 MiB of `range`, or the whole range if
+    /// it is smaller than 2 MiB.
+    ///
+    /// Filesystems and other formats store their signatures (e.g.
+    /// superblocks) near the start or end of a partition. Tools such as
+    /// `blkid` may detect stale signatures in a newly-created partition
+    /// that reuses space from an old one. Call this on the data range of
+    /// a partition (see [`GptPartitionEntry::lba_range`]) after creating
+    /// it or before deleting it to prevent that.
+    ///
+    /// [`add_partition`], [`remove_partition`], and [`apply_layout`]
+    /// can call this automatically.
+    ///
+    /// The blocks are zeroed with [`BlockIo::write_zeroes`].
+    ///
+    /// [`add_partition`]: Self::add_partition
+    /// [`remove_partition`]: Self::remove_partition
+    /// [`apply_layout`]: Self::apply_layout
+    pub fn wipe_filesystem_signatures(
+        &mut self,
+        range: LbaRangeInclusive,
+    ) -> Result<(), DiskError<Io::Error>> {
+        let block_size = self.io.block_size().to_u64();
+        let wipe_blocks = (SIGNATURE_WIPE_SIZE + block_size - 1) / block_size;
+        log_debug!("wiping filesystem signatures in {range}");
+
+        if range.num_blocks() <= wipe_blocks * 2 {
+            self.io.write_zeroes(range)?;
+            return Ok(());
+        }
+
+        // OK to unwrap: the range is known to be larger than
+        // `wipe_blocks` at each end.
+        let head = LbaRangeInclusive::new(
+            range.start(),
+            Lba(range.start().to_u64() + wipe_blocks - 1),
+        )
+        .unwrap();
+        let tail = LbaRangeInclusive::new(
+            Lba(range.end().to_u64() - wipe_blocks + 1),
+            range.end(),
+        )
+        .unwrap();
+        self.io.write_zeroes(head)?;
+        self.io.write_zeroes(tail)?;
+        Ok(())
+    }
+
+    /// Wipe the filesystem signatures of the partition at `index` in
+    /// `table`. Does nothing if the entry is not in use.
+    fn wipe_partition_signatures<const N: usize>(
+        &mut self,
+        table: &GptPartitionTable<N>,
+        index: PartitionIndex,
+    ) -> Result<(), DiskError<Io::Error>> {
+        if let Some(range) = table
+            .get(index)
+            .filter(|entry| entry.is_used())
+            .and_then(GptPartitionEntry::lba_range)
+        {
+            self.wipe_filesystem_signatures(range)?;
+        }
+        Ok(())
+    }
+
+    /// Add a partition to `table` with [`GptPartitionTable::add`], and
+    /// return its index.
+    ///
+    /// If `wipe_signatures` is true, the new partition's data range is
+    /// then wiped with [`wipe_filesystem_signatures`], so that data left
+    /// over from an earlier partition in the same space is not detected
+    /// as a filesystem.
+    ///
+    /// `table` is only modified if the whole operation succeeds. It is
+    /// not written to the disk; write the partition entry array and
+    /// headers afterwards.
+    ///
+    /// [`wipe_filesystem_signatures`]: Self::wipe_filesystem_signatures
+    pub fn add_partition<const N: usize>(
+        &mut self,
+        table: &mut GptPartitionTable<N>,
+        entry: GptPartitionEntry,
+        wipe_signatures: bool,
+    ) -> Result<PartitionIndex, DiskError<Io::Error>> {
+        let mut added = *table;
+        let index = added.add(entry).map_err(DiskError::PartitionTable)?;
+        if wipe_signatures {
+            self.wipe_partition_signatures(&added, index)?;
+        }
+        *table = added;
+        Ok(index)
+    }
+
+    /// Remove the partition at `index` from `table` with
+    /// [`GptPartitionTable::remove`], and return the removed entry, or
+    /// `None` if the entry is not in use.
+    ///
+    /// If `wipe_signatures` is true, the partition's data range is wiped
+    /// with [`wipe_filesystem_signatures`] first. This destroys the
+    /// partition's filesystem even if the updated table is never
+    /// written to the disk.
+    ///
+    /// `table` is only modified if the whole operation succeeds. It is
+    /// not written to the disk; write the partition entry array and
+    /// headers afterwards.
+    ///
+    /// [`wipe_filesystem_signatures`]: Self::wipe_filesystem_signatures
+    pub fn remove_partition<const N: usize>(
+        &mut self,
+        table: &mut GptPartitionTable<N>,
+        index: PartitionIndex,
+        wipe_signatures: bool,
+    ) -> Result<Option<GptPartitionEntry>, DiskError<Io::Error>> {
+        if wipe_signatures {
+            self.wipe_partition_signatures(table, index)?;
+        }
+        Ok(table.remove(index))
+    }
+
+    /// Update `table` to match `desired` with
+    /// [`GptPartitionTable::apply_layout`].
+    ///
+    /// If `wipe_signatures` is true, the data range of each partition
+    /// that was created is wiped with [`wipe_filesystem_signatures`].
+    /// Existing partitions, including ones that were grown, are not
+    /// wiped.
+    ///
+    /// `on_change` is called for each description before any blocks
+    /// are wiped. `table` is only modified if the whole operation
+    /// succeeds. It is not written to the disk; write the partition
+    /// entry array and headers afterwards.
+    ///
+    /// [`wipe_filesystem_signatures`]: Self::wipe_filesystem_signatures
+    pub fn apply_layout<const N: usize, F>(
+        &mut self,
+        table: &mut GptPartitionTable<N>,
+        desired: &[GptPartitionDescription],
+        policy: ApplyLayoutPolicy,
+        wipe_signatures: bool,
+        mut on_change: F,
+    ) -> Result<(), DiskError<Io::Error>>
+    where
+        F: FnMut(GptLayoutChange),
+    {
+        let mut applied = *table;
+        let mut created = [false; N];
+        applied
+            .apply_layout(desired, policy, |change| {
+                if let GptLayoutChange::Created(index) = change {
+                    // OK to unwrap: the index is within the table.
+                    created[usize::try_from(index.to_u32()).unwrap()] = true;
+                }
+                on_change(change);
+            })
+            .map_err(DiskError::PartitionTable)?;
+
+        if wipe_signatures {
+            for (i, _) in created.iter().enumerate().filter(|(_, c)| **c) {
+                // OK to unwrap: the index is within the table.
+                let index = PartitionIndex::new(u32::try_from(i).unwrap());
+                self.wipe_partition_signatures(&applied, index)?;
+            }
+        }
+        *table = applied;
+        Ok(())
+    }
+
+    /// Copy the blocks in `src_range` to the same number of blocks
+    /// starting at `dst_start`.
+    ///
+    /// The source and destination ranges may overlap. If the
+    /// destination is after the source the blocks are copied from the
+    /// end of the range backwards, otherwise they are copied from the
+    /// start forwards, so that no block is overwritten before it has
+    /// been read.
+    ///
+    /// `block_buf` is used to hold data in transit. It must be at least
+    /// one block in size; larger buffers allow more blocks to be copied
+    /// per read and write. Any partial block at the end of the buffer is
+    /// not used.
+    ///
+    /// Returns [`DiskError::OutOfBounds`] if either range extends past
+    /// the end of the disk.
+    pub fn copy_lba_range(
+        &mut self,
+        src_range: LbaRangeInclusive,
+        dst_start: Lba,
+        block_buf: &mut [u8],
+    ) -> Result<(), DiskError<Io::Error>> {
+        self.copy_lba_range_with_progress(
+            src_range,
+            dst_start,
+            block_buf,
+            |_| {},
+        )
+    }
+
+    /// Same as [`copy_lba_range`], but calls `progress` with the total
+    /// number of blocks copied so far after each chunk.
+    ///
+    /// [`copy_lba_range`]: Self::copy_lba_range
+    fn copy_lba_range_with_progress<P>(
+        &mut self,
+        src_range: LbaRangeInclusive,
+        dst_start: Lba,
+        block_buf: &mut [u8],
+        mut progress: P,
+    ) -> Result<(), DiskError<Io::Error>>
+    where
+        P: FnMut(u64),
+    {
+        let block_size = self.io.block_size();
+        let chunk_blocks = u64::try_from(block_buf.len())
+            .map_err(|_| DiskError::Overflow)?
+            / block_size.to_u64();
+        if chunk_blocks == 0 {
+            return Err(DiskError::BufferTooSmall);
+        }
+
+        let num_blocks = src_range.num_blocks();
+        let src_start = src_range.start().to_u64();
+        let dst_start = dst_start.to_u64();
+        let dst_end = dst_start
+            .checked_add(num_blocks - 1)
+            .ok_or(DiskError::Overflow)?;
+        let disk_blocks = self.num_blocks()?;
+        if src_range.end().to_u64() >= disk_blocks || dst_end >= disk_blocks {
+            return Err(DiskError::OutOfBounds);
+        }
+        if src_start == dst_start {
+            return Ok(());
+        }
+
+        log_debug!("copying {src_range} to LBA {dst_start}");
+        let backward = dst_start > src_start;
+        let mut copied = 0;
+        while copied < num_blocks {
+            let n = chunk_blocks.min(num_blocks - copied);
+            let offset = if backward {
+                num_blocks - copied - n
+            } else {
+                copied
+            };
+            let len = usize::try_from(n * block_size.to_u64())
+                .map_err(|_| DiskError::Overflow)?;
+            let buf = &mut block_buf[..len];
+
+            self.io.read_blocks(Lba(src_start + offset), buf)?;
+            self.io.write_blocks(Lba(dst_start + offset), buf)?;
+            copied += n;
+            progress(copied);
+        }
+        Ok(())
+    }
+
+    /// Move the data of the partition at `index` in `table` so that it
+    /// starts at `new_start`, and update the partition's entry in
+    /// `table` to match.
+    ///
+    /// The new location is checked with
+    /// [`GptPartitionTable::move_partition`] before any data is copied.
+    /// The data is then copied with [`copy_lba_range`], so the old and
+    /// new locations may overlap. `progress` is called with the number
+    /// of blocks copied so far after each chunk.
+    ///
+    /// If `verify` is true, the CRC32 of the partition data is
+    /// calculated before the copy and compared with the CRC32 of the
+    /// data at the new location afterwards. If they differ,
+    /// [`DiskError::VerificationFailed`] is returned.
+    ///
+    /// `table` is only modified if the move succeeds. It is not written
+    /// to the disk; write the partition entry array and headers
+    /// afterwards. If an error occurs during the copy, the data at the
+    /// old location may have been partially overwritten.
+    ///
+    /// `block_buf` must be a non-empty multiple of the block size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `block_buf` is not a multiple of the
+    /// block size.
+    ///
+    /// [`copy_lba_range`]: Self::copy_lba_range
+    pub fn move_partition<const N: usize, P>(
+        &mut self,
+        table: &mut GptPartitionTable<N>,
+        index: PartitionIndex,
+        new_start: Lba,
+        verify: bool,
+        block_buf: &mut [u8],
+        progress: P,
+    ) -> Result<(), DiskError<Io::Error>>
+    where
+        P: FnMut(u64),
+    {
+        self.io.block_size().assert_valid_block_buffer(block_buf);
+
+        let mut moved = *table;
+        let old_range = moved
+            .move_partition(index, new_start)
+            .map_err(DiskError::PartitionTable)?;
+        // OK to unwrap: the moved entry was validated by the table.
+        let new_range = moved.get(index).unwrap().lba_range().unwrap();
+        if new_range.end().to_u64() >= self.num_blocks()? {
+            return Err(DiskError::OutOfBounds);
+        }
+
+        let crc = crc::Crc::<u32>::new(&Crc32::ALGORITHM);
+        let expected_crc = if verify {
+            let mut digest = crc.digest();
+            hash_lba_range(
+                &mut *self.io,
+                old_range,
+                block_buf,
+                |data| digest.update(data),
+                |_| {},
+            )?;
+            Some(digest.finalize())
+        } else {
+            None
+        };
+
+        log_debug!("moving partition {index} from {old_range} to {new_range}");
+        self.copy_lba_range_with_progress(
+            old_range, new_start, block_buf, progress,
+        )?;
+
+        if let Some(expected_crc) = expected_crc {
+            let mut digest = crc.digest();
+            hash_lba_range(
+                &mut *self.io,
+                new_range,
+                block_buf,
+                |data| digest.update(data),
+                |_| {},
+            )?;
+            if digest.finalize() != expected_crc {
+                return Err(DiskError::VerificationFailed);
+            }
+        }
+
+        *table = moved;
+        Ok(())
+    }
+
     /// Flush any pending writes to the disk.
     ///
     /// This is called automatically when the disk is dropped, but if an
@@ -425,11 +2191,103 @@ impl<Io: BlockIo> Disk<Io> {
     pub fn flush(&mut self) -> Result<(), DiskError<Io::Error>> {
         Ok(self.io.flush()?)
     }
+
+    /// Flush the disk and return the underlying [`BlockIo`].
+    ///
+    /// Unlike dropping the disk, errors from the flush are reported. The
+    /// `BlockIo` is returned along with the error so that the caller can
+    /// retry or otherwise recover.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_io::gpt_disk_types::BlockSize;
+    /// use gpt_disk_io::{BlockIoAdapter, Disk};
+    ///
+    /// let mut data = vec![0; 512 * 8];
+    /// let io = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    /// let disk = Disk::new(io).unwrap();
+    /// let io = disk.close().map_err(|(_io, err)| err).unwrap();
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn close(mut self) -> Result<Io, (Io, DiskError<Io::Error>)> {
+        let r = self.flush();
+        let io = self.into_io();
+        match r {
+            Ok(()) => Ok(io),
+            Err(err) => Err((io, err)),
+        }
+    }
+
+    /// Consume the disk and return the underlying [`BlockIo`] without
+    /// flushing.
+    fn into_io(self) -> Io {
+        self.io.into_inner()
+    }
 }
 
-impl<Io: BlockIo> Drop for Disk<Io> {
-    fn drop(&mut self) {
-        // Throw away any errors.
-        let _r = self.flush();
+/// [`Disk`] that accesses its [`BlockIo`] through a trait object.
+///
+/// This allows the backend to be selected at runtime without making
+/// the code that uses the disk generic. All backends must have the same
+/// [`BlockIo::Error`] type `E`.
+///
+/// Requires the `alloc` feature.
+///
+/// # Examples
+///
+/// Select a backend at runtime:
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::BlockSize;
+/// use gpt_disk_io::{BlockIoAdapter, DiskError, DynDisk};
+/// use std::io::{self, Cursor};
+///
+/// #[cfg(feature = "std")]
+/// fn open_disk(
+///     path: Option<&str>,
+/// ) -> Result<DynDisk<'static, io::Error>, DiskError<io::Error>> {
+///     let bs = BlockSize::BS_512;
+///     match path {
+///         Some(path) => {
+///             let file = std::fs::File::open(path)?;
+///             DynDisk::from_dyn(BlockIoAdapter::new(file, bs))
+///         }
+///         None => {
+///             let cursor = Cursor::new(vec![0; 4 * 1024 * 1024]);
+///             DynDisk::from_dyn(BlockIoAdapter::new(cursor, bs))
+///         }
+///     }
+/// }
+///
+/// #[cfg(feature = "std")]
+/// fn print_disk_guid(disk: &mut DynDisk<'_, io::Error>) {
+///     let mut block_buf = vec![0; 512];
+///     if let Ok(header) = disk.read_primary_gpt_header(&mut block_buf) {
+///         println!("{}", { header.disk_guid });
+///     }
+/// }
+///
+/// #[cfg(feature = "std")]
+/// {
+///     let mut disk = open_disk(None)?;
+///     print_disk_guid(&mut disk);
+/// }
+/// # Ok::<(), DiskError<io::Error>>(())
+/// ```
+#[cfg(feature = "alloc")]
+pub type DynDisk<'io, E> = Disk<Box<dyn BlockIo<Error = E> + 'io>>;
+
+#[cfg(feature = "alloc")]
+impl<'io, E> Disk<Box<dyn BlockIo<Error = E> + 'io>>
+where
+    E: Debug + Display + Send + Sync + 'static,
+{
+    /// Create a [`DynDisk`] by boxing `io` as a trait object.
+    pub fn from_dyn<Io>(io: Io) -> Result<Self, DiskError<E>>
+    where
+        Io: BlockIo<Error = E> + 'io,
+    {
+        Self::new(Box::new(io))
     }
 }
@@ -0,0 +1,161 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::fmt::{self, Display, Formatter};
+
+/// Offset of the ext superblock from the start of the partition.
+const EXT_SUPERBLOCK_OFFSET: usize = 1024;
+
+/// Compatible feature flag for a journal, which distinguishes ext3 from
+/// ext2.
+const EXT_COMPAT_HAS_JOURNAL: u32 = 0x4;
+
+/// Incompatible features understood by ext3: `FILETYPE`, `RECOVER`, and
+/// `META_BG`. Any other incompatible feature means ext4.
+const EXT3_INCOMPAT_SUPPORTED: u32 = 0x2 | 0x4 | 0x10;
+
+/// Read-only compatible features understood by ext3: `SPARSE_SUPER`,
+/// `LARGE_FILE`, and `BTREE_DIR`. Any other feature means ext4.
+const EXT3_RO_COMPAT_SUPPORTED: u32 = 0x1 | 0x2 | 0x4;
+
+/// Filesystem (or other format) identified by its signature.
+///
+/// The [`Display`] impl uses the same names as the `TYPE` reported by
+/// `blkid`, e.g. `vfat` or `ext4`.
+///
+/// See [`FilesystemKind::identify`] and
+/// [`Disk::probe_partition_filesystem`].
+///
+/// [`Disk::probe_partition_filesystem`]: crate::Disk::probe_partition_filesystem
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum FilesystemKind {
+    /// FAT12, FAT16, or FAT32.
+    Fat,
+
+    /// exFAT.
+    Exfat,
+
+    /// NTFS.
+    Ntfs,
+
+    /// ext2: an ext filesystem without a journal.
+    Ext2,
+
+    /// ext3: an ext filesystem with a journal, and no features beyond
+    /// those supported by ext3.
+    Ext3,
+
+    /// ext4: an ext filesystem using features not supported by ext3,
+    /// such as extents.
+    Ext4,
+
+    /// Linux swap space.
+    Swap,
+
+    /// LUKS encrypted volume header.
+    Luks,
+}
+
+impl FilesystemKind {
+    /// Number of bytes from the start of a partition that
+    /// [`FilesystemKind::identify`] needs to detect all supported
+    /// signatures.
+    pub const PROBE_NUM_BYTES: usize = 4096;
+
+    /// Identify the filesystem from `data`, the first bytes of a
+    /// partition. Returns `None` if no known signature is found.
+    ///
+    /// `data` should be at least [`PROBE_NUM_BYTES`] long. Signatures
+    /// that lie beyond the end of a shorter `data` are not detected.
+    ///
+    /// Only signatures are checked; the filesystem is not validated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_io::FilesystemKind;
+    ///
+    /// let mut data = vec![0; 4096];
+    /// data[0..6].copy_from_slice(b"LUKS\xba\xbe");
+    /// assert_eq!(FilesystemKind::identify(&data), Some(FilesystemKind::Luks));
+    ///
+    /// assert_eq!(FilesystemKind::identify(&[0; 4096]), None);
+    /// ```
+    ///
+    /// [`PROBE_NUM_BYTES`]: Self::PROBE_NUM_BYTES
+    #[must_use]
+    pub fn identify(data: &[u8]) -> Option<Self> {
+        let has = |offset: usize, magic: &[u8]| {
+            data.get(offset..offset + magic.len()) == Some(magic)
+        };
+
+        if has(0, b"LUKS\xba\xbe") {
+            return Some(Self::Luks);
+        }
+        // NTFS and exFAT boot sectors also end in 0x55aa, so check them
+        // before FAT.
+        if has(3, b"NTFS    ") {
+            return Some(Self::Ntfs);
+        }
+        if has(3, b"EXFAT   ") {
+            return Some(Self::Exfat);
+        }
+        if has(510, &[0x55, 0xaa]) && (has(54, b"FAT") || has(82, b"FAT32")) {
+            return Some(Self::Fat);
+        }
+        if has(EXT_SUPERBLOCK_OFFSET + 56, &[0x53, 0xef]) {
+            return Some(Self::identify_ext(data));
+        }
+        // Swap signatures are at the end of the first page. Only 4 KiB
+        // pages are checked.
+        if has(Self::PROBE_NUM_BYTES - 10, b"SWAPSPACE2")
+            || has(Self::PROBE_NUM_BYTES - 10, b"SWAP-SPACE")
+        {
+            return Some(Self::Swap);
+        }
+        None
+    }
+
+    /// Identify the ext version from the superblock's feature flags.
+    fn identify_ext(data: &[u8]) -> Self {
+        let read_u32 = |offset: usize| {
+            let offset = EXT_SUPERBLOCK_OFFSET + offset;
+            data.get(offset..offset + 4)
+                // OK to unwrap: the slice is four bytes.
+                .map_or(0, |b| u32::from_le_bytes(b.try_into().unwrap()))
+        };
+        let compat = read_u32(0x5c);
+        let incompat = read_u32(0x60);
+        let ro_compat = read_u32(0x64);
+
+        if incompat & !EXT3_INCOMPAT_SUPPORTED != 0
+            || ro_compat & !EXT3_RO_COMPAT_SUPPORTED != 0
+        {
+            Self::Ext4
+        } else if compat & EXT_COMPAT_HAS_JOURNAL != 0 {
+            Self::Ext3
+        } else {
+            Self::Ext2
+        }
+    }
+}
+
+impl Display for FilesystemKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Fat => "vfat",
+            Self::Exfat => "exfat",
+            Self::Ntfs => "ntfs",
+            Self::Ext2 => "ext2",
+            Self::Ext3 => "ext3",
+            Self::Ext4 => "ext4",
+            Self::Swap => "swap",
+            Self::Luks => "crypto_LUKS",
+        })
+    }
+}
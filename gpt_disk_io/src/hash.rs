@@ -0,0 +1,91 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{BlockIo, DiskError};
+use gpt_disk_types::{Lba, LbaRangeInclusive};
+
+/// Stream the blocks in `range` through a hash function.
+///
+/// The blocks are read in chunks the size of `buf`, which must be a
+/// multiple of the block size and at least one block. Each chunk is
+/// passed to `update` in order, so `update` can feed any incremental
+/// hasher. For example, with a [`digest::Digest`] such as `Sha256`,
+/// pass `|data| hasher.update(data)`.
+///
+/// After each chunk, `progress` is called with the number of blocks
+/// hashed so far, out of [`LbaRangeInclusive::num_blocks`].
+///
+/// Returns [`DiskError::BufferTooSmall`] if `buf` is empty.
+///
+/// # Panics
+///
+/// Panics if the length of `buf` is not a multiple of the block size.
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_io::gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+/// use gpt_disk_io::{hash_lba_range, BlockIoAdapter};
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::Hasher;
+///
+/// let data = [1; 4096];
+/// let mut bio = BlockIoAdapter::new(&data[..], BlockSize::BS_512);
+///
+/// let mut hasher = DefaultHasher::new();
+/// let mut blocks_done = 0;
+/// let range = LbaRangeInclusive::new(Lba(0), Lba(7)).unwrap();
+/// let mut buf = [0; 1024];
+/// hash_lba_range(
+///     &mut bio,
+///     range,
+///     &mut buf,
+///     |data| hasher.write(data),
+///     |n| blocks_done = n,
+/// )?;
+/// assert_eq!(blocks_done, 8);
+/// println!("{:x}", hasher.finish());
+/// # Ok::<(), gpt_disk_io::DiskError<gpt_disk_io::SliceBlockIoError>>(())
+/// ```
+///
+/// [`digest::Digest`]: https://docs.rs/digest/latest/digest/trait.Digest.html
+pub fn hash_lba_range<Io, U, P>(
+    io: &mut Io,
+    range: LbaRangeInclusive,
+    buf: &mut [u8],
+    mut update: U,
+    mut progress: P,
+) -> Result<(), DiskError<Io::Error>>
+where
+    Io: BlockIo,
+    U: FnMut(&[u8]),
+    P: FnMut(u64),
+{
+    let block_size = io.block_size();
+    block_size.assert_valid_block_buffer(buf);
+    let chunk_num_blocks = u64::try_from(buf.len())
+        .map_err(|_| DiskError::Overflow)?
+        / block_size.to_u64();
+    if chunk_num_blocks == 0 {
+        return Err(DiskError::BufferTooSmall);
+    }
+
+    let num_blocks = range.num_blocks();
+    let mut blocks_done = 0;
+    while blocks_done < num_blocks {
+        let chunk_blocks = chunk_num_blocks.min(num_blocks - blocks_done);
+        // OK to unwrap: the chunk is no larger than `buf`.
+        let chunk = &mut buf
+            [..usize::try_from(chunk_blocks * block_size.to_u64()).unwrap()];
+        io.read_blocks(Lba(range.start().to_u64() + blocks_done), chunk)?;
+        update(chunk);
+        blocks_done += chunk_blocks;
+        progress(blocks_done);
+    }
+    Ok(())
+}
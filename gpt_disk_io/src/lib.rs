@@ -21,10 +21,39 @@
 //!
 //! # Features
 //!
-//! * `alloc`: Enables [`Vec`] implementation of [`BlockIoAdapter`].
+//! * `alloc`: Enables [`Vec`] implementation of [`BlockIoAdapter`], the
+//!   [`BlockIo`] implementation for `Box<T>`, the [`DynDisk`] type, the
+//!   [`RecordingBlockIo`] and [`ReplayBlockIo`] types, and the
+//!   [`RetryBlockIo`] type.
 //! * `std`: Enables [`std::io`] implementations of [`BlockIoAdapter`],
-//!   as well as `std::error::Error` implementations for all of the
-//!   error types. Off by default.
+//!   the [`CompressedBlockIo`] type for reading compressed disk images,
+//!   the [`FixedVhdBlockIo`] type for fixed-size VHD images, the
+//!   [`ReadOnlyBlockIo`] type for read-only sources, the
+//!   [`SplitBlockIo`] type for disk images split into multiple files,
+//!   [`BlockIo`] implementations for `Arc<Mutex<T>>` and
+//!   `Arc<RwLock<T>>`, as well as `std::error::Error` implementations
+//!   for all of the error types. Off by default.
+//! * `devices`: Enables the [`devices`] module for listing, opening,
+//!   and querying block devices. Implies `std`. Off by default.
+//! * `io_uring`: Enables the [`IoUringBlockIo`] type, which batches
+//!   writes to a file using `io_uring`. Only available on Linux. Implies
+//!   `std`. Off by default.
+//! * `qcow2`: Enables the [`Qcow2BlockIo`] type for reading qcow2
+//!   images. Implies `std`. Off by default.
+//! * `rayon`: Enables [`copy_blocks_parallel`], which copies blocks
+//!   between two [`SharedBlockIo`] devices using multiple threads.
+//!   Implies `std`. Off by default.
+//! * `ureq`: Enables the [`HttpBlockIo`] type for reading remote disk
+//!   images with HTTP range requests. Implies `std`. Off by default.
+//! * `zstd`: Enables the [`ZstdSeekableBlockIo`] type for reading disk
+//!   images in the zstd seekable format. Implies `std`. Off by default.
+//! * `log`: Emits [`log`] events at the debug level for each header,
+//!   partition entry array, and MBR read or written by [`Disk`], as
+//!   well as other operations that modify the disk and failed
+//!   validation checks. Off by default.
+//! * `test_support`: Enables the [`test_support`] module for generating
+//!   valid and corrupted GPT disk images in tests. Implies `alloc`. Off
+//!   by default.
 //!
 //! # Examples
 //!
@@ -134,17 +163,75 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+/// Emit a [`log::debug!`] event if the `log` feature is enabled.
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)*);
+    };
+}
+
 mod block_io;
+mod copy;
+#[cfg(feature = "devices")]
+pub mod devices;
 mod disk;
+mod filesystem;
+mod hash;
 #[cfg(feature = "std")]
 mod std_support;
+#[cfg(feature = "test_support")]
+pub mod test_support;
 
 // Re-export dependencies.
 pub use gpt_disk_types;
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+pub use io_uring;
 
+pub use block_io::shared_block_io::SharedBlockIo;
 pub use block_io::slice_block_io::SliceBlockIoError;
+pub use block_io::sub_range_block_io::{
+    PartitionBlockIo, SubRangeBlockIo, SubRangeBlockIoError,
+};
+pub use block_io::verifying_block_io::{
+    VerifyingBlockIo, VerifyingBlockIoError,
+};
+pub use block_io::write_protected_block_io::{
+    WriteProtectedBlockIo, WriteProtectedBlockIoError,
+};
 pub use block_io::{BlockIo, BlockIoAdapter};
-pub use disk::{Disk, DiskError};
+pub use copy::{copy_blocks, CopyBlocksError};
+pub use disk::{CheckedGptHeader, Disk, DiskError, GptValidationOptions};
+pub use filesystem::FilesystemKind;
+pub use hash::hash_lba_range;
+
+#[cfg(feature = "rayon")]
+pub use copy::copy_blocks_parallel;
+
+#[cfg(feature = "alloc")]
+pub use block_io::recording_block_io::{
+    IoOp, IoTraceEntry, RecordingBlockIo, ReplayBlockIo, ReplayBlockIoError,
+};
+#[cfg(feature = "alloc")]
+pub use block_io::retry_block_io::{RetryBlockIo, RetryBlockIoError};
+#[cfg(feature = "alloc")]
+pub use disk::DynDisk;
 
 #[cfg(feature = "std")]
-pub use block_io::std_block_io::ReadWriteSeek;
+pub use block_io::compressed_block_io::CompressedBlockIo;
+#[cfg(feature = "ureq")]
+pub use block_io::http_block_io::HttpBlockIo;
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+pub use block_io::io_uring_block_io::IoUringBlockIo;
+#[cfg(feature = "qcow2")]
+pub use block_io::qcow2_block_io::Qcow2BlockIo;
+#[cfg(feature = "std")]
+pub use block_io::read_only_block_io::ReadOnlyBlockIo;
+#[cfg(feature = "std")]
+pub use block_io::split_block_io::SplitBlockIo;
+#[cfg(feature = "std")]
+pub use block_io::std_block_io::{ReadWriteSeek, SeekCache};
+#[cfg(feature = "std")]
+pub use block_io::vhd_block_io::FixedVhdBlockIo;
+#[cfg(feature = "zstd")]
+pub use block_io::zstd_seekable_block_io::ZstdSeekableBlockIo;
@@ -6,10 +6,44 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{DiskError, SliceBlockIoError};
+use crate::{
+    CopyBlocksError, DiskError, ReplayBlockIoError, RetryBlockIoError,
+    SliceBlockIoError, SubRangeBlockIoError, VerifyingBlockIoError,
+    WriteProtectedBlockIoError,
+};
 use std::error::Error;
 use std::fmt::{Debug, Display};
 
+impl<SrcError, DstError> Error for CopyBlocksError<SrcError, DstError>
+where
+    SrcError: Debug + Display,
+    DstError: Debug + Display,
+{
+}
+
 impl<Custom> Error for DiskError<Custom> where Custom: Debug + Display {}
 
+impl<IoError> Error for ReplayBlockIoError<IoError> where
+    IoError: Debug + Display
+{
+}
+
+impl<IoError> Error for RetryBlockIoError<IoError> where IoError: Debug + Display
+{}
+
 impl Error for SliceBlockIoError {}
+
+impl<IoError> Error for SubRangeBlockIoError<IoError> where
+    IoError: Debug + Display
+{
+}
+
+impl<IoError> Error for VerifyingBlockIoError<IoError> where
+    IoError: Debug + Display
+{
+}
+
+impl<IoError> Error for WriteProtectedBlockIoError<IoError> where
+    IoError: Debug + Display
+{
+}
@@ -0,0 +1,242 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generate GPT disk images for tests.
+//!
+//! Code that reads partition tables needs to be tested against both
+//! valid disks and disks with common kinds of damage. Rather than
+//! shipping binary fixtures, this module generates well-known images in
+//! memory. [`create_test_disk`] creates a valid disk, and
+//! [`create_corrupted_test_disk`] creates the same disk with a specific
+//! [`Corruption`] applied.
+//!
+//! Every image has [`TEST_DISK_NUM_BLOCKS`] blocks, a protective MBR,
+//! both GPT headers with the disk GUID [`TEST_DISK_GUID`], and the
+//! partitions returned by [`test_partition_entries`]. The output is
+//! deterministic, so it can be compared byte for byte.
+//!
+//! Requires the `test_support` feature. This feature is intended to be
+//! enabled in `dev-dependencies` only.
+//!
+//! # Examples
+//!
+//! ```
+//! use gpt_disk_io::gpt_disk_types::BlockSize;
+//! use gpt_disk_io::test_support::{
+//!     create_corrupted_test_disk, Corruption,
+//! };
+//! use gpt_disk_io::{BlockIoAdapter, Disk};
+//!
+//! let bs = BlockSize::BS_512;
+//! let mut data =
+//!     create_corrupted_test_disk(bs, Corruption::PrimaryHeaderCrc32);
+//! let mut disk = Disk::new(BlockIoAdapter::new(data.as_mut_slice(), bs))?;
+//!
+//! let mut block_buf = vec![0; 512];
+//! let primary = disk.read_primary_gpt_header(&mut block_buf)?;
+//! assert!(!primary.is_header_crc32_valid());
+//! let secondary = disk.read_secondary_gpt_header(&mut block_buf)?;
+//! assert!(secondary.is_header_crc32_valid());
+//! # Ok::<(), gpt_disk_io::DiskError<gpt_disk_io::SliceBlockIoError>>(())
+//! ```
+
+use crate::{BlockIoAdapter, Disk};
+use alloc::vec;
+use alloc::vec::Vec;
+use gpt_disk_types::{
+    guid, BlockSize, GptDiskLayout, GptPartitionAttributes, GptPartitionEntry,
+    GptPartitionEntryArray, GptPartitionName, GptPartitionType, Guid, Lba,
+    LbaLe,
+};
+
+/// Number of blocks in every test disk.
+pub const TEST_DISK_NUM_BLOCKS: u64 = 8192;
+
+/// Disk GUID in both headers of every test disk.
+pub const TEST_DISK_GUID: Guid = guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870");
+
+/// Byte offset of the CRC32 field within a [`GptHeader`].
+///
+/// [`GptHeader`]: gpt_disk_types::GptHeader
+const HEADER_CRC32_OFFSET: usize = 16;
+
+/// Byte offset of the attributes field within a [`GptPartitionEntry`].
+const ENTRY_ATTRIBUTES_OFFSET: usize = 48;
+
+/// Kinds of damage that can be applied to a test disk by
+/// [`create_corrupted_test_disk`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Corruption {
+    /// The stored CRC32 of the primary header does not match its
+    /// contents. The secondary header is intact.
+    PrimaryHeaderCrc32,
+
+    /// The stored CRC32 of the secondary header does not match its
+    /// contents. The primary header is intact.
+    SecondaryHeaderCrc32,
+
+    /// The primary partition entry array was modified after its CRC32
+    /// was calculated, so it does not match the
+    /// `partition_entry_array_crc32` in the primary header. Both headers
+    /// and the secondary array are intact.
+    PrimaryPartitionEntryArrayCrc32,
+
+    /// The second partition starts before the first partition ends.
+    /// All CRC32 values are correct, so this can only be detected by
+    /// checking the entries themselves.
+    OverlappingPartitions,
+
+    /// The image ends just before the secondary partition entry array,
+    /// as if it was truncated while being copied. The primary header
+    /// still refers to the missing secondary header.
+    TruncatedSecondary,
+}
+
+fn partition_entry(
+    type_guid: Guid,
+    unique_guid: Guid,
+    starting_lba: u64,
+    ending_lba: u64,
+    name: &str,
+) -> GptPartitionEntry {
+    GptPartitionEntry {
+        partition_type_guid: GptPartitionType(type_guid),
+        unique_partition_guid: unique_guid,
+        starting_lba: LbaLe::from_u64(starting_lba),
+        ending_lba: LbaLe::from_u64(ending_lba),
+        attributes: GptPartitionAttributes::default(),
+        // OK to unwrap: the names are short ASCII strings.
+        name: name.parse::<GptPartitionName>().unwrap(),
+    }
+}
+
+/// Get the partitions on a valid test disk, in partition entry array
+/// order. All other entries in the array are unused.
+#[must_use]
+pub fn test_partition_entries() -> [GptPartitionEntry; 2] {
+    [
+        partition_entry(
+            GptPartitionType::EFI_SYSTEM.0,
+            guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12"),
+            2048,
+            4095,
+            "EFI system",
+        ),
+        partition_entry(
+            GptPartitionType::BASIC_DATA.0,
+            guid!("8e9e6b2b-3c4a-4b8a-9f7e-2a0c5d1e6f40"),
+            4096,
+            6143,
+            "data",
+        ),
+    ]
+}
+
+/// Write a GPT disk containing `entries` and return its layout and
+/// data.
+fn write_test_disk(
+    block_size: BlockSize,
+    entries: &[GptPartitionEntry],
+) -> (GptDiskLayout, Vec<u8>) {
+    // OK to unwrap: the disk is large enough for any block size.
+    let layout = GptDiskLayout::new(block_size, TEST_DISK_NUM_BLOCKS).unwrap();
+    let block_size_usize = block_size.to_usize().unwrap();
+    let num_bytes =
+        usize::try_from(TEST_DISK_NUM_BLOCKS).unwrap() * block_size_usize;
+    let mut data = vec![0; num_bytes];
+
+    let array_layout = layout.primary_partition_entry_array_layout();
+    let mut array_storage = vec![
+        0;
+        array_layout
+            .num_bytes_rounded_to_block_as_usize(block_size)
+            .unwrap()
+    ];
+    let mut entry_array = GptPartitionEntryArray::new(
+        array_layout,
+        block_size,
+        &mut array_storage,
+    )
+    .unwrap();
+    for (i, entry) in entries.iter().enumerate() {
+        *entry_array
+            .get_partition_entry_mut(u32::try_from(i).unwrap())
+            .unwrap() = *entry;
+    }
+
+    // Writing to a slice of the correct size cannot fail.
+    let mut block_buf = vec![0; block_size_usize];
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(data.as_mut_slice(), block_size))
+            .unwrap();
+    disk.write_protective_mbr(&mut block_buf).unwrap();
+    disk.update_and_write_gpt(
+        &layout.primary_header(TEST_DISK_GUID),
+        &mut entry_array,
+        &mut block_buf,
+    )
+    .unwrap();
+    drop(disk);
+
+    (layout, data)
+}
+
+/// Get the byte offset of `lba`.
+fn byte_offset(block_size: BlockSize, lba: Lba) -> usize {
+    usize::try_from(lba.to_u64() * block_size.to_u64()).unwrap()
+}
+
+/// Create a valid GPT disk image with [`TEST_DISK_NUM_BLOCKS`] blocks
+/// of `block_size` bytes.
+///
+/// The disk contains a protective MBR, both headers, and both partition
+/// entry arrays, with the partitions from [`test_partition_entries`].
+#[must_use]
+pub fn create_test_disk(block_size: BlockSize) -> Vec<u8> {
+    write_test_disk(block_size, &test_partition_entries()).1
+}
+
+/// Create a GPT disk image like [`create_test_disk`], then apply
+/// `corruption` to it.
+#[must_use]
+pub fn create_corrupted_test_disk(
+    block_size: BlockSize,
+    corruption: Corruption,
+) -> Vec<u8> {
+    let mut entries = test_partition_entries();
+    if corruption == Corruption::OverlappingPartitions {
+        entries[1].starting_lba =
+            LbaLe::from_u64(entries[0].ending_lba.to_u64() - 1024);
+    }
+    let (layout, mut data) = write_test_disk(block_size, &entries);
+
+    let header_crc32_offset =
+        |lba| byte_offset(block_size, lba) + HEADER_CRC32_OFFSET;
+    match corruption {
+        Corruption::PrimaryHeaderCrc32 => {
+            data[header_crc32_offset(layout.primary_header_lba())] ^= 0xff;
+        }
+        Corruption::SecondaryHeaderCrc32 => {
+            data[header_crc32_offset(layout.secondary_header_lba())] ^= 0xff;
+        }
+        Corruption::PrimaryPartitionEntryArrayCrc32 => {
+            let array_lba =
+                layout.primary_partition_entry_array_layout().start_lba;
+            data[byte_offset(block_size, array_lba)
+                + ENTRY_ATTRIBUTES_OFFSET] ^= 0x01;
+        }
+        Corruption::OverlappingPartitions => {}
+        Corruption::TruncatedSecondary => {
+            let array_lba =
+                layout.secondary_partition_entry_array_layout().start_lba;
+            data.truncate(byte_offset(block_size, array_lba));
+        }
+    }
+
+    data
+}
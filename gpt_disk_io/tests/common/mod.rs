@@ -13,7 +13,7 @@ use gpt_disk_types::{
 };
 use std::collections::hash_map::DefaultHasher;
 
-#[allow(dead_code)]
+#[allow(clippy::eq_op, dead_code)]
 pub fn check_derives<T>()
 where
     T: Clone
@@ -9,7 +9,12 @@
 mod common;
 
 use common::check_derives;
-use gpt_disk_types::{BlockSize, Lba, LbaLe, LbaRangeInclusive, U64Le};
+use gpt_disk_types::{
+    BlockSize, BlockSizeError, Lba, LbaLe, LbaRangeInclusive, U64Le,
+};
+use serde_test::{
+    assert_de_tokens, assert_de_tokens_error, assert_tokens, Token,
+};
 
 #[test]
 fn test_lba() {
@@ -67,6 +72,38 @@ fn test_lba_range_inclusive() {
     .is_none());
 }
 
+#[test]
+fn test_lba_range_inclusive_iter() {
+    let range = LbaRangeInclusive::new(Lba(3), Lba(5)).unwrap();
+
+    let mut iter = range.iter();
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    assert_eq!(iter.next(), Some(Lba(3)));
+    assert_eq!(iter.next_back(), Some(Lba(5)));
+    assert_eq!(iter.next(), Some(Lba(4)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let mut lbas = Vec::new();
+    for lba in range {
+        lbas.push(lba);
+    }
+    assert_eq!(lbas, [Lba(3), Lba(4), Lba(5)]);
+    assert_eq!(
+        range.iter().rev().collect::<Vec<_>>(),
+        [Lba(5), Lba(4), Lba(3)]
+    );
+
+    // Single-block range.
+    let range = LbaRangeInclusive::new(Lba(7), Lba(7)).unwrap();
+    assert_eq!(range.iter().collect::<Vec<_>>(), [Lba(7)]);
+
+    // The iterator does not overflow at the end of the LBA space.
+    let range =
+        LbaRangeInclusive::new(Lba(u64::MAX - 1), Lba(u64::MAX)).unwrap();
+    assert_eq!(range.iter().count(), 2);
+}
+
 #[test]
 fn test_block_size() {
     check_derives::<BlockSize>();
@@ -80,6 +117,78 @@ fn test_block_size() {
 
     assert_eq!(BlockSize::default().to_u32(), 512);
     assert_eq!(BlockSize::default().to_u64(), 512);
+
+    assert_eq!(BlockSize::BS_512.to_u32(), 512);
+    assert_eq!(BlockSize::BS_1024.to_u32(), 1024);
+    assert_eq!(BlockSize::BS_2048.to_u32(), 2048);
+    assert_eq!(BlockSize::BS_4096.to_u32(), 4096);
+    assert_eq!(BlockSize::BS_8192.to_u32(), 8192);
+
+    assert_eq!(BlockSize::BS_512.shift(), Some(9));
+    assert_eq!(BlockSize::BS_8192.shift(), Some(13));
+    assert_eq!(BlockSize::new(0x8000_0000).unwrap().shift(), Some(31));
+    assert_eq!(BlockSize::new(520).unwrap().shift(), None);
+    assert_eq!(BlockSize::new(u32::MAX).unwrap().shift(), None);
+
+    assert_eq!(BlockSize::new_power_of_two(9), Some(BlockSize::BS_512));
+    assert_eq!(BlockSize::new_power_of_two(11), Some(BlockSize::BS_2048));
+    assert_eq!(
+        BlockSize::new_power_of_two(31),
+        Some(BlockSize::new(0x8000_0000).unwrap())
+    );
+    assert_eq!(BlockSize::new_power_of_two(8), None);
+    assert_eq!(BlockSize::new_power_of_two(32), None);
+    assert_eq!(BlockSize::new_strict(512), Some(BlockSize::BS_512));
+    assert_eq!(
+        BlockSize::new_strict(65536),
+        Some(BlockSize::new(65536).unwrap())
+    );
+    assert_eq!(BlockSize::new_strict(256), None);
+    assert_eq!(BlockSize::new_strict(767), None);
+    assert_eq!(BlockSize::new_strict(131_072), None);
+    assert_eq!(BlockSize::new_strict(0), None);
+
+    for shift in 9..32 {
+        let bs = BlockSize::new_power_of_two(shift).unwrap();
+        assert_eq!(bs.shift(), Some(shift));
+    }
+}
+
+#[test]
+fn test_block_size_try_from() {
+    assert_eq!(BlockSize::try_from(4096u32), Ok(BlockSize::BS_4096));
+    assert_eq!(BlockSize::try_from(4096u64), Ok(BlockSize::BS_4096));
+    assert_eq!(BlockSize::try_from(4096usize), Ok(BlockSize::BS_4096));
+    assert_eq!(u32::from(BlockSize::BS_4096), 4096);
+
+    assert_eq!(BlockSize::try_from(0u32), Err(BlockSizeError::TooSmall(0)));
+    assert_eq!(
+        BlockSize::try_from(511u64),
+        Err(BlockSizeError::TooSmall(511))
+    );
+    assert_eq!(
+        BlockSize::try_from(0x1_0000_0000u64),
+        Err(BlockSizeError::TooLarge(0x1_0000_0000))
+    );
+
+    assert_eq!(
+        BlockSizeError::TooSmall(511).to_string(),
+        "block size of 511 bytes is less than the minimum of 512 bytes"
+    );
+    assert_eq!(
+        BlockSizeError::TooLarge(0x1_0000_0000).to_string(),
+        "block size of 4294967296 bytes is too large (must fit in a u32)"
+    );
+}
+
+#[test]
+fn test_block_size_serde() {
+    assert_tokens(&BlockSize::BS_4096, &[Token::U32(4096)]);
+    assert_de_tokens(&BlockSize::BS_512, &[Token::U64(512)]);
+    assert_de_tokens_error::<BlockSize>(
+        &[Token::U32(511)],
+        "block size of 511 bytes is less than the minimum of 512 bytes",
+    );
 }
 
 #[test]
@@ -9,16 +9,36 @@
 mod common;
 
 use common::check_derives;
-use gpt_disk_io::{BlockIo, BlockIoAdapter, SliceBlockIoError};
-use gpt_disk_types::{BlockSize, Lba};
+use gpt_disk_io::{
+    BlockIo, BlockIoAdapter, SliceBlockIoError, VerifyingBlockIo,
+    VerifyingBlockIoError, WriteProtectedBlockIo, WriteProtectedBlockIoError,
+};
+
+#[cfg(feature = "alloc")]
+use gpt_disk_io::{
+    IoOp, RecordingBlockIo, ReplayBlockIo, ReplayBlockIoError, RetryBlockIo,
+};
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+use std::mem::MaybeUninit;
 
 #[cfg(feature = "std")]
 use {
-    gpt_disk_io::ReadWriteSeek,
+    gpt_disk_io::{
+        CompressedBlockIo, FixedVhdBlockIo, ReadOnlyBlockIo, ReadWriteSeek,
+        SeekCache, SplitBlockIo,
+    },
+    gpt_disk_types::Guid,
+    std::cell::Cell,
     std::fs::{self, OpenOptions},
-    std::io::Write,
+    std::io::{Cursor, Read, Seek, SeekFrom, Write},
+    std::sync::{Arc, Mutex, RwLock},
 };
 
+#[cfg(feature = "qcow2")]
+use gpt_disk_io::Qcow2BlockIo;
+#[cfg(feature = "zstd")]
+use gpt_disk_io::ZstdSeekableBlockIo;
+
 #[test]
 fn test_block_io_adapter() {
     let mut bio = BlockIoAdapter::new(123, BlockSize::BS_512);
@@ -29,6 +49,29 @@ fn test_block_io_adapter() {
     assert_eq!(data, 123);
 }
 
+#[test]
+fn test_block_io_adapter_physical_block_size() {
+    let mut data = vec![0; 4096];
+
+    let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    assert_eq!(bio.physical_block_size(), None);
+    assert_eq!(bio.optimal_io_size(), None);
+
+    let bio = bio
+        .with_physical_block_size(Some(BlockSize::BS_4096))
+        .with_optimal_io_size(Some(1024 * 1024));
+    assert_eq!(bio.physical_block_size(), Some(BlockSize::BS_4096));
+    assert_eq!(bio.optimal_io_size(), Some(1024 * 1024));
+
+    // Check the `BlockIo` impl, via a wrapper that forwards the sizes.
+    let mut verify_buf = vec![0; 512];
+    let mut bio = VerifyingBlockIo::new(bio, &mut verify_buf);
+    assert_eq!(BlockIo::physical_block_size(&bio), Some(BlockSize::BS_4096));
+    assert_eq!(BlockIo::optimal_io_size(&bio), Some(1024 * 1024));
+    let bio = &mut bio;
+    assert_eq!(bio.physical_block_size(), Some(BlockSize::BS_4096));
+}
+
 #[test]
 fn test_slice_block_io_error() {
     check_derives::<SliceBlockIoError>();
@@ -96,6 +139,18 @@ where
     assert_eq!(buf[512], 3);
     assert_eq!(buf[1023], 4);
 
+    // Read into an uninitialized buffer.
+    let mut buf = vec![MaybeUninit::uninit(); 1024];
+    let buf = bio
+        .read_blocks_uninit(Lba(1), &mut buf)
+        .expect("read_blocks_uninit failed");
+    assert_eq!(buf.len(), 1024);
+    assert_eq!(buf[0], 3);
+    assert_eq!(buf[511], 4);
+    assert_eq!(buf[512..], [0; 512]);
+    let mut buf = vec![MaybeUninit::uninit(); 512];
+    assert!(bio.read_blocks_uninit(Lba(3), &mut buf).is_err());
+
     bio.take_storage()
 }
 
@@ -143,6 +198,17 @@ where
     expected[1024] = 11;
     expected[1535] = 12;
     assert_eq!(get_bytes(&bio), expected);
+
+    // Zero the last two blocks.
+    bio.write_zeroes(LbaRangeInclusive::new(Lba(1), Lba(2)).unwrap())
+        .unwrap();
+    bio.flush().unwrap();
+    expected[512..].fill(0);
+    assert_eq!(get_bytes(&bio), expected);
+
+    // Discard is only a hint, so the contents are not checked.
+    bio.discard_blocks(LbaRangeInclusive::new(Lba(0), Lba(0)).unwrap())
+        .unwrap();
 }
 
 fn check_read_and_write<S, G>(storage: S, get_bytes: G)
@@ -179,6 +245,12 @@ fn test_block_io_mut_slice() {
     check_read_and_write(storage, |bio| bio.storage().to_vec());
 }
 
+#[test]
+fn test_block_io_array() {
+    let data: [u8; 512 * 3] = get_read_data().try_into().unwrap();
+    check_read_and_write(data, |bio| bio.storage().to_vec());
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn test_block_io_vec() {
@@ -194,6 +266,7 @@ fn test_block_io_file() {
         .read(true)
         .write(true)
         .create(true)
+        .truncate(true)
         .open(path)
         .unwrap();
     file.write_all(&get_read_data()).unwrap();
@@ -203,6 +276,43 @@ fn test_block_io_file() {
     fs::remove_file(path).unwrap();
 }
 
+/// Test that `File` reads and writes use positioned IO, which does not
+/// depend on or change the file position.
+#[cfg(all(feature = "std", unix))]
+#[test]
+fn test_block_io_file_positioned() {
+    use std::io::ErrorKind;
+
+    let path = "/tmp/test_block_io_std_positioned.bin";
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .unwrap();
+    file.write_all(&get_read_data()).unwrap();
+    file.seek(SeekFrom::Start(5)).unwrap();
+
+    let mut bio = BlockIoAdapter::new(&file, BlockSize::BS_512);
+    let mut buf = vec![0; 512];
+    bio.read_blocks(Lba(1), &mut buf).unwrap();
+    assert_eq!(buf, get_read_data()[512..1024]);
+    buf.fill(0xab);
+    bio.write_blocks(Lba(2), &buf).unwrap();
+
+    assert_eq!((&file).stream_position().unwrap(), 5);
+    assert_eq!(fs::read(path).unwrap()[1024..1536], [0xab; 512]);
+
+    // Reading past the end fails.
+    assert_eq!(
+        bio.read_blocks(Lba(100), &mut buf).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    fs::remove_file(path).unwrap();
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_block_io_dyn_readwriteseek() {
@@ -211,6 +321,7 @@ fn test_block_io_dyn_readwriteseek() {
         .read(true)
         .write(true)
         .create(true)
+        .truncate(true)
         .open(path)
         .unwrap();
     file.write_all(&get_read_data()).unwrap();
@@ -220,3 +331,1135 @@ fn test_block_io_dyn_readwriteseek() {
 
     fs::remove_file(path).unwrap();
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn test_block_io_owned_cursor() {
+    check_read_and_write(Cursor::new(get_read_data()), |bio| {
+        bio.storage().get_ref().clone()
+    });
+    check_read_and_write(
+        Cursor::new(get_read_data().into_boxed_slice()),
+        |bio| bio.storage().get_ref().to_vec(),
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_block_io_boxed_readwriteseek() {
+    let path = "/tmp/test_block_io_std_boxed.bin";
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .unwrap();
+    file.write_all(&get_read_data()).unwrap();
+
+    let storage: Box<dyn ReadWriteSeek> = Box::new(file);
+    check_read_and_write(storage, |_| fs::read(path).unwrap());
+
+    let storage: Box<dyn ReadWriteSeek> =
+        Box::new(Cursor::new(get_read_data()));
+    check_read(storage);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_block_io_arc_mutex() {
+    let shared = Arc::new(Mutex::new(BlockIoAdapter::new(
+        vec![0; 512 * 2],
+        BlockSize::BS_512,
+    )));
+    let mut a = shared.clone();
+    let mut b = shared.clone();
+    assert_eq!(a.block_size(), BlockSize::BS_512);
+    assert_eq!(a.num_blocks().unwrap(), 2);
+
+    // Writes through one handle are visible through the other.
+    a.write_blocks(Lba(1), &[1; 512]).unwrap();
+    let mut block = [0; 512];
+    b.read_blocks(Lba(1), &mut block).unwrap();
+    assert_eq!(block, [1; 512]);
+
+    // Poisoning is ignored.
+    let c = shared.clone();
+    std::thread::spawn(move || {
+        let _guard = c.lock().unwrap();
+        panic!("poison the lock");
+    })
+    .join()
+    .unwrap_err();
+    assert!(shared.is_poisoned());
+    b.write_blocks(Lba(0), &[2; 512]).unwrap();
+    a.read_blocks(Lba(0), &mut block).unwrap();
+    assert_eq!(block, [2; 512]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_block_io_arc_rwlock() {
+    let shared = Arc::new(RwLock::new(BlockIoAdapter::new(
+        vec![0; 512 * 2],
+        BlockSize::BS_512,
+    )));
+    let mut a = shared.clone();
+    let mut b = shared;
+    assert_eq!(a.block_size(), BlockSize::BS_512);
+    assert_eq!(a.num_blocks().unwrap(), 2);
+
+    a.write_blocks(Lba(1), &[1; 512]).unwrap();
+    let mut block = [0; 512];
+    b.read_blocks(Lba(1), &mut block).unwrap();
+    assert_eq!(block, [1; 512]);
+    b.flush().unwrap();
+}
+
+/// Wrapper that counts calls to `seek`.
+#[cfg(feature = "std")]
+struct SeekCounter<'a> {
+    inner: Cursor<Vec<u8>>,
+    num_seeks: &'a Cell<usize>,
+}
+
+#[cfg(feature = "std")]
+impl Read for SeekCounter<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Write for SeekCounter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Seek for SeekCounter<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.num_seeks.set(self.num_seeks.get() + 1);
+        self.inner.seek(pos)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_seek_cache() {
+    let num_seeks = Cell::new(0);
+    let counter = SeekCounter {
+        inner: Cursor::new(get_read_data()),
+        num_seeks: &num_seeks,
+    };
+    let mut bio =
+        BlockIoAdapter::new(SeekCache::new(counter), BlockSize::BS_512);
+    let mut buf = vec![0; 512];
+
+    // Sequential reads only seek once.
+    for lba in 0..3 {
+        bio.read_blocks(Lba(lba), &mut buf).unwrap();
+        assert_eq!(buf, get_read_data()[lba as usize * 512..][..512]);
+    }
+    assert_eq!(num_seeks.get(), 1);
+
+    // Non-sequential access seeks again.
+    bio.read_blocks(Lba(1), &mut buf).unwrap();
+    assert_eq!(num_seeks.get(), 2);
+    bio.write_blocks(Lba(2), &[0xab; 512]).unwrap();
+    assert_eq!(num_seeks.get(), 2);
+
+    // Seeking to the end to get the size invalidates the position.
+    assert_eq!(bio.num_blocks().unwrap(), 3);
+    assert_eq!(num_seeks.get(), 3);
+    bio.read_blocks(Lba(0), &mut buf).unwrap();
+    assert_eq!(num_seeks.get(), 4);
+
+    // A short read fails, and the position is still tracked.
+    assert!(bio.read_blocks(Lba(2), &mut [0; 1024]).is_err());
+    assert_eq!(num_seeks.get(), 5);
+    bio.read_blocks(Lba(2), &mut buf).unwrap();
+    assert_eq!(num_seeks.get(), 6);
+    assert_eq!(buf, [0xab; 512]);
+
+    // Mutable access to the inner value clears the cached position.
+    let cache = bio.storage_mut();
+    cache.get_mut().inner.set_position(0);
+    bio.read_blocks(Lba(0), &mut buf).unwrap();
+    assert_eq!(num_seeks.get(), 7);
+    assert_eq!(buf, get_read_data()[..512]);
+
+    let data = bio.take_storage().into_inner().inner.into_inner();
+    assert_eq!(data[1024..], [0xab; 512]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_compressed_block_io() {
+    let data = get_read_data();
+    let num_opens = Cell::new(0);
+    let open = || {
+        num_opens.set(num_opens.get() + 1);
+        Ok(Cursor::new(data.as_slice()))
+    };
+    let mut bio = CompressedBlockIo::new(open, BlockSize::BS_512);
+    assert_eq!(BlockIo::block_size(&bio), BlockSize::BS_512);
+    assert_eq!(num_opens.get(), 0);
+
+    // Read the second block, then the third. Moving forward does not
+    // reopen the stream.
+    let mut buf = vec![0; 512];
+    bio.read_blocks(Lba(1), &mut buf).unwrap();
+    assert_eq!(buf[0], 3);
+    assert_eq!(buf[511], 4);
+    bio.read_blocks(Lba(2), &mut buf).unwrap();
+    assert_eq!(buf, [0; 512]);
+    assert_eq!(num_opens.get(), 1);
+
+    // Moving backward reopens the stream.
+    bio.read_blocks(Lba(0), &mut buf).unwrap();
+    assert_eq!(buf[0], 1);
+    assert_eq!(buf[511], 2);
+    assert_eq!(num_opens.get(), 2);
+
+    // Getting the size reads to the end, and the result is cached.
+    assert_eq!(bio.num_blocks().unwrap(), 3);
+    assert_eq!(bio.num_blocks().unwrap(), 3);
+    assert_eq!(num_opens.get(), 2);
+
+    // Out of bounds.
+    assert!(bio.read_blocks(Lba(3), &mut buf).is_err());
+
+    // Read two blocks at once.
+    let mut buf = vec![0; 1024];
+    bio.read_blocks(Lba(0), &mut buf).unwrap();
+    assert_eq!(buf[0], 1);
+    assert_eq!(buf[1023], 4);
+
+    // Writing is not supported.
+    assert!(bio.write_blocks(Lba(0), &buf).is_err());
+    bio.flush().unwrap();
+}
+
+/// Create a zstd frame that stores `data` in a single raw
+/// (uncompressed) block, so that the tests don't need a compressor.
+#[cfg(feature = "zstd")]
+fn zstd_raw_frame(data: &[u8]) -> Vec<u8> {
+    let len = u32::try_from(data.len()).unwrap();
+    let mut frame = Vec::new();
+    frame.extend(0xfd2f_b528u32.to_le_bytes());
+    // Single segment with a four-byte frame content size.
+    frame.push(0xa0);
+    frame.extend(len.to_le_bytes());
+    // Block header: last block, raw block type.
+    frame.extend(&((len << 3) | 1).to_le_bytes()[..3]);
+    frame.extend(data);
+    frame
+}
+
+/// Create an image in the zstd seekable format with one frame per
+/// element of `chunks`.
+#[cfg(feature = "zstd")]
+fn zstd_seekable_image(chunks: &[Vec<u8>], checksums: bool) -> Vec<u8> {
+    let mut image = Vec::new();
+    let mut seek_table = Vec::new();
+    for chunk in chunks {
+        let frame = zstd_raw_frame(chunk);
+        image.extend(&frame);
+        seek_table.extend(u32::try_from(frame.len()).unwrap().to_le_bytes());
+        seek_table.extend(u32::try_from(chunk.len()).unwrap().to_le_bytes());
+        if checksums {
+            // Checksums are not verified.
+            seek_table.extend([0xff; 4]);
+        }
+    }
+    seek_table.extend(u32::try_from(chunks.len()).unwrap().to_le_bytes());
+    seek_table.push(if checksums { 0x80 } else { 0 });
+    seek_table.extend(0x8f92_eab1u32.to_le_bytes());
+
+    image.extend(0x184d_2a5eu32.to_le_bytes());
+    image.extend(u32::try_from(seek_table.len()).unwrap().to_le_bytes());
+    image.extend(seek_table);
+    image
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd_seekable_block_io() {
+    // Five blocks in three frames, with each block filled with its
+    // index.
+    let chunks = [vec![0, 1], vec![2], vec![3, 4]].map(|blocks| {
+        blocks
+            .iter()
+            .flat_map(|block| [*block; 512])
+            .collect::<Vec<u8>>()
+    });
+
+    for checksums in [false, true] {
+        let image = zstd_seekable_image(&chunks, checksums);
+        let mut bio =
+            ZstdSeekableBlockIo::open(Cursor::new(image), BlockSize::BS_512)
+                .unwrap();
+        assert_eq!(BlockIo::block_size(&bio), BlockSize::BS_512);
+        assert_eq!(bio.num_blocks().unwrap(), 5);
+
+        // Read within a frame, forward and backward.
+        let mut buf = vec![0; 512];
+        bio.read_blocks(Lba(1), &mut buf).unwrap();
+        assert_eq!(buf, [1; 512]);
+        bio.read_blocks(Lba(0), &mut buf).unwrap();
+        assert_eq!(buf, [0; 512]);
+
+        // Read the last frame, then go back to an earlier one.
+        bio.read_blocks(Lba(4), &mut buf).unwrap();
+        assert_eq!(buf, [4; 512]);
+        bio.read_blocks(Lba(2), &mut buf).unwrap();
+        assert_eq!(buf, [2; 512]);
+
+        // Read across all three frames.
+        let mut buf = vec![0; 512 * 3];
+        bio.read_blocks(Lba(1), &mut buf).unwrap();
+        assert_eq!(buf[..512], [1; 512]);
+        assert_eq!(buf[512..1024], [2; 512]);
+        assert_eq!(buf[1024..], [3; 512]);
+
+        // Out of bounds.
+        assert!(bio.read_blocks(Lba(4), &mut buf).is_err());
+        let mut buf = vec![0; 512];
+        bio.read_blocks(Lba(3), &mut buf).unwrap();
+        assert_eq!(buf, [3; 512]);
+
+        // Writing is not supported.
+        assert!(bio.write_blocks(Lba(0), &buf).is_err());
+        bio.flush().unwrap();
+
+        assert_eq!(
+            bio.into_inner().into_inner(),
+            zstd_seekable_image(&chunks, checksums)
+        );
+    }
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd_seekable_block_io_invalid() {
+    let chunks = [vec![0; 512]];
+    let open = |image: Vec<u8>| {
+        ZstdSeekableBlockIo::open(Cursor::new(image), BlockSize::BS_512)
+    };
+
+    // An image with no frames is valid.
+    let mut bio = open(zstd_seekable_image(&[], false)).unwrap();
+    assert_eq!(bio.num_blocks().unwrap(), 0);
+
+    // A plain zstd stream without a seek table.
+    assert!(open(zstd_raw_frame(&chunks[0])).is_err());
+
+    // The seek table claims more frames than fit in the image.
+    let mut image = zstd_seekable_image(&chunks, false);
+    let num_frames_offset = image.len() - 9;
+    image[num_frames_offset] = 2;
+    assert!(open(image).is_err());
+
+    // Reserved descriptor bits are set.
+    let mut image = zstd_seekable_image(&chunks, false);
+    let descriptor_offset = image.len() - 5;
+    image[descriptor_offset] = 0x04;
+    assert!(open(image).is_err());
+
+    // The seek table frame has the wrong magic.
+    let mut image = zstd_seekable_image(&chunks, false);
+    let header_offset = image.len() - 25;
+    image[header_offset] = 0;
+    assert!(open(image).is_err());
+
+    // The frame sizes in the seek table exceed the compressed data.
+    let mut image = zstd_seekable_image(&chunks, false);
+    let entry_offset = image.len() - 17;
+    image[entry_offset + 1] = 0xff;
+    assert!(open(image).is_err());
+
+    // The seek table claims more decompressed data than the frame
+    // contains, so the read fails rather than returning garbage. The
+    // next read starts over and still works.
+    let mut image = zstd_seekable_image(&chunks, false);
+    image[entry_offset + 5] = 6;
+    let mut bio = open(image).unwrap();
+    assert_eq!(bio.num_blocks().unwrap(), 3);
+    let mut buf = vec![0; 512];
+    assert!(bio.read_blocks(Lba(1), &mut buf).is_err());
+    bio.read_blocks(Lba(0), &mut buf).unwrap();
+    assert_eq!(buf, [0; 512]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_fixed_vhd_block_io() {
+    let mut image = Cursor::new(get_read_data());
+
+    // Opening a raw image without a footer fails.
+    assert!(FixedVhdBlockIo::open(&mut image, BlockSize::BS_512).is_err());
+
+    // The size must be a multiple of 512.
+    assert!(FixedVhdBlockIo::create(
+        &mut image,
+        BlockSize::BS_512,
+        1000,
+        Guid::ZERO
+    )
+    .is_err());
+
+    // Add a footer to the existing data.
+    FixedVhdBlockIo::create(&mut image, BlockSize::BS_512, 512 * 3, Guid::ZERO)
+        .unwrap();
+    assert_eq!(image.get_ref().len(), 512 * 4);
+    assert_eq!(&image.get_ref()[512 * 3..512 * 3 + 8], b"conectix");
+
+    // Reopen the image and check that the footer is excluded.
+    let mut bio = FixedVhdBlockIo::open(&mut image, BlockSize::BS_512).unwrap();
+    assert_eq!(bio.num_blocks().unwrap(), 3);
+
+    let mut buf = vec![0; 512];
+    bio.read_blocks(Lba(1), &mut buf).unwrap();
+    assert_eq!(buf[0], 3);
+    assert_eq!(buf[511], 4);
+
+    // Reads and writes that overlap the footer are rejected.
+    assert!(bio.read_blocks(Lba(3), &mut buf).is_err());
+    assert!(bio.write_blocks(Lba(3), &buf).is_err());
+    assert!(bio.write_blocks(Lba(2), &[0; 1024]).is_err());
+
+    // Writes within the data succeed and leave the footer intact.
+    bio.write_blocks(Lba(2), &[5; 512]).unwrap();
+    bio.flush().unwrap();
+    let image = bio.take_storage();
+    assert_eq!(&image.get_ref()[512 * 2..512 * 3], &[5; 512]);
+    assert!(FixedVhdBlockIo::open(image, BlockSize::BS_512).is_ok());
+}
+
+/// Create a small version 3 qcow2 image with 512-byte clusters.
+///
+/// Layout of the image: header in cluster 0, L1 table in cluster 1, L2
+/// table in cluster 2, and data clusters starting at cluster 3. The
+/// virtual disk is four clusters:
+/// * Cluster 0 is unallocated.
+/// * Cluster 1 is allocated at host cluster 3.
+/// * Cluster 2 has the zero flag set.
+/// * Cluster 3 is marked as compressed.
+#[cfg(feature = "qcow2")]
+fn create_qcow2_image() -> Vec<u8> {
+    let cluster = 512;
+    let mut image = vec![0; cluster * 4];
+
+    // Header.
+    image[0..4].copy_from_slice(b"QFI\xfb");
+    image[4..8].copy_from_slice(&3u32.to_be_bytes());
+    image[20..24].copy_from_slice(&9u32.to_be_bytes());
+    image[24..32].copy_from_slice(&(512u64 * 4).to_be_bytes());
+    image[36..40].copy_from_slice(&1u32.to_be_bytes());
+    image[40..48].copy_from_slice(&512u64.to_be_bytes());
+    image[96..100].copy_from_slice(&4u32.to_be_bytes());
+    image[100..104].copy_from_slice(&104u32.to_be_bytes());
+
+    // L1 table.
+    image[512..520].copy_from_slice(&(1024u64 | 1 << 63).to_be_bytes());
+
+    // L2 table.
+    image[1032..1040].copy_from_slice(&(1536u64 | 1 << 63).to_be_bytes());
+    image[1040..1048].copy_from_slice(&1u64.to_be_bytes());
+    image[1048..1056].copy_from_slice(&(1536u64 | 1 << 62).to_be_bytes());
+
+    // Data.
+    image[1536..2048].fill(7);
+
+    image
+}
+
+#[cfg(feature = "qcow2")]
+#[test]
+fn test_qcow2_block_io() {
+    let image = create_qcow2_image();
+
+    // Raw images are rejected.
+    assert!(
+        Qcow2BlockIo::open(Cursor::new(vec![0; 1024]), BlockSize::BS_512)
+            .is_err()
+    );
+
+    // Images with a backing file are rejected.
+    let mut backing = image.clone();
+    backing[8..16].copy_from_slice(&2000u64.to_be_bytes());
+    assert!(
+        Qcow2BlockIo::open(Cursor::new(backing), BlockSize::BS_512).is_err()
+    );
+
+    let mut bio =
+        Qcow2BlockIo::open(Cursor::new(image), BlockSize::BS_512).unwrap();
+    assert_eq!(bio.num_blocks().unwrap(), 4);
+
+    // Read the unallocated, allocated, and zero clusters.
+    let mut buf = vec![1; 512 * 3];
+    bio.read_blocks(Lba(0), &mut buf).unwrap();
+    assert!(buf[..512].iter().all(|b| *b == 0));
+    assert!(buf[512..1024].iter().all(|b| *b == 7));
+    assert!(buf[1024..].iter().all(|b| *b == 0));
+
+    // Compressed clusters are not supported.
+    let mut buf = vec![0; 512];
+    assert!(bio.read_blocks(Lba(3), &mut buf).is_err());
+
+    // Reading past the end fails.
+    assert!(bio.read_blocks(Lba(4), &mut buf).is_err());
+
+    // Writing is not supported.
+    assert!(bio.write_blocks(Lba(1), &buf).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_read_only_block_io() {
+    use std::io::ErrorKind;
+
+    let mut bio =
+        ReadOnlyBlockIo::new(Cursor::new(get_read_data()), BlockSize::BS_512);
+    assert_eq!(bio.num_blocks().unwrap(), 3);
+    let mut buf = vec![0; 1024];
+    bio.read_blocks(Lba(0), &mut buf).unwrap();
+    assert_eq!(buf, get_read_data()[..1024]);
+    assert!(bio.read_blocks(Lba(2), &mut buf).is_err());
+
+    // All modifications fail.
+    let range = LbaRangeInclusive::new(Lba(0), Lba(1)).unwrap();
+    for err in [
+        bio.write_blocks(Lba(0), &buf).unwrap_err(),
+        bio.write_zeroes(range).unwrap_err(),
+        bio.discard_blocks(range).unwrap_err(),
+    ] {
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+    }
+    bio.flush().unwrap();
+    assert_eq!(bio.into_inner().into_inner(), get_read_data());
+
+    // A file opened without write access.
+    let path = "/tmp/test_block_io_read_only.bin";
+    fs::write(path, get_read_data()).unwrap();
+    let file = OpenOptions::new().read(true).open(path).unwrap();
+    let mut bio = ReadOnlyBlockIo::new(file, BlockSize::BS_512);
+    assert_eq!(bio.num_blocks().unwrap(), 3);
+    let mut buf = vec![0; 512];
+    bio.read_blocks(Lba(1), &mut buf).unwrap();
+    assert_eq!(buf[0], 3);
+    assert_eq!(
+        bio.write_blocks(Lba(0), &buf).unwrap_err().kind(),
+        ErrorKind::Unsupported
+    );
+    fs::remove_file(path).unwrap();
+}
+
+/// Start an HTTP server on localhost that serves `data` with support
+/// for `Range` requests. Returns the URL and a counter of requests.
+#[cfg(feature = "ureq")]
+fn start_range_server(
+    data: Vec<u8>,
+) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+    use std::io::BufRead;
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/disk.img", listener.local_addr().unwrap());
+    let num_requests = Arc::new(AtomicUsize::new(0));
+    let counter = num_requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+
+            let mut range = None;
+            let mut reader = std::io::BufReader::new(&stream);
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = line.strip_prefix("Range: bytes=") {
+                    let (start, end) = value.trim().split_once('-').unwrap();
+                    let start: usize = start.parse().unwrap();
+                    let end: usize = end.parse().unwrap();
+                    range = Some((start, end.min(data.len() - 1)));
+                }
+            }
+
+            let (status, headers, body) = match range {
+                Some((start, end)) => (
+                    "206 Partial Content",
+                    format!(
+                        "Content-Range: bytes {start}-{end}/{}\r\n",
+                        data.len()
+                    ),
+                    &data[start..=end],
+                ),
+                None => ("200 OK", String::new(), &data[..]),
+            };
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(body).unwrap();
+        }
+    });
+    (url, num_requests)
+}
+
+#[cfg(feature = "ureq")]
+#[test]
+fn test_http_block_io() {
+    use gpt_disk_io::HttpBlockIo;
+    use std::io::ErrorKind;
+    use std::sync::atomic::Ordering;
+
+    // 200 KiB image where each block is filled with its LBA.
+    let data: Vec<u8> = (0..400u32)
+        .flat_map(|i| [u8::try_from(i % 256).unwrap(); 512])
+        .collect();
+    let (url, num_requests) = start_range_server(data.clone());
+
+    let mut bio = HttpBlockIo::new(&url, BlockSize::BS_512).unwrap();
+    assert_eq!(bio.url(), url);
+    assert_eq!(bio.num_blocks().unwrap(), 400);
+    assert_eq!(num_requests.load(Ordering::SeqCst), 1);
+
+    // Read within the first chunk.
+    let mut buf = vec![0; 512];
+    bio.read_blocks(Lba(1), &mut buf).unwrap();
+    assert_eq!(buf, [1; 512]);
+    assert_eq!(num_requests.load(Ordering::SeqCst), 2);
+
+    // Cached.
+    bio.read_blocks(Lba(2), &mut buf).unwrap();
+    assert_eq!(buf, [2; 512]);
+    assert_eq!(num_requests.load(Ordering::SeqCst), 2);
+
+    // Read spanning three chunks, including the partial last chunk.
+    let mut buf = vec![0; 512 * 300];
+    bio.read_blocks(Lba(100), &mut buf).unwrap();
+    assert_eq!(buf, data[512 * 100..]);
+    assert_eq!(num_requests.load(Ordering::SeqCst), 5);
+
+    // Reading past the end and writing fail.
+    let mut buf = vec![0; 1024];
+    assert_eq!(
+        bio.read_blocks(Lba(399), &mut buf).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(
+        bio.write_blocks(Lba(0), &buf).unwrap_err().kind(),
+        ErrorKind::Unsupported
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_split_block_io() {
+    // Chunk sizes that don't line up with block boundaries.
+    let mut chunks = [
+        Cursor::new(vec![1; 700]),
+        Cursor::new(vec![2; 1000]),
+        Cursor::new(vec![3; 348]),
+    ];
+    let mut bio =
+        SplitBlockIo::new(chunks.iter_mut().collect(), BlockSize::BS_512)
+            .unwrap();
+    assert_eq!(bio.chunks().len(), 3);
+    assert_eq!(bio.num_blocks().unwrap(), 4);
+
+    // Read a block that spans the first two chunks.
+    let mut buf = vec![0; 512];
+    bio.read_blocks(Lba(1), &mut buf).unwrap();
+    assert_eq!(buf[..188], [1; 188]);
+    assert_eq!(buf[188..], [2; 324]);
+
+    // Read across all three chunks.
+    let mut buf = vec![0; 512 * 3];
+    bio.read_blocks(Lba(1), &mut buf).unwrap();
+    assert_eq!(buf[..188], [1; 188]);
+    assert_eq!(buf[188..1188], [2; 1000]);
+    assert_eq!(buf[1188..], [3; 348]);
+
+    // Write a block that spans the last two chunks.
+    bio.write_blocks(Lba(3), &[4; 512]).unwrap();
+    bio.flush().unwrap();
+
+    // Access past the end fails.
+    let mut buf = vec![0; 512];
+    assert!(bio.read_blocks(Lba(4), &mut buf).is_err());
+    assert!(bio.write_blocks(Lba(4), &buf).is_err());
+
+    drop(bio);
+    assert_eq!(chunks[1].get_ref()[..836], [2; 836]);
+    assert_eq!(chunks[1].get_ref()[836..], [4; 164]);
+    assert_eq!(chunks[2].get_ref(), &[4; 348]);
+}
+
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+#[test]
+fn test_io_uring_block_io() {
+    use gpt_disk_io::IoUringBlockIo;
+
+    let path = "/tmp/test_block_io_io_uring.bin";
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .unwrap();
+    file.set_len(512 * 8).unwrap();
+
+    // Skip the test if io_uring is not available, e.g. in a sandbox.
+    let Ok(mut bio) =
+        IoUringBlockIo::with_queue_depth(file, BlockSize::BS_512, 4)
+    else {
+        return;
+    };
+    assert_eq!(bio.num_blocks().unwrap(), 8);
+
+    // More writes than the queue depth.
+    for lba in 0..6 {
+        bio.write_blocks(Lba(lba), &[u8::try_from(lba).unwrap() + 1; 512])
+            .unwrap();
+    }
+
+    // Reading drains the queue first.
+    let mut buf = vec![0; 512 * 2];
+    bio.read_blocks(Lba(4), &mut buf).unwrap();
+    assert_eq!(buf[..512], [5; 512]);
+    assert_eq!(buf[512..], [6; 512]);
+
+    // Multi-block write.
+    let mut buf = vec![7; 512 * 2];
+    buf[512..].fill(8);
+    bio.write_blocks(Lba(6), &buf).unwrap();
+    bio.flush().unwrap();
+    drop(bio);
+
+    let data = fs::read(path).unwrap();
+    for (i, block) in data.chunks(512).enumerate() {
+        assert_eq!(block, [u8::try_from(i).unwrap() + 1; 512]);
+    }
+    fs::remove_file(path).unwrap();
+}
+
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+#[test]
+fn test_io_uring_block_io_submit_error() {
+    use gpt_disk_io::io_uring::IoUring;
+    use gpt_disk_io::IoUringBlockIo;
+
+    let path = "/tmp/test_block_io_io_uring_submit_error.bin";
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .unwrap();
+    file.set_len(512 * 8).unwrap();
+
+    // A ring created disabled rejects submissions until it is enabled,
+    // which forces the submit to fail. Skip the test if io_uring is not
+    // available, e.g. in a sandbox.
+    let Ok(ring) = IoUring::builder().setup_r_disabled().build(4) else {
+        fs::remove_file(path).unwrap();
+        return;
+    };
+    let mut bio =
+        IoUringBlockIo::from_ring(file, BlockSize::BS_512, ring).unwrap();
+
+    bio.write_blocks(Lba(0), &[1; 512]).unwrap();
+    assert!(bio.flush().is_err());
+
+    // The ring is in an unknown state, so everything else fails too.
+    let mut buf = vec![0; 512];
+    assert!(bio.write_blocks(Lba(1), &buf).is_err());
+    assert!(bio.read_blocks(Lba(0), &mut buf).is_err());
+    assert!(bio.flush().is_err());
+    drop(bio);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_block_io_default_write_zeroes() {
+    // `SplitBlockIo` uses the default implementation of
+    // `BlockIo::write_zeroes`. Zero a range larger than the default
+    // implementation's buffer.
+    let mut chunks = [Cursor::new(vec![1; 512 * 10])];
+    let mut bio =
+        SplitBlockIo::new(chunks.iter_mut().collect(), BlockSize::BS_512)
+            .unwrap();
+    bio.write_zeroes(LbaRangeInclusive::new(Lba(1), Lba(9)).unwrap())
+        .unwrap();
+    bio.discard_blocks(LbaRangeInclusive::new(Lba(0), Lba(9)).unwrap())
+        .unwrap();
+    drop(bio);
+
+    let data = chunks[0].get_ref();
+    assert_eq!(data[..512], [1; 512]);
+    assert!(data[512..].iter().all(|b| *b == 0));
+}
+
+#[test]
+fn test_block_io_default_write_zeroes_large_block_size() {
+    // `VerifyingBlockIo` uses the default implementation of
+    // `BlockIo::write_zeroes`.
+    let mut data = vec![1; 8192 * 10];
+    let mut verify_buf = vec![0; 8192];
+    let mut bio = VerifyingBlockIo::new(
+        BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_8192),
+        &mut verify_buf,
+    );
+    bio.write_zeroes(LbaRangeInclusive::new(Lba(1), Lba(9)).unwrap())
+        .unwrap();
+    assert_eq!(data[..8192], [1; 8192]);
+    assert!(data[8192..].iter().all(|b| *b == 0));
+
+    // Block sizes larger than the static zero buffer use an allocated
+    // buffer.
+    #[cfg(feature = "alloc")]
+    {
+        let bs = BlockSize::new(128 * 1024).unwrap();
+        let mut data = vec![1; 128 * 1024 * 3];
+        let mut bio = BlockIoAdapter::new(data.as_mut_slice(), bs);
+        let mut verify_buf = vec![0; 128 * 1024];
+        let mut bio = VerifyingBlockIo::new(&mut bio, &mut verify_buf);
+        bio.write_zeroes(LbaRangeInclusive::new(Lba(1), Lba(2)).unwrap())
+            .unwrap();
+        assert!(data[..128 * 1024].iter().all(|b| *b == 1));
+        assert!(data[128 * 1024..].iter().all(|b| *b == 0));
+    }
+}
+
+/// `BlockIo` that silently drops writes to one block.
+struct DroppingBlockIo<'a> {
+    inner: BlockIoAdapter<&'a mut [u8]>,
+    dropped_lba: Lba,
+}
+
+impl BlockIo for DroppingBlockIo<'_> {
+    type Error = SliceBlockIoError;
+
+    fn block_size(&self) -> BlockSize {
+        self.inner.block_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        self.inner.num_blocks()
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.inner.read_blocks(start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        for (i, block) in src.chunks(512).enumerate() {
+            let lba = Lba(start_lba.0 + u64::try_from(i).unwrap());
+            if lba != self.dropped_lba {
+                self.inner.write_blocks(lba, block)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+#[test]
+fn test_verifying_block_io() {
+    let mut data = vec![0; 512 * 4];
+    let mut verify_buf = vec![0; 1024];
+    let mut bio = VerifyingBlockIo::new(
+        DroppingBlockIo {
+            inner: BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512),
+            dropped_lba: Lba(2),
+        },
+        &mut verify_buf,
+    );
+    assert_eq!(bio.num_blocks().unwrap(), 4);
+
+    // Successful write.
+    bio.write_blocks(Lba(0), &[1; 1024]).unwrap();
+
+    // Writes that include the dropped block fail verification. The
+    // write is larger than the verification buffer, so multiple reads
+    // are needed.
+    assert_eq!(
+        bio.write_blocks(Lba(0), &[2; 512 * 4]),
+        Err(VerifyingBlockIoError::VerificationFailed { lba: Lba(2) })
+    );
+    assert_eq!(
+        bio.write_blocks(Lba(2), &[2; 512]).unwrap_err().to_string(),
+        "verification failed at LBA 2"
+    );
+
+    // Errors from the underlying IO are passed through.
+    assert_eq!(
+        bio.write_blocks(Lba(4), &[2; 512]),
+        Err(VerifyingBlockIoError::Io(SliceBlockIoError::OutOfBounds {
+            start_lba: Lba(4),
+            length_in_bytes: 512
+        }))
+    );
+
+    // The verification buffer must be at least one block.
+    let mut data = vec![0; 512];
+    let mut verify_buf = vec![0; 511];
+    let mut bio = VerifyingBlockIo::new(
+        BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512),
+        &mut verify_buf,
+    );
+    assert_eq!(
+        bio.write_blocks(Lba(0), &[1; 512]),
+        Err(VerifyingBlockIoError::BufferTooSmall)
+    );
+    assert_eq!(bio.take_io().take_storage(), &[0; 512]);
+}
+
+#[test]
+fn test_write_protected_block_io() {
+    let mut data = vec![1; 512 * 4];
+    let mut bio = WriteProtectedBlockIo::new(BlockIoAdapter::new(
+        data.as_mut_slice(),
+        BlockSize::BS_512,
+    ));
+    assert_eq!(bio.block_size(), BlockSize::BS_512);
+    assert_eq!(bio.num_blocks().unwrap(), 4);
+
+    // Reads are passed through.
+    let mut block = [0; 512];
+    bio.read_blocks(Lba(3), &mut block).unwrap();
+    assert_eq!(block, [1; 512]);
+    assert_eq!(
+        bio.read_blocks(Lba(4), &mut block),
+        Err(WriteProtectedBlockIoError::Io(
+            SliceBlockIoError::OutOfBounds {
+                start_lba: Lba(4),
+                length_in_bytes: 512
+            }
+        ))
+    );
+
+    // Modifications are rejected.
+    let range = LbaRangeInclusive::new(Lba(0), Lba(1)).unwrap();
+    assert_eq!(
+        bio.write_blocks(Lba(0), &[2; 512]),
+        Err(WriteProtectedBlockIoError::WriteProtected)
+    );
+    assert_eq!(
+        bio.write_zeroes(range),
+        Err(WriteProtectedBlockIoError::WriteProtected)
+    );
+    assert_eq!(
+        bio.discard_blocks(range),
+        Err(WriteProtectedBlockIoError::WriteProtected)
+    );
+    assert_eq!(
+        bio.write_blocks(Lba(0), &[2; 512]).unwrap_err().to_string(),
+        "storage is write protected"
+    );
+    bio.flush().unwrap();
+
+    // The underlying storage is unchanged.
+    assert_eq!(bio.take_io().take_storage(), [1; 512 * 4]);
+}
+
+/// `BlockIo` that fails the first `failures` reads.
+#[cfg(feature = "alloc")]
+struct FlakyBlockIo {
+    inner: BlockIoAdapter<Vec<u8>>,
+    failures: u32,
+}
+
+#[cfg(feature = "alloc")]
+impl BlockIo for FlakyBlockIo {
+    type Error = SliceBlockIoError;
+
+    fn block_size(&self) -> BlockSize {
+        self.inner.block_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        self.inner.num_blocks()
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        if self.failures > 0 {
+            self.failures -= 1;
+            return Err(SliceBlockIoError::Overflow);
+        }
+        self.inner.read_blocks(start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.inner.write_blocks(start_lba, src)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_retry_block_io() {
+    let mut retries = Vec::new();
+    let mut bio = RetryBlockIo::new(
+        FlakyBlockIo {
+            inner: BlockIoAdapter::new(get_read_data(), BlockSize::BS_512),
+            failures: 2,
+        },
+        2,
+    )
+    .with_backoff(|retry| retries.push(retry));
+    assert_eq!(bio.max_retries(), 2);
+
+    // Succeeds on the last attempt.
+    let mut buf = vec![0; 512];
+    bio.read_blocks(Lba(1), &mut buf).unwrap();
+    assert_eq!(buf[0], 3);
+
+    // Fails every attempt.
+    bio.io_mut().failures = 2;
+    let err = bio.read_blocks(Lba(3), &mut buf).unwrap_err();
+    assert_eq!(
+        err.errors(),
+        [
+            SliceBlockIoError::Overflow,
+            SliceBlockIoError::Overflow,
+            SliceBlockIoError::OutOfBounds {
+                start_lba: Lba(3),
+                length_in_bytes: 512
+            }
+        ]
+    );
+    assert_eq!(
+        *err.last(),
+        SliceBlockIoError::OutOfBounds {
+            start_lba: Lba(3),
+            length_in_bytes: 512
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "failed after 3 attempts: numeric overflow occurred; \
+         numeric overflow occurred; \
+         out of bounds: start_lba=3, length_in_bytes=512"
+    );
+
+    // Operations that succeed the first time don't call the backoff.
+    bio.write_blocks(Lba(0), &[1; 512]).unwrap();
+    bio.flush().unwrap();
+
+    drop(bio);
+    assert_eq!(retries, [1, 2, 1, 2]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_recording_and_replay_block_io() {
+    fn run<Io: BlockIo>(bio: &mut Io, data: u8) -> Result<(), Io::Error> {
+        let range = LbaRangeInclusive::new(Lba(2), Lba(3)).unwrap();
+        let mut buf = [0; 1024];
+        bio.read_blocks(Lba(0), &mut buf)?;
+        bio.write_blocks(Lba(1), &[data; 512])?;
+        bio.write_zeroes(range)?;
+        bio.discard_blocks(range)?;
+        bio.flush()
+    }
+
+    let mut data = vec![0; 512 * 4];
+    let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    let mut bio = RecordingBlockIo::with_data_hashes(bio);
+    run(&mut bio, 1).unwrap();
+    // Queries are not recorded.
+    assert_eq!(bio.num_blocks().unwrap(), 4);
+    let trace = bio.take_trace();
+    assert!(bio.trace().is_empty());
+    assert_eq!(trace[4].op, IoOp::Flush);
+    let ops: Vec<_> = trace.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        ops,
+        [
+            "read 0+2",
+            "write 1+1 crc32=0x83e5c6ff",
+            "write_zeroes 2+2",
+            "discard 2+2",
+            "flush",
+        ]
+    );
+
+    // Identical IO succeeds.
+    let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    let mut bio = ReplayBlockIo::new(bio, &trace);
+    run(&mut bio, 1).unwrap();
+    bio.finish().unwrap();
+
+    // Different data is detected.
+    let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    let mut bio = ReplayBlockIo::new(bio, &trace);
+    let err = run(&mut bio, 2).unwrap_err();
+    assert!(matches!(err, ReplayBlockIoError::Mismatch { index: 1, .. }));
+
+    // Without data hashes, only the operations are compared.
+    let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    let mut bio = RecordingBlockIo::new(bio);
+    run(&mut bio, 1).unwrap();
+    let (_, trace) = bio.into_parts();
+    assert_eq!(trace[1].data_crc32, None);
+    let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    let mut bio = ReplayBlockIo::new(bio, &trace);
+    run(&mut bio, 2).unwrap();
+
+    // Extra and missing operations.
+    let err = bio.flush().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "operation 5 is past the end of the trace: flush"
+    );
+    let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    let bio = ReplayBlockIo::new(bio, &trace);
+    assert_eq!(
+        bio.finish().unwrap_err(),
+        ReplayBlockIoError::Incomplete { remaining: 5 }
+    );
+}
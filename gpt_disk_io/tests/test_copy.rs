@@ -0,0 +1,126 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use gpt_disk_io::{
+    copy_blocks, BlockIoAdapter, CopyBlocksError, SliceBlockIoError,
+};
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+
+fn range(start: u64, end: u64) -> LbaRangeInclusive {
+    LbaRangeInclusive::new(Lba(start), Lba(end)).unwrap()
+}
+
+/// Create data where each block is filled with its LBA.
+fn numbered_blocks(num_blocks: u8) -> Vec<u8> {
+    (0..num_blocks).flat_map(|i| [i; 512]).collect()
+}
+
+#[test]
+fn test_copy_blocks() {
+    let src_data = numbered_blocks(10);
+    let mut dst_data = vec![0xff; 512 * 10];
+    let mut src = BlockIoAdapter::new(src_data.as_slice(), BlockSize::BS_512);
+    let mut dst =
+        BlockIoAdapter::new(dst_data.as_mut_slice(), BlockSize::BS_512);
+
+    // Buffer size that does not evenly divide the range.
+    let mut buf = vec![0; 512 * 2];
+    copy_blocks(&mut src, &mut dst, range(2, 6), Lba(3), &mut buf).unwrap();
+    assert_eq!(dst_data[..512 * 3], [0xff; 512 * 3]);
+    assert_eq!(dst_data[512 * 3..512 * 8], src_data[512 * 2..512 * 7]);
+    assert_eq!(dst_data[512 * 8..], [0xff; 512 * 2]);
+}
+
+#[test]
+fn test_copy_blocks_errors() {
+    let src_data = numbered_blocks(4);
+    let mut dst_data = vec![0; 4096 * 2];
+    let mut src = BlockIoAdapter::new(src_data.as_slice(), BlockSize::BS_512);
+    let mut dst =
+        BlockIoAdapter::new(dst_data.as_mut_slice(), BlockSize::BS_512);
+    let mut buf = vec![0; 512];
+
+    assert_eq!(
+        copy_blocks(&mut src, &mut dst, range(0, 3), Lba(0), &mut []),
+        Err(CopyBlocksError::BufferTooSmall)
+    );
+    assert_eq!(
+        copy_blocks(&mut src, &mut dst, range(0, 3), Lba(u64::MAX), &mut buf),
+        Err(CopyBlocksError::Overflow)
+    );
+    assert_eq!(
+        copy_blocks(&mut src, &mut dst, range(2, 4), Lba(0), &mut buf),
+        Err(CopyBlocksError::Read(SliceBlockIoError::OutOfBounds {
+            start_lba: Lba(4),
+            length_in_bytes: 512,
+        }))
+    );
+    assert_eq!(
+        copy_blocks(&mut src, &mut dst, range(0, 3), Lba(15), &mut buf),
+        Err(CopyBlocksError::Write(SliceBlockIoError::OutOfBounds {
+            start_lba: Lba(16),
+            length_in_bytes: 512,
+        }))
+    );
+
+    let mut dst =
+        BlockIoAdapter::new(dst_data.as_mut_slice(), BlockSize::BS_4096);
+    assert_eq!(
+        copy_blocks(&mut src, &mut dst, range(0, 3), Lba(0), &mut buf),
+        Err(CopyBlocksError::BlockSizeMismatch)
+    );
+
+    assert_eq!(
+        CopyBlocksError::<SliceBlockIoError, SliceBlockIoError>::Write(
+            SliceBlockIoError::ReadOnly
+        )
+        .to_string(),
+        "write failed: attempted to write to a read-only byte slice"
+    );
+}
+
+#[cfg(all(feature = "rayon", any(unix, windows)))]
+#[test]
+fn test_copy_blocks_parallel() {
+    use gpt_disk_io::{copy_blocks_parallel, SharedBlockIo};
+    use std::fs::{self, OpenOptions};
+
+    let src_path = "/tmp/test_copy_blocks_parallel_src.bin";
+    let dst_path = "/tmp/test_copy_blocks_parallel_dst.bin";
+    let src_data: Vec<u8> = (0..200).flat_map(|i| [i; 512]).collect();
+    fs::write(src_path, &src_data).unwrap();
+    fs::write(dst_path, vec![0xff; 512 * 210]).unwrap();
+
+    let src_file = OpenOptions::new().read(true).open(src_path).unwrap();
+    let dst_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(dst_path)
+        .unwrap();
+    let src = BlockIoAdapter::new(&src_file, BlockSize::BS_512);
+    let dst = BlockIoAdapter::new(dst_file, BlockSize::BS_512);
+    assert_eq!(src.num_blocks().unwrap(), 200);
+    assert_eq!(dst.num_blocks().unwrap(), 210);
+
+    copy_blocks_parallel(&src, &dst, range(0, 199), Lba(5), 512 * 3).unwrap();
+    dst.flush().unwrap();
+
+    let dst_data = fs::read(dst_path).unwrap();
+    assert_eq!(dst_data[..512 * 5], [0xff; 512 * 5]);
+    assert_eq!(dst_data[512 * 5..512 * 205], src_data);
+    assert_eq!(dst_data[512 * 205..], [0xff; 512 * 5]);
+
+    // Reading past the end of the source fails.
+    assert!(matches!(
+        copy_blocks_parallel(&src, &dst, range(190, 209), Lba(0), 512 * 3),
+        Err(CopyBlocksError::Read(_))
+    ));
+
+    fs::remove_file(src_path).unwrap();
+    fs::remove_file(dst_path).unwrap();
+}
@@ -19,3 +19,13 @@ fn test_crc32_display() {
     assert_eq!(format!("{crc:#x}"), "0x78563412");
     assert_eq!(format!("{crc}"), "0x78563412");
 }
+
+#[test]
+fn test_crc32_of() {
+    let expected = Crc32(U32Le::from_u32(0xcbf4_3926));
+    assert_eq!(Crc32::of(b"123456789"), expected);
+    assert!(Crc32::verify(b"123456789", expected));
+    assert!(!Crc32::verify(b"123456780", expected));
+
+    assert_eq!(Crc32::of(&[]), Crc32(U32Le::from_u32(0)));
+}
@@ -0,0 +1,142 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(all(feature = "devices", target_os = "linux"))]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_list_block_devices() {
+    use gpt_disk_io::devices::list_block_devices;
+    use std::path::Path;
+
+    // The devices present depend on the machine running the test, so
+    // just check that the results are consistent.
+    let devices = list_block_devices().unwrap();
+    for device in &devices {
+        assert!(device.path.starts_with("/dev"));
+        let name = device.path.file_name().unwrap();
+        assert!(Path::new("/sys/block").join(name).exists());
+
+        // On Linux, all fields are available.
+        assert!(device.num_bytes.unwrap() > 0);
+        assert!(device.block_size.is_some());
+        assert!(device.removable.is_some());
+    }
+
+    // Sorted by path.
+    assert!(devices.windows(2).all(|w| w[0].path < w[1].path));
+}
+
+#[cfg(all(feature = "devices", any(target_os = "macos", windows)))]
+#[test]
+fn test_list_block_devices() {
+    use gpt_disk_io::devices::list_block_devices;
+
+    // Every machine has at least one disk, and all fields are
+    // available without special permissions.
+    let devices = list_block_devices().unwrap();
+    assert!(!devices.is_empty());
+    for device in &devices {
+        assert!(device.num_bytes.unwrap() > 0);
+        assert!(device.block_size.is_some());
+        assert!(device.removable.is_some());
+    }
+}
+
+#[cfg(feature = "devices")]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_device_open_options() {
+    use gpt_disk_io::devices::{is_device_in_use, DeviceOpenOptions};
+    use std::fs;
+
+    // Image files are never in use.
+    let path = "tmp_test_device_open_options.bin";
+    fs::write(path, vec![0; 1024]).unwrap();
+    assert!(!is_device_in_use(path).unwrap());
+    DeviceOpenOptions::new().write(true).open(path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    // Opening a missing file fails.
+    assert!(DeviceOpenOptions::new().open(path).is_err());
+}
+
+#[cfg(feature = "devices")]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_query_block_sizes() {
+    use gpt_disk_io::devices::{query_block_sizes, DeviceBlockSizes};
+    use gpt_disk_io::gpt_disk_types::BlockSize;
+    use gpt_disk_io::{BlockIo, BlockIoAdapter};
+    use std::fs::{self, File};
+
+    // Image files don't have any block sizes.
+    let path = "tmp_test_query_block_sizes.bin";
+    fs::write(path, vec![0; 1024]).unwrap();
+    let file = File::open(path).unwrap();
+    assert_eq!(query_block_sizes(&file), DeviceBlockSizes::default());
+
+    let mut bio = BlockIoAdapter::from_device(file);
+    assert_eq!(bio.block_size(), BlockSize::BS_512);
+    assert_eq!(bio.physical_block_size(), None);
+    assert_eq!(bio.optimal_io_size(), None);
+    assert_eq!(bio.num_blocks().unwrap(), 2);
+    fs::remove_file(path).unwrap();
+}
+
+#[cfg(all(feature = "devices", target_os = "linux"))]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_device_in_use() {
+    use gpt_disk_io::devices::{is_device_in_use, DeviceOpenOptions};
+    use gpt_disk_io::DiskError;
+    use std::fs;
+    use std::os::unix::fs::FileTypeExt;
+
+    // Find the source of the root mount. If it's a block device, it
+    // must be reported as in use.
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").unwrap();
+    let root_source = mountinfo.lines().find_map(|line| {
+        let fields: Vec<_> = line.split(' ').collect();
+        if fields[4] != "/" {
+            return None;
+        }
+        let sep = fields.iter().position(|f| *f == "-")?;
+        fields.get(sep + 2).map(|s| s.to_string())
+    });
+    let Some(root_source) = root_source else {
+        return;
+    };
+    let is_block_device = fs::metadata(&root_source)
+        .map_or(false, |m| m.file_type().is_block_device());
+    if !is_block_device {
+        return;
+    }
+
+    assert!(is_device_in_use(&root_source).unwrap());
+    assert!(matches!(
+        DeviceOpenOptions::new().write(true).open(&root_source),
+        Err(DiskError::TargetInUse)
+    ));
+}
+
+#[cfg(all(feature = "devices", windows))]
+#[test]
+fn test_device_in_use() {
+    use gpt_disk_io::devices::{is_device_in_use, DeviceOpenOptions};
+    use gpt_disk_io::DiskError;
+    use std::env;
+
+    // The system drive is always mounted.
+    let drive = env::var("SystemDrive").unwrap_or_else(|_| "C:".to_owned());
+    let path = format!(r"\\.\{drive}");
+    assert!(is_device_in_use(&path).unwrap());
+    assert!(matches!(
+        DeviceOpenOptions::new().write(true).open(&path),
+        Err(DiskError::TargetInUse)
+    ));
+}
@@ -11,8 +11,19 @@ mod common;
 use common::{
     create_partition_entry, create_primary_header, create_secondary_header,
 };
-use gpt_disk_io::{BlockIo, BlockIoAdapter, Disk};
-use gpt_disk_types::{BlockSize, GptPartitionEntryArray};
+use gpt_disk_io::{
+    BlockIo, BlockIoAdapter, Disk, DiskError, GptValidationOptions,
+    PartitionBlockIo, SliceBlockIoError, SubRangeBlockIoError,
+};
+use gpt_disk_types::{
+    guid, ApplyLayoutPolicy, BlockSize, Crc32, GptDiskLayout, GptLayoutChange,
+    GptPartitionDescription, GptPartitionEntry, GptPartitionEntryArray,
+    GptPartitionEntryArrayLayout, GptPartitionEntrySize, GptPartitionTable,
+    GptPartitionTableError, GptPartitionType, Guid, Lba, LbaLe,
+    LbaRangeInclusive, PartitionIndex, U32Le,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 
 #[cfg(feature = "std")]
 use std::fs::{self, File, OpenOptions};
@@ -29,7 +40,7 @@ impl SparseChunk {
 }
 
 #[rustfmt::skip]
-const SPARSE_DISK: &'static [SparseChunk] = &[
+const SPARSE_DISK: &[SparseChunk] = &[
 // Test data generated as follows:
 //
 // truncate --size 4MiB disk.bin
@@ -211,6 +222,45 @@ fn test_with_file(test_disk: &[u8]) {
     fs::remove_file(path).unwrap();
 }
 
+#[cfg(feature = "alloc")]
+fn test_with_dyn(test_disk: &[u8]) {
+    use gpt_disk_io::{DynDisk, SliceBlockIoError};
+
+    let mut contents = test_disk.to_vec();
+
+    // Boxed trait objects can be used as the `BlockIo` directly.
+    let bio: Box<dyn BlockIo<Error = SliceBlockIoError>> =
+        Box::new(BlockIoAdapter::new(test_disk, BlockSize::BS_512));
+    test_disk_read(bio);
+    let bio: Box<dyn BlockIo<Error = SliceBlockIoError>> = Box::new(
+        BlockIoAdapter::new(contents.as_mut_slice(), BlockSize::BS_512),
+    );
+    test_disk_read(bio);
+
+    // Select the backend at runtime.
+    for read_only in [false, true] {
+        let mut disk: DynDisk<'_, SliceBlockIoError> = if read_only {
+            DynDisk::from_dyn(BlockIoAdapter::new(test_disk, BlockSize::BS_512))
+                .unwrap()
+        } else {
+            DynDisk::from_dyn(BlockIoAdapter::new(
+                contents.as_mut_slice(),
+                BlockSize::BS_512,
+            ))
+            .unwrap()
+        };
+        let mut block_buf = vec![0; 512];
+        assert_eq!(
+            disk.read_primary_gpt_header(&mut block_buf).unwrap(),
+            create_primary_header()
+        );
+        assert_eq!(
+            disk.write_protective_mbr(&mut block_buf).is_err(),
+            read_only
+        );
+    }
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn test_disk() {
@@ -219,6 +269,1593 @@ fn test_disk() {
     test_with_slice(&test_disk);
     test_with_mut_slice(&test_disk);
 
+    #[cfg(feature = "alloc")]
+    test_with_dyn(&test_disk);
+
     #[cfg(feature = "std")]
     test_with_file(&test_disk);
 }
+
+#[test]
+fn test_disk_new_strict() {
+    let mut data = vec![0; 4096];
+
+    let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512);
+    assert!(Disk::new_strict(bio).is_ok());
+
+    let bio =
+        BlockIoAdapter::new(data.as_mut_slice(), BlockSize::new(767).unwrap());
+    assert!(matches!(
+        Disk::new_strict(bio),
+        Err(DiskError::UnsupportedBlockSize)
+    ));
+
+    // Block sizes are forwarded from the `BlockIo`.
+    let bio = BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512)
+        .with_physical_block_size(Some(BlockSize::BS_4096))
+        .with_optimal_io_size(Some(65536));
+    let disk = Disk::new_strict(bio).unwrap();
+    assert_eq!(disk.physical_block_size(), Some(BlockSize::BS_4096));
+    assert_eq!(disk.optimal_io_size(), Some(65536));
+    drop(disk);
+
+    // The non-strict constructor accepts any block size.
+    let bio =
+        BlockIoAdapter::new(data.as_mut_slice(), BlockSize::new(767).unwrap());
+    assert!(Disk::new(bio).is_ok());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_disk_from_mut() {
+    use gpt_disk_io::{IoOp, RecordingBlockIo};
+
+    let mut bio = RecordingBlockIo::new(BlockIoAdapter::new(
+        vec![0; 512 * 8],
+        BlockSize::BS_512,
+    ));
+    let mut block_buf = vec![0; 512];
+
+    let header = create_primary_header();
+    let mut disk = Disk::from_mut(&mut bio).unwrap();
+    disk.write_primary_gpt_header(&header, &mut block_buf)
+        .unwrap();
+    drop(disk);
+
+    // Dropping the disk flushed the borrowed backend.
+    let ops: Vec<_> = bio.trace().iter().map(|e| e.op).collect();
+    assert_eq!(ops, [IoOp::Write, IoOp::Flush]);
+
+    // The backend can still be used directly, and as a disk again.
+    bio.read_blocks(Lba(1), &mut block_buf).unwrap();
+    assert_eq!(&block_buf[..8], b"EFI PART");
+    let mut disk = Disk::from_mut(&mut bio).unwrap();
+    assert_eq!(
+        disk.read_primary_gpt_header(&mut block_buf).unwrap(),
+        header
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_disk_close() {
+    use gpt_disk_io::{IoOp, RecordingBlockIo};
+
+    let new_bio = || {
+        RecordingBlockIo::new(BlockIoAdapter::new(
+            vec![0; 512 * 8],
+            BlockSize::BS_512,
+        ))
+    };
+    let ops = |bio: &RecordingBlockIo<_>| -> Vec<IoOp> {
+        bio.trace().iter().map(|e| e.op).collect()
+    };
+
+    // `close` flushes once and returns the backend.
+    let disk = Disk::new(new_bio()).unwrap();
+    let bio = disk.close().unwrap();
+    assert_eq!(ops(&bio), [IoOp::Flush]);
+
+    // Flush errors are reported along with the backend.
+    let mut data = vec![0; 512 * 8];
+    let disk = Disk::new(FailingFlushBlockIo(BlockIoAdapter::new(
+        data.as_mut_slice(),
+        BlockSize::BS_512,
+    )))
+    .unwrap();
+    let Err((bio, err)) = disk.close() else {
+        panic!("close should fail");
+    };
+    assert!(matches!(err, DiskError::Io(SliceBlockIoError::ReadOnly)));
+    assert_eq!(bio.0.storage().len(), 512 * 8);
+
+    // Flush on drop can be disabled.
+    let mut bio = new_bio();
+    let disk = Disk::new(&mut bio).unwrap().with_flush_on_drop(false);
+    drop(disk);
+    assert!(ops(&bio).is_empty());
+    let disk = Disk::new(&mut bio).unwrap();
+    drop(disk);
+    assert_eq!(ops(&bio), [IoOp::Flush]);
+}
+
+/// `BlockIo` whose `flush` always fails.
+#[cfg(feature = "alloc")]
+struct FailingFlushBlockIo<Io>(Io);
+
+#[cfg(feature = "alloc")]
+impl<Io: BlockIo<Error = SliceBlockIoError>> BlockIo
+    for FailingFlushBlockIo<Io>
+{
+    type Error = SliceBlockIoError;
+
+    fn block_size(&self) -> BlockSize {
+        self.0.block_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        self.0.num_blocks()
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.0.read_blocks(start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.0.write_blocks(start_lba, src)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Err(SliceBlockIoError::ReadOnly)
+    }
+}
+
+#[test]
+fn test_gpt_header_with_tail() {
+    let mut data = vec![0; 512 * 4];
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(data.as_mut_slice(), BlockSize::BS_512))
+            .unwrap();
+    let mut block_buf = vec![0; 512];
+
+    // Header written by another tool, with extra bytes after the
+    // struct.
+    let tail = [0xab; 36];
+    let mut header = create_primary_header();
+    header.header_size = U32Le::from_u32(128);
+    header.header_crc32 = header.calculate_header_crc32_with_tail(&tail);
+    disk.write_gpt_header_with_tail(Lba(1), &header, &tail, &mut block_buf)
+        .unwrap();
+
+    // Read it back and rewrite it with a change, preserving the tail.
+    let (mut header, read_tail) = disk
+        .read_gpt_header_with_tail(Lba(1), &mut block_buf)
+        .unwrap();
+    assert_eq!(read_tail, tail);
+    let read_tail = read_tail.to_vec();
+    header.my_lba = LbaLe::from_u64(2);
+    header.header_crc32 = header.calculate_header_crc32_with_tail(&read_tail);
+    disk.write_gpt_header_with_tail(
+        Lba(2),
+        &header,
+        &read_tail,
+        &mut block_buf,
+    )
+    .unwrap();
+
+    let (read_header, read_tail) = disk
+        .read_gpt_header_with_tail(Lba(2), &mut block_buf)
+        .unwrap();
+    assert_eq!(read_header, header);
+    assert_eq!(read_tail, tail);
+    assert!(disk
+        .is_gpt_header_block_remainder_zero(Lba(2), &mut block_buf)
+        .unwrap());
+
+    // The tail is clipped to the block, and is empty for a normal
+    // header.
+    header.header_size = U32Le::from_u32(4096);
+    disk.write_gpt_header(Lba(3), &header, &mut block_buf)
+        .unwrap();
+    let (_, read_tail) = disk
+        .read_gpt_header_with_tail(Lba(3), &mut block_buf)
+        .unwrap();
+    assert_eq!(read_tail.len(), 512 - 92);
+    header.header_size = U32Le::from_u32(92);
+    disk.write_gpt_header(Lba(3), &header, &mut block_buf)
+        .unwrap();
+    let (_, read_tail) = disk
+        .read_gpt_header_with_tail(Lba(3), &mut block_buf)
+        .unwrap();
+    assert!(read_tail.is_empty());
+
+    // A tail that doesn't fit in the block is an error.
+    assert!(matches!(
+        disk.write_gpt_header_with_tail(
+            Lba(3),
+            &header,
+            &[0; 512 - 91],
+            &mut block_buf
+        ),
+        Err(DiskError::BufferTooSmall)
+    ));
+}
+
+#[test]
+fn test_wipe_filesystem_signatures() {
+    let mb = 1024 * 1024;
+
+    // Large range: only the first and last 1 MiB are zeroed.
+    let mut contents = vec![1u8; 8 * mb];
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    // Range from 512 bytes to 7 MiB - 512 bytes.
+    let range = LbaRangeInclusive::new(Lba(1), Lba(14334)).unwrap();
+    disk.wipe_filesystem_signatures(range).unwrap();
+    drop(disk);
+    assert_eq!(contents[..512], [1; 512]);
+    assert!(contents[512..mb + 512].iter().all(|b| *b == 0));
+    assert!(contents[mb + 512..6 * mb - 512].iter().all(|b| *b == 1));
+    assert!(contents[6 * mb - 512..7 * mb - 512].iter().all(|b| *b == 0));
+    assert!(contents[7 * mb - 512..].iter().all(|b| *b == 1));
+
+    // Small range: the whole range is zeroed.
+    let mut contents = vec![1u8; 4 * mb];
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_4096,
+    ))
+    .unwrap();
+    let range = LbaRangeInclusive::new(Lba(1), Lba(2)).unwrap();
+    disk.wipe_filesystem_signatures(range).unwrap();
+    drop(disk);
+    assert_eq!(contents[..4096], [1; 4096]);
+    assert!(contents[4096..3 * 4096].iter().all(|b| *b == 0));
+    assert!(contents[3 * 4096..].iter().all(|b| *b == 1));
+}
+
+#[test]
+fn test_partition_ops_wipe_signatures() {
+    let mb = 1024 * 1024;
+    let bs = BlockSize::BS_512;
+    let layout = GptDiskLayout::new(bs, 8 * 2048).unwrap();
+    let entry = |start, end| GptPartitionEntry {
+        partition_type_guid: GptPartitionType::BASIC_DATA,
+        unique_partition_guid: guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12"),
+        starting_lba: LbaLe::from_u64(start),
+        ending_lba: LbaLe::from_u64(end),
+        ..Default::default()
+    };
+    // Check which MiB-sized chunks of the disk are zero.
+    let zeroed_mbs = |contents: &[u8]| -> Vec<bool> {
+        contents
+            .chunks(mb)
+            .map(|chunk| chunk.iter().all(|b| *b == 0))
+            .collect()
+    };
+
+    let mut contents = vec![1u8; 8 * mb];
+    let mut table = GptPartitionTable::<4>::new(layout, Guid::ZERO).unwrap();
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+
+    // Without the flag, nothing is written.
+    let index = disk
+        .add_partition(&mut table, entry(2048, 5 * 2048 - 1), false)
+        .unwrap();
+    assert!(disk
+        .remove_partition(&mut table, index, false)
+        .unwrap()
+        .is_some());
+
+    // Partitions 1 MiB to 5 MiB and 5 MiB to 6 MiB.
+    let index = disk
+        .add_partition(&mut table, entry(2048, 5 * 2048 - 1), true)
+        .unwrap();
+    disk.add_partition(&mut table, entry(5 * 2048, 6 * 2048 - 1), true)
+        .unwrap();
+
+    // A failed add leaves the table unchanged.
+    let saved = table;
+    assert!(matches!(
+        disk.add_partition(&mut table, entry(2048, 4095), true),
+        Err(DiskError::PartitionTable(
+            GptPartitionTableError::Overlap { .. }
+        ))
+    ));
+    assert_eq!(table, saved);
+
+    // Removing an unused entry does nothing.
+    assert!(disk
+        .remove_partition(&mut table, PartitionIndex::new(3), true)
+        .unwrap()
+        .is_none());
+    drop(disk);
+    assert_eq!(
+        zeroed_mbs(&contents),
+        [false, true, false, false, true, true, false, false]
+    );
+
+    // Removing a partition wipes it first.
+    contents.fill(1);
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    let removed = disk.remove_partition(&mut table, index, true).unwrap();
+    assert_eq!(removed.unwrap().lba_range().unwrap().start(), Lba(2048));
+    assert!(!table.get(index).unwrap().is_used());
+    drop(disk);
+    assert_eq!(
+        zeroed_mbs(&contents),
+        [false, true, false, false, true, false, false, false]
+    );
+
+    // Only created partitions are wiped by `apply_layout`.
+    contents.fill(1);
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    let desired = [
+        GptPartitionDescription {
+            partition_type: GptPartitionType::BASIC_DATA,
+            unique_partition_guid: guid!(
+                "37c75ffd-8932-467a-9c56-8cf1f0456b12"
+            ),
+            num_blocks: 2048,
+            ..Default::default()
+        },
+        GptPartitionDescription {
+            partition_type: GptPartitionType::BASIC_DATA,
+            unique_partition_guid: guid!(
+                "ccf0994f-f7e0-4e26-a011-843e38aa2eac"
+            ),
+            num_blocks: 2048,
+            ..Default::default()
+        },
+    ];
+    let policy = ApplyLayoutPolicy {
+        alignment_blocks: 2048,
+        ..Default::default()
+    };
+    let mut changes = Vec::new();
+    disk.apply_layout(&mut table, &desired, policy, true, |c| changes.push(c))
+        .unwrap();
+    assert!(matches!(changes[0], GptLayoutChange::Unchanged(_)));
+    assert!(matches!(changes[1], GptLayoutChange::Created(_)));
+    drop(disk);
+    assert_eq!(
+        zeroed_mbs(&contents),
+        [false, true, false, false, false, false, false, false]
+    );
+}
+
+#[test]
+fn test_copy_lba_range() {
+    // Each block is filled with its LBA.
+    let new_contents =
+        || -> Vec<u8> { (0..16u8).flat_map(|i| [i; 512]).collect() };
+    let block = |contents: &[u8], lba: usize| contents[lba * 512];
+    let range =
+        |start, end| LbaRangeInclusive::new(Lba(start), Lba(end)).unwrap();
+
+    // Forward overlapping copy with a buffer smaller than the range.
+    let mut contents = new_contents();
+    let mut block_buf = vec![0; 512 * 2 + 100];
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    disk.copy_lba_range(range(4, 8), Lba(2), &mut block_buf)
+        .unwrap();
+    drop(disk);
+    let blocks: Vec<_> = (0..16).map(|lba| block(&contents, lba)).collect();
+    assert_eq!(
+        blocks,
+        [0, 1, 4, 5, 6, 7, 8, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+    );
+
+    // Backward overlapping copy.
+    let mut contents = new_contents();
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    disk.copy_lba_range(range(4, 8), Lba(6), &mut block_buf)
+        .unwrap();
+    drop(disk);
+    let blocks: Vec<_> = (0..16).map(|lba| block(&contents, lba)).collect();
+    assert_eq!(
+        blocks,
+        [0, 1, 2, 3, 4, 5, 4, 5, 6, 7, 8, 11, 12, 13, 14, 15]
+    );
+
+    // Errors.
+    let mut contents = new_contents();
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    assert!(matches!(
+        disk.copy_lba_range(range(4, 8), Lba(2), &mut [0; 511]),
+        Err(DiskError::BufferTooSmall)
+    ));
+    assert!(matches!(
+        disk.copy_lba_range(range(4, 8), Lba(12), &mut block_buf),
+        Err(DiskError::OutOfBounds)
+    ));
+    assert!(matches!(
+        disk.copy_lba_range(range(14, 16), Lba(0), &mut block_buf),
+        Err(DiskError::OutOfBounds)
+    ));
+    assert!(matches!(
+        disk.copy_lba_range(range(4, 8), Lba(u64::MAX), &mut block_buf),
+        Err(DiskError::Overflow)
+    ));
+}
+
+#[test]
+fn test_move_partition() {
+    let bs = BlockSize::BS_512;
+    let layout = GptDiskLayout::new(bs, 128).unwrap();
+    let entry = |start, end, unique| GptPartitionEntry {
+        partition_type_guid: GptPartitionType::BASIC_DATA,
+        unique_partition_guid: unique,
+        starting_lba: LbaLe::from_u64(start),
+        ending_lba: LbaLe::from_u64(end),
+        ..Default::default()
+    };
+    let mut table = GptPartitionTable::<4>::new(layout, Guid::ZERO).unwrap();
+    let index = table
+        .add(entry(40, 49, guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12")))
+        .unwrap();
+    table
+        .add(entry(80, 89, guid!("ccf0994f-f7e0-4e26-a011-843e38aa2eac")))
+        .unwrap();
+
+    // Fill each block of the first partition with its index.
+    let mut contents = vec![0; 512 * 128];
+    for (i, block) in contents[40 * 512..50 * 512].chunks_mut(512).enumerate() {
+        block.fill(u8::try_from(i).unwrap() + 1);
+    }
+    let expected = contents[40 * 512..50 * 512].to_vec();
+    let mut block_buf = vec![0; 512 * 4];
+
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    let mut progress = Vec::new();
+    disk.move_partition(
+        &mut table,
+        index,
+        Lba(45),
+        true,
+        &mut block_buf,
+        |n| progress.push(n),
+    )
+    .unwrap();
+    assert_eq!(progress, [4, 8, 10]);
+    let moved = table.get(index).unwrap();
+    assert_eq!({ moved.starting_lba }.to_u64(), 45);
+    assert_eq!({ moved.ending_lba }.to_u64(), 54);
+
+    // Can't overlap another partition.
+    let before = table;
+    assert!(matches!(
+        disk.move_partition(
+            &mut table,
+            index,
+            Lba(75),
+            false,
+            &mut block_buf,
+            |_| {}
+        ),
+        Err(DiskError::PartitionTable(
+            GptPartitionTableError::Overlap { .. }
+        ))
+    ));
+    assert_eq!(table, before);
+    drop(disk);
+    assert_eq!(contents[45 * 512..55 * 512], expected);
+
+    // Verification catches bad writes.
+    let mut disk = Disk::new(CorruptingBlockIo(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        bs,
+    )))
+    .unwrap();
+    assert!(matches!(
+        disk.move_partition(
+            &mut table,
+            index,
+            Lba(60),
+            true,
+            &mut block_buf,
+            |_| {}
+        ),
+        Err(DiskError::VerificationFailed)
+    ));
+    assert_eq!(table, before);
+}
+
+/// `BlockIo` that flips the first bit of every write.
+struct CorruptingBlockIo<Io>(Io);
+
+impl<Io: BlockIo> BlockIo for CorruptingBlockIo<Io> {
+    type Error = Io::Error;
+
+    fn block_size(&self) -> BlockSize {
+        self.0.block_size()
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        self.0.num_blocks()
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.0.read_blocks(start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        let mut src = src.to_vec();
+        src[0] ^= 1;
+        self.0.write_blocks(start_lba, &src)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush()
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_block_remainder_zero() {
+    let bs = BlockSize::BS_512;
+    let mut block_buf = vec![0u8; 512];
+
+    let mut contents = load_test_disk();
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    assert!(disk
+        .is_gpt_header_block_remainder_zero(Lba(1), &mut block_buf)
+        .unwrap());
+    assert!(disk
+        .is_gpt_header_block_remainder_zero(Lba(8191), &mut block_buf)
+        .unwrap());
+    // The MBR fills the whole block.
+    assert!(disk.is_mbr_block_remainder_zero(&mut block_buf).unwrap());
+    drop(disk);
+
+    // Junk after the primary header.
+    contents[512 + 511] = 1;
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    assert!(!disk
+        .is_gpt_header_block_remainder_zero(Lba(1), &mut block_buf)
+        .unwrap());
+    assert!(disk
+        .is_gpt_header_block_remainder_zero(Lba(8191), &mut block_buf)
+        .unwrap());
+
+    // Junk after the MBR in a 4096-byte block.
+    let bs = BlockSize::BS_4096;
+    let mut block_buf = vec![0u8; 4096];
+    let mut contents = vec![0u8; 4096 * 8];
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    disk.write_protective_mbr(&mut block_buf).unwrap();
+    assert!(disk.is_mbr_block_remainder_zero(&mut block_buf).unwrap());
+    drop(disk);
+    contents[4095] = 1;
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    assert!(!disk.is_mbr_block_remainder_zero(&mut block_buf).unwrap());
+}
+
+#[test]
+fn test_write_protective_mbr_with_bootcode() {
+    let bs = BlockSize::BS_512;
+    let mut block_buf = vec![0u8; 512];
+    let mut expected = vec![0u8; 512 * 8];
+    let mut contents = vec![0u8; 512 * 8];
+    let code = [0xab; 440];
+
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(expected.as_mut_slice(), bs)).unwrap();
+    disk.write_protective_mbr(&mut block_buf).unwrap();
+    drop(disk);
+
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    disk.write_protective_mbr_with_bootcode(&code, &mut block_buf)
+        .unwrap();
+    drop(disk);
+
+    // Only the boot code differs from a plain protective MBR.
+    assert_eq!(contents[..440], code);
+    assert_eq!(contents[440..], expected[440..]);
+}
+
+#[test]
+fn test_write_partition_image() {
+    let bs = BlockSize::BS_512;
+    let mut contents = vec![1u8; 512 * 16];
+    let mut block_buf = vec![0u8; 512];
+    let entry = GptPartitionEntry {
+        starting_lba: LbaLe::from_u64(4),
+        ending_lba: LbaLe::from_u64(7),
+        ..create_partition_entry()
+    };
+    let image: Vec<u8> = (0..1100).map(|i| (i % 251) as u8 + 2).collect();
+
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    disk.write_partition_image(&entry, &image, &mut block_buf)
+        .unwrap();
+    assert!(matches!(
+        disk.write_partition_image(&entry, &[0; 512 * 4 + 1], &mut block_buf),
+        Err(DiskError::ImageTooLarge)
+    ));
+    let past_end = GptPartitionEntry {
+        ending_lba: LbaLe::from_u64(16),
+        ..entry
+    };
+    assert!(matches!(
+        disk.write_partition_image(&past_end, &image, &mut block_buf),
+        Err(DiskError::InvalidPartitionRange)
+    ));
+    drop(disk);
+
+    // The last partial block is zero-padded, and the rest of the disk is
+    // unchanged.
+    assert!(contents[..512 * 4].iter().all(|b| *b == 1));
+    assert_eq!(contents[512 * 4..512 * 4 + 1100], image);
+    assert!(contents[512 * 4 + 1100..512 * 7].iter().all(|b| *b == 0));
+    assert!(contents[512 * 7..].iter().all(|b| *b == 1));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_open_partition_as_disk() {
+    let mut contents = load_test_disk();
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    let mut block_buf = vec![0u8; 512];
+
+    // The partition covers LBA 2048 through 4096.
+    let entry = create_partition_entry();
+    {
+        let mut nested = disk.open_partition_as_disk(&entry).unwrap();
+
+        // Write a nested GPT header to the last block of the partition.
+        let header = create_primary_header();
+        nested
+            .write_secondary_gpt_header(&header, &mut block_buf)
+            .unwrap();
+        assert_eq!(
+            nested.read_gpt_header(Lba(2048), &mut block_buf).unwrap(),
+            header
+        );
+
+        // Access outside the partition fails.
+        assert!(matches!(
+            nested.read_gpt_header(Lba(2049), &mut block_buf),
+            Err(DiskError::Io(SubRangeBlockIoError::OutOfBounds {
+                start_lba: Lba(2049),
+                length_in_bytes: 512,
+            }))
+        ));
+    }
+    assert_eq!(
+        disk.read_gpt_header(Lba(4096), &mut block_buf).unwrap(),
+        create_primary_header()
+    );
+
+    // Invalid partition ranges are rejected.
+    let mut entry = create_partition_entry();
+    entry.ending_lba = LbaLe::from_u64(8192);
+    assert!(matches!(
+        disk.open_partition_as_disk(&entry),
+        Err(DiskError::InvalidPartitionRange)
+    ));
+    entry.ending_lba = LbaLe::from_u64(1);
+    assert!(matches!(
+        disk.open_partition_as_disk(&entry),
+        Err(DiskError::InvalidPartitionRange)
+    ));
+}
+
+#[test]
+fn test_validate_gpt() {
+    let mut contents = load_test_disk();
+    let mut block_buf = vec![0u8; 512 * 4];
+    let no_mbr = GptValidationOptions {
+        check_protective_mbr: false,
+    };
+
+    let mut validate = |contents: &mut [u8], options| {
+        let mut disk =
+            Disk::new(BlockIoAdapter::new(contents, BlockSize::BS_512))
+                .unwrap();
+        disk.validate_gpt(options, &mut block_buf)
+    };
+
+    let header =
+        validate(&mut contents, GptValidationOptions::default()).unwrap();
+    assert_eq!(header, create_primary_header());
+    validate(&mut contents, no_mbr).unwrap();
+
+    // Without an MBR, as in a GPT embedded in a partition, the table is
+    // only valid if the MBR check is skipped.
+    contents[..512].fill(0);
+    assert!(matches!(
+        validate(&mut contents, GptValidationOptions::default()),
+        Err(DiskError::MissingProtectiveMbr)
+    ));
+    validate(&mut contents, no_mbr).unwrap();
+
+    // An MBR without a protective partition.
+    contents[510] = 0x55;
+    contents[511] = 0xaa;
+    assert!(matches!(
+        validate(&mut contents, GptValidationOptions::default()),
+        Err(DiskError::MissingProtectiveMbr)
+    ));
+
+    // Corrupt partition entry.
+    contents[512 * 2 + 0x38] ^= 1;
+    assert!(matches!(
+        validate(&mut contents, no_mbr),
+        Err(DiskError::InvalidGptHeader)
+    ));
+
+    // Corrupt header.
+    contents[512 * 2 + 0x38] ^= 1;
+    contents[512 + 0x30] ^= 1;
+    assert!(matches!(
+        validate(&mut contents, no_mbr),
+        Err(DiskError::InvalidGptHeader)
+    ));
+}
+
+#[test]
+fn test_read_gpt() {
+    let mut contents = load_test_disk();
+    let mut block_buf = vec![0u8; 512 * 4];
+
+    let mut read = |contents: &mut [u8], lba| {
+        let mut disk =
+            Disk::new(BlockIoAdapter::new(contents, BlockSize::BS_512))
+                .unwrap();
+        disk.read_gpt::<4>(Lba(lba), &mut block_buf)
+    };
+
+    let (header, table) = read(&mut contents, 1).unwrap();
+    assert_eq!(header, create_primary_header());
+    assert_eq!(table.disk_guid(), { header.disk_guid });
+    assert_eq!(table.layout().num_blocks(), 8192);
+    assert_eq!(table.entries()[0], create_partition_entry());
+    assert_eq!(table.iter_used().count(), 1);
+
+    // Damage the primary header; the secondary header can still be
+    // read.
+    contents[512 + 0x30] ^= 1;
+    assert!(matches!(
+        read(&mut contents, 1),
+        Err(DiskError::InvalidGptHeader)
+    ));
+    let (header, secondary_table) = read(&mut contents, 8191).unwrap();
+    assert_eq!(header, create_secondary_header());
+    assert_eq!(secondary_table.entries(), table.entries());
+
+    // `my_lba` must match the LBA that was read.
+    assert!(matches!(
+        read(&mut contents, 2),
+        Err(DiskError::InvalidGptHeader)
+    ));
+
+    // The table can't be larger than the array.
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    assert!(matches!(
+        disk.read_gpt::<129>(Lba(8191), &mut block_buf),
+        Err(DiskError::PartitionTable(
+            GptPartitionTableError::CapacityTooLarge
+        ))
+    ));
+}
+
+#[test]
+fn test_read_gpt_too_many_entries() {
+    let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    let mut entries = [GptPartitionEntry::default(); 128];
+    entries[5] = create_partition_entry();
+    let table =
+        GptPartitionTable::from_entries(layout, Guid::ZERO, entries).unwrap();
+
+    let mut contents = vec![0; 8192 * 512];
+    let mut block_buf = vec![0u8; 512];
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    let mut array_buf = vec![0; 512 * 32];
+    let mut array = GptPartitionEntryArray::new(
+        layout.primary_partition_entry_array_layout(),
+        BlockSize::BS_512,
+        &mut array_buf,
+    )
+    .unwrap();
+    table.write_partition_entry_array(&mut array).unwrap();
+    disk.update_and_write_gpt(
+        &table.primary_header(),
+        &mut array,
+        &mut block_buf,
+    )
+    .unwrap();
+
+    // The used entry at index 5 doesn't fit in a table of four
+    // entries.
+    assert!(matches!(
+        disk.read_gpt::<4>(Lba(1), &mut block_buf),
+        Err(DiskError::TooManyPartitionEntries)
+    ));
+    let (_, read_table) = disk.read_gpt::<6>(Lba(1), &mut block_buf).unwrap();
+    assert_eq!(read_table.entries()[5], create_partition_entry());
+}
+
+#[test]
+fn test_read_gpt_without_min_array_size() {
+    let layout = GptDiskLayout::new_with_options(
+        BlockSize::BS_512,
+        16384,
+        GptPartitionEntrySize::new(128).unwrap(),
+        4,
+        false,
+    )
+    .unwrap();
+    let mut table = GptPartitionTable::<4>::new(layout, Guid::ZERO).unwrap();
+    table
+        .add(GptPartitionEntry {
+            partition_type_guid: GptPartitionType::BASIC_DATA,
+            unique_partition_guid: guid!(
+                "37c75ffd-8932-467a-9c56-8cf1f0456b12"
+            ),
+            starting_lba: LbaLe::from_u64(3),
+            ending_lba: LbaLe::from_u64(16381),
+            ..Default::default()
+        })
+        .unwrap();
+
+    let mut contents = vec![0; 16384 * 512];
+    let mut block_buf = vec![0u8; 512];
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    let mut array_buf = vec![0; 512];
+    let mut array = GptPartitionEntryArray::new(
+        layout.primary_partition_entry_array_layout(),
+        BlockSize::BS_512,
+        &mut array_buf,
+    )
+    .unwrap();
+    table.write_partition_entry_array(&mut array).unwrap();
+    disk.update_and_write_gpt(
+        &table.primary_header(),
+        &mut array,
+        &mut block_buf,
+    )
+    .unwrap();
+
+    let (header, read_table) =
+        disk.read_gpt::<4>(Lba(1), &mut block_buf).unwrap();
+    assert_eq!(header, table.primary_header());
+    assert_eq!(read_table.layout(), &layout);
+    assert_eq!(read_table.entries(), table.entries());
+    let (header, _) = disk.read_gpt::<4>(Lba(16383), &mut block_buf).unwrap();
+    assert_eq!(header, table.secondary_header());
+    assert!(disk.verify_matches(&table, &mut block_buf).unwrap());
+}
+
+#[test]
+fn test_read_gpt_reserved() {
+    let layout = GptDiskLayout::new(BlockSize::BS_512, 8192)
+        .unwrap()
+        .reserve_leading_bytes(1024 * 1024)
+        .unwrap()
+        .reserve_trailing_bytes(4096)
+        .unwrap();
+    let table = GptPartitionTable::<128>::new(layout, Guid::ZERO).unwrap();
+
+    let mut contents = vec![0; 8192 * 512];
+    let mut block_buf = vec![0u8; 512];
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    let mut array_buf = vec![0; 512 * 32];
+    let mut array = GptPartitionEntryArray::new(
+        layout.primary_partition_entry_array_layout(),
+        BlockSize::BS_512,
+        &mut array_buf,
+    )
+    .unwrap();
+    table.write_partition_entry_array(&mut array).unwrap();
+    disk.update_and_write_gpt(
+        &table.primary_header(),
+        &mut array,
+        &mut block_buf,
+    )
+    .unwrap();
+
+    let (_, read_table) = disk.read_gpt::<128>(Lba(1), &mut block_buf).unwrap();
+    assert_eq!(read_table.layout(), &layout);
+    assert_eq!(read_table.layout().reserved_leading_blocks(), 2048);
+    assert_eq!(read_table.layout().reserved_trailing_blocks(), 8);
+}
+
+#[test]
+fn test_verify_matches() {
+    let mut contents = load_test_disk();
+    let mut block_buf = vec![0u8; 512 * 4];
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+
+    let (_, table) = disk.read_gpt::<128>(Lba(1), &mut block_buf).unwrap();
+    assert!(disk.verify_matches(&table, &mut block_buf).unwrap());
+    assert_eq!(
+        disk.gpt_generation(&mut block_buf).unwrap(),
+        (
+            Crc32(U32Le::from_u32(0xa4877843)),
+            Crc32(U32Le::from_u32(0x9206adff))
+        )
+    );
+
+    // A cached copy of the table still matches.
+    let cached: GptPartitionTable<128> =
+        serde_json::from_str(&serde_json::to_string(&table).unwrap()).unwrap();
+    assert!(disk.verify_matches(&cached, &mut block_buf).unwrap());
+
+    // Change the partitions on disk.
+    let mut new_table = table;
+    new_table
+        .add(GptPartitionEntry {
+            partition_type_guid: GptPartitionType::BASIC_DATA,
+            unique_partition_guid: guid!(
+                "37c75ffd-8932-467a-9c56-8cf1f0456b12"
+            ),
+            starting_lba: LbaLe::from_u64(8000),
+            ending_lba: LbaLe::from_u64(8001),
+            ..Default::default()
+        })
+        .unwrap();
+    disk.write_primary_gpt_header(&new_table.primary_header(), &mut block_buf)
+        .unwrap();
+    assert!(!disk.verify_matches(&cached, &mut block_buf).unwrap());
+    assert!(disk.verify_matches(&new_table, &mut block_buf).unwrap());
+
+    // The generation changes along with the partitions.
+    let (header_crc32, array_crc32) =
+        disk.gpt_generation(&mut block_buf).unwrap();
+    let header = new_table.primary_header();
+    assert_eq!(header_crc32, header.header_crc32);
+    assert_eq!(array_crc32, header.partition_entry_array_crc32);
+    assert_ne!(header_crc32, table.primary_header().header_crc32);
+
+    // A table with a different block size never matches.
+    let layout = GptDiskLayout::new(BlockSize::BS_4096, 1024).unwrap();
+    let other = GptPartitionTable::<4>::new(layout, Guid::ZERO).unwrap();
+    assert!(!disk.verify_matches(&other, &mut block_buf).unwrap());
+}
+
+#[test]
+fn test_check_block_size() {
+    let disk_guid = guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870");
+    let mut block_buf = vec![0u8; 4096];
+    let mut contents = vec![0u8; 4096 * 64];
+
+    let mut check = |contents: &mut [u8], bs| {
+        let mut disk = Disk::new(BlockIoAdapter::new(contents, bs)).unwrap();
+        disk.check_block_size(&mut block_buf)
+    };
+
+    // A blank disk passes.
+    check(&mut contents, BlockSize::BS_512).unwrap();
+
+    // Write a GPT header with 4096-byte blocks.
+    let write_header = |contents: &mut [u8], bs| {
+        let mut block_buf = vec![0u8; 4096];
+        let mut disk = Disk::new(BlockIoAdapter::new(contents, bs)).unwrap();
+        let num_blocks = disk.num_blocks().unwrap();
+        let layout = GptDiskLayout::new(bs, num_blocks).unwrap();
+        disk.write_primary_gpt_header(
+            &layout.primary_header(disk_guid),
+            &mut block_buf,
+        )
+        .unwrap();
+    };
+    write_header(&mut contents, BlockSize::BS_4096);
+    check(&mut contents, BlockSize::BS_4096).unwrap();
+    assert!(matches!(
+        check(&mut contents, BlockSize::BS_512),
+        Err(DiskError::ProbableBlockSizeMismatch {
+            suggested: BlockSize::BS_4096
+        })
+    ));
+
+    let probe = |contents: &mut [u8], bs| {
+        let mut block_buf = vec![0u8; 8192];
+        let mut disk = Disk::new(BlockIoAdapter::new(contents, bs)).unwrap();
+        disk.probe_block_size(&mut block_buf).unwrap()
+    };
+    for bs in [BlockSize::BS_512, BlockSize::BS_2048, BlockSize::BS_4096] {
+        assert_eq!(probe(&mut contents, bs), Some(BlockSize::BS_4096));
+    }
+
+    // Write a GPT header with 512-byte blocks.
+    contents.fill(0);
+    write_header(&mut contents, BlockSize::BS_512);
+    check(&mut contents, BlockSize::BS_512).unwrap();
+    assert!(matches!(
+        check(&mut contents, BlockSize::BS_4096),
+        Err(DiskError::ProbableBlockSizeMismatch {
+            suggested: BlockSize::BS_512
+        })
+    ));
+    assert_eq!(
+        probe(&mut contents, BlockSize::BS_8192),
+        Some(BlockSize::BS_512)
+    );
+
+    // No GPT.
+    contents.fill(0);
+    assert_eq!(probe(&mut contents, BlockSize::BS_512), None);
+}
+
+#[test]
+fn test_partition_block_io() {
+    let bs = BlockSize::BS_512;
+    let mut contents = vec![0u8; 512 * 16];
+    let entry = GptPartitionEntry {
+        starting_lba: LbaLe::from_u64(4),
+        ending_lba: LbaLe::from_u64(7),
+        ..create_partition_entry()
+    };
+
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    {
+        let mut io: PartitionBlockIo<'_, _> =
+            disk.partition_block_io(&entry).unwrap();
+        assert_eq!(io.num_blocks().unwrap(), 4);
+        io.write_blocks(Lba(1), &[1; 512]).unwrap();
+        assert!(matches!(
+            io.write_blocks(Lba(3), &[1; 1024]),
+            Err(SubRangeBlockIoError::OutOfBounds {
+                start_lba: Lba(3),
+                length_in_bytes: 1024,
+            })
+        ));
+    }
+
+    let past_end = GptPartitionEntry {
+        ending_lba: LbaLe::from_u64(16),
+        ..entry
+    };
+    assert!(matches!(
+        disk.partition_block_io(&past_end),
+        Err(DiskError::InvalidPartitionRange)
+    ));
+    drop(disk);
+
+    assert!(contents[..512 * 5].iter().all(|b| *b == 0));
+    assert!(contents[512 * 5..512 * 6].iter().all(|b| *b == 1));
+    assert!(contents[512 * 6..].iter().all(|b| *b == 0));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_update_and_write_gpt() {
+    let original = load_test_disk();
+    let mut contents = original.clone();
+    let mut block_buf = vec![0u8; 512];
+    let mut array_buf = vec![0u8; 512 * 32];
+
+    // Writing back without changes reproduces the original disk, even
+    // if the secondary location and CRC fields are stale.
+    {
+        let mut disk = Disk::new(BlockIoAdapter::new(
+            contents.as_mut_slice(),
+            BlockSize::BS_512,
+        ))
+        .unwrap();
+        let mut header = disk.read_primary_gpt_header(&mut block_buf).unwrap();
+        let layout = header.get_partition_entry_array_layout().unwrap();
+        let mut array = disk
+            .read_gpt_partition_entry_array(layout, &mut array_buf)
+            .unwrap();
+
+        header.alternate_lba = LbaLe::from_u64(1234);
+        header.header_crc32 = Crc32::default();
+        header.partition_entry_array_crc32 = Crc32::default();
+        disk.update_and_write_gpt(&header, &mut array, &mut block_buf)
+            .unwrap();
+        assert_eq!(array.layout().start_lba, Lba(2));
+    }
+    assert!(contents == original);
+
+    // Modify an entry.
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    let header = disk.read_primary_gpt_header(&mut block_buf).unwrap();
+    let layout = header.get_partition_entry_array_layout().unwrap();
+    let mut array = disk
+        .read_gpt_partition_entry_array(layout, &mut array_buf)
+        .unwrap();
+    array.get_partition_entry_mut(0).unwrap().name = "renamed".parse().unwrap();
+    disk.update_and_write_gpt(&header, &mut array, &mut block_buf)
+        .unwrap();
+
+    let primary = disk.read_primary_gpt_header(&mut block_buf).unwrap();
+    let secondary = disk.read_secondary_gpt_header(&mut block_buf).unwrap();
+    assert!(primary.is_header_crc32_valid());
+    assert!(secondary.is_header_crc32_valid());
+    assert_eq!(primary.my_lba.to_u64(), 1);
+    assert_eq!(primary.alternate_lba.to_u64(), 8191);
+    assert_eq!(secondary.my_lba.to_u64(), 8191);
+    assert_eq!(secondary.alternate_lba.to_u64(), 1);
+    assert_eq!(secondary.partition_entry_lba.to_u64(), 8159);
+
+    for header in [primary, secondary] {
+        let layout = header.get_partition_entry_array_layout().unwrap();
+        let mut array_buf = vec![0u8; 512 * 32];
+        let array = disk
+            .read_gpt_partition_entry_array(layout, &mut array_buf)
+            .unwrap();
+        assert_eq!(array.get_partition_entry(0).unwrap().name, "renamed");
+        assert_eq!(array.calculate_crc32(), header.partition_entry_array_crc32);
+    }
+}
+
+#[test]
+fn test_update_and_write_gpt_disk_too_small() {
+    let original = load_test_disk();
+    let mut block_buf = vec![0u8; 512];
+    let mut array_buf = vec![0u8; 512 * 32];
+
+    // Copy the primary GPT of an 8192-block disk to a smaller one.
+    let mut contents = original[..8191 * 512].to_vec();
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    let header = disk.read_primary_gpt_header(&mut block_buf).unwrap();
+    let layout = header.get_partition_entry_array_layout().unwrap();
+    let mut array = disk
+        .read_gpt_partition_entry_array(layout, &mut array_buf)
+        .unwrap();
+    let err = disk
+        .update_and_write_gpt(&header, &mut array, &mut block_buf)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        DiskError::DiskTooSmall {
+            min_num_blocks: 8192
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "disk is too small for the GPT (needs at least 8192 blocks)"
+    );
+    drop(disk);
+
+    // Nothing was written.
+    assert!(contents == original[..8191 * 512]);
+}
+
+#[test]
+fn test_update_and_write_gpt_checked() {
+    let original = load_test_disk();
+    let mut block_buf = vec![0u8; 512];
+    let mut array_buf = vec![0u8; 512 * 32];
+    let disk_guid = guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870");
+    let other_guid = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+
+    // Rewriting the existing GPT is allowed.
+    let mut contents = original.clone();
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    let mut header = disk.read_primary_gpt_header(&mut block_buf).unwrap();
+    let layout = header.get_partition_entry_array_layout().unwrap();
+    let mut array = disk
+        .read_gpt_partition_entry_array(layout, &mut array_buf)
+        .unwrap();
+    disk.update_and_write_gpt_checked(&header, &mut array, &mut block_buf)
+        .unwrap();
+
+    // A GPT with a different disk GUID is not overwritten.
+    header.disk_guid = other_guid;
+    assert!(matches!(
+        disk.update_and_write_gpt_checked(&header, &mut array, &mut block_buf),
+        Err(DiskError::ExistingGpt { disk_guid: g }) if g == disk_guid
+    ));
+    drop(disk);
+    assert!(contents == original);
+
+    // A blank disk is allowed.
+    let mut contents = vec![0u8; original.len()];
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    disk.check_existing_partitioning(other_guid, &mut block_buf)
+        .unwrap();
+
+    // A protective MBR alone is allowed.
+    disk.write_protective_mbr(&mut block_buf).unwrap();
+    disk.check_existing_partitioning(other_guid, &mut block_buf)
+        .unwrap();
+
+    // An MBR with a FAT32 partition is not overwritten.
+    drop(disk);
+    contents[0x1be + 4] = 0x0c;
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    assert!(matches!(
+        disk.check_existing_partitioning(other_guid, &mut block_buf),
+        Err(DiskError::ExistingMbrPartitions)
+    ));
+}
+
+#[test]
+fn test_write_gpt_partition_entries_from_iter() {
+    let bs = BlockSize::BS_512;
+    let mut contents = vec![0xffu8; 512 * 600];
+    let mut block_buf = vec![0u8; 512];
+
+    // 1024 entries of 256 bytes, only the first three of which are
+    // produced by the iterator.
+    let layout = GptPartitionEntryArrayLayout {
+        start_lba: Lba(2),
+        entry_size: GptPartitionEntrySize::new(256).unwrap(),
+        num_entries: 1024,
+    };
+    let entries = (0..3u64).map(|i| GptPartitionEntry {
+        starting_lba: LbaLe::from_u64(100 * i + 34),
+        ending_lba: LbaLe::from_u64(100 * i + 133),
+        ..create_partition_entry()
+    });
+
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    let crc = disk
+        .write_gpt_partition_entries_from_iter(layout, entries, &mut block_buf)
+        .unwrap();
+
+    // Read the array back and compare.
+    let mut array_buf = vec![0u8; 512 * 512];
+    let array = disk
+        .read_gpt_partition_entry_array(layout, &mut array_buf)
+        .unwrap();
+    assert_eq!(array.calculate_crc32(), crc);
+    for (i, entry) in array.iter().enumerate() {
+        if i < 3 {
+            assert_eq!(entry.starting_lba.to_u64(), 100 * i as u64 + 34);
+        } else {
+            assert!(!entry.is_used());
+        }
+    }
+    // Padding after each entry is zeroed.
+    assert!(array.storage()[128..256].iter().all(|b| *b == 0));
+    drop(disk);
+    // The block after the array is untouched.
+    assert_eq!(contents[514 * 512], 0xff);
+
+    // Too many entries.
+    let layout = GptPartitionEntryArrayLayout {
+        num_entries: 2,
+        ..layout
+    };
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    assert!(matches!(
+        disk.write_gpt_partition_entries_from_iter(
+            layout,
+            [create_partition_entry(); 3],
+            &mut block_buf
+        ),
+        Err(DiskError::TooManyPartitionEntries)
+    ));
+
+    // Block buffer too small for the entry size.
+    let layout = GptPartitionEntryArrayLayout {
+        entry_size: GptPartitionEntrySize::new(1024).unwrap(),
+        ..layout
+    };
+    assert!(matches!(
+        disk.write_gpt_partition_entries_from_iter(layout, [], &mut block_buf),
+        Err(DiskError::BlockSizeSmallerThanPartitionEntry)
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_calculate_gpt_partition_entry_array_crc32() {
+    let mut contents = load_test_disk();
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_512,
+    ))
+    .unwrap();
+    let mut block_buf = vec![0u8; 512];
+    let header = disk.read_primary_gpt_header(&mut block_buf).unwrap();
+    let layout = header.get_partition_entry_array_layout().unwrap();
+
+    // Buffers of one block, and of a size that doesn't evenly divide
+    // the array.
+    for num_blocks in [1, 5, 64] {
+        let mut buf = vec![0u8; 512 * num_blocks];
+        assert_eq!(
+            disk.calculate_gpt_partition_entry_array_crc32(layout, &mut buf)
+                .unwrap(),
+            header.partition_entry_array_crc32
+        );
+    }
+
+    // An array whose size is not a multiple of the block size.
+    let layout = GptPartitionEntryArrayLayout {
+        num_entries: 3,
+        ..layout
+    };
+    let mut array_buf = vec![0u8; 512];
+    let expected = disk
+        .read_gpt_partition_entry_array(layout, &mut array_buf)
+        .unwrap()
+        .calculate_crc32();
+    assert_eq!(
+        disk.calculate_gpt_partition_entry_array_crc32(layout, &mut block_buf)
+            .unwrap(),
+        expected
+    );
+
+    // An empty array.
+    let layout = GptPartitionEntryArrayLayout {
+        num_entries: 0,
+        ..layout
+    };
+    assert_eq!(
+        disk.calculate_gpt_partition_entry_array_crc32(layout, &mut block_buf)
+            .unwrap(),
+        Crc32::of(&[])
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_read_gpt_header_checked() {
+    let mut contents = load_test_disk();
+    let mut block_buf = vec![0u8; 512 * 4];
+    let mut read_checked = |contents: &mut [u8], lba| {
+        let mut disk =
+            Disk::new(BlockIoAdapter::new(contents, BlockSize::BS_512))
+                .unwrap();
+        disk.read_gpt_header_checked(Lba(lba), &mut block_buf)
+            .unwrap()
+    };
+
+    let checked = read_checked(&mut contents, 1);
+    assert_eq!(checked.header, create_primary_header());
+    assert!(checked.header_crc32_valid);
+    assert!(checked.partition_entry_array_crc32_valid);
+    assert!(checked.is_valid());
+    assert!(read_checked(&mut contents, 8191).is_valid());
+
+    // Corrupt the primary header's disk GUID.
+    contents[0x238] ^= 1;
+    let checked = read_checked(&mut contents, 1);
+    assert!(!checked.header_crc32_valid);
+    assert!(checked.partition_entry_array_crc32_valid);
+    assert!(!checked.is_valid());
+    contents[0x238] ^= 1;
+
+    // Corrupt the primary partition entry array.
+    contents[0x400] ^= 1;
+    let checked = read_checked(&mut contents, 1);
+    assert!(checked.header_crc32_valid);
+    assert!(!checked.partition_entry_array_crc32_valid);
+    contents[0x400] ^= 1;
+
+    // A header whose array is past the end of the disk is returned
+    // rather than causing a read error.
+    let mut header = create_primary_header();
+    header.partition_entry_lba = LbaLe::from_u64(8190);
+    header.update_header_crc32();
+    contents[512..512 + 92].copy_from_slice(bytemuck::bytes_of(&header));
+    let checked = read_checked(&mut contents, 1);
+    assert_eq!(checked.header, header);
+    assert!(checked.header_crc32_valid);
+    assert!(!checked.partition_entry_array_crc32_valid);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_gpt_partition_entry_array_iter_batching() {
+    use gpt_disk_io::{IoOp, RecordingBlockIo};
+
+    let test_disk = load_test_disk();
+    let bio = BlockIoAdapter::new(test_disk.as_slice(), BlockSize::BS_512);
+    let mut bio = RecordingBlockIo::new(bio);
+
+    let mut disk = Disk::new(&mut bio).unwrap();
+    let mut block_buf = vec![0u8; 512];
+    let header = disk.read_primary_gpt_header(&mut block_buf).unwrap();
+    let layout = header.get_partition_entry_array_layout().unwrap();
+    let mut array_buf = vec![0u8; 512 * 32];
+    let array = disk
+        .read_gpt_partition_entry_array(layout, &mut array_buf)
+        .unwrap();
+    let expected: Vec<_> = array.iter().copied().collect();
+    drop(disk);
+
+    // Buffer size in blocks, and the expected size of each read. A
+    // buffer larger than the array does not read past its end, and a
+    // partial block at the end of the buffer is not used.
+    let cases: &[(usize, &[u64])] = &[
+        (1, &[1; 32]),
+        (5, &[5, 5, 5, 5, 5, 5, 2]),
+        (32, &[32]),
+        (40, &[32]),
+    ];
+    for (buf_num_blocks, expected_reads) in cases {
+        bio.take_trace();
+        let mut buf = vec![0u8; 512 * buf_num_blocks + 100];
+        let mut disk = Disk::new(&mut bio).unwrap();
+        let entries: Vec<_> = disk
+            .gpt_partition_entry_array_iter(layout, &mut buf)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(entries, expected);
+        drop(disk);
+
+        let reads: Vec<_> = bio
+            .trace()
+            .iter()
+            .filter(|e| e.op == IoOp::Read)
+            .map(|e| e.num_blocks)
+            .collect();
+        assert_eq!(reads, *expected_reads);
+    }
+}
+
+/// `BlockIo` whose storage can be resized while it is in use, and which
+/// counts calls to `num_blocks`.
+struct ResizableBlockIo {
+    data: Rc<RefCell<Vec<u8>>>,
+    num_blocks_calls: Rc<Cell<u32>>,
+}
+
+impl BlockIo for ResizableBlockIo {
+    type Error = SliceBlockIoError;
+
+    fn block_size(&self) -> BlockSize {
+        BlockSize::BS_512
+    }
+
+    fn num_blocks(&mut self) -> Result<u64, Self::Error> {
+        self.num_blocks_calls.set(self.num_blocks_calls.get() + 1);
+        BlockIoAdapter::new(self.data.borrow().as_slice(), BlockSize::BS_512)
+            .num_blocks()
+    }
+
+    fn read_blocks(
+        &mut self,
+        start_lba: Lba,
+        dst: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        BlockIoAdapter::new(self.data.borrow().as_slice(), BlockSize::BS_512)
+            .read_blocks(start_lba, dst)
+    }
+
+    fn write_blocks(
+        &mut self,
+        start_lba: Lba,
+        src: &[u8],
+    ) -> Result<(), Self::Error> {
+        BlockIoAdapter::new(
+            self.data.borrow_mut().as_mut_slice(),
+            BlockSize::BS_512,
+        )
+        .write_blocks(start_lba, src)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_num_blocks_cache() {
+    let data = Rc::new(RefCell::new(vec![0; 512 * 64]));
+    let num_blocks_calls = Rc::new(Cell::new(0));
+    let mut disk = Disk::new(ResizableBlockIo {
+        data: data.clone(),
+        num_blocks_calls: num_blocks_calls.clone(),
+    })
+    .unwrap();
+    let mut block_buf = vec![0; 512];
+
+    // Not queried until needed.
+    assert_eq!(num_blocks_calls.get(), 0);
+
+    // Queried once and then cached.
+    assert_eq!(disk.num_blocks().unwrap(), 64);
+    disk.write_protective_mbr(&mut block_buf).unwrap();
+    assert!(!disk
+        .read_secondary_gpt_header(&mut block_buf)
+        .unwrap()
+        .is_signature_valid());
+    assert_eq!(disk.num_blocks().unwrap(), 64);
+    assert_eq!(num_blocks_calls.get(), 1);
+
+    // Growing the storage is not seen until the cache is refreshed.
+    data.borrow_mut().resize(512 * 128, 0);
+    assert_eq!(disk.num_blocks().unwrap(), 64);
+    assert_eq!(disk.refresh_num_blocks().unwrap(), 128);
+    assert_eq!(disk.num_blocks().unwrap(), 128);
+    assert_eq!(num_blocks_calls.get(), 2);
+
+    // A partition opened as a disk knows its size without a query.
+    let entry = GptPartitionEntry {
+        starting_lba: LbaLe::from_u64(10),
+        ending_lba: LbaLe::from_u64(19),
+        ..Default::default()
+    };
+    let mut part = disk.open_partition_as_disk(&entry).unwrap();
+    assert_eq!(part.num_blocks().unwrap(), 10);
+    drop(part);
+    assert_eq!(num_blocks_calls.get(), 2);
+}
@@ -0,0 +1,382 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use gpt_disk_types::{
+    guid, BlockSize, GptDiskLayout, GptDiskLayoutError, GptPartitionEntrySize,
+    Lba, LbaRangeInclusive,
+};
+
+fn entry_size(size: u32) -> GptPartitionEntrySize {
+    GptPartitionEntrySize::new(size).unwrap()
+}
+
+#[test]
+fn test_disk_layout_default() {
+    let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    assert_eq!(layout.block_size(), BlockSize::BS_512);
+    assert_eq!(layout.num_blocks(), 8192);
+    assert_eq!(layout.partition_entry_array_num_blocks(), 32);
+    assert_eq!(layout.primary_header_lba(), Lba(1));
+    assert_eq!(layout.secondary_header_lba(), Lba(8191));
+    assert_eq!(layout.primary_partition_entry_array_layout().start_lba, 2);
+    assert_eq!(
+        layout.secondary_partition_entry_array_layout().start_lba,
+        8159
+    );
+    assert_eq!(
+        layout.usable_range(),
+        LbaRangeInclusive::new(Lba(34), Lba(8158)).unwrap()
+    );
+    assert_eq!(
+        layout.to_string(),
+        "block_size=512/num_blocks=8192/usable=34..=8158/primary_array=(start_lba=2/entry_size=128/num_entries=128)/secondary_array=(start_lba=8159/entry_size=128/num_entries=128)"
+    );
+
+    let layout = GptDiskLayout::new(BlockSize::BS_4096, 1024).unwrap();
+    assert_eq!(layout.partition_entry_array_num_blocks(), 4);
+    assert_eq!(layout.first_usable_lba(), Lba(6));
+    assert_eq!(layout.last_usable_lba(), Lba(1018));
+}
+
+#[test]
+fn test_disk_layout_entries() {
+    // More entries than the default.
+    let layout = GptDiskLayout::new_with_entries(
+        BlockSize::BS_512,
+        8192,
+        entry_size(128),
+        1024,
+    )
+    .unwrap();
+    assert_eq!(layout.partition_entry_array_num_blocks(), 256);
+    assert_eq!(layout.first_usable_lba(), Lba(258));
+    assert_eq!(layout.last_usable_lba(), Lba(7934));
+
+    // Fewer entries than the default still reserve 16 KiB.
+    let layout = GptDiskLayout::new_with_entries(
+        BlockSize::BS_512,
+        8192,
+        entry_size(128),
+        4,
+    )
+    .unwrap();
+    assert_eq!(layout.partition_entry_array_num_blocks(), 32);
+    assert_eq!(layout.first_usable_lba(), Lba(34));
+    assert_eq!(layout.primary_partition_entry_array_layout().num_entries, 4);
+
+    // Larger entries.
+    let layout = GptDiskLayout::new_with_entries(
+        BlockSize::BS_4096,
+        1024,
+        entry_size(512),
+        128,
+    )
+    .unwrap();
+    assert_eq!(layout.partition_entry_array_num_blocks(), 16);
+    assert_eq!(layout.first_usable_lba(), Lba(18));
+    assert_eq!(layout.last_usable_lba(), Lba(1006));
+
+    // Array size that is not a multiple of the block size.
+    let layout = GptDiskLayout::new_with_entries(
+        BlockSize::BS_4096,
+        1024,
+        entry_size(128),
+        129,
+    )
+    .unwrap();
+    assert_eq!(layout.partition_entry_array_num_blocks(), 5);
+}
+
+#[test]
+fn test_disk_layout_without_min_array_size() {
+    // Four entries on an 8 MiB image.
+    let layout = GptDiskLayout::new_with_options(
+        BlockSize::BS_512,
+        16384,
+        entry_size(128),
+        4,
+        false,
+    )
+    .unwrap();
+    assert_eq!(layout.partition_entry_array_num_blocks(), 1);
+    assert_eq!(
+        layout.usable_range(),
+        LbaRangeInclusive::new(Lba(3), Lba(16381)).unwrap()
+    );
+    assert_eq!(
+        layout.secondary_partition_entry_array_layout().start_lba,
+        16382
+    );
+
+    // The smallest possible disk.
+    assert!(GptDiskLayout::new_with_options(
+        BlockSize::BS_512,
+        6,
+        entry_size(128),
+        4,
+        false
+    )
+    .is_ok());
+    assert_eq!(
+        GptDiskLayout::new_with_options(
+            BlockSize::BS_512,
+            5,
+            entry_size(128),
+            4,
+            false
+        ),
+        Err(GptDiskLayoutError::DiskTooSmall)
+    );
+
+    // With the minimum, the result is the same as `new_with_entries`.
+    assert_eq!(
+        GptDiskLayout::new_with_options(
+            BlockSize::BS_512,
+            16384,
+            entry_size(128),
+            4,
+            true
+        ),
+        GptDiskLayout::new_with_entries(
+            BlockSize::BS_512,
+            16384,
+            entry_size(128),
+            4
+        )
+    );
+
+    // Arrays larger than the minimum are unaffected.
+    assert_eq!(
+        GptDiskLayout::new_with_options(
+            BlockSize::BS_512,
+            16384,
+            entry_size(128),
+            1024,
+            false
+        ),
+        GptDiskLayout::new_with_entries(
+            BlockSize::BS_512,
+            16384,
+            entry_size(128),
+            1024
+        )
+    );
+
+    // The option survives serialization.
+    let json = serde_json::to_string(&layout).unwrap();
+    assert!(json.contains("\"reserve_min_array_size\":false"));
+    assert_eq!(
+        serde_json::from_str::<GptDiskLayout>(&json).unwrap(),
+        layout
+    );
+    let spec_layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    let json = serde_json::to_string(&spec_layout).unwrap();
+    assert!(json.contains("\"reserve_min_array_size\":true"));
+    let json = json.replace(",\"reserve_min_array_size\":true", "");
+    assert_eq!(
+        serde_json::from_str::<GptDiskLayout>(&json).unwrap(),
+        spec_layout
+    );
+}
+
+#[test]
+fn test_disk_layout_reserved() {
+    let layout = GptDiskLayout::new(BlockSize::BS_512, 8192)
+        .unwrap()
+        .reserve_leading_bytes(1000)
+        .unwrap()
+        .reserve_trailing_bytes(512)
+        .unwrap();
+    assert_eq!(layout.reserved_leading_blocks(), 2);
+    assert_eq!(layout.reserved_trailing_blocks(), 1);
+    assert_eq!(
+        layout.usable_range(),
+        LbaRangeInclusive::new(Lba(36), Lba(8157)).unwrap()
+    );
+    // The arrays don't move.
+    assert_eq!(layout.primary_partition_entry_array_layout().start_lba, 2);
+    assert_eq!(
+        layout.secondary_partition_entry_array_layout().start_lba,
+        8159
+    );
+
+    // A new reservation replaces the old one.
+    let unreserved = layout.reserve_leading_bytes(0).unwrap();
+    assert_eq!(unreserved.first_usable_lba(), Lba(34));
+    assert_eq!(unreserved.last_usable_lba(), Lba(8157));
+
+    // At least one usable block must remain.
+    let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    assert_eq!(
+        layout
+            .reserve_leading_bytes(8124 * 512)
+            .unwrap()
+            .usable_range(),
+        LbaRangeInclusive::new(Lba(8158), Lba(8158)).unwrap()
+    );
+    assert_eq!(
+        layout.reserve_leading_bytes(8124 * 512 + 1),
+        Err(GptDiskLayoutError::DiskTooSmall)
+    );
+    assert_eq!(
+        layout.reserve_trailing_bytes(u64::MAX),
+        Err(GptDiskLayoutError::DiskTooSmall)
+    );
+
+    let layout = layout.reserve_leading_bytes(4096).unwrap();
+    let json = serde_json::to_string(&layout).unwrap();
+    assert_eq!(
+        serde_json::from_str::<GptDiskLayout>(&json).unwrap(),
+        layout
+    );
+}
+
+#[test]
+fn test_disk_layout_errors() {
+    // Smallest possible disk: MBR, two headers, two arrays, and one
+    // usable block.
+    let layout = GptDiskLayout::new(BlockSize::BS_512, 68).unwrap();
+    assert_eq!(layout.first_usable_lba(), layout.last_usable_lba());
+    assert_eq!(
+        GptDiskLayout::new(BlockSize::BS_512, 67),
+        Err(GptDiskLayoutError::DiskTooSmall)
+    );
+    assert_eq!(
+        GptDiskLayout::new(BlockSize::BS_512, 0),
+        Err(GptDiskLayoutError::DiskTooSmall)
+    );
+
+    // Largest possible array.
+    let layout = GptDiskLayout::new_with_entries(
+        BlockSize::BS_512,
+        u64::MAX,
+        entry_size(0x8000_0000),
+        u32::MAX,
+    )
+    .unwrap();
+    assert_eq!(
+        layout.partition_entry_array_num_blocks(),
+        0x8000_0000 * u64::from(u32::MAX) / 512
+    );
+    assert_eq!(
+        GptDiskLayout::new_with_entries(
+            BlockSize::BS_512,
+            1 << 40,
+            entry_size(0x8000_0000),
+            u32::MAX,
+        ),
+        Err(GptDiskLayoutError::DiskTooSmall)
+    );
+
+    assert_eq!(
+        GptDiskLayoutError::DiskTooSmall.to_string(),
+        "disk is too small for a GPT"
+    );
+}
+
+#[test]
+fn test_disk_layout_headers() {
+    let disk_guid = guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870");
+    let layout = GptDiskLayout::new_with_entries(
+        BlockSize::BS_512,
+        8192,
+        entry_size(256),
+        64,
+    )
+    .unwrap();
+
+    let primary = layout.primary_header(disk_guid);
+    assert!(primary.is_signature_valid());
+    assert_eq!(primary.my_lba.to_u64(), 1);
+    assert_eq!(primary.alternate_lba.to_u64(), 8191);
+    assert_eq!(primary.first_usable_lba.to_u64(), 34);
+    assert_eq!(primary.last_usable_lba.to_u64(), 8158);
+    assert_eq!({ primary.disk_guid }, disk_guid);
+    assert_eq!(
+        primary.get_partition_entry_array_layout().unwrap(),
+        layout.primary_partition_entry_array_layout()
+    );
+
+    let secondary = layout.secondary_header(disk_guid);
+    assert_eq!(secondary.my_lba.to_u64(), 8191);
+    assert_eq!(secondary.alternate_lba.to_u64(), 1);
+    assert_eq!(secondary.first_usable_lba, primary.first_usable_lba);
+    assert_eq!(secondary.last_usable_lba, primary.last_usable_lba);
+    assert_eq!(
+        secondary.get_partition_entry_array_layout().unwrap(),
+        layout.secondary_partition_entry_array_layout()
+    );
+}
+
+#[test]
+fn test_disk_layout_const() {
+    const LAYOUT: GptDiskLayout =
+        match GptDiskLayout::new(BlockSize::BS_4096, 1024) {
+            Ok(layout) => layout,
+            Err(_) => panic!("invalid layout"),
+        };
+    const ARRAY_NUM_BYTES: Option<u64> = LAYOUT
+        .primary_partition_entry_array_layout()
+        .num_bytes_rounded_to_block(BlockSize::BS_4096);
+    const USABLE_NUM_BLOCKS: u64 = LAYOUT.usable_range().num_blocks();
+
+    assert_eq!(LAYOUT.partition_entry_array_num_blocks(), 4);
+    assert_eq!(ARRAY_NUM_BYTES, Some(16384));
+    assert_eq!(USABLE_NUM_BLOCKS, 1024 - 2 - 4 * 2 - 1);
+    assert_eq!(
+        LAYOUT,
+        GptDiskLayout::new(BlockSize::BS_4096, 1024).unwrap()
+    );
+}
+
+#[test]
+fn test_disk_layout_min_num_blocks() {
+    for block_size in [BlockSize::BS_512, BlockSize::BS_4096] {
+        for (size, num_entries) in [(128, 4), (128, 128), (256, 1024)] {
+            for reserve_min_array_size in [false, true] {
+                let min = GptDiskLayout::min_num_blocks(
+                    block_size,
+                    entry_size(size),
+                    num_entries,
+                    reserve_min_array_size,
+                );
+                let new = |num_blocks| {
+                    GptDiskLayout::new_with_options(
+                        block_size,
+                        num_blocks,
+                        entry_size(size),
+                        num_entries,
+                        reserve_min_array_size,
+                    )
+                };
+                let layout = new(min).unwrap();
+                assert_eq!(layout.usable_range().num_blocks(), 1);
+                assert_eq!(new(min - 1), Err(GptDiskLayoutError::DiskTooSmall));
+            }
+        }
+    }
+
+    assert_eq!(
+        GptDiskLayout::min_num_blocks(
+            BlockSize::BS_512,
+            entry_size(128),
+            128,
+            true
+        ),
+        68
+    );
+    assert_eq!(
+        GptDiskLayout::min_num_blocks(
+            BlockSize::BS_512,
+            entry_size(128),
+            4,
+            false
+        ),
+        6
+    );
+}
@@ -0,0 +1,114 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use gpt_disk_io::{BlockIoAdapter, Disk, DiskError, FilesystemKind};
+use gpt_disk_types::{BlockSize, GptPartitionEntry, LbaLe};
+
+fn with_bytes(offset: usize, bytes: &[u8]) -> Vec<u8> {
+    let mut data = vec![0; 4096];
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    data
+}
+
+/// Create an ext superblock with the given compat, incompat, and
+/// ro_compat feature flags.
+fn ext(compat: u32, incompat: u32, ro_compat: u32) -> Vec<u8> {
+    let mut data = with_bytes(1024 + 56, &[0x53, 0xef]);
+    data[1024 + 0x5c..1024 + 0x60].copy_from_slice(&compat.to_le_bytes());
+    data[1024 + 0x60..1024 + 0x64].copy_from_slice(&incompat.to_le_bytes());
+    data[1024 + 0x64..1024 + 0x68].copy_from_slice(&ro_compat.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_identify_filesystem() {
+    let identify = |data: &[u8]| FilesystemKind::identify(data);
+
+    assert_eq!(identify(&[0; 4096]), None);
+    assert_eq!(identify(&[]), None);
+
+    assert_eq!(
+        identify(&with_bytes(0, b"LUKS\xba\xbe")),
+        Some(FilesystemKind::Luks)
+    );
+
+    let mut fat16 = with_bytes(54, b"FAT16   ");
+    fat16[510..512].copy_from_slice(&[0x55, 0xaa]);
+    assert_eq!(identify(&fat16), Some(FilesystemKind::Fat));
+    let mut fat32 = with_bytes(82, b"FAT32   ");
+    fat32[510..512].copy_from_slice(&[0x55, 0xaa]);
+    assert_eq!(identify(&fat32), Some(FilesystemKind::Fat));
+    // The boot sector signature is required.
+    assert_eq!(identify(&with_bytes(82, b"FAT32   ")), None);
+
+    let mut ntfs = with_bytes(3, b"NTFS    ");
+    ntfs[510..512].copy_from_slice(&[0x55, 0xaa]);
+    assert_eq!(identify(&ntfs), Some(FilesystemKind::Ntfs));
+    assert_eq!(
+        identify(&with_bytes(3, b"EXFAT   ")),
+        Some(FilesystemKind::Exfat)
+    );
+
+    assert_eq!(identify(&ext(0, 0x2, 0x1)), Some(FilesystemKind::Ext2));
+    assert_eq!(identify(&ext(0x4, 0x2, 0x1)), Some(FilesystemKind::Ext3));
+    // Extents.
+    assert_eq!(identify(&ext(0x4, 0x42, 0x1)), Some(FilesystemKind::Ext4));
+    // Huge files.
+    assert_eq!(identify(&ext(0x4, 0x2, 0x9)), Some(FilesystemKind::Ext4));
+
+    assert_eq!(
+        identify(&with_bytes(4086, b"SWAPSPACE2")),
+        Some(FilesystemKind::Swap)
+    );
+    // Too short to contain the swap signature.
+    assert_eq!(identify(&with_bytes(4086, b"SWAPSPACE2")[..4095]), None);
+
+    assert_eq!(FilesystemKind::Ext4.to_string(), "ext4");
+    assert_eq!(FilesystemKind::Fat.to_string(), "vfat");
+    assert_eq!(FilesystemKind::Luks.to_string(), "crypto_LUKS");
+}
+
+#[test]
+fn test_probe_partition_filesystem() {
+    let bs = BlockSize::BS_512;
+    let mut contents = vec![0; 512 * 32];
+    // Partition starting at LBA 16.
+    contents[512 * 16..512 * 24].copy_from_slice(&ext(0x4, 0x40, 0));
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+
+    let mut entry = GptPartitionEntry {
+        starting_lba: LbaLe::from_u64(16),
+        ending_lba: LbaLe::from_u64(31),
+        ..Default::default()
+    };
+    let mut buf = vec![0; 4096];
+    assert_eq!(
+        disk.probe_partition_filesystem(&entry, &mut buf).unwrap(),
+        Some(FilesystemKind::Ext4)
+    );
+
+    assert!(matches!(
+        disk.probe_partition_filesystem(&entry, &mut buf[..4095]),
+        Err(DiskError::BufferTooSmall)
+    ));
+
+    // A small partition only needs a buffer large enough to hold it.
+    entry.ending_lba = LbaLe::from_u64(17);
+    assert_eq!(
+        disk.probe_partition_filesystem(&entry, &mut buf[..1024])
+            .unwrap(),
+        None
+    );
+
+    entry.ending_lba = LbaLe::from_u64(32);
+    assert!(matches!(
+        disk.probe_partition_filesystem(&entry, &mut buf),
+        Err(DiskError::InvalidPartitionRange)
+    ));
+}
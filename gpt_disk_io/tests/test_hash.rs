@@ -0,0 +1,47 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use gpt_disk_io::{
+    hash_lba_range, BlockIoAdapter, DiskError, SliceBlockIoError,
+};
+use gpt_disk_types::{BlockSize, Lba, LbaRangeInclusive};
+
+#[test]
+fn test_hash_lba_range() {
+    let data: Vec<u8> = (0..10).flat_map(|i| [i; 512]).collect();
+    let mut bio = BlockIoAdapter::new(data.as_slice(), BlockSize::BS_512);
+    let range = LbaRangeInclusive::new(Lba(2), Lba(6)).unwrap();
+
+    // Buffer size that does not evenly divide the range.
+    let mut buf = vec![0; 512 * 2];
+    let mut hashed = Vec::new();
+    let mut progress = Vec::new();
+    hash_lba_range(
+        &mut bio,
+        range,
+        &mut buf,
+        |chunk| hashed.extend_from_slice(chunk),
+        |n| progress.push(n),
+    )
+    .unwrap();
+    assert_eq!(hashed, data[512 * 2..512 * 7]);
+    assert_eq!(progress, [2, 4, 5]);
+
+    assert!(matches!(
+        hash_lba_range(&mut bio, range, &mut [], |_| {}, |_| {}),
+        Err(DiskError::BufferTooSmall)
+    ));
+
+    let range = LbaRangeInclusive::new(Lba(8), Lba(10)).unwrap();
+    let mut progress = Vec::new();
+    assert!(matches!(
+        hash_lba_range(&mut bio, range, &mut buf, |_| {}, |n| progress.push(n)),
+        Err(DiskError::Io(SliceBlockIoError::OutOfBounds { .. }))
+    ));
+    assert_eq!(progress, [2]);
+}
@@ -8,11 +8,15 @@
 
 mod common;
 
-use common::{check_derives, create_primary_header};
+use common::{
+    check_derives, create_partition_entry, create_primary_header,
+    create_secondary_header,
+};
 use gpt_disk_types::{
-    Crc32, GptHeader, GptHeaderRevision, GptHeaderSignature,
-    GptPartitionEntryArrayLayout, GptPartitionEntrySize,
-    GptPartitionEntrySizeError, Lba, U32Le,
+    BlockSize, Crc32, GptHeader, GptHeaderRevision, GptHeaderSignature,
+    GptPartitionEntryArray, GptPartitionEntryArrayLayout,
+    GptPartitionEntryArrayLocationError, GptPartitionEntrySize,
+    GptPartitionEntrySizeError, Lba, LbaLe, U32Le,
 };
 
 #[test]
@@ -23,6 +27,11 @@ fn test_signature() {
         GptHeaderSignature::EFI_COMPATIBLE_PARTITION_TABLE_HEADER.to_u64(),
         0x5452415020494645
     );
+    assert_eq!(
+        GptHeaderSignature::from_u64(0x5452415020494645),
+        GptHeaderSignature::EFI_COMPATIBLE_PARTITION_TABLE_HEADER
+    );
+    assert_eq!(GptHeaderSignature::from_u64(0x1234).to_u64(), 0x1234);
 }
 
 #[test]
@@ -35,6 +44,24 @@ fn test_revision() {
     let rev = GptHeaderRevision(U32Le::from_u32(0x1234_5678));
     assert_eq!(rev.major(), 0x1234);
     assert_eq!(rev.minor(), 0x5678);
+    assert!(!rev.is_compatible());
+
+    assert!(GptHeaderRevision::VERSION_1_0.is_compatible());
+    assert!(GptHeaderRevision(U32Le::from_u32(0x0001_0001)).is_compatible());
+    assert!(!GptHeaderRevision(U32Le::from_u32(0x0000_ffff)).is_compatible());
+    assert!(!GptHeaderRevision(U32Le::from_u32(0x0002_0000)).is_compatible());
+
+    assert_eq!(GptHeaderRevision::new(1, 0), GptHeaderRevision::VERSION_1_0);
+    assert_eq!(GptHeaderRevision::new(0x1234, 0x5678), rev);
+    assert_eq!(GptHeaderRevision::VERSION_1_0.to_string(), "1.0");
+    assert_eq!(rev.to_string(), "4660.22136");
+
+    let header = GptHeader {
+        revision: GptHeaderRevision::new(1, 2),
+        ..GptHeader::default()
+    };
+    assert_eq!(header.revision_tuple(), (1, 2));
+    assert_eq!(GptHeader::default().revision_tuple(), (1, 0));
 }
 
 #[test]
@@ -53,6 +80,94 @@ fn test_header_crc32() {
 
     header.update_header_crc32();
     assert_eq!(header.header_crc32, Crc32(U32Le::from_u32(0xa4877843)));
+    assert!(header.is_header_crc32_valid());
+
+    // The calculation ignores the current value of the field.
+    header.header_crc32 = Crc32(U32Le::from_u32(0x1234));
+    assert!(!header.is_header_crc32_valid());
+    assert_eq!(
+        header.calculate_header_crc32(),
+        Crc32(U32Le::from_u32(0xa4877843))
+    );
+
+    header.my_lba = LbaLe::from_u64(2);
+    header.header_crc32 = Crc32(U32Le::from_u32(0xa4877843));
+    assert!(!header.is_header_crc32_valid());
+}
+
+#[test]
+fn test_header_crc32_with_tail() {
+    let header = create_primary_header();
+
+    // An empty tail is the same as the normal calculation.
+    assert_eq!(
+        header.calculate_header_crc32_with_tail(&[]),
+        header.calculate_header_crc32()
+    );
+
+    // The tail is included after the struct.
+    let tail = [0xab; 36];
+    let mut bytes = [0; 128];
+    bytes[..92].copy_from_slice(bytemuck::bytes_of(&header));
+    bytes[16..20].fill(0);
+    bytes[92..].copy_from_slice(&tail);
+    assert_eq!(
+        header.calculate_header_crc32_with_tail(&tail),
+        Crc32::of(&bytes)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_header_to_bytes_with_len() {
+    let header = create_primary_header();
+    assert_eq!(header.to_bytes_with_len(91), None);
+
+    let bytes = header.to_bytes_with_len(92).unwrap();
+    assert_eq!(bytes, bytemuck::bytes_of(&header));
+
+    let bytes = header.to_bytes_with_len(512).unwrap();
+    assert_eq!(bytes.len(), 512);
+    assert_eq!(&bytes[..92], bytemuck::bytes_of(&header));
+    assert!(bytes[92..].iter().all(|b| *b == 0));
+}
+
+#[test]
+fn test_header_set_partition_entry_array_crc32() {
+    let mut header = create_primary_header();
+    let layout = header.get_partition_entry_array_layout().unwrap();
+    let mut storage = vec![0; 128 * 128];
+    let mut array =
+        GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+            .unwrap();
+    *array.get_partition_entry_mut(0).unwrap() = create_partition_entry();
+
+    header.partition_entry_array_crc32 = Crc32::default();
+    header.set_partition_entry_array_crc32(&array);
+    assert_eq!(
+        header.partition_entry_array_crc32,
+        Crc32(U32Le::from_u32(0x9206adff))
+    );
+}
+
+#[test]
+fn test_header_update_crcs() {
+    let expected = create_primary_header();
+    let layout = expected.get_partition_entry_array_layout().unwrap();
+    let mut storage = vec![0; 128 * 128];
+    let mut array =
+        GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+            .unwrap();
+    *array.get_partition_entry_mut(0).unwrap() = create_partition_entry();
+
+    let mut header = GptHeader {
+        header_crc32: Crc32::default(),
+        partition_entry_array_crc32: Crc32::default(),
+        ..expected
+    };
+    header.update_crcs(&array);
+    assert!(header.is_header_crc32_valid());
+    assert_eq!(header, expected);
 }
 
 #[test]
@@ -99,3 +214,48 @@ fn test_header_partition_layout() {
     header.size_of_partition_entry = U32Le::from_u32(64);
     assert!(header.get_partition_entry_array_layout().is_err());
 }
+
+#[test]
+fn test_header_check_partition_entry_array_location() {
+    use GptPartitionEntryArrayLocationError as E;
+
+    let bs = BlockSize::BS_512;
+    let check = |lba: u64| {
+        let mut header = create_primary_header();
+        // Leave a gap after the header, as some tools do.
+        header.first_usable_lba = LbaLe::from_u64(2048);
+        header.partition_entry_lba = LbaLe::from_u64(lba);
+        header.check_partition_entry_array_location(bs)
+    };
+
+    // Conventional layout.
+    let header = create_primary_header();
+    assert_eq!(header.check_partition_entry_array_location(bs), Ok(()));
+    let header = create_secondary_header();
+    assert_eq!(header.check_partition_entry_array_location(bs), Ok(()));
+
+    // Non-standard locations outside the usable range.
+    assert_eq!(check(2), Ok(()));
+    assert_eq!(check(1000), Ok(()));
+    assert_eq!(check(2016), Ok(()));
+
+    assert_eq!(check(0), Err(E::OverlapsMbr));
+    assert_eq!(check(1), Err(E::OverlapsHeader));
+    assert_eq!(check(8160), Err(E::OverlapsHeader));
+    assert_eq!(check(2017), Err(E::OverlapsUsableRange));
+    assert_eq!(check(4000), Err(E::OverlapsUsableRange));
+    assert_eq!(check(u64::MAX), Err(E::Overflow));
+
+    let mut header = create_primary_header();
+    header.size_of_partition_entry = U32Le::from_u32(0);
+    assert_eq!(
+        header.check_partition_entry_array_location(bs),
+        Err(E::InvalidEntrySize)
+    );
+
+    // An empty array can't overlap anything.
+    let mut header = create_primary_header();
+    header.number_of_partition_entries = U32Le::from_u32(0);
+    header.partition_entry_lba = LbaLe::from_u64(0);
+    assert_eq!(header.check_partition_entry_array_location(bs), Ok(()));
+}
@@ -0,0 +1,119 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "log")]
+
+use gpt_disk_io::{BlockIoAdapter, Disk, GptValidationOptions};
+use gpt_disk_types::{BlockSize, GptDiskLayout, GptPartitionEntryArray, Guid};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::sync::Mutex;
+
+static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct TestLogger;
+
+impl Log for TestLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        MESSAGES.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn test_log_events() {
+    log::set_logger(&TestLogger).unwrap();
+    log::set_max_level(LevelFilter::Debug);
+
+    let bs = BlockSize::BS_512;
+    let mut contents = vec![0; 512 * 128];
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    let mut block_buf = vec![0; 512];
+
+    let layout = GptDiskLayout::new(bs, 128).unwrap();
+    let array_layout = layout.primary_partition_entry_array_layout();
+    let mut storage = vec![0; 512 * 32];
+    let mut array =
+        GptPartitionEntryArray::new(array_layout, bs, &mut storage).unwrap();
+    disk.write_protective_mbr(&mut block_buf).unwrap();
+    disk.update_and_write_gpt(
+        &layout.primary_header(Guid::ZERO),
+        &mut array,
+        &mut block_buf,
+    )
+    .unwrap();
+    disk.read_primary_gpt_header(&mut block_buf).unwrap();
+
+    let messages = MESSAGES.lock().unwrap();
+    let prefixes: Vec<_> = messages
+        .iter()
+        .map(|m| m.split(':').next().unwrap())
+        .collect();
+    assert_eq!(
+        prefixes,
+        [
+            "writing MBR",
+            "updating GPT",
+            "writing GPT partition entry array",
+            "writing GPT header at LBA 127",
+            "writing GPT partition entry array",
+            "writing GPT header at LBA 1",
+            "reading GPT header at LBA 1",
+        ]
+    );
+    drop(messages);
+    drop(disk);
+
+    // Validation failures. Reads are filtered out below.
+    MESSAGES.lock().unwrap().clear();
+    let mut disk = Disk::new(BlockIoAdapter::new(
+        contents.as_mut_slice(),
+        BlockSize::BS_4096,
+    ))
+    .unwrap();
+    let mut block_buf_4096 = vec![0; 4096];
+    assert!(disk.check_block_size(&mut block_buf_4096).is_err());
+    drop(disk);
+
+    // Corrupt the primary header's CRC32.
+    contents[512 + 16] ^= 1;
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(contents.as_mut_slice(), bs)).unwrap();
+    assert!(disk
+        .validate_gpt(GptValidationOptions::default(), &mut block_buf)
+        .is_err());
+    assert!(disk
+        .update_and_write_gpt_checked(
+            &layout.primary_header(Guid::from_bytes([1; 16])),
+            &mut array,
+            &mut block_buf,
+        )
+        .is_err());
+
+    let messages = MESSAGES.lock().unwrap();
+    let failures: Vec<_> = messages
+        .iter()
+        .map(|m| m.split(':').next().unwrap())
+        .filter(|m| !m.starts_with("reading"))
+        .collect();
+    assert_eq!(
+        failures,
+        [
+            "found primary GPT header with block size 512",
+            "block size mismatch",
+            "invalid header CRC32 in GPT header at LBA 1",
+            "invalid GPT header at LBA 1",
+            "found existing GPT with disk GUID 00000000-0000-0000-0000-000000000000",
+        ]
+    );
+}
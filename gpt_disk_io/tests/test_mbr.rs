@@ -98,3 +98,27 @@ signature: 0x3412
         .to_string()
         .starts_with("MasterBootRecord { boot_strap_code: <non-zero>,"));
 }
+
+#[test]
+fn test_mbr_with_boot_strap_code() {
+    let mut code = [0; 440];
+    code[0] = 0xeb;
+    code[439] = 0x90;
+    let protective = MasterBootRecord::protective_mbr(8192);
+    let mbr = protective.with_boot_strap_code(&code);
+    assert_eq!(mbr.boot_strap_code, code);
+    assert!(!mbr.is_boot_strap_code_zero());
+    assert_eq!(mbr.partitions, protective.partitions);
+    assert_eq!(mbr.signature, protective.signature);
+}
+
+#[test]
+fn test_protective_mbr_small_disk() {
+    for num_blocks in [0, 1] {
+        let mbr = MasterBootRecord::protective_mbr(num_blocks);
+        assert_eq!(mbr.partitions[0].os_indicator, 0xee);
+        assert_eq!(mbr.partitions[0].size_in_lba.to_u32(), 0);
+    }
+    let mbr = MasterBootRecord::protective_mbr(2);
+    assert_eq!(mbr.partitions[0].size_in_lba.to_u32(), 1);
+}
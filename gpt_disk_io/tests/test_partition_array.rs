@@ -10,7 +10,9 @@ mod common;
 
 use common::check_derives;
 use gpt_disk_types::{
-    BlockSize, GptPartitionEntryArrayLayout, GptPartitionEntrySize, Lba,
+    guid, BlockSize, GptPartitionEntry, GptPartitionEntryArray,
+    GptPartitionEntryArrayLayout, GptPartitionEntrySize, GptPartitionType, Lba,
+    LbaLe, PartitionIndex,
 };
 
 #[test]
@@ -43,3 +45,186 @@ fn test_partition_entry_array_layout() {
     );
     assert_eq!(layout.num_bytes_exact_as_usize().unwrap(), 256 * 128);
 }
+
+#[test]
+fn test_partition_index() {
+    check_derives::<PartitionIndex>();
+
+    let index = PartitionIndex::new(2);
+    assert_eq!(index.to_u32(), 2);
+    assert_eq!(u32::from(index), 2);
+    assert_eq!(index.to_display_number(), 3);
+    assert_eq!(index.to_string(), "3");
+    assert_eq!(PartitionIndex::from_display_number(3), Some(index));
+
+    assert_eq!(PartitionIndex::default().to_display_number(), 1);
+    assert_eq!(PartitionIndex::from_display_number(0), None);
+
+    let last = PartitionIndex::new(u32::MAX);
+    assert_eq!(last.to_display_number(), 1 << 32);
+    assert_eq!(PartitionIndex::from_display_number(1 << 32), Some(last));
+    assert_eq!(PartitionIndex::from_display_number((1 << 32) + 1), None);
+}
+
+#[test]
+fn test_partition_entry_array_get() {
+    let layout = GptPartitionEntryArrayLayout {
+        start_lba: Lba(2),
+        entry_size: GptPartitionEntrySize::new(128).unwrap(),
+        num_entries: 4,
+    };
+    let mut storage = [0; 512];
+    let mut array =
+        GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+            .unwrap();
+
+    let first = PartitionIndex::from_display_number(1).unwrap();
+    array.get_mut(first).unwrap().name = "first".parse().unwrap();
+    assert_eq!(array.get(first).unwrap().name, "first");
+    assert_eq!(array.get_partition_entry(0).unwrap().name, "first");
+
+    let fourth = PartitionIndex::from_display_number(4).unwrap();
+    assert_eq!(*array.get(fourth).unwrap(), GptPartitionEntry::default());
+    assert!(array.get(PartitionIndex::new(4)).is_none());
+    assert!(array.get_mut(PartitionIndex::new(4)).is_none());
+}
+
+#[test]
+fn test_partition_entry_array_iter() {
+    // Use a larger entry size to check that the padding after each
+    // entry is skipped.
+    let layout = GptPartitionEntryArrayLayout {
+        start_lba: Lba(2),
+        entry_size: GptPartitionEntrySize::new(256).unwrap(),
+        num_entries: 3,
+    };
+    let mut storage = [0; 1024];
+    let mut array =
+        GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+            .unwrap();
+
+    assert_eq!(array.iter().count(), 3);
+    for (i, entry) in array.iter_mut().enumerate() {
+        entry.name = ["a", "b", "c"][i].parse().unwrap();
+    }
+    let names: Vec<_> = array.iter().map(|e| e.name.to_string()).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+    assert_eq!(array.get_partition_entry(2).unwrap().name, "c");
+
+    // Padding and the unused tail of the block are untouched.
+    assert!(storage[128..256].iter().all(|b| *b == 0));
+    assert!(storage[768..].iter().all(|b| *b == 0));
+
+    // Empty array.
+    let layout = GptPartitionEntryArrayLayout {
+        num_entries: 0,
+        ..layout
+    };
+    let mut storage = [0; 512];
+    let mut array =
+        GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+            .unwrap();
+    assert_eq!(array.iter().count(), 0);
+    assert_eq!(array.iter_mut().count(), 0);
+}
+
+#[test]
+fn test_partition_entry_array_find_by_partuuid() {
+    let layout = GptPartitionEntryArrayLayout {
+        start_lba: Lba(2),
+        entry_size: GptPartitionEntrySize::new(128).unwrap(),
+        num_entries: 4,
+    };
+    let mut storage = [0; 512];
+    let mut array =
+        GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+            .unwrap();
+    let partuuid = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    *array.get_partition_entry_mut(2).unwrap() = GptPartitionEntry {
+        partition_type_guid: GptPartitionType::BASIC_DATA,
+        unique_partition_guid: partuuid,
+        ..Default::default()
+    };
+    // Unused entries are ignored even if the GUID matches.
+    array
+        .get_partition_entry_mut(1)
+        .unwrap()
+        .unique_partition_guid = partuuid;
+
+    let (index, entry) = array
+        .find_by_partuuid("37C75FFD-8932-467A-9C56-8CF1F0456B12")
+        .unwrap();
+    assert_eq!(index, PartitionIndex::new(2));
+    assert_eq!({ entry.unique_partition_guid }, partuuid);
+
+    assert!(array
+        .find_by_partuuid("00000000-0000-0000-0000-000000000001")
+        .is_none());
+    assert!(array.find_by_partuuid("not a guid").is_none());
+}
+
+#[test]
+fn test_partition_entry_array_display() {
+    let layout = GptPartitionEntryArrayLayout {
+        start_lba: Lba(2),
+        entry_size: GptPartitionEntrySize::new(128).unwrap(),
+        num_entries: 4,
+    };
+    let mut storage = [0; 512];
+    let mut array =
+        GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+            .unwrap();
+
+    assert_eq!(
+        format!("{array:?}"),
+        "GptPartitionEntryArray { layout: GptPartitionEntryArrayLayout { start_lba: Lba(2), entry_size: GptPartitionEntrySize(128), num_entries: 4 }, num_used_entries: 0, .. }"
+    );
+    assert_eq!(array.to_string(), "");
+
+    let entry = array.get_partition_entry_mut(2).unwrap();
+    entry.partition_type_guid = GptPartitionType::BASIC_DATA;
+    entry.starting_lba = LbaLe::from_u64(34);
+    entry.ending_lba = LbaLe::from_u64(99);
+    entry.name = "data".parse().unwrap();
+
+    assert!(format!("{array:?}").ends_with("num_used_entries: 1, .. }"));
+    assert_eq!(
+        array.to_string(),
+        "3: GptPartitionEntry { partition_type_guid: ebd0a0a2-b9e5-4433-87c0-68b6b72699c7, unique_partition_guid: 00000000-0000-0000-0000-000000000000, starting_lba: 34, ending_lba: 99, attributes: (empty), name: \"data\" }\n"
+    );
+}
+
+#[test]
+fn test_partition_entry_array_layout_calculate_crc32() {
+    let layout = GptPartitionEntryArrayLayout {
+        start_lba: Lba(2),
+        entry_size: GptPartitionEntrySize::new(256).unwrap(),
+        num_entries: 6,
+    };
+    let entry = GptPartitionEntry {
+        partition_type_guid: GptPartitionType::BASIC_DATA,
+        starting_lba: LbaLe::from_u64(34),
+        ending_lba: LbaLe::from_u64(100),
+        ..Default::default()
+    };
+
+    let mut storage = vec![0; 512 * 3];
+    let mut array =
+        GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+            .unwrap();
+    *array.get_mut(PartitionIndex::new(0)).unwrap() = entry;
+    *array.get_mut(PartitionIndex::new(1)).unwrap() = entry;
+    let expected = array.calculate_crc32();
+
+    assert_eq!(layout.calculate_crc32([entry, entry]), Some(expected));
+    assert_eq!(
+        layout.calculate_crc32(
+            [entry, entry]
+                .into_iter()
+                .chain([GptPartitionEntry::default(); 4])
+        ),
+        Some(expected)
+    );
+    assert_ne!(layout.calculate_crc32([entry]), Some(expected));
+    assert_eq!(layout.calculate_crc32([entry; 7]), None);
+}
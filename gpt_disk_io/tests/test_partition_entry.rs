@@ -10,9 +10,11 @@ mod common;
 
 use common::check_derives;
 use gpt_disk_types::{
-    GptPartitionAttributes, GptPartitionEntry, GptPartitionName,
+    guid, Arch, GptPartitionAttributes, GptPartitionEntry, GptPartitionName,
+    GptPartitionNameFromStrError, GptPartitionNameSetCharError,
     GptPartitionType, Guid, U16Le, U64Le,
 };
+use serde_test::{assert_tokens, Configure, Token};
 
 #[test]
 fn test_partition_type() {
@@ -32,6 +34,16 @@ fn test_partition_type() {
         GptPartitionType(guid).to_string(),
         "01234567-89ab-cdef-0123-456789abcdef"
     );
+
+    // Conversions to and from `Guid`.
+    let partition_type = GptPartitionType::new(guid);
+    assert_eq!(partition_type, GptPartitionType(guid));
+    assert_eq!(GptPartitionType::from(guid), partition_type);
+    assert_eq!(Guid::from(partition_type), guid);
+    assert_eq!(partition_type, guid);
+    assert_eq!(guid, partition_type);
+    assert_ne!(GptPartitionType::UNUSED, guid);
+    assert_ne!(guid, GptPartitionType::UNUSED);
 }
 
 #[test]
@@ -86,6 +98,191 @@ fn test_type_specific_attributes() {
     assert_eq!(attr.type_specific_attributes().to_u16(), 0xabcd);
 }
 
+#[test]
+fn test_conventional_attributes() {
+    let conventional =
+        |t| GptPartitionAttributes::conventional_for(t).0.to_u64();
+    assert_eq!(
+        conventional(GptPartitionType::WINDOWS_RECOVERY),
+        0x8000_0000_0000_0001
+    );
+    for t in [
+        GptPartitionType::EFI_SYSTEM,
+        GptPartitionType::MICROSOFT_RESERVED,
+        GptPartitionType::BASIC_DATA,
+        GptPartitionType::CHROME_OS_KERNEL,
+    ] {
+        assert_eq!(conventional(t), 0);
+    }
+
+    // Existing attributes are replaced.
+    let entry = GptPartitionEntry {
+        partition_type_guid: GptPartitionType::EFI_SYSTEM,
+        attributes: GptPartitionAttributes(U64Le::from_u64(0b100)),
+        ..Default::default()
+    }
+    .with_conventional_attributes();
+    assert_eq!({ entry.attributes }.0.to_u64(), 0);
+}
+
+#[test]
+fn test_attributes_serde() {
+    let attrs = |bits| GptPartitionAttributes(U64Le::from_u64(bits));
+
+    assert_tokens(
+        &attrs(0x8000_0000_0000_0005).readable(),
+        &[
+            Token::Seq { len: None },
+            Token::Str("required_partition"),
+            Token::Str("legacy_bios_bootable"),
+            Token::Str("0x8000000000000000"),
+            Token::SeqEnd,
+        ],
+    );
+    assert_tokens(
+        &attrs(0).readable(),
+        &[Token::Seq { len: None }, Token::SeqEnd],
+    );
+    assert_tokens(&attrs(0b11).compact(), &[Token::U64(0b11)]);
+
+    // Integers, hex values, and flag names are all accepted.
+    let from_json =
+        |s| serde_json::from_str::<GptPartitionAttributes>(s).unwrap();
+    assert_eq!(from_json("3"), attrs(3));
+    assert_eq!(
+        from_json(r#"["no_block_io_protocol", "0x10", "0X100"]"#),
+        attrs(0x112)
+    );
+    assert_eq!(
+        serde_json::to_string(&attrs(0x8000_0000_0000_0001)).unwrap(),
+        r#"["required_partition","0x8000000000000000"]"#
+    );
+
+    assert!(
+        serde_json::from_str::<GptPartitionAttributes>(r#"["bogus"]"#).is_err()
+    );
+    assert!(
+        serde_json::from_str::<GptPartitionAttributes>(r#"["0xzz"]"#).is_err()
+    );
+}
+
+#[test]
+fn test_partition_name_serde() {
+    let name: GptPartitionName = "EFI System".parse().unwrap();
+    assert_tokens(&name.readable(), &[Token::Str("EFI System")]);
+
+    const EFI_BYTES: [u8; 72] = {
+        let mut bytes = [0; 72];
+        bytes[0] = b'E';
+        bytes[2] = b'F';
+        bytes[4] = b'I';
+        bytes
+    };
+    let name: GptPartitionName = "EFI".parse().unwrap();
+    assert_tokens(&name.compact(), &[Token::Bytes(&EFI_BYTES)]);
+
+    // Invalid characters and backslashes are escaped.
+    let name =
+        GptPartitionName::from_code_units(&[0x41, 0xd800, 0x5c]).unwrap();
+    assert_tokens(&name.readable(), &[Token::Str("A\\u{d800}\\\\")]);
+
+    let from_str = |s: &str| {
+        serde_json::from_value::<GptPartitionName>(serde_json::Value::from(s))
+    };
+    assert_eq!(
+        from_str("\\u{D800}").unwrap(),
+        GptPartitionName::from_code_units(&[0xd800]).unwrap()
+    );
+    assert_eq!(from_str(&"a".repeat(36)).unwrap(), "a".repeat(36).as_str());
+    assert!(from_str(&"a".repeat(37)).is_err());
+    assert!(from_str("\u{1f600}").is_err());
+    assert!(from_str("\\x").is_err());
+    assert!(from_str("\\u{0}").is_err());
+    assert!(from_str("\\u{10000}").is_err());
+    assert!(from_str("\\u{d800").is_err());
+}
+
+#[test]
+fn test_known_partition_types() {
+    let known = GptPartitionType::KNOWN_TYPES;
+    assert!(known.contains(&("EFI_SYSTEM", GptPartitionType::EFI_SYSTEM)));
+    assert!(known.contains(&("BIOS_BOOT", GptPartitionType::BIOS_BOOT)));
+    assert!(!known.iter().any(|(_, t)| *t == GptPartitionType::UNUSED));
+
+    for (name, partition_type) in known {
+        assert_eq!(GptPartitionType::from_name(name), Some(*partition_type));
+        assert_eq!(partition_type.name(), Some(*name));
+    }
+
+    assert_eq!(
+        GptPartitionType::from_name("basic-data"),
+        Some(GptPartitionType::BASIC_DATA)
+    );
+    assert_eq!(
+        GptPartitionType::from_name("Windows_Recovery"),
+        Some(GptPartitionType::WINDOWS_RECOVERY)
+    );
+    assert_eq!(GptPartitionType::from_name("BASIC"), None);
+    assert_eq!(GptPartitionType::from_name(""), None);
+    assert_eq!(GptPartitionType::UNUSED.name(), None);
+}
+
+#[test]
+fn test_dps_partition_types() {
+    assert_eq!(
+        GptPartitionType::root_for_arch(Arch::Aarch64),
+        guid!("b921b045-1df0-41c3-af44-4c6f280d3fae")
+    );
+    assert_eq!(
+        GptPartitionType::usr_for_arch(Arch::Aarch64),
+        guid!("b0e01050-ee5f-4390-949a-9101b17104e9")
+    );
+
+    // Every type is distinct.
+    let arches = [
+        Arch::X86,
+        Arch::X86_64,
+        Arch::Arm,
+        Arch::Aarch64,
+        Arch::RiscV32,
+        Arch::RiscV64,
+        Arch::LoongArch64,
+    ];
+    let mut types: Vec<_> = arches
+        .iter()
+        .flat_map(|&arch| {
+            [
+                GptPartitionType::root_for_arch(arch),
+                GptPartitionType::usr_for_arch(arch),
+            ]
+        })
+        .collect();
+    types.sort();
+    types.dedup();
+    assert_eq!(types.len(), arches.len() * 2);
+}
+
+#[test]
+fn test_dps_attributes() {
+    let mut attr = GptPartitionAttributes::default();
+    assert!(!attr.dps_growfs());
+    assert!(!attr.dps_read_only());
+    assert!(!attr.dps_no_auto());
+
+    attr.update_dps_growfs(true);
+    assert_eq!(attr.0.to_u64(), 1 << 59);
+    attr.update_dps_read_only(true);
+    attr.update_dps_no_auto(true);
+    assert_eq!(attr.0.to_u64(), 0b1_0011 << 59);
+    assert!(attr.dps_growfs());
+    assert!(attr.dps_read_only());
+    assert!(attr.dps_no_auto());
+    assert_eq!(attr.type_specific_attributes().to_u16(), 0x9800);
+
+    attr.update_dps_read_only(false);
+    assert_eq!(attr.0.to_u64(), 0b1_0001 << 59);
+}
+
 #[test]
 fn test_partition_attribute_display() {
     let mut attr = GptPartitionAttributes(U64Le::from_u64(0));
@@ -142,7 +339,121 @@ fn test_partition_name() {
     assert_eq!(name.to_string(), "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
 }
 
+#[test]
+fn test_partition_name_max_len() {
+    let s35 = "a".repeat(35);
+    let s36 = "a".repeat(36);
+    let s37 = "a".repeat(37);
+
+    // A full-length name has no null terminator.
+    let name: GptPartitionName = s36.parse().unwrap();
+    assert_eq!(name.0, *[b'a', 0].repeat(36));
+    assert_eq!(name.to_string(), s36);
+    assert_eq!(
+        s37.parse::<GptPartitionName>(),
+        Err(GptPartitionNameFromStrError::Length)
+    );
+
+    // Old behavior: leave room for the null terminator.
+    let name = GptPartitionName::from_str_with_options(&s35, true).unwrap();
+    assert_eq!(name.to_string(), s35);
+    assert_eq!(
+        GptPartitionName::from_str_with_options(&s36, true),
+        Err(GptPartitionNameFromStrError::Length)
+    );
+
+    // The last character can be set.
+    let mut name = GptPartitionName::default();
+    name.set_char(35, 'z').unwrap();
+    assert_eq!(name.0[70..], [b'z', 0]);
+    assert_eq!(
+        name.set_char(36, 'z'),
+        Err(GptPartitionNameSetCharError::Index)
+    );
+}
+
+#[test]
+fn test_partition_name_eq_str() {
+    let name: GptPartitionName = "EFI System".parse().unwrap();
+    assert_eq!(name, "EFI System");
+    assert!(name == *"EFI System");
+    assert_ne!(name, "EFI");
+    assert_ne!(name, "EFI System Partition");
+    assert_ne!(name, "efi system");
+
+    assert!(name.matches_ignore_case("efi system"));
+    assert!(name.matches_ignore_case("EFI SYSTEM"));
+    assert!(!name.matches_ignore_case("efi"));
+
+    assert_eq!(GptPartitionName::default(), "");
+    assert!(GptPartitionName::default().matches_ignore_case(""));
+
+    // Non-ASCII.
+    let name: GptPartitionName = "Ünïcode".parse().unwrap();
+    assert_eq!(name, "Ünïcode");
+    assert!(name.matches_ignore_case("üNÏCODE"));
+
+    // Invalid characters never match, not even the replacement
+    // character that `Display` uses.
+    let mut name = GptPartitionName::default();
+    name.set_char(0, 'a').unwrap();
+    name.0[2] = 0x00;
+    name.0[3] = 0xd8;
+    assert_ne!(name, "a\u{fffd}");
+    assert!(!name.matches_ignore_case("a\u{fffd}"));
+
+    // No trailing null.
+    let name = GptPartitionName([b'a', 0].repeat(36).try_into().unwrap());
+    assert_eq!(name, "a".repeat(36).as_str());
+}
+
+#[test]
+fn test_partition_name_code_units() {
+    let name: GptPartitionName = "abc".parse().unwrap();
+    assert_eq!(name.code_units().collect::<Vec<_>>(), [0x61, 0x62, 0x63]);
+
+    // Invalid characters are preserved.
+    let units = [0x61, 0xd800, 0x63];
+    let name = GptPartitionName::from_code_units(&units).unwrap();
+    assert_eq!(name.to_string(), "a�c");
+    assert_eq!(name.code_units().collect::<Vec<_>>(), units);
+
+    assert_eq!(
+        GptPartitionName::from_code_units(&[]).unwrap(),
+        GptPartitionName::default()
+    );
+
+    // A null terminator is not required.
+    let name = GptPartitionName::from_code_units(&[0x61; 36]).unwrap();
+    assert_eq!(name.code_units().count(), 36);
+    assert_eq!(
+        GptPartitionName::from_code_units(&[0x61; 37]),
+        Err(GptPartitionNameFromStrError::Length)
+    );
+
+    // No trailing null.
+    let name = GptPartitionName([b'a', 0].repeat(36).try_into().unwrap());
+    assert_eq!(name.code_units().count(), 36);
+}
+
 #[test]
 fn test_partition_entry() {
     check_derives::<GptPartitionEntry>();
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_partuuid_and_partlabel_strings() {
+    let entry = GptPartitionEntry {
+        unique_partition_guid: "37C75FFD-8932-467A-9C56-8CF1F0456B12"
+            .parse()
+            .unwrap(),
+        name: "EFI System".parse().unwrap(),
+        ..Default::default()
+    };
+    assert_eq!(
+        entry.partuuid_string(),
+        "37c75ffd-8932-467a-9c56-8cf1f0456b12"
+    );
+    assert_eq!(entry.partlabel_string(), "EFI System");
+}
@@ -0,0 +1,834 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use gpt_disk_types::{
+    guid, ApplyLayoutPolicy, BlockSize, EspSizeWarning, GptChange,
+    GptDiskLayout, GptDiskRegion, GptDiskRegionKind, GptLayoutChange,
+    GptPartitionAttributes, GptPartitionDescription, GptPartitionEntry,
+    GptPartitionEntryArray, GptPartitionTable, GptPartitionTableError,
+    GptPartitionTableSummary, GptPartitionType, Guid, Lba, LbaLe,
+    LbaRangeInclusive, PartitionIndex, U16Le,
+};
+use serde_json::json;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+const DISK_GUID: Guid = guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870");
+
+fn layout() -> GptDiskLayout {
+    GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap()
+}
+
+fn entry(start: u64, end: u64, unique: Guid) -> GptPartitionEntry {
+    GptPartitionEntry {
+        partition_type_guid: GptPartitionType::BASIC_DATA,
+        unique_partition_guid: unique,
+        starting_lba: LbaLe::from_u64(start),
+        ending_lba: LbaLe::from_u64(end),
+        name: "data".parse().unwrap(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_partition_table_add() {
+    let mut table = GptPartitionTable::<2>::new(layout(), DISK_GUID).unwrap();
+    assert_eq!(table.capacity(), 2);
+    assert_eq!(table.disk_guid(), DISK_GUID);
+    assert_eq!(table.iter_used().count(), 0);
+
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let guid2 = guid!("ccf0994f-f7e0-4e26-a011-843e38aa2eac");
+
+    // Unused entries can't be added.
+    assert_eq!(
+        table.add(GptPartitionEntry::default()),
+        Err(GptPartitionTableError::UnusedEntry)
+    );
+
+    // Invalid ranges.
+    assert_eq!(
+        table.add(entry(100, 99, guid1)),
+        Err(GptPartitionTableError::InvalidLbaRange {
+            index: PartitionIndex::new(0)
+        })
+    );
+    assert_eq!(
+        table.add(entry(33, 100, guid1)),
+        Err(GptPartitionTableError::OutOfBounds {
+            index: PartitionIndex::new(0)
+        })
+    );
+    assert_eq!(
+        table.add(entry(100, 8159, guid1)),
+        Err(GptPartitionTableError::OutOfBounds {
+            index: PartitionIndex::new(0)
+        })
+    );
+
+    assert_eq!(table.add(entry(34, 100, guid1)), Ok(PartitionIndex::new(0)));
+    assert_eq!(
+        table.add(entry(100, 200, guid2)),
+        Err(GptPartitionTableError::Overlap {
+            index: PartitionIndex::new(1),
+            other: PartitionIndex::new(0),
+        })
+    );
+    assert_eq!(
+        table.add(entry(101, 8158, guid2)),
+        Ok(PartitionIndex::new(1))
+    );
+    assert_eq!(
+        table.add(entry(200, 300, guid2)),
+        Err(GptPartitionTableError::Full)
+    );
+    assert_eq!(table.iter_used().count(), 2);
+    assert_eq!(table.validate(), Ok(()));
+
+    // Remove a partition and reuse its slot.
+    let removed = table.remove(PartitionIndex::new(0)).unwrap();
+    assert_eq!({ removed.unique_partition_guid }, guid1);
+    assert!(!table.get(PartitionIndex::new(0)).unwrap().is_used());
+    assert_eq!(table.remove(PartitionIndex::new(0)), None);
+    assert_eq!(table.remove(PartitionIndex::new(2)), None);
+    assert_eq!(table.add(entry(34, 50, guid1)), Ok(PartitionIndex::new(0)));
+}
+
+#[test]
+fn test_partition_table_find() {
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let guid2 = guid!("ccf0994f-f7e0-4e26-a011-843e38aa2eac");
+
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    let esp = GptPartitionEntry {
+        partition_type_guid: GptPartitionType::EFI_SYSTEM,
+        name: "esp".parse().unwrap(),
+        ..entry(34, 100, guid1)
+    };
+    table.add(esp).unwrap();
+    table.add(entry(101, 200, guid2)).unwrap();
+
+    let (index, _) = table.find_by_unique_guid(guid2).unwrap();
+    assert_eq!(index, PartitionIndex::new(1));
+    assert!(table.find_by_unique_guid(DISK_GUID).is_none());
+
+    let (index, _) = table
+        .find_by_partuuid("CCF0994F-F7E0-4E26-A011-843E38AA2EAC")
+        .unwrap();
+    assert_eq!(index, PartitionIndex::new(1));
+    assert!(table
+        .find_by_partuuid("00000000-0000-0000-0000-000000000001")
+        .is_none());
+    assert!(table.find_by_partuuid("not a guid").is_none());
+
+    let (index, _) = table.find_by_type(GptPartitionType::EFI_SYSTEM).unwrap();
+    assert_eq!(index, PartitionIndex::new(0));
+    assert!(table
+        .find_by_type(GptPartitionType::CHROME_OS_KERNEL)
+        .is_none());
+
+    let (index, _) = table.find_by_name("data").unwrap();
+    assert_eq!(index, PartitionIndex::new(1));
+    assert!(table.find_by_name("root").is_none());
+}
+
+#[test]
+fn test_partition_table_validate() {
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let guid2 = guid!("ccf0994f-f7e0-4e26-a011-843e38aa2eac");
+
+    let table = GptPartitionTable::from_entries(
+        layout(),
+        DISK_GUID,
+        [
+            entry(34, 100, guid1),
+            GptPartitionEntry::default(),
+            entry(50, 60, guid2),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        table.validate(),
+        Err(GptPartitionTableError::Overlap {
+            index: PartitionIndex::new(0),
+            other: PartitionIndex::new(2),
+        })
+    );
+
+    // The capacity can't exceed the number of entries in the layout.
+    let layout = GptDiskLayout::new_with_entries(
+        BlockSize::BS_512,
+        8192,
+        Default::default(),
+        1,
+    )
+    .unwrap();
+    assert_eq!(
+        GptPartitionTable::<2>::new(layout, DISK_GUID),
+        Err(GptPartitionTableError::CapacityTooLarge)
+    );
+    assert!(GptPartitionTable::<1>::new(layout, DISK_GUID).is_ok());
+    assert!(GptPartitionTable::<0>::new(layout, DISK_GUID).is_ok());
+}
+
+#[test]
+fn test_partition_table_write_array() {
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let layout = layout();
+
+    let mut table = GptPartitionTable::<2>::new(layout, DISK_GUID).unwrap();
+    table.add(entry(34, 100, guid1)).unwrap();
+
+    let mut storage = [0xff; 128 * 128];
+    let mut array = GptPartitionEntryArray::new(
+        layout.primary_partition_entry_array_layout(),
+        layout.block_size(),
+        &mut storage,
+    )
+    .unwrap();
+    table.write_partition_entry_array(&mut array).unwrap();
+    assert_eq!(array.iter().filter(|e| e.is_used()).count(), 1);
+    assert_eq!(
+        *array.get(PartitionIndex::new(0)).unwrap(),
+        entry(34, 100, guid1)
+    );
+    assert!(array
+        .iter()
+        .skip(1)
+        .all(|e| *e == GptPartitionEntry::default()));
+
+    // An array with fewer entries than the capacity is rejected.
+    let mut small_layout = layout.primary_partition_entry_array_layout();
+    small_layout.num_entries = 1;
+    let mut array = GptPartitionEntryArray::new(
+        small_layout,
+        layout.block_size(),
+        &mut storage,
+    )
+    .unwrap();
+    assert_eq!(
+        table.write_partition_entry_array(&mut array),
+        Err(GptPartitionTableError::ArrayTooSmall)
+    );
+}
+
+#[test]
+fn test_partition_table_apply_layout() {
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    table.add(entry(2048, 3071, guid1)).unwrap();
+
+    let desired = [
+        // Matched by unique GUID, larger than the existing partition.
+        GptPartitionDescription {
+            partition_type: GptPartitionType::BASIC_DATA,
+            unique_partition_guid: guid1,
+            num_blocks: 2048,
+            ..Default::default()
+        },
+        // Matched by type and name.
+        GptPartitionDescription {
+            partition_type: GptPartitionType::EFI_SYSTEM,
+            name: "esp".parse().unwrap(),
+            num_blocks: 1024,
+            ..Default::default()
+        },
+    ];
+    let apply = |table: &mut GptPartitionTable<4>,
+                 desired: &[GptPartitionDescription],
+                 policy| {
+        let mut changes = Vec::new();
+        table
+            .apply_layout(desired, policy, |c| changes.push(c))
+            .map(|()| changes)
+    };
+    let mut policy = ApplyLayoutPolicy {
+        allow_grow: false,
+        alignment_blocks: 2048,
+    };
+
+    // Without growing, the existing partition is left alone and the
+    // new one is created at the next aligned free block.
+    let mut t = table;
+    assert_eq!(
+        apply(&mut t, &desired, policy).unwrap(),
+        [
+            GptLayoutChange::Unchanged(PartitionIndex::new(0)),
+            GptLayoutChange::Created(PartitionIndex::new(1)),
+        ]
+    );
+    assert_eq!(t.entries()[0], table.entries()[0]);
+    let esp = t.entries()[1];
+    assert_eq!({ esp.starting_lba }.to_u64(), 4096);
+    assert_eq!({ esp.ending_lba }.to_u64(), 5119);
+    assert_eq!({ esp.partition_type_guid }, GptPartitionType::EFI_SYSTEM);
+
+    // Applying again changes nothing.
+    let before = t;
+    assert_eq!(
+        apply(&mut t, &desired, policy).unwrap(),
+        [
+            GptLayoutChange::Unchanged(PartitionIndex::new(0)),
+            GptLayoutChange::Unchanged(PartitionIndex::new(1)),
+        ]
+    );
+    assert_eq!(t, before);
+
+    // With growing allowed, the existing partition is grown in place,
+    // and the new partition is placed after it.
+    policy.allow_grow = true;
+    let mut t = table;
+    assert_eq!(
+        apply(&mut t, &desired, policy).unwrap(),
+        [
+            GptLayoutChange::Grown {
+                index: PartitionIndex::new(0),
+                old_num_blocks: 1024
+            },
+            GptLayoutChange::Created(PartitionIndex::new(1)),
+        ]
+    );
+    assert_eq!({ t.entries()[0].ending_lba }.to_u64(), 4095);
+    assert_eq!({ t.entries()[1].starting_lba }.to_u64(), 4096);
+
+    // A partition can't grow into another partition.
+    let mut t = table;
+    t.add(entry(
+        3072,
+        3100,
+        guid!("ccf0994f-f7e0-4e26-a011-843e38aa2eac"),
+    ))
+    .unwrap();
+    assert_eq!(
+        apply(&mut t, &desired[..1], policy).unwrap(),
+        [GptLayoutChange::Unchanged(PartitionIndex::new(0))]
+    );
+    assert_eq!({ t.entries()[0].ending_lba }.to_u64(), 3071);
+
+    // If there isn't enough space, the table is not modified.
+    let too_big = GptPartitionDescription {
+        num_blocks: 8192,
+        ..desired[1]
+    };
+    let mut t = table;
+    assert_eq!(
+        apply(&mut t, &[desired[0], too_big], policy),
+        Err(GptPartitionTableError::NoSpace)
+    );
+    assert_eq!(t, table);
+}
+
+#[test]
+fn test_partition_table_snapshot() {
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let guid2 = guid!("ccf0994f-f7e0-4e26-a011-843e38aa2eac");
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    assert!(!table.is_dirty());
+
+    let empty = table.snapshot();
+    table.add(entry(2048, 3071, guid1)).unwrap();
+    assert!(table.is_dirty());
+    let one = table.snapshot();
+    table.add(entry(3072, 4095, guid2)).unwrap();
+
+    // Undo both changes.
+    table.restore(&one);
+    assert_eq!(table.iter_used().count(), 1);
+    assert!(table.is_dirty());
+    table.restore(&empty);
+    assert_eq!(table.iter_used().count(), 0);
+    assert!(!table.is_dirty());
+
+    // Failed and no-op operations don't make the table dirty.
+    assert!(table.remove(PartitionIndex::new(0)).is_none());
+    assert!(table.add(entry(0, 1, guid1)).is_err());
+    assert!(!table.is_dirty());
+
+    // After saving, restoring an older snapshot leaves the table dirty,
+    // even if the snapshot was clean.
+    table.add(entry(2048, 3071, guid1)).unwrap();
+    table.mark_clean();
+    assert!(!table.is_dirty());
+    table.restore(&empty);
+    assert!(table.is_dirty());
+
+    // Removing a partition makes the table dirty.
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    table.add(entry(2048, 3071, guid1)).unwrap();
+    table.mark_clean();
+    table.remove(PartitionIndex::new(0)).unwrap();
+    assert!(table.is_dirty());
+}
+
+#[test]
+fn test_partition_table_eq() {
+    let hash = |table: &GptPartitionTable<4>| {
+        let mut hasher = DefaultHasher::new();
+        table.hash(&mut hasher);
+        hasher.finish()
+    };
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+
+    // Editing and reverting leaves the table dirty, but equal.
+    let mut edited = table;
+    let index = edited.add(entry(2048, 3071, guid1)).unwrap();
+    assert_ne!(edited, table);
+    edited.remove(index).unwrap();
+    assert!(edited.is_dirty());
+    assert_eq!(edited, table);
+    assert_eq!(hash(&edited), hash(&table));
+
+    // Marking the table clean doesn't change equality either.
+    edited.mark_clean();
+    assert_eq!(edited, table);
+    assert_eq!(hash(&edited), hash(&table));
+
+    // The disk GUID is compared.
+    let other = GptPartitionTable::<4>::new(
+        layout(),
+        guid!("ccf0994f-f7e0-4e26-a011-843e38aa2eac"),
+    )
+    .unwrap();
+    assert_ne!(other, table);
+}
+
+#[test]
+fn test_partition_table_pending_changes() {
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let guid2 = guid!("ccf0994f-f7e0-4e26-a011-843e38aa2eac");
+    let guid3 = guid!("a9f7b3a1-2e1c-4a5e-8f61-3b4e0d2c9f10");
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    table.add(entry(2048, 3071, guid1)).unwrap();
+    table.add(entry(3072, 4095, guid2)).unwrap();
+    let saved = table.snapshot();
+    assert_eq!(table.pending_changes(&saved).count(), 0);
+
+    // Renaming to the same name is not a change.
+    let index0 = PartitionIndex::new(0);
+    let index1 = PartitionIndex::new(1);
+    table.mark_clean();
+    table.set_name(index0, "data".parse().unwrap()).unwrap();
+    assert!(!table.is_dirty());
+    table.set_name(index0, "root".parse().unwrap()).unwrap();
+    assert!(table.is_dirty());
+    assert_eq!(
+        table.set_name(PartitionIndex::new(2), "x".parse().unwrap()),
+        Err(GptPartitionTableError::UnusedEntry)
+    );
+    assert_eq!(
+        table.set_name(PartitionIndex::new(9), "x".parse().unwrap()),
+        Err(GptPartitionTableError::UnusedEntry)
+    );
+
+    // Replace the second partition and add a third.
+    table.remove(index1).unwrap();
+    table.add(entry(3072, 4095, guid3)).unwrap();
+    table.add(entry(4096, 5119, guid2)).unwrap();
+
+    let changes: Vec<_> = table.pending_changes(&saved).collect();
+    assert_eq!(
+        changes,
+        [
+            GptChange::SetName(index0),
+            GptChange::DeletePartition(index1),
+            GptChange::CreatePartition(index1),
+            GptChange::CreatePartition(PartitionIndex::new(2)),
+        ]
+    );
+    assert_eq!(changes[1].index(), index1);
+    assert_eq!({ saved.entries()[1].unique_partition_guid }, guid2);
+
+    // Growing a partition changes its range.
+    table.restore(&saved);
+    table
+        .apply_layout(
+            &[GptPartitionDescription {
+                unique_partition_guid: guid2,
+                num_blocks: 2048,
+                ..Default::default()
+            }],
+            ApplyLayoutPolicy {
+                allow_grow: true,
+                ..Default::default()
+            },
+            |_| {},
+        )
+        .unwrap();
+    assert!(table
+        .pending_changes(&saved)
+        .eq([GptChange::SetRange(index1)]));
+}
+
+#[test]
+fn test_partition_table_move_partition() {
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let guid2 = guid!("ccf0994f-f7e0-4e26-a011-843e38aa2eac");
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    let index = table.add(entry(2048, 3071, guid1)).unwrap();
+    table.add(entry(4096, 5119, guid2)).unwrap();
+    table.mark_clean();
+
+    assert_eq!(
+        table.move_partition(index, Lba(3000)).unwrap(),
+        LbaRangeInclusive::new(Lba(2048), Lba(3071)).unwrap()
+    );
+    assert!(table.is_dirty());
+    let entry0 = table.entries()[0];
+    assert_eq!({ entry0.starting_lba }.to_u64(), 3000);
+    assert_eq!({ entry0.ending_lba }.to_u64(), 4023);
+
+    let before = table;
+    assert_eq!(
+        table.move_partition(index, Lba(4000)),
+        Err(GptPartitionTableError::Overlap {
+            index,
+            other: PartitionIndex::new(1)
+        })
+    );
+    assert_eq!(
+        table.move_partition(index, Lba(8000)),
+        Err(GptPartitionTableError::OutOfBounds { index })
+    );
+    assert_eq!(
+        table.move_partition(index, Lba(u64::MAX)),
+        Err(GptPartitionTableError::OutOfBounds { index })
+    );
+    assert_eq!(
+        table.move_partition(PartitionIndex::new(2), Lba(34)),
+        Err(GptPartitionTableError::UnusedEntry)
+    );
+    assert_eq!(table, before);
+}
+
+#[test]
+fn test_partition_table_add_bios_boot_partition() {
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    let index = table.add_bios_boot_partition(guid1, 2014).unwrap();
+    let entry = table.get(index).unwrap();
+    assert_eq!({ entry.partition_type_guid }, GptPartitionType::BIOS_BOOT);
+    assert_eq!({ entry.unique_partition_guid }, guid1);
+    assert_eq!({ entry.starting_lba }.to_u64(), 34);
+    assert_eq!({ entry.ending_lba }.to_u64(), 2047);
+    assert_eq!(entry.name, "BIOS boot partition");
+
+    // The start of the usable range is already taken.
+    assert!(matches!(
+        table.add_bios_boot_partition(guid1, 1),
+        Err(GptPartitionTableError::Overlap { .. })
+    ));
+
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    assert_eq!(
+        table.add_bios_boot_partition(guid1, 0),
+        Err(GptPartitionTableError::InvalidLbaRange { index })
+    );
+    assert_eq!(
+        table.add_bios_boot_partition(guid1, u64::MAX),
+        Err(GptPartitionTableError::OutOfBounds { index })
+    );
+}
+
+#[test]
+fn test_partition_description_esp() {
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let mib = |n: u64, bs: BlockSize| n * 1024 * 1024 / bs.to_u64();
+
+    let (desc, warning) = GptPartitionDescription::esp(
+        guid1,
+        mib(512, BlockSize::BS_512),
+        BlockSize::BS_512,
+    );
+    assert_eq!(warning, None);
+    assert_eq!(desc.partition_type, GptPartitionType::EFI_SYSTEM);
+    assert_eq!(desc.unique_partition_guid, guid1);
+    assert_eq!(desc.name, "EFI System Partition");
+    assert_eq!(desc.attributes, GptPartitionAttributes::default());
+    assert_eq!(desc.num_blocks, mib(512, BlockSize::BS_512));
+
+    let check =
+        |num_blocks, bs| GptPartitionDescription::esp(guid1, num_blocks, bs).1;
+
+    // 512-byte blocks: FAT32 needs about 32.5 MiB.
+    let bs = BlockSize::BS_512;
+    assert_eq!(check(mib(100, bs), bs), None);
+    assert_eq!(
+        check(mib(100, bs) - 1, bs),
+        Some(EspSizeWarning::BelowRecommended)
+    );
+    assert_eq!(check(66581, bs), Some(EspSizeWarning::BelowRecommended));
+    assert_eq!(check(66580, bs), Some(EspSizeWarning::TooSmallForFat32));
+    assert_eq!(check(0, bs), Some(EspSizeWarning::TooSmallForFat32));
+
+    // 4096-byte blocks: FAT32 needs about 256.6 MiB.
+    let bs = BlockSize::BS_4096;
+    assert_eq!(check(mib(260, bs), bs), None);
+    assert_eq!(
+        check(mib(260, bs) - 1, bs),
+        Some(EspSizeWarning::BelowRecommended)
+    );
+    assert_eq!(check(65685, bs), Some(EspSizeWarning::BelowRecommended));
+    assert_eq!(check(65684, bs), Some(EspSizeWarning::TooSmallForFat32));
+
+    // The description can be used with `apply_layout`.
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    let (desc, _) =
+        GptPartitionDescription::esp(guid1, 1024, BlockSize::BS_512);
+    table
+        .apply_layout(&[desc], ApplyLayoutPolicy::default(), |_| {})
+        .unwrap();
+    let (_, entry) = table.find_by_unique_guid(guid1).unwrap();
+    assert_eq!(entry.name, "EFI System Partition");
+}
+
+#[test]
+fn test_partition_table_summary() {
+    let range = |start, end| LbaRangeInclusive::new(Lba(start), Lba(end));
+
+    // Empty table: the whole usable range is free.
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    assert_eq!(
+        table.summary(2048),
+        GptPartitionTableSummary {
+            num_used_entries: 0,
+            num_free_entries: 4,
+            allocated_bytes: 0,
+            largest_free_range: range(34, 8158),
+            num_misaligned: 0,
+        }
+    );
+
+    table
+        .add(entry(
+            2048,
+            3071,
+            guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12"),
+        ))
+        .unwrap();
+    table
+        .add(entry(
+            3072,
+            3999,
+            guid!("6a3bd1f5-3c4e-4b7f-9a41-2f1fb7a6c0de"),
+        ))
+        .unwrap();
+    table
+        .add(entry(
+            7000,
+            8158,
+            guid!("b0d6f3a4-1a3d-4c8e-9e65-5a2d1c7e4f10"),
+        ))
+        .unwrap();
+    assert_eq!(
+        table.summary(2048),
+        GptPartitionTableSummary {
+            num_used_entries: 3,
+            num_free_entries: 1,
+            allocated_bytes: (1024 + 928 + 1159) * 512,
+            largest_free_range: range(4000, 6999),
+            num_misaligned: 2,
+        }
+    );
+
+    // Zero alignment is treated as one.
+    assert_eq!(table.summary(0).num_misaligned, 0);
+
+    // No free blocks.
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    table
+        .add(entry(
+            34,
+            8158,
+            guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12"),
+        ))
+        .unwrap();
+    assert_eq!(table.summary(1).largest_free_range, None);
+}
+
+#[test]
+fn test_partition_table_regions() {
+    let region = |kind, start, end| GptDiskRegion {
+        kind,
+        range: LbaRangeInclusive::new(Lba(start), Lba(end)).unwrap(),
+    };
+    let guid1 = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    let guid2 = guid!("6a3bd1f5-3c4e-4b7f-9a41-2f1fb7a6c0de");
+
+    // Partitions at the start and end of the usable range, added out
+    // of order.
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    let i0 = table.add(entry(4096, 8158, guid1)).unwrap();
+    let i1 = table.add(entry(34, 2047, guid2)).unwrap();
+    assert_eq!(
+        table.regions().collect::<Vec<_>>(),
+        [
+            region(GptDiskRegionKind::Mbr, 0, 0),
+            region(GptDiskRegionKind::PrimaryHeader, 1, 1),
+            region(GptDiskRegionKind::PrimaryPartitionEntryArray, 2, 33),
+            region(GptDiskRegionKind::Partition(i1), 34, 2047),
+            region(GptDiskRegionKind::Free, 2048, 4095),
+            region(GptDiskRegionKind::Partition(i0), 4096, 8158),
+            region(GptDiskRegionKind::SecondaryPartitionEntryArray, 8159, 8190),
+            region(GptDiskRegionKind::SecondaryHeader, 8191, 8191),
+        ]
+    );
+
+    // Invalid partitions are skipped.
+    let mut entries = [GptPartitionEntry::default(); 4];
+    entries[0] = entry(100, 199, guid1);
+    entries[1] = entry(150, 299, guid2);
+    entries[2] = entry(8000, 8191, guid2);
+    let table =
+        GptPartitionTable::from_entries(layout(), DISK_GUID, entries).unwrap();
+    let regions: Vec<_> = table.regions().collect();
+    assert_eq!(
+        regions[3..6],
+        [
+            region(GptDiskRegionKind::Free, 34, 99),
+            region(
+                GptDiskRegionKind::Partition(PartitionIndex::new(0)),
+                100,
+                199
+            ),
+            region(GptDiskRegionKind::Free, 200, 8158),
+        ]
+    );
+    assert_eq!(regions.len(), 8);
+
+    // Reserved space on either side of the usable range.
+    let layout = layout()
+        .reserve_leading_bytes(512 * 14)
+        .unwrap()
+        .reserve_trailing_bytes(512)
+        .unwrap();
+    let table = GptPartitionTable::<4>::new(layout, DISK_GUID).unwrap();
+    assert_eq!(
+        table.regions().collect::<Vec<_>>(),
+        [
+            region(GptDiskRegionKind::Mbr, 0, 0),
+            region(GptDiskRegionKind::PrimaryHeader, 1, 1),
+            region(GptDiskRegionKind::PrimaryPartitionEntryArray, 2, 33),
+            region(GptDiskRegionKind::Reserved, 34, 47),
+            region(GptDiskRegionKind::Free, 48, 8157),
+            region(GptDiskRegionKind::Reserved, 8158, 8158),
+            region(GptDiskRegionKind::SecondaryPartitionEntryArray, 8159, 8190),
+            region(GptDiskRegionKind::SecondaryHeader, 8191, 8191),
+        ]
+    );
+}
+
+#[test]
+fn test_partition_table_serde() {
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    table
+        .add(entry(
+            2048,
+            4095,
+            guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12"),
+        ))
+        .unwrap();
+
+    let json = serde_json::to_string(&table).unwrap();
+    let loaded: GptPartitionTable<4> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.layout(), table.layout());
+    assert_eq!(loaded.disk_guid(), DISK_GUID);
+    assert_eq!(loaded.entries(), table.entries());
+    assert!(!loaded.is_dirty());
+    assert_eq!(loaded.primary_header(), table.primary_header());
+
+    // The number of entries must match the table's capacity.
+    assert!(serde_json::from_str::<GptPartitionTable<3>>(&json).is_err());
+    assert!(serde_json::from_str::<GptPartitionTable<5>>(&json).is_err());
+
+    // The layout is checked.
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let mut bad = value.clone();
+    bad["layout"]["num_blocks"] = 4.into();
+    assert!(serde_json::from_value::<GptPartitionTable<4>>(bad).is_err());
+    let mut bad = value;
+    bad["layout"]["entry_size"] = 100.into();
+    assert!(serde_json::from_value::<GptPartitionTable<4>>(bad).is_err());
+}
+
+/// Get the set of keys in a JSON object.
+fn object_keys(value: &serde_json::Value) -> Vec<&str> {
+    let mut keys: Vec<_> = value
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    keys
+}
+
+#[test]
+fn test_partition_table_schema() {
+    let schema = schemars::schema_for!(GptPartitionTable<4>);
+    let schema = serde_json::to_value(&schema).unwrap();
+    let definitions = &schema["definitions"];
+
+    assert_eq!(schema["title"], "GptPartitionTable_for_4");
+    assert_eq!(schema["properties"]["entries"]["minItems"], 4);
+    assert_eq!(schema["properties"]["entries"]["maxItems"], 4);
+    assert_eq!(
+        definitions["BlockSize"],
+        json!({"type": "integer", "format": "uint32", "minimum": 512.0})
+    );
+    assert_eq!(definitions["GptPartitionName"], json!({"type": "string"}));
+    assert_eq!(
+        definitions["GptPartitionAttributes"],
+        json!({
+            "type": "array",
+            "items": {
+                "anyOf": [
+                    {
+                        "type": "string",
+                        "enum": [
+                            "required_partition",
+                            "no_block_io_protocol",
+                            "legacy_bios_bootable",
+                        ],
+                    },
+                    {"type": "string", "pattern": "^0[xX][0-9a-fA-F]+$"},
+                ],
+            },
+        })
+    );
+    assert_eq!(
+        definitions["GptPartitionEntry"]["properties"]["partition_type_guid"],
+        json!({"$ref": "#/definitions/Guid"})
+    );
+
+    // The properties in the schema match the serialized output, and
+    // only the optional layout fields are not required.
+    let mut table = GptPartitionTable::<4>::new(layout(), DISK_GUID).unwrap();
+    let mut new_entry = entry(2048, 4095, DISK_GUID);
+    new_entry.attributes.update_required_partition(true);
+    new_entry
+        .attributes
+        .update_type_specific_attributes(U16Le::from_u16(0x8000));
+    table.add(new_entry).unwrap();
+    let value = serde_json::to_value(table).unwrap();
+    for (value, schema) in [
+        (&value, &schema),
+        (&value["layout"], &definitions["GptDiskLayout"]),
+        (&value["entries"][0], &definitions["GptPartitionEntry"]),
+    ] {
+        assert_eq!(object_keys(value), object_keys(&schema["properties"]));
+    }
+    assert_eq!(
+        definitions["GptDiskLayout"]["required"],
+        json!(["block_size", "entry_size", "num_blocks", "num_entries"])
+    );
+    assert_eq!(
+        value["entries"][0]["attributes"],
+        json!(["required_partition", "0x8000000000000000"])
+    );
+}
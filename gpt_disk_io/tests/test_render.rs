@@ -0,0 +1,96 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use gpt_disk_types::{
+    guid, BlockSize, GptDiskLayout, GptPartitionEntry, GptPartitionTable,
+    GptPartitionType, Guid, LbaLe,
+};
+
+const DISK_GUID: Guid = guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870");
+
+fn create_table() -> GptPartitionTable<4> {
+    let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    let mut table = GptPartitionTable::new(layout, DISK_GUID).unwrap();
+    table
+        .add(GptPartitionEntry {
+            partition_type_guid: GptPartitionType::EFI_SYSTEM,
+            unique_partition_guid: guid!(
+                "37c75ffd-8932-467a-9c56-8cf1f0456b12"
+            ),
+            starting_lba: LbaLe::from_u64(34),
+            ending_lba: LbaLe::from_u64(2047),
+            name: "<esp> & \"boot\"".parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+    table
+        .add(GptPartitionEntry {
+            partition_type_guid: GptPartitionType::BASIC_DATA,
+            unique_partition_guid: guid!(
+                "6a3bd1f5-3c4e-4b7f-9a41-2f1fb7a6c0de"
+            ),
+            starting_lba: LbaLe::from_u64(4096),
+            ending_lba: LbaLe::from_u64(8158),
+            ..Default::default()
+        })
+        .unwrap();
+    table
+}
+
+#[test]
+fn test_render_text() {
+    let table = create_table();
+
+    let mut bar = String::new();
+    table.render_text(16, &mut bar).unwrap();
+    assert_eq!(bar, "M111....22222222");
+
+    // Small regions appear when there is a column per block.
+    let mut bar = String::new();
+    table.render_text(8192, &mut bar).unwrap();
+    assert_eq!(bar.len(), 8192);
+    assert_eq!(&bar[..3], "MHA");
+    assert_eq!(&bar[33..35], "A1");
+    assert_eq!(&bar[8158..8160], "2A");
+    assert_eq!(&bar[8190..], "AH");
+
+    let mut bar = String::new();
+    table.render_text(0, &mut bar).unwrap();
+    assert_eq!(bar, "");
+}
+
+#[test]
+fn test_render_svg() {
+    let table = create_table();
+
+    let mut svg = String::new();
+    table.render_svg(8192, 10, &mut svg).unwrap();
+    let lines: Vec<_> = svg.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"8192\" height=\"10\" viewBox=\"0 0 8192 10\">",
+            "<rect x=\"0\" y=\"0\" width=\"1\" height=\"10\" fill=\"#555555\" stroke=\"#000000\"><title>MBR: 0..=0</title></rect>",
+            "<rect x=\"1\" y=\"0\" width=\"1\" height=\"10\" fill=\"#555555\" stroke=\"#000000\"><title>primary header: 1..=1</title></rect>",
+            "<rect x=\"2\" y=\"0\" width=\"32\" height=\"10\" fill=\"#999999\" stroke=\"#000000\"><title>primary partition entry array: 2..=33</title></rect>",
+            "<rect x=\"34\" y=\"0\" width=\"2014\" height=\"10\" fill=\"#4e79a7\" stroke=\"#000000\"><title>partition 1 \"&lt;esp&gt; &amp; &quot;boot&quot;\": 34..=2047</title></rect>",
+            "<rect x=\"2048\" y=\"0\" width=\"2048\" height=\"10\" fill=\"#ffffff\" stroke=\"#000000\"><title>free: 2048..=4095</title></rect>",
+            "<rect x=\"4096\" y=\"0\" width=\"4063\" height=\"10\" fill=\"#f28e2b\" stroke=\"#000000\"><title>partition 2: 4096..=8158</title></rect>",
+            "<rect x=\"8159\" y=\"0\" width=\"32\" height=\"10\" fill=\"#999999\" stroke=\"#000000\"><title>secondary partition entry array: 8159..=8190</title></rect>",
+            "<rect x=\"8191\" y=\"0\" width=\"1\" height=\"10\" fill=\"#555555\" stroke=\"#000000\"><title>secondary header: 8191..=8191</title></rect>",
+            "</svg>",
+        ]
+    );
+
+    // Regions smaller than a pixel are still drawn.
+    let mut svg = String::new();
+    table.render_svg(100, 10, &mut svg).unwrap();
+    assert!(svg.contains(
+        "<rect x=\"0\" y=\"0\" width=\"1\" height=\"10\" fill=\"#555555\""
+    ));
+}
@@ -0,0 +1,116 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "test_support")]
+
+use gpt_disk_io::test_support::{
+    create_corrupted_test_disk, create_test_disk, test_partition_entries,
+    Corruption, TEST_DISK_GUID, TEST_DISK_NUM_BLOCKS,
+};
+use gpt_disk_io::{BlockIo, BlockIoAdapter, Disk};
+use gpt_disk_types::{BlockSize, GptHeader, GptPartitionEntryArray};
+
+/// Read the primary or secondary header and check whether its CRC32
+/// and partition entry array CRC32 are valid.
+fn check_gpt(data: &mut [u8], bs: BlockSize, secondary: bool) -> (bool, bool) {
+    let mut disk = Disk::new(BlockIoAdapter::new(data, bs)).unwrap();
+    let mut block_buf = vec![0; bs.to_usize().unwrap()];
+    let header: GptHeader = if secondary {
+        disk.read_secondary_gpt_header(&mut block_buf)
+    } else {
+        disk.read_primary_gpt_header(&mut block_buf)
+    }
+    .unwrap();
+    assert!(header.is_signature_valid());
+    assert_eq!({ header.disk_guid }, TEST_DISK_GUID);
+
+    let layout = header.get_partition_entry_array_layout().unwrap();
+    let mut storage =
+        vec![0; layout.num_bytes_rounded_to_block_as_usize(bs).unwrap()];
+    let array: GptPartitionEntryArray = disk
+        .read_gpt_partition_entry_array(layout, &mut storage)
+        .unwrap();
+    (
+        header.is_header_crc32_valid(),
+        array.calculate_crc32() == header.partition_entry_array_crc32,
+    )
+}
+
+#[test]
+fn test_create_test_disk() {
+    for bs in [BlockSize::BS_512, BlockSize::BS_4096] {
+        let mut data = create_test_disk(bs);
+        assert_eq!(data.len() as u64, TEST_DISK_NUM_BLOCKS * bs.to_u64());
+        assert_eq!(data, create_test_disk(bs));
+
+        assert_eq!(check_gpt(&mut data, bs, false), (true, true));
+        assert_eq!(check_gpt(&mut data, bs, true), (true, true));
+
+        let mut disk =
+            Disk::new(BlockIoAdapter::new(data.as_mut_slice(), bs)).unwrap();
+        let mut block_buf = vec![0; bs.to_usize().unwrap()];
+        let header = disk.read_primary_gpt_header(&mut block_buf).unwrap();
+        let layout = header.get_partition_entry_array_layout().unwrap();
+        let entries: Vec<_> = disk
+            .gpt_partition_entry_array_iter(layout, &mut block_buf)
+            .unwrap()
+            .map(Result::unwrap)
+            .filter(|entry| entry.is_used())
+            .collect();
+        assert_eq!(entries, test_partition_entries());
+    }
+}
+
+#[test]
+fn test_create_corrupted_test_disk() {
+    let bs = BlockSize::BS_512;
+    let check = |corruption| {
+        let mut data = create_corrupted_test_disk(bs, corruption);
+        (
+            check_gpt(&mut data, bs, false),
+            check_gpt(&mut data, bs, true),
+        )
+    };
+
+    assert_eq!(
+        check(Corruption::PrimaryHeaderCrc32),
+        ((false, true), (true, true))
+    );
+    assert_eq!(
+        check(Corruption::SecondaryHeaderCrc32),
+        ((true, true), (false, true))
+    );
+    assert_eq!(
+        check(Corruption::PrimaryPartitionEntryArrayCrc32),
+        ((true, false), (true, true))
+    );
+    assert_eq!(
+        check(Corruption::OverlappingPartitions),
+        ((true, true), (true, true))
+    );
+
+    let mut data =
+        create_corrupted_test_disk(bs, Corruption::OverlappingPartitions);
+    let mut disk =
+        Disk::new(BlockIoAdapter::new(data.as_mut_slice(), bs)).unwrap();
+    let mut block_buf = vec![0; 512];
+    let header = disk.read_primary_gpt_header(&mut block_buf).unwrap();
+    let layout = header.get_partition_entry_array_layout().unwrap();
+    let entries: Vec<_> = disk
+        .gpt_partition_entry_array_iter(layout, &mut block_buf)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert!(entries[1].starting_lba.to_u64() <= entries[0].ending_lba.to_u64());
+
+    let mut data =
+        create_corrupted_test_disk(bs, Corruption::TruncatedSecondary);
+    let mut bio = BlockIoAdapter::new(data.as_mut_slice(), bs);
+    assert!(bio.num_blocks().unwrap() < TEST_DISK_NUM_BLOCKS);
+    assert_eq!(check_gpt(&mut data, bs, false), (true, true));
+}
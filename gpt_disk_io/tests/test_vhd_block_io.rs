@@ -0,0 +1,131 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "std")]
+
+use gpt_disk_io::FixedVhdBlockIo;
+use gpt_disk_types::{BlockSize, Guid};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+
+/// Storage that only keeps the bytes at or after `base`, so that a
+/// footer can be written at the end of a very large image.
+struct SparseTail {
+    base: u64,
+    pos: u64,
+    tail: Vec<u8>,
+}
+
+impl SparseTail {
+    fn new(base: u64) -> Self {
+        Self {
+            base,
+            pos: 0,
+            tail: Vec::new(),
+        }
+    }
+
+    fn tail_offset(&self) -> io::Result<usize> {
+        self.pos
+            .checked_sub(self.base)
+            .and_then(|offset| usize::try_from(offset).ok())
+            .ok_or_else(|| io::ErrorKind::Unsupported.into())
+    }
+}
+
+impl Read for SparseTail {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let offset = self.tail_offset()?;
+        let n = (&self.tail[offset.min(self.tail.len())..]).read(buf)?;
+        self.pos += u64::try_from(n).unwrap();
+        Ok(n)
+    }
+}
+
+impl Write for SparseTail {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let offset = self.tail_offset()?;
+        let end = offset + buf.len();
+        if self.tail.len() < end {
+            self.tail.resize(end, 0);
+        }
+        self.tail[offset..end].copy_from_slice(buf);
+        self.pos += u64::try_from(buf.len()).unwrap();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for SparseTail {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = match pos {
+            SeekFrom::Start(pos) => pos,
+            SeekFrom::End(delta) => (self.base
+                + u64::try_from(self.tail.len()).unwrap())
+            .checked_add_signed(delta)
+            .unwrap(),
+            SeekFrom::Current(delta) => {
+                self.pos.checked_add_signed(delta).unwrap()
+            }
+        };
+        Ok(self.pos)
+    }
+}
+
+/// Get the CHS geometry bytes from a footer.
+fn footer_geometry(footer: &[u8]) -> [u8; 4] {
+    footer[56..60].try_into().unwrap()
+}
+
+#[test]
+fn test_vhd_disk_geometry() {
+    // 4MiB: 8192 sectors.
+    let num_bytes = 4 * 1024 * 1024;
+    let mut image = Cursor::new(Vec::new());
+    FixedVhdBlockIo::create(
+        &mut image,
+        BlockSize::BS_512,
+        num_bytes,
+        Guid::from_bytes([0x12; 16]),
+    )
+    .unwrap();
+    let footer = &image.get_ref()[usize::try_from(num_bytes).unwrap()..];
+    assert_eq!(footer_geometry(footer), [0, 120, 4, 17]);
+
+    // Maximum size.
+    let num_bytes = 1 << 63;
+    let mut image = SparseTail::new(num_bytes);
+    FixedVhdBlockIo::create(
+        &mut image,
+        BlockSize::BS_512,
+        num_bytes,
+        Guid::from_bytes([0x12; 16]),
+    )
+    .unwrap();
+    assert_eq!(footer_geometry(&image.tail), [0xff, 0xff, 16, 255]);
+}
+
+#[test]
+fn test_vhd_footer_checksum() {
+    let num_bytes = 1024 * 1024;
+    let mut image = Cursor::new(Vec::new());
+    FixedVhdBlockIo::create(
+        &mut image,
+        BlockSize::BS_512,
+        num_bytes,
+        Guid::from_bytes([0x12; 16]),
+    )
+    .unwrap();
+    assert!(FixedVhdBlockIo::open(&mut image, BlockSize::BS_512).is_ok());
+
+    // Any change to the footer invalidates the checksum.
+    image.get_mut()[usize::try_from(num_bytes).unwrap() + 100] = 1;
+    assert!(FixedVhdBlockIo::open(&mut image, BlockSize::BS_512).is_err());
+}
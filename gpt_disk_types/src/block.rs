@@ -8,6 +8,7 @@
 
 use crate::U64Le;
 use core::fmt::{self, Display, Formatter};
+use core::iter::FusedIterator;
 use core::num::{NonZeroU32, TryFromIntError};
 use core::ops::RangeInclusive;
 
@@ -17,13 +18,19 @@ use bytemuck::{Pod, Zeroable};
 /// Logical block address.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct Lba(pub u64);
 
 impl Lba {
     /// Convert to a plain [`u64`].
     #[must_use]
-    pub fn to_u64(self) -> u64 {
+    pub const fn to_u64(self) -> u64 {
         self.0
     }
 }
@@ -57,6 +64,12 @@ impl From<LbaLe> for Lba {
 /// Logical block address stored as a [`U64Le`].
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct LbaLe(pub U64Le);
 
@@ -89,6 +102,12 @@ impl From<Lba> for LbaLe {
 /// Inclusive range of logical block addresses.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(C)]
 pub struct LbaRangeInclusive {
     start: Lba,
@@ -212,10 +231,32 @@ impl LbaRangeInclusive {
     /// assert_eq!(r.num_blocks(), 2);
     /// ```
     #[must_use]
-    pub fn num_blocks(self) -> u64 {
+    pub const fn num_blocks(self) -> u64 {
         // Add one here since the range is inclusive.
         self.end().to_u64() - self.start.to_u64() + 1
     }
+
+    /// Get an iterator over each [`Lba`] in the range.
+    ///
+    /// `LbaRangeInclusive` also implements [`IntoIterator`], so the
+    /// range can be used directly in a `for` loop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{Lba, LbaRangeInclusive};
+    ///
+    /// let r = LbaRangeInclusive::new(Lba(2), Lba(4)).unwrap();
+    /// assert_eq!(r.iter().collect::<Vec<_>>(), [Lba(2), Lba(3), Lba(4)]);
+    ///
+    /// for lba in r {
+    ///     assert!(lba >= Lba(2) && lba <= Lba(4));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn iter(self) -> LbaRangeInclusiveIter {
+        LbaRangeInclusiveIter(self.start.0..=self.end.0)
+    }
 }
 
 impl Display for LbaRangeInclusive {
@@ -224,6 +265,44 @@ impl Display for LbaRangeInclusive {
     }
 }
 
+impl IntoIterator for LbaRangeInclusive {
+    type Item = Lba;
+    type IntoIter = LbaRangeInclusiveIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over each [`Lba`] in an [`LbaRangeInclusive`]. See
+/// [`LbaRangeInclusive::iter`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LbaRangeInclusiveIter(RangeInclusive<u64>);
+
+impl Iterator for LbaRangeInclusiveIter {
+    type Item = Lba;
+
+    fn next(&mut self) -> Option<Lba> {
+        self.0.next().map(Lba)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Lba> {
+        self.0.nth(n).map(Lba)
+    }
+}
+
+impl DoubleEndedIterator for LbaRangeInclusiveIter {
+    fn next_back(&mut self) -> Option<Lba> {
+        self.0.next_back().map(Lba)
+    }
+}
+
+impl FusedIterator for LbaRangeInclusiveIter {}
+
 /// Size of a block in bytes.
 ///
 /// This type enforces some restrictions on the block size: it must be
@@ -236,6 +315,11 @@ impl Display for LbaRangeInclusive {
 ///
 /// [`MasterBootRecord`]: crate::MasterBootRecord
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(try_from = "u32", into = "u32")
+)]
 #[repr(transparent)]
 pub struct BlockSize(NonZeroU32);
 
@@ -247,6 +331,20 @@ impl BlockSize {
         unreachable!()
     });
 
+    /// 1024-byte block size.
+    pub const BS_1024: Self = Self(if let Some(nz) = NonZeroU32::new(1024) {
+        nz
+    } else {
+        unreachable!()
+    });
+
+    /// 2048-byte block size, commonly used by optical media.
+    pub const BS_2048: Self = Self(if let Some(nz) = NonZeroU32::new(2048) {
+        nz
+    } else {
+        unreachable!()
+    });
+
     /// 4096-byte block size.
     pub const BS_4096: Self = Self(if let Some(nz) = NonZeroU32::new(4096) {
         nz
@@ -254,6 +352,13 @@ impl BlockSize {
         unreachable!()
     });
 
+    /// 8192-byte block size.
+    pub const BS_8192: Self = Self(if let Some(nz) = NonZeroU32::new(8192) {
+        nz
+    } else {
+        unreachable!()
+    });
+
     /// Create a `BlockSize`.
     #[must_use]
     pub const fn new(num_bytes: u32) -> Option<Self> {
@@ -268,6 +373,55 @@ impl BlockSize {
         }
     }
 
+    /// Create a `BlockSize`, with stricter validation than [`new`].
+    ///
+    /// In addition to the restrictions of [`new`], the size must be a
+    /// power of two and at most 64 KiB. This rejects sizes that no real
+    /// hardware uses, such as 767, which helps catch configuration
+    /// mistakes.
+    ///
+    /// [`new`]: Self::new
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::BlockSize;
+    ///
+    /// assert_eq!(BlockSize::new_strict(4096), Some(BlockSize::BS_4096));
+    /// assert_eq!(BlockSize::new_strict(767), None);
+    /// assert_eq!(BlockSize::new_strict(128 * 1024), None);
+    /// ```
+    #[must_use]
+    pub const fn new_strict(num_bytes: u32) -> Option<Self> {
+        if num_bytes.is_power_of_two() && num_bytes <= 64 * 1024 {
+            Self::new(num_bytes)
+        } else {
+            None
+        }
+    }
+
+    /// Create a `BlockSize` of `1 << shift` bytes.
+    ///
+    /// Returns `None` if the size would be less than 512 bytes or not
+    /// fit in a [`u32`], i.e. if `shift` is not in the range `9..=31`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::BlockSize;
+    ///
+    /// assert_eq!(BlockSize::new_power_of_two(12), Some(BlockSize::BS_4096));
+    /// assert_eq!(BlockSize::new_power_of_two(8), None);
+    /// ```
+    #[must_use]
+    pub const fn new_power_of_two(shift: u32) -> Option<Self> {
+        if let Some(num_bytes) = 1u32.checked_shl(shift) {
+            Self::new(num_bytes)
+        } else {
+            None
+        }
+    }
+
     /// Create a `BlockSize`.
     #[must_use]
     pub fn from_usize(num_bytes: usize) -> Option<Self> {
@@ -287,6 +441,28 @@ impl BlockSize {
         self.0.get() as u64
     }
 
+    /// Get the base-2 logarithm of the size, if the size is a power of
+    /// two. This allows converting between bytes and blocks with shifts
+    /// rather than division.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::BlockSize;
+    ///
+    /// assert_eq!(BlockSize::BS_512.shift(), Some(9));
+    /// assert_eq!(BlockSize::new(520).unwrap().shift(), None);
+    /// ```
+    #[must_use]
+    pub const fn shift(self) -> Option<u32> {
+        let num_bytes = self.0.get();
+        if num_bytes.is_power_of_two() {
+            Some(num_bytes.trailing_zeros())
+        } else {
+            None
+        }
+    }
+
     /// Get the size in bytes as a [`usize`].
     #[must_use]
     pub fn to_usize(self) -> Option<usize> {
@@ -333,3 +509,68 @@ impl Display for BlockSize {
         write!(f, "{}", self.0)
     }
 }
+
+impl From<BlockSize> for u32 {
+    fn from(block_size: BlockSize) -> Self {
+        block_size.to_u32()
+    }
+}
+
+impl TryFrom<u32> for BlockSize {
+    type Error = BlockSizeError;
+
+    fn try_from(num_bytes: u32) -> Result<Self, Self::Error> {
+        Self::new(num_bytes).ok_or(BlockSizeError::TooSmall(num_bytes.into()))
+    }
+}
+
+impl TryFrom<u64> for BlockSize {
+    type Error = BlockSizeError;
+
+    fn try_from(num_bytes: u64) -> Result<Self, Self::Error> {
+        let num_bytes_u32 = u32::try_from(num_bytes)
+            .map_err(|_| BlockSizeError::TooLarge(num_bytes))?;
+        Self::try_from(num_bytes_u32)
+    }
+}
+
+impl TryFrom<usize> for BlockSize {
+    type Error = BlockSizeError;
+
+    fn try_from(num_bytes: usize) -> Result<Self, Self::Error> {
+        // A `usize` too large for a `u64` is certainly too large for a
+        // `u32`, so saturating still produces the right error.
+        let num_bytes = u64::try_from(num_bytes).unwrap_or(u64::MAX);
+        Self::try_from(num_bytes)
+    }
+}
+
+/// Error type for converting an integer to a [`BlockSize`].
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum BlockSizeError {
+    /// The size is less than the 512-byte minimum.
+    TooSmall(u64),
+
+    /// The size does not fit in a [`u32`].
+    TooLarge(u64),
+}
+
+impl Display for BlockSizeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooSmall(n) => write!(
+                f,
+                "block size of {n} bytes is less than the minimum of 512 bytes"
+            ),
+            Self::TooLarge(n) => write!(
+                f,
+                "block size of {n} bytes is too large (must fit in a u32)"
+            ),
+        }
+    }
+}
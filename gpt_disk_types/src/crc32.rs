@@ -15,6 +15,12 @@ use bytemuck::{Pod, Zeroable};
 /// 32-bit CRC (cyclic redundancy check).
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct Crc32(pub U32Le);
 
@@ -44,6 +50,32 @@ impl Crc32 {
     ///
     /// [Catalogue of parametrised CRC algorithms]: https://reveng.sourceforge.io/crc-catalogue/17plus.htm
     pub const ALGORITHM: crc::Algorithm<u32> = crc::CRC_32_ISO_HDLC;
+
+    /// Calculate the CRC32 checksum of `bytes` using [`ALGORITHM`].
+    ///
+    /// [`ALGORITHM`]: Self::ALGORITHM
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{Crc32, U32Le};
+    ///
+    /// assert_eq!(
+    ///     Crc32::of(b"123456789"),
+    ///     Crc32(U32Le::from_u32(0xcbf43926))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn of(bytes: &[u8]) -> Self {
+        let crc = crc::Crc::<u32>::new(&Self::ALGORITHM);
+        Self(U32Le(crc.checksum(bytes).to_le_bytes()))
+    }
+
+    /// Check if the CRC32 checksum of `bytes` is `expected`.
+    #[must_use]
+    pub fn verify(bytes: &[u8], expected: Self) -> bool {
+        Self::of(bytes) == expected
+    }
 }
 
 impl Display for Crc32 {
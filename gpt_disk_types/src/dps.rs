@@ -0,0 +1,155 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Helpers for the [Discoverable Partitions Specification][dps] (DPS).
+//!
+//! [dps]: https://uapi-group.org/specifications/specs/discoverable_partitions_specification/
+
+use crate::{guid, GptPartitionAttributes, GptPartitionType};
+
+/// CPU architecture, used to look up architecture-specific partition
+/// types in the Discoverable Partitions Specification.
+// This lint incorrectly says that "AArch64" and "LoongArch" should be
+// in backticks.
+#[allow(clippy::doc_markdown)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Arch {
+    /// 32-bit x86.
+    X86,
+
+    /// 64-bit x86 (amd64).
+    X86_64,
+
+    /// 32-bit ARM.
+    Arm,
+
+    /// 64-bit ARM (AArch64).
+    Aarch64,
+
+    /// 32-bit RISC-V.
+    RiscV32,
+
+    /// 64-bit RISC-V.
+    RiscV64,
+
+    /// 64-bit LoongArch.
+    LoongArch64,
+}
+
+impl GptPartitionType {
+    /// Get the DPS partition type for the root filesystem (`/`) of
+    /// `arch`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{guid, Arch, GptPartitionType};
+    ///
+    /// assert_eq!(
+    ///     GptPartitionType::root_for_arch(Arch::X86_64),
+    ///     guid!("4f68bce3-e8cd-4db1-96e7-fbcaf984b709")
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn root_for_arch(arch: Arch) -> Self {
+        Self(match arch {
+            Arch::X86 => guid!("44479540-f297-41b2-9af7-d131d5f0458a"),
+            Arch::X86_64 => guid!("4f68bce3-e8cd-4db1-96e7-fbcaf984b709"),
+            Arch::Arm => guid!("69dad710-2ce4-4e3c-b16c-21a1d49abed3"),
+            Arch::Aarch64 => guid!("b921b045-1df0-41c3-af44-4c6f280d3fae"),
+            Arch::RiscV32 => guid!("60d5a7fe-8e7d-435c-b714-3dd8162144e1"),
+            Arch::RiscV64 => guid!("72ec70a6-cf74-40e6-bd49-4bda08e8f224"),
+            Arch::LoongArch64 => guid!("77055800-792c-4f94-b39a-98c91b762bb6"),
+        })
+    }
+
+    /// Get the DPS partition type for the `/usr` filesystem of `arch`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{guid, Arch, GptPartitionType};
+    ///
+    /// assert_eq!(
+    ///     GptPartitionType::usr_for_arch(Arch::X86_64),
+    ///     guid!("8484680c-9521-48c6-9c11-b0720656f69e")
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn usr_for_arch(arch: Arch) -> Self {
+        Self(match arch {
+            Arch::X86 => guid!("75250d76-8cc6-458e-bd66-bd47cc81a812"),
+            Arch::X86_64 => guid!("8484680c-9521-48c6-9c11-b0720656f69e"),
+            Arch::Arm => guid!("7d0359a3-02b3-4f0a-865c-654403e70625"),
+            Arch::Aarch64 => guid!("b0e01050-ee5f-4390-949a-9101b17104e9"),
+            Arch::RiscV32 => guid!("b933fb22-5c3f-4f91-af90-e2bb0fa50702"),
+            Arch::RiscV64 => guid!("beaec34b-8442-439b-a40b-984381ed097d"),
+            Arch::LoongArch64 => guid!("e611c702-575c-4cbe-9a46-434fa0bf7e3f"),
+        })
+    }
+}
+
+impl GptPartitionAttributes {
+    /// If set, bit `59` tells the DPS auto-mounter to grow the
+    /// filesystem to fill the partition. Only meaningful for DPS
+    /// partition types.
+    pub const DPS_GROWFS_BIT: u8 = 59;
+
+    /// If set, bit `60` tells the DPS auto-mounter to mount the
+    /// partition read-only. Only meaningful for DPS partition types.
+    pub const DPS_READ_ONLY_BIT: u8 = 60;
+
+    /// If set, bit `63` tells the DPS auto-mounter not to mount the
+    /// partition. Only meaningful for DPS partition types.
+    pub const DPS_NO_AUTO_BIT: u8 = 63;
+
+    /// Get the [`DPS_GROWFS_BIT`] attribute value.
+    ///
+    /// [`DPS_GROWFS_BIT`]: Self::DPS_GROWFS_BIT
+    #[must_use]
+    pub fn dps_growfs(self) -> bool {
+        self.get_bit(Self::DPS_GROWFS_BIT)
+    }
+
+    /// Update the [`DPS_GROWFS_BIT`] attribute value.
+    ///
+    /// [`DPS_GROWFS_BIT`]: Self::DPS_GROWFS_BIT
+    pub fn update_dps_growfs(&mut self, growfs: bool) {
+        self.set_bit(Self::DPS_GROWFS_BIT, growfs);
+    }
+
+    /// Get the [`DPS_READ_ONLY_BIT`] attribute value.
+    ///
+    /// [`DPS_READ_ONLY_BIT`]: Self::DPS_READ_ONLY_BIT
+    #[must_use]
+    pub fn dps_read_only(self) -> bool {
+        self.get_bit(Self::DPS_READ_ONLY_BIT)
+    }
+
+    /// Update the [`DPS_READ_ONLY_BIT`] attribute value.
+    ///
+    /// [`DPS_READ_ONLY_BIT`]: Self::DPS_READ_ONLY_BIT
+    pub fn update_dps_read_only(&mut self, read_only: bool) {
+        self.set_bit(Self::DPS_READ_ONLY_BIT, read_only);
+    }
+
+    /// Get the [`DPS_NO_AUTO_BIT`] attribute value.
+    ///
+    /// [`DPS_NO_AUTO_BIT`]: Self::DPS_NO_AUTO_BIT
+    #[must_use]
+    pub fn dps_no_auto(self) -> bool {
+        self.get_bit(Self::DPS_NO_AUTO_BIT)
+    }
+
+    /// Update the [`DPS_NO_AUTO_BIT`] attribute value.
+    ///
+    /// [`DPS_NO_AUTO_BIT`]: Self::DPS_NO_AUTO_BIT
+    pub fn update_dps_no_auto(&mut self, no_auto: bool) {
+        self.set_bit(Self::DPS_NO_AUTO_BIT, no_auto);
+    }
+}
@@ -7,9 +7,9 @@
 // except according to those terms.
 
 use crate::{
-    Crc32, GptPartitionEntry, GptPartitionEntryArrayLayout,
-    GptPartitionEntrySize, GptPartitionEntrySizeError, Guid, LbaLe, U32Le,
-    U64Le,
+    BlockSize, Crc32, GptPartitionEntry, GptPartitionEntryArray,
+    GptPartitionEntryArrayLayout, GptPartitionEntrySize,
+    GptPartitionEntrySizeError, Guid, Lba, LbaLe, U32Le, U64Le,
 };
 use core::fmt::{self, Display, Formatter};
 use core::mem;
@@ -17,9 +17,18 @@ use core::mem;
 #[cfg(feature = "bytemuck")]
 use bytemuck::{bytes_of, Pod, Zeroable};
 
+#[cfg(all(feature = "alloc", feature = "bytemuck"))]
+use alloc::vec::Vec;
+
 /// GPT header signature.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct GptHeaderSignature(pub U64Le);
 
@@ -41,6 +50,23 @@ impl GptHeaderSignature {
     pub const EFI_COMPATIBLE_PARTITION_TABLE_HEADER: Self =
         Self(U64Le(*b"EFI PART"));
 
+    /// Create a signature from a [`u64`] with the host's endianness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::GptHeaderSignature;
+    ///
+    /// assert_eq!(
+    ///     GptHeaderSignature::from_u64(0x5452_4150_2049_4645),
+    ///     GptHeaderSignature::EFI_COMPATIBLE_PARTITION_TABLE_HEADER
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn from_u64(v: u64) -> Self {
+        Self(U64Le::from_u64(v))
+    }
+
     /// Convert to [`u64`] with the host's endianness.
     #[must_use]
     pub const fn to_u64(self) -> u64 {
@@ -57,12 +83,37 @@ impl Default for GptHeaderSignature {
 /// GPT header revision.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct GptHeaderRevision(pub U32Le);
 
 impl GptHeaderRevision {
-    /// Version 1.0. This is the only valid revision.
-    pub const VERSION_1_0: Self = Self(U32Le::from_u32(0x0001_0000));
+    /// Version 1.0. This is the only revision defined by the UEFI
+    /// Specification so far.
+    pub const VERSION_1_0: Self = Self::new(1, 0);
+
+    /// Create a revision from its major and minor parts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::GptHeaderRevision;
+    ///
+    /// let rev = GptHeaderRevision::new(1, 2);
+    /// assert_eq!(rev.major(), 1);
+    /// assert_eq!(rev.minor(), 2);
+    /// assert_eq!(rev.to_string(), "1.2");
+    /// ```
+    #[allow(clippy::as_conversions)]
+    #[must_use]
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self(U32Le::from_u32(((major as u32) << 16) | minor as u32))
+    }
 
     /// Get the major part of the version.
     #[allow(clippy::missing_panics_doc)]
@@ -77,6 +128,20 @@ impl GptHeaderRevision {
     pub fn minor(self) -> u16 {
         u16::from_le_bytes(self.0 .0[0..2].try_into().unwrap())
     }
+
+    /// Check whether a header with this revision can be read by code
+    /// written for [`VERSION_1_0`].
+    ///
+    /// A new minor revision may only add fields in the reserved space
+    /// after the fields defined in 1.0, so any revision with a major
+    /// version of 1 is compatible. A different major version means the
+    /// header format may have changed in incompatible ways.
+    ///
+    /// [`VERSION_1_0`]: Self::VERSION_1_0
+    #[must_use]
+    pub fn is_compatible(self) -> bool {
+        self.major() == Self::VERSION_1_0.major()
+    }
 }
 
 impl Default for GptHeaderRevision {
@@ -86,8 +151,9 @@ impl Default for GptHeaderRevision {
 }
 
 impl Display for GptHeaderRevision {
+    /// Format as `major.minor`, e.g. `1.0`.
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:#08x}", self.0)
+        write!(f, "{}.{}", self.major(), self.minor())
     }
 }
 
@@ -100,8 +166,10 @@ pub struct GptHeader {
     /// [`GptHeaderSignature::EFI_COMPATIBLE_PARTITION_TABLE_HEADER`].
     pub signature: GptHeaderSignature,
 
-    /// Revision number for the header. In a valid header this must be
-    /// [`GptHeaderRevision::VERSION_1_0`].
+    /// Revision number for the header. This is normally
+    /// [`GptHeaderRevision::VERSION_1_0`]; see
+    /// [`GptHeaderRevision::is_compatible`] for accepting later minor
+    /// revisions.
     pub revision: GptHeaderRevision,
 
     /// Size of the header in bytes. In a valid header this must be
@@ -147,7 +215,20 @@ pub struct GptHeader {
     pub partition_entry_array_crc32: Crc32,
 }
 
+// The derive macros can't be used because the struct is packed.
+#[cfg(feature = "rkyv")]
+impl_rkyv_archive_as_self!(GptHeader);
+
 impl GptHeader {
+    /// Get the header's [`revision`] as a `(major, minor)` tuple.
+    ///
+    /// [`revision`]: Self::revision
+    #[must_use]
+    pub fn revision_tuple(&self) -> (u16, u16) {
+        let revision = self.revision;
+        (revision.major(), revision.minor())
+    }
+
     /// Check if the header's signature matches
     /// [`GptHeaderSignature::EFI_COMPATIBLE_PARTITION_TABLE_HEADER`].
     #[must_use]
@@ -161,31 +242,116 @@ impl GptHeader {
     #[cfg(feature = "bytemuck")]
     #[must_use]
     pub fn calculate_header_crc32(&self) -> Crc32 {
+        // The checksum is calculated with the `header_crc32` field
+        // zeroed.
+        let header = Self {
+            header_crc32: Crc32::default(),
+            ..*self
+        };
+        Crc32::of(bytes_of(&header))
+    }
+
+    /// Calculate the header's CRC32 checksum, including `tail`.
+    ///
+    /// If [`header_size`] is larger than the `GptHeader` struct, the
+    /// checksum covers the extra bytes after the struct as well. This
+    /// crate does not model those bytes, so they are passed in as
+    /// `tail`, for example as returned by
+    /// `gpt_disk_io::Disk::read_gpt_header_with_tail`. The length of
+    /// `tail` should be `header_size` minus the size of the struct.
+    ///
+    /// [`header_size`]: Self::header_size
+    #[cfg(feature = "bytemuck")]
+    #[must_use]
+    pub fn calculate_header_crc32_with_tail(&self, tail: &[u8]) -> Crc32 {
+        let header = Self {
+            header_crc32: Crc32::default(),
+            ..*self
+        };
         let crc = crc::Crc::<u32>::new(&Crc32::ALGORITHM);
         let mut digest = crc.digest();
-        digest.update(bytes_of(&self.signature));
-        digest.update(bytes_of(&self.revision));
-        digest.update(bytes_of(&self.header_size));
-        digest.update(&[0u8; 4]); // Zeroes for the `header_crc32` field.
-        digest.update(bytes_of(&self.reserved));
-        digest.update(bytes_of(&self.my_lba));
-        digest.update(bytes_of(&self.alternate_lba));
-        digest.update(bytes_of(&self.first_usable_lba));
-        digest.update(bytes_of(&self.last_usable_lba));
-        digest.update(bytes_of(&{ self.disk_guid }));
-        digest.update(bytes_of(&self.partition_entry_lba));
-        digest.update(bytes_of(&self.number_of_partition_entries));
-        digest.update(bytes_of(&self.size_of_partition_entry));
-        digest.update(bytes_of(&self.partition_entry_array_crc32));
+        digest.update(bytes_of(&header));
+        digest.update(tail);
         Crc32(U32Le(digest.finalize().to_le_bytes()))
     }
 
+    /// Serialize the header into `header_size` bytes: the struct
+    /// followed by zeroes.
+    ///
+    /// Returns `None` if `header_size` is smaller than the struct.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::GptHeader;
+    ///
+    /// let bytes = GptHeader::default().to_bytes_with_len(128).unwrap();
+    /// assert_eq!(bytes.len(), 128);
+    /// assert_eq!(&bytes[..8], b"EFI PART");
+    /// assert!(bytes[92..].iter().all(|b| *b == 0));
+    /// ```
+    #[cfg(all(feature = "alloc", feature = "bytemuck"))]
+    #[must_use]
+    pub fn to_bytes_with_len(&self, header_size: usize) -> Option<Vec<u8>> {
+        let bytes = bytes_of(self);
+        if header_size < bytes.len() {
+            return None;
+        }
+        let mut v = Vec::with_capacity(header_size);
+        v.extend_from_slice(bytes);
+        v.resize(header_size, 0);
+        Some(v)
+    }
+
     /// Update the header's CRC32 checksum.
     #[cfg(feature = "bytemuck")]
     pub fn update_header_crc32(&mut self) {
         self.header_crc32 = self.calculate_header_crc32();
     }
 
+    /// Check if the header's [`header_crc32`] field matches the
+    /// calculated checksum.
+    ///
+    /// [`header_crc32`]: Self::header_crc32
+    #[cfg(feature = "bytemuck")]
+    #[must_use]
+    pub fn is_header_crc32_valid(&self) -> bool {
+        self.header_crc32 == self.calculate_header_crc32()
+    }
+
+    /// Set the [`partition_entry_array_crc32`] field to the checksum of
+    /// `array`.
+    ///
+    /// This changes the header's contents, so the header CRC32 must be
+    /// updated afterwards with [`update_header_crc32`]. Use
+    /// [`update_crcs`] to do both in the correct order.
+    ///
+    /// [`partition_entry_array_crc32`]: Self::partition_entry_array_crc32
+    /// [`update_crcs`]: Self::update_crcs
+    /// [`update_header_crc32`]: Self::update_header_crc32
+    pub fn set_partition_entry_array_crc32(
+        &mut self,
+        array: &GptPartitionEntryArray,
+    ) {
+        self.partition_entry_array_crc32 = array.calculate_crc32();
+    }
+
+    /// Update both CRC32 fields: first set
+    /// [`partition_entry_array_crc32`] from `array`, then recalculate
+    /// [`header_crc32`].
+    ///
+    /// The header checksum covers the array checksum field, so the
+    /// order matters. Updating the header checksum first leaves a
+    /// header that fails validation.
+    ///
+    /// [`header_crc32`]: Self::header_crc32
+    /// [`partition_entry_array_crc32`]: Self::partition_entry_array_crc32
+    #[cfg(feature = "bytemuck")]
+    pub fn update_crcs(&mut self, array: &GptPartitionEntryArray) {
+        self.set_partition_entry_array_crc32(array);
+        self.update_header_crc32();
+    }
+
     /// Get the [`GptPartitionEntryArrayLayout`] for this header.
     pub fn get_partition_entry_array_layout(
         &self,
@@ -198,6 +364,103 @@ impl GptHeader {
             num_entries: self.number_of_partition_entries.to_u32(),
         })
     }
+
+    /// Check that the partition entry array described by this header is
+    /// in a valid location.
+    ///
+    /// The UEFI Specification does not require the primary array to
+    /// start at LBA 2 or the secondary array to end just before the
+    /// secondary header. An array may be placed anywhere that doesn't
+    /// overlap the protective MBR, either header, or the usable range
+    /// of blocks, and some tools place it elsewhere, for example to
+    /// leave a gap for a bootloader. This check allows any such
+    /// location.
+    ///
+    /// The size of the disk is not known here, so the array is not
+    /// checked against the end of the disk.
+    pub fn check_partition_entry_array_location(
+        &self,
+        block_size: BlockSize,
+    ) -> Result<(), GptPartitionEntryArrayLocationError> {
+        let layout = self.get_partition_entry_array_layout().map_err(|_| {
+            GptPartitionEntryArrayLocationError::InvalidEntrySize
+        })?;
+        let num_blocks = layout
+            .num_blocks(block_size)
+            .ok_or(GptPartitionEntryArrayLocationError::Overflow)?;
+        if num_blocks == 0 {
+            return Ok(());
+        }
+        let start = layout.start_lba.to_u64();
+        let end = start
+            .checked_add(num_blocks - 1)
+            .ok_or(GptPartitionEntryArrayLocationError::Overflow)?;
+        let overlaps = |first: u64, last: u64| start <= last && first <= end;
+
+        if start == 0 {
+            return Err(GptPartitionEntryArrayLocationError::OverlapsMbr);
+        }
+        for lba in [self.my_lba, self.alternate_lba] {
+            let lba = Lba::from(lba).to_u64();
+            if overlaps(lba, lba) {
+                return Err(
+                    GptPartitionEntryArrayLocationError::OverlapsHeader,
+                );
+            }
+        }
+        let first_usable = self.first_usable_lba.to_u64();
+        let last_usable = self.last_usable_lba.to_u64();
+        if first_usable <= last_usable && overlaps(first_usable, last_usable) {
+            return Err(
+                GptPartitionEntryArrayLocationError::OverlapsUsableRange,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by [`GptHeader::check_partition_entry_array_location`].
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum GptPartitionEntryArrayLocationError {
+    /// The header's partition entry size is invalid.
+    InvalidEntrySize,
+
+    /// Numeric overflow occurred.
+    Overflow,
+
+    /// The array overlaps the protective MBR at LBA 0.
+    OverlapsMbr,
+
+    /// The array overlaps the primary or secondary header.
+    OverlapsHeader,
+
+    /// The array overlaps the range of blocks usable by partitions.
+    OverlapsUsableRange,
+}
+
+impl Display for GptPartitionEntryArrayLocationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntrySize => {
+                f.write_str("partition entry size is invalid")
+            }
+            Self::Overflow => f.write_str("numeric overflow occurred"),
+            Self::OverlapsMbr => {
+                f.write_str("partition entry array overlaps the MBR")
+            }
+            Self::OverlapsHeader => {
+                f.write_str("partition entry array overlaps a GPT header")
+            }
+            Self::OverlapsUsableRange => {
+                f.write_str("partition entry array overlaps the usable range")
+            }
+        }
+    }
 }
 
 impl Default for GptHeader {
@@ -0,0 +1,553 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{
+    BlockSize, GptHeader, GptPartitionEntryArrayLayout, GptPartitionEntrySize,
+    Guid, Lba, LbaLe, LbaRangeInclusive, U32Le,
+};
+use core::fmt::{self, Display, Formatter};
+
+/// Error type for [`GptDiskLayout::new`].
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum GptDiskLayoutError {
+    /// The disk does not have enough blocks to hold the MBR, both GPT
+    /// headers, both partition entry arrays, and at least one usable
+    /// block. See [`GptDiskLayout::min_num_blocks`].
+    DiskTooSmall,
+}
+
+impl Display for GptDiskLayoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DiskTooSmall => f.write_str("disk is too small for a GPT"),
+        }
+    }
+}
+
+/// Location of each GPT component on a disk.
+///
+/// The layout is calculated from the disk size, block size, and the
+/// shape of the partition entry array:
+///
+/// ```text
+/// LBA 0                 protective MBR
+/// LBA 1                 primary header
+/// LBA 2..               primary partition entry array
+///                       reserved leading blocks (if any)
+/// first_usable_lba..    partition data
+/// ..=last_usable_lba
+///                       reserved trailing blocks (if any)
+/// secondary array       secondary partition entry array
+/// num_blocks - 1        secondary header
+/// ```
+///
+/// Any number of entries and any valid entry size can be used. The
+/// UEFI Specification requires that at least 16 KiB be reserved for
+/// each partition entry array, so an array smaller than that (e.g. fewer
+/// than 128 entries of 128 bytes) still takes up 16 KiB of space, and
+/// a larger array pushes `first_usable_lba` later and the secondary
+/// array earlier. See [`GptDiskLayout::new_with_options`] to opt out
+/// of the minimum for small non-UEFI images.
+///
+/// Space can also be reserved on either side of the usable range with
+/// [`GptDiskLayout::reserve_leading_bytes`] and
+/// [`GptDiskLayout::reserve_trailing_bytes`].
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_types::{BlockSize, GptDiskLayout, GptPartitionEntrySize, Lba};
+///
+/// // Default layout: 128 entries of 128 bytes.
+/// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+/// assert_eq!(layout.first_usable_lba(), Lba(34));
+/// assert_eq!(layout.last_usable_lba(), Lba(8158));
+///
+/// // 256 entries of 256 bytes takes up 128 blocks per array.
+/// let layout = GptDiskLayout::new_with_entries(
+///     BlockSize::BS_512,
+///     8192,
+///     GptPartitionEntrySize::new(256).unwrap(),
+///     256,
+/// )
+/// .unwrap();
+/// assert_eq!(layout.first_usable_lba(), Lba(130));
+/// assert_eq!(layout.last_usable_lba(), Lba(8062));
+/// ```
+///
+/// The layout can also be calculated at compile time, for example to
+/// size buffers in firmware that targets a fixed disk geometry:
+///
+/// ```
+/// use gpt_disk_types::{BlockSize, GptDiskLayout, Lba};
+///
+/// const LAYOUT: GptDiskLayout =
+///     match GptDiskLayout::new(BlockSize::BS_512, 8192) {
+///         Ok(layout) => layout,
+///         Err(_) => panic!("invalid layout"),
+///     };
+/// const ARRAY_NUM_BLOCKS: u64 = LAYOUT.partition_entry_array_num_blocks();
+/// const FIRST_USABLE_LBA: Lba = LAYOUT.first_usable_lba();
+///
+/// static ARRAY_BUF: [u8; ARRAY_NUM_BLOCKS as usize * 512] =
+///     [0; ARRAY_NUM_BLOCKS as usize * 512];
+/// assert_eq!(ARRAY_BUF.len(), 16384);
+/// assert_eq!(FIRST_USABLE_LBA, Lba(34));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct GptDiskLayout {
+    block_size: BlockSize,
+    num_blocks: u64,
+    entry_size: GptPartitionEntrySize,
+    num_entries: u32,
+    partition_entry_array_num_blocks: u64,
+    reserved_leading_blocks: u64,
+    reserved_trailing_blocks: u64,
+}
+
+impl GptDiskLayout {
+    /// Minimum number of bytes that must be reserved for each partition
+    /// entry array, regardless of the number of entries.
+    pub const MIN_PARTITION_ENTRY_ARRAY_BYTES: u64 = 16 * 1024;
+
+    /// Number of entries in the partition entry array used by
+    /// [`GptDiskLayout::new`].
+    pub const DEFAULT_NUM_PARTITION_ENTRIES: u32 = 128;
+
+    /// Calculate the layout of a disk with `num_blocks` blocks, using
+    /// the default partition entry array of 128 entries of 128 bytes
+    /// each.
+    pub const fn new(
+        block_size: BlockSize,
+        num_blocks: u64,
+    ) -> Result<Self, GptDiskLayoutError> {
+        let Ok(entry_size) = GptPartitionEntrySize::new(128) else {
+            unreachable!()
+        };
+        Self::new_with_entries(
+            block_size,
+            num_blocks,
+            entry_size,
+            Self::DEFAULT_NUM_PARTITION_ENTRIES,
+        )
+    }
+
+    /// Calculate the layout of a disk with `num_blocks` blocks and a
+    /// partition entry array of `num_entries` entries of `entry_size`
+    /// bytes each.
+    pub const fn new_with_entries(
+        block_size: BlockSize,
+        num_blocks: u64,
+        entry_size: GptPartitionEntrySize,
+        num_entries: u32,
+    ) -> Result<Self, GptDiskLayoutError> {
+        Self::new_with_options(
+            block_size,
+            num_blocks,
+            entry_size,
+            num_entries,
+            true,
+        )
+    }
+
+    /// Calculate the layout of a disk like [`new_with_entries`], but
+    /// with control over the minimum partition entry array size.
+    ///
+    /// If `reserve_min_array_size` is true, each array takes up at least
+    /// [`MIN_PARTITION_ENTRY_ARRAY_BYTES`], as the UEFI Specification
+    /// requires. If false, each array only takes up as many blocks as
+    /// its entries need. That is not spec compliant, but can be useful
+    /// for small flash images used by embedded software that reads the
+    /// GPT itself, where 32 KiB of mostly empty arrays is significant.
+    /// UEFI firmware and most operating systems can still read such a
+    /// disk, but some partitioning tools may rewrite it with full-size
+    /// arrays.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{BlockSize, GptDiskLayout, GptPartitionEntrySize, Lba};
+    ///
+    /// // Four entries on an 8 MiB SPI-NOR image take up a single block.
+    /// let layout = GptDiskLayout::new_with_options(
+    ///     BlockSize::BS_512,
+    ///     16384,
+    ///     GptPartitionEntrySize::new(128).unwrap(),
+    ///     4,
+    ///     false,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(layout.partition_entry_array_num_blocks(), 1);
+    /// assert_eq!(layout.first_usable_lba(), Lba(3));
+    /// ```
+    ///
+    /// [`MIN_PARTITION_ENTRY_ARRAY_BYTES`]: Self::MIN_PARTITION_ENTRY_ARRAY_BYTES
+    /// [`new_with_entries`]: Self::new_with_entries
+    pub const fn new_with_options(
+        block_size: BlockSize,
+        num_blocks: u64,
+        entry_size: GptPartitionEntrySize,
+        num_entries: u32,
+        reserve_min_array_size: bool,
+    ) -> Result<Self, GptDiskLayoutError> {
+        let partition_entry_array_num_blocks = Self::array_num_blocks(
+            block_size,
+            entry_size,
+            num_entries,
+            reserve_min_array_size,
+        );
+        // The MBR, two headers, two arrays, and at least one usable
+        // block must fit on the disk. See `min_num_blocks`.
+        if num_blocks < partition_entry_array_num_blocks * 2 + 4 {
+            return Err(GptDiskLayoutError::DiskTooSmall);
+        }
+
+        Ok(Self {
+            block_size,
+            num_blocks,
+            entry_size,
+            num_entries,
+            partition_entry_array_num_blocks,
+            reserved_leading_blocks: 0,
+            reserved_trailing_blocks: 0,
+        })
+    }
+
+    /// Reserve at least `num_bytes` between the primary partition entry
+    /// array and the first usable block, replacing any previous
+    /// reservation. The size is rounded up to whole blocks.
+    ///
+    /// This moves [`first_usable_lba`] later without moving the primary
+    /// array, leaving space for data that must be at a fixed location
+    /// near the start of the disk, such as a boot loader loaded by a
+    /// system-on-chip boot ROM.
+    ///
+    /// Returns [`GptDiskLayoutError::DiskTooSmall`] if no usable blocks
+    /// would be left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{BlockSize, GptDiskLayout, Lba};
+    ///
+    /// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192)
+    ///     .unwrap()
+    ///     .reserve_leading_bytes(1024 * 1024)
+    ///     .unwrap();
+    /// assert_eq!(layout.first_usable_lba(), Lba(34 + 2048));
+    /// assert_eq!(layout.primary_partition_entry_array_layout().start_lba, Lba(2));
+    /// ```
+    ///
+    /// [`first_usable_lba`]: Self::first_usable_lba
+    pub const fn reserve_leading_bytes(
+        self,
+        num_bytes: u64,
+    ) -> Result<Self, GptDiskLayoutError> {
+        self.with_reserved_blocks(
+            self.bytes_to_blocks(num_bytes),
+            self.reserved_trailing_blocks,
+        )
+    }
+
+    /// Reserve at least `num_bytes` between the last usable block and
+    /// the secondary partition entry array, replacing any previous
+    /// reservation. The size is rounded up to whole blocks.
+    ///
+    /// This moves [`last_usable_lba`] earlier without moving the
+    /// secondary array or header, leaving a guard region before them,
+    /// for example for firmware update schemes that store data at the
+    /// end of the disk.
+    ///
+    /// Returns [`GptDiskLayoutError::DiskTooSmall`] if no usable blocks
+    /// would be left.
+    ///
+    /// [`last_usable_lba`]: Self::last_usable_lba
+    pub const fn reserve_trailing_bytes(
+        self,
+        num_bytes: u64,
+    ) -> Result<Self, GptDiskLayoutError> {
+        self.with_reserved_blocks(
+            self.reserved_leading_blocks,
+            self.bytes_to_blocks(num_bytes),
+        )
+    }
+
+    /// Number of blocks reserved before the first usable block. See
+    /// [`reserve_leading_bytes`].
+    ///
+    /// [`reserve_leading_bytes`]: Self::reserve_leading_bytes
+    #[must_use]
+    pub const fn reserved_leading_blocks(&self) -> u64 {
+        self.reserved_leading_blocks
+    }
+
+    /// Number of blocks reserved after the last usable block. See
+    /// [`reserve_trailing_bytes`].
+    ///
+    /// [`reserve_trailing_bytes`]: Self::reserve_trailing_bytes
+    #[must_use]
+    pub const fn reserved_trailing_blocks(&self) -> u64 {
+        self.reserved_trailing_blocks
+    }
+
+    /// Set both reserved regions, checking that at least one usable
+    /// block remains.
+    pub(crate) const fn with_reserved_blocks(
+        mut self,
+        leading: u64,
+        trailing: u64,
+    ) -> Result<Self, GptDiskLayoutError> {
+        // The usable range without any reservations. `new_with_options`
+        // checked that this has at least one block.
+        let first = 2 + self.partition_entry_array_num_blocks;
+        let last = self.num_blocks - 2 - self.partition_entry_array_num_blocks;
+        let available = last - first + 1;
+
+        match leading.checked_add(trailing) {
+            Some(reserved) if reserved < available => {
+                self.reserved_leading_blocks = leading;
+                self.reserved_trailing_blocks = trailing;
+                Ok(self)
+            }
+            _ => Err(GptDiskLayoutError::DiskTooSmall),
+        }
+    }
+
+    /// Convert `num_bytes` to blocks, rounding up.
+    const fn bytes_to_blocks(&self, num_bytes: u64) -> u64 {
+        let block_size = self.block_size.to_u64();
+        let mut num_blocks = num_bytes / block_size;
+        if num_bytes % block_size != 0 {
+            num_blocks += 1;
+        }
+        num_blocks
+    }
+
+    /// Get the smallest number of blocks that a disk must have to hold
+    /// the MBR, both GPT headers, both partition entry arrays, and at
+    /// least one usable block. The arguments are the same as for
+    /// [`new_with_options`], which returns
+    /// [`GptDiskLayoutError::DiskTooSmall`] for smaller disks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{BlockSize, GptDiskLayout, GptPartitionEntrySize};
+    ///
+    /// let entry_size = GptPartitionEntrySize::new(128).unwrap();
+    /// assert_eq!(
+    ///     GptDiskLayout::min_num_blocks(BlockSize::BS_512, entry_size, 128, true),
+    ///     68
+    /// );
+    /// assert_eq!(
+    ///     GptDiskLayout::min_num_blocks(BlockSize::BS_4096, entry_size, 4, false),
+    ///     6
+    /// );
+    /// ```
+    ///
+    /// [`new_with_options`]: Self::new_with_options
+    #[must_use]
+    pub const fn min_num_blocks(
+        block_size: BlockSize,
+        entry_size: GptPartitionEntrySize,
+        num_entries: u32,
+        reserve_min_array_size: bool,
+    ) -> u64 {
+        // The array size is at most 2^31 * 2^32 bytes, so with blocks of
+        // at least 512 bytes this can't overflow.
+        Self::array_num_blocks(
+            block_size,
+            entry_size,
+            num_entries,
+            reserve_min_array_size,
+        ) * 2
+            + 4
+    }
+
+    /// Number of blocks needed for one partition entry array.
+    #[allow(clippy::as_conversions)]
+    const fn array_num_blocks(
+        block_size: BlockSize,
+        entry_size: GptPartitionEntrySize,
+        num_entries: u32,
+        reserve_min_array_size: bool,
+    ) -> u64 {
+        // Both inputs are 32-bit, so this can't overflow.
+        let mut array_num_bytes = entry_size.to_u64() * (num_entries as u64);
+        if reserve_min_array_size
+            && array_num_bytes < Self::MIN_PARTITION_ENTRY_ARRAY_BYTES
+        {
+            array_num_bytes = Self::MIN_PARTITION_ENTRY_ARRAY_BYTES;
+        }
+        let block_size_u64 = block_size.to_u64();
+        let mut num_blocks = array_num_bytes / block_size_u64;
+        if array_num_bytes % block_size_u64 != 0 {
+            num_blocks += 1;
+        }
+        num_blocks
+    }
+
+    /// Size of each block.
+    #[must_use]
+    pub const fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    /// Total number of blocks on the disk.
+    #[must_use]
+    pub const fn num_blocks(&self) -> u64 {
+        self.num_blocks
+    }
+
+    /// Number of blocks reserved for each partition entry array. This
+    /// may be more than is needed for the entries themselves due to the
+    /// 16 KiB minimum (see [`new_with_options`]).
+    ///
+    /// [`new_with_options`]: Self::new_with_options
+    #[must_use]
+    pub const fn partition_entry_array_num_blocks(&self) -> u64 {
+        self.partition_entry_array_num_blocks
+    }
+
+    /// Location of the primary GPT header.
+    #[must_use]
+    pub const fn primary_header_lba(&self) -> Lba {
+        Lba(1)
+    }
+
+    /// Location of the secondary GPT header, which is the last block of
+    /// the disk.
+    #[must_use]
+    pub const fn secondary_header_lba(&self) -> Lba {
+        Lba(self.num_blocks - 1)
+    }
+
+    /// Layout of the primary partition entry array.
+    #[must_use]
+    pub const fn primary_partition_entry_array_layout(
+        &self,
+    ) -> GptPartitionEntryArrayLayout {
+        GptPartitionEntryArrayLayout {
+            start_lba: Lba(2),
+            entry_size: self.entry_size,
+            num_entries: self.num_entries,
+        }
+    }
+
+    /// Layout of the secondary partition entry array, which ends just
+    /// before the secondary header.
+    #[must_use]
+    pub const fn secondary_partition_entry_array_layout(
+        &self,
+    ) -> GptPartitionEntryArrayLayout {
+        GptPartitionEntryArrayLayout {
+            start_lba: Lba(self.secondary_header_lba().0
+                - self.partition_entry_array_num_blocks),
+            entry_size: self.entry_size,
+            num_entries: self.num_entries,
+        }
+    }
+
+    /// First block that can be used by a partition.
+    #[must_use]
+    pub const fn first_usable_lba(&self) -> Lba {
+        Lba(2
+            + self.partition_entry_array_num_blocks
+            + self.reserved_leading_blocks)
+    }
+
+    /// Last block that can be used by a partition.
+    #[must_use]
+    pub const fn last_usable_lba(&self) -> Lba {
+        Lba(self.secondary_partition_entry_array_layout().start_lba.0
+            - 1
+            - self.reserved_trailing_blocks)
+    }
+
+    /// Range of blocks that can be used by partitions.
+    #[allow(clippy::missing_panics_doc)]
+    #[must_use]
+    pub const fn usable_range(&self) -> LbaRangeInclusive {
+        // `new_with_options` and `with_reserved_blocks` checked that
+        // there is at least one usable block, so this can't fail.
+        match LbaRangeInclusive::new(
+            self.first_usable_lba(),
+            self.last_usable_lba(),
+        ) {
+            Some(range) => range,
+            None => unreachable!(),
+        }
+    }
+
+    /// Create the primary GPT header for this layout.
+    ///
+    /// The CRC32 fields are left as zero; update them once the
+    /// partition entry array has been filled in.
+    #[must_use]
+    pub fn primary_header(&self, disk_guid: Guid) -> GptHeader {
+        self.header(
+            disk_guid,
+            self.primary_header_lba(),
+            self.secondary_header_lba(),
+            self.primary_partition_entry_array_layout(),
+        )
+    }
+
+    /// Create the secondary GPT header for this layout.
+    ///
+    /// The CRC32 fields are left as zero; update them once the
+    /// partition entry array has been filled in.
+    #[must_use]
+    pub fn secondary_header(&self, disk_guid: Guid) -> GptHeader {
+        self.header(
+            disk_guid,
+            self.secondary_header_lba(),
+            self.primary_header_lba(),
+            self.secondary_partition_entry_array_layout(),
+        )
+    }
+
+    fn header(
+        &self,
+        disk_guid: Guid,
+        my_lba: Lba,
+        alternate_lba: Lba,
+        array_layout: GptPartitionEntryArrayLayout,
+    ) -> GptHeader {
+        GptHeader {
+            my_lba: LbaLe::from(my_lba),
+            alternate_lba: LbaLe::from(alternate_lba),
+            first_usable_lba: LbaLe::from(self.first_usable_lba()),
+            last_usable_lba: LbaLe::from(self.last_usable_lba()),
+            disk_guid,
+            partition_entry_lba: LbaLe::from(array_layout.start_lba),
+            number_of_partition_entries: U32Le::from_u32(self.num_entries),
+            size_of_partition_entry: U32Le::from_u32(self.entry_size.to_u32()),
+            ..GptHeader::default()
+        }
+    }
+}
+
+impl Display for GptDiskLayout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "block_size={}/num_blocks={}/usable={}/primary_array=({})/secondary_array=({})",
+            self.block_size,
+            self.num_blocks,
+            self.usable_range(),
+            self.primary_partition_entry_array_layout(),
+            self.secondary_partition_entry_array_layout(),
+        )
+    }
+}
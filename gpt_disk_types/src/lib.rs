@@ -41,11 +41,29 @@
 //!
 //! # Features
 //!
+//! * `alloc`: Enables methods that return a `String`, such as
+//!   [`GptPartitionEntry::partuuid_string`]. Off by default.
 //! * `bytemuck`: Implements bytemuck's `Pod` and `Zeroable` traits for
-//!    many of the types in this crate. Also enables some methods that
-//!    rely on byte access.
+//!   many of the types in this crate. Also enables some methods that
+//!   rely on byte access.
+//! * `render`: Enables [`GptPartitionTable::render_text`] and
+//!   [`GptPartitionTable::render_svg`], which draw the disk layout as a
+//!   text bar or an SVG image. Off by default.
+//! * `rkyv`: Implements rkyv's `Archive`, `Serialize`, and `Deserialize`
+//!   traits for the fixed-layout types in this crate, so that they can
+//!   be used in zero-copy archives.
+//! * `serde`: Implements serde's `Serialize` and `Deserialize` traits for
+//!   `BlockSize` (as an integer number of bytes), `Guid`,
+//!   `GptDiskLayout`, `GptPartitionEntry`, and `GptPartitionTable`, so
+//!   that a parsed partition table can be cached.
+//! * `schemars`: Implements schemars's `JsonSchema` trait for the types
+//!   that implement serde's traits, including `BlockSize`,
+//!   `GptPartitionAttributes`, `GptPartitionName`, and
+//!   `GptPartitionTable`. Each schema describes the JSON produced by the
+//!   serde implementation, such as the list of flag names for
+//!   attributes. Implies `serde` and `std`. Off by default.
 //! * `std`: Provides `std::error::Error` implementations for all of the
-//!   error types. Off by default.
+//!   error types. Implies `alloc`. Off by default.
 //!
 //! # Examples
 //!
@@ -100,13 +118,71 @@
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::module_name_repetitions)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// Implement rkyv's traits for a `Copy` type such that the archived form
+/// is the type itself.
+///
+/// This is used for packed structs, which can't use the derive macros
+/// because they take references to fields.
+#[cfg(feature = "rkyv")]
+macro_rules! impl_rkyv_archive_as_self {
+    ($ty:ty) => {
+        impl rkyv::Archive for $ty {
+            type Archived = Self;
+            type Resolver = ();
+
+            #[allow(unsafe_code)]
+            unsafe fn resolve(
+                &self,
+                _pos: usize,
+                _resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                // SAFETY: the caller guarantees that `out` is valid for
+                // writes. The type is packed, so `out` is always
+                // sufficiently aligned.
+                unsafe { out.write(*self) }
+            }
+        }
+
+        impl<S: rkyv::Fallible + ?Sized> rkyv::Serialize<S> for $ty {
+            fn serialize(
+                &self,
+                _serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                Ok(())
+            }
+        }
+
+        impl<D: rkyv::Fallible + ?Sized> rkyv::Deserialize<$ty, D> for $ty {
+            fn deserialize(
+                &self,
+                _deserializer: &mut D,
+            ) -> Result<$ty, D::Error> {
+                Ok(*self)
+            }
+        }
+    };
+}
+
 mod block;
 mod crc32;
+mod dps;
 mod header;
+mod layout;
 mod mbr;
 mod num;
 mod partition_array;
 mod partition_entry;
+mod partition_table;
+#[cfg(feature = "render")]
+mod render;
+#[cfg(feature = "schemars")]
+mod schemars_support;
+#[cfg(feature = "serde")]
+mod serde_support;
 #[cfg(feature = "std")]
 mod std_support;
 
@@ -115,17 +191,31 @@ pub use crc;
 pub use ucs2;
 pub use uguid::{guid, Guid, GuidFromStrError};
 
-pub use block::{BlockSize, Lba, LbaLe, LbaRangeInclusive};
+pub use block::{
+    BlockSize, BlockSizeError, Lba, LbaLe, LbaRangeInclusive,
+    LbaRangeInclusiveIter,
+};
 pub use crc32::Crc32;
-pub use header::{GptHeader, GptHeaderRevision, GptHeaderSignature};
+pub use dps::Arch;
+pub use header::{
+    GptHeader, GptHeaderRevision, GptHeaderSignature,
+    GptPartitionEntryArrayLocationError,
+};
+pub use layout::{GptDiskLayout, GptDiskLayoutError};
 pub use mbr::{Chs, DiskGeometry, MasterBootRecord, MbrPartitionRecord};
 pub use num::{U16Le, U32Le, U64Le};
 pub use partition_array::{
     GptPartitionEntryArray, GptPartitionEntryArrayError,
-    GptPartitionEntryArrayLayout,
+    GptPartitionEntryArrayLayout, PartitionIndex,
 };
 pub use partition_entry::{
     GptPartitionAttributes, GptPartitionEntry, GptPartitionEntrySize,
     GptPartitionEntrySizeError, GptPartitionName, GptPartitionNameFromStrError,
     GptPartitionNameSetCharError, GptPartitionType,
 };
+pub use partition_table::{
+    ApplyLayoutPolicy, EspSizeWarning, GptChange, GptDiskRegion,
+    GptDiskRegionKind, GptLayoutChange, GptPartitionDescription,
+    GptPartitionTable, GptPartitionTableError, GptPartitionTableSnapshot,
+    GptPartitionTableSummary,
+};
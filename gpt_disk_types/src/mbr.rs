@@ -51,6 +51,12 @@ impl Display for DiskGeometry {
 /// Legacy MBR cylinder/head/sector.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(C)]
 pub struct Chs(pub [u8; 3]);
 
@@ -136,6 +142,12 @@ impl Display for Chs {
 /// See Table 5-2 "Legacy MBR Partition Record" in the UEFI Specification.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(C)]
 pub struct MbrPartitionRecord {
     /// A value of `0x80` indicates this is a legacy bootable
@@ -185,6 +197,12 @@ impl Display for MbrPartitionRecord {
 ///
 /// See Table 5-1 "Legacy MBR" in the UEFI Specification.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(C)]
 pub struct MasterBootRecord {
     /// Executable code used on non-UEFI systems select a partition and
@@ -237,12 +255,44 @@ impl MasterBootRecord {
         self.boot_strap_code.iter().all(|b| *b == 0)
     }
 
+    /// Return a copy of the MBR with [`boot_strap_code`] replaced by
+    /// `code`. The partition records and signatures are unchanged.
+    ///
+    /// This can be used to embed stage-1 boot code, such as GRUB's
+    /// `boot.img`, in a protective MBR for disks that also boot on
+    /// legacy BIOS systems.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::MasterBootRecord;
+    ///
+    /// let boot_code = [0x90; 440];
+    /// let mbr = MasterBootRecord::protective_mbr(8192)
+    ///     .with_boot_strap_code(&boot_code);
+    /// assert_eq!(mbr.boot_strap_code, boot_code);
+    /// assert_eq!(mbr.partitions[0].os_indicator, 0xee);
+    /// ```
+    ///
+    /// [`boot_strap_code`]: Self::boot_strap_code
+    #[must_use]
+    pub fn with_boot_strap_code(mut self, code: &[u8; 440]) -> Self {
+        self.boot_strap_code = *code;
+        self
+    }
+
     /// Create a protective MBR for the given disk size.
     ///
     /// See section 5.2.3 "Protective MBR" of the UEFI Specification.
     #[must_use]
     pub fn protective_mbr(num_blocks: u64) -> Self {
-        let size_in_lba = u32::try_from(num_blocks).unwrap_or(0xffff_ffff);
+        // The protective partition covers everything after the MBR. Use
+        // saturating math so that an empty disk produces an empty record
+        // rather than wrapping around.
+        let last_lba = num_blocks.saturating_sub(1);
+        let size_in_lba = u32::try_from(num_blocks)
+            .unwrap_or(0xffff_ffff)
+            .saturating_sub(1);
 
         Self {
             boot_strap_code: [0; 440],
@@ -255,12 +305,12 @@ impl MasterBootRecord {
                     start_chs: Chs([0, 2, 0]),
                     os_indicator: 0xee,
                     end_chs: Chs::from_lba(
-                        Lba(num_blocks - 1),
+                        Lba(last_lba),
                         DiskGeometry::UNKNOWN,
                     )
                     .unwrap_or(Chs([0xff, 0xff, 0xff])),
                     starting_lba: U32Le::from_u32(1),
-                    size_in_lba: U32Le::from_u32(size_in_lba - 1),
+                    size_in_lba: U32Le::from_u32(size_in_lba),
                 },
                 MbrPartitionRecord::default(),
                 MbrPartitionRecord::default(),
@@ -14,6 +14,12 @@ use bytemuck::{Pod, Zeroable};
 /// 16-bit unsigned integer stored as a little-endian.
 #[derive(Clone, Copy, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct U16Le(pub [u8; 2]);
 
@@ -57,6 +63,12 @@ impl LowerHex for U16Le {
 /// 32-bit unsigned integer stored as a little-endian.
 #[derive(Clone, Copy, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct U32Le(pub [u8; 4]);
 
@@ -100,6 +112,12 @@ impl LowerHex for U32Le {
 /// 64-bit unsigned integer stored as a little-endian.
 #[derive(Clone, Copy, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct U64Le(pub [u8; 8]);
 
@@ -6,13 +6,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{BlockSize, Crc32, GptPartitionEntrySize, Lba, U32Le};
-use core::fmt::{self, Display, Formatter};
+use crate::{BlockSize, Crc32, GptPartitionEntrySize, Lba};
+use core::fmt::{self, Debug, Display, Formatter};
 
 #[cfg(feature = "bytemuck")]
 use {
-    crate::GptPartitionEntry,
-    bytemuck::{from_bytes, from_bytes_mut},
+    crate::partition_entry::parse_partuuid,
+    crate::{GptPartitionEntry, U32Le},
+    bytemuck::{bytes_of, from_bytes, from_bytes_mut},
     core::mem,
     core::ops::Range,
 };
@@ -34,16 +35,18 @@ impl GptPartitionEntryArrayLayout {
     /// Get the number of blocks needed for this layout. Returns `None`
     /// if overflow occurs.
     #[must_use]
-    pub fn num_blocks(&self, block_size: BlockSize) -> Option<u64> {
+    pub const fn num_blocks(&self, block_size: BlockSize) -> Option<u64> {
         let block_size = block_size.to_u64();
-        let num_bytes_exact = self.num_bytes_exact()?;
+        let Some(num_bytes_exact) = self.num_bytes_exact() else {
+            return None;
+        };
 
-        let mut num_blocks = num_bytes_exact / block_size;
+        let num_blocks = num_bytes_exact / block_size;
         if num_bytes_exact % block_size != 0 {
-            num_blocks = num_blocks.checked_add(1)?;
+            num_blocks.checked_add(1)
+        } else {
+            Some(num_blocks)
         }
-
-        Some(num_blocks)
     }
 
     /// Get the number of blocks needed for this layout. Returns `None`
@@ -62,9 +65,10 @@ impl GptPartitionEntryArrayLayout {
     ///
     /// [`partition_entry_array_crc32`]: crate::GptHeader::partition_entry_array_crc32
     #[must_use]
-    pub fn num_bytes_exact(&self) -> Option<u64> {
+    #[allow(clippy::as_conversions)]
+    pub const fn num_bytes_exact(&self) -> Option<u64> {
         let entry_size = self.entry_size.to_u64();
-        let num_entries = u64::from(self.num_entries);
+        let num_entries = self.num_entries as u64;
         entry_size.checked_mul(num_entries)
     }
 
@@ -89,11 +93,13 @@ impl GptPartitionEntryArrayLayout {
     ///
     /// [`num_blocks`]: Self::num_blocks
     #[must_use]
-    pub fn num_bytes_rounded_to_block(
+    pub const fn num_bytes_rounded_to_block(
         &self,
         block_size: BlockSize,
     ) -> Option<u64> {
-        let num_blocks = self.num_blocks(block_size)?;
+        let Some(num_blocks) = self.num_blocks(block_size) else {
+            return None;
+        };
         num_blocks.checked_mul(block_size.to_u64())
     }
 
@@ -111,6 +117,69 @@ impl GptPartitionEntryArrayLayout {
     ) -> Option<usize> {
         self.num_bytes_rounded_to_block(block_size)?.try_into().ok()
     }
+
+    /// Calculate the CRC32 of a partition entry array with this layout,
+    /// taking the entries from `entries` instead of from storage. This
+    /// allows the checksum of a large array to be calculated without a
+    /// buffer for the whole array.
+    ///
+    /// If `entries` produces fewer than [`num_entries`] entries, the
+    /// rest are treated as unused (zeroed) entries. Returns `None` if
+    /// it produces more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{
+    ///     BlockSize, GptPartitionEntry, GptPartitionEntryArray,
+    ///     GptPartitionEntryArrayLayout, GptPartitionEntrySize, Lba,
+    /// };
+    ///
+    /// let layout = GptPartitionEntryArrayLayout {
+    ///     start_lba: Lba(2),
+    ///     entry_size: GptPartitionEntrySize::new(128).unwrap(),
+    ///     num_entries: 128,
+    /// };
+    /// let mut storage = vec![0; 128 * 128];
+    /// let array =
+    ///     GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+    ///         .unwrap();
+    ///
+    /// let entries = [GptPartitionEntry::default(); 4];
+    /// assert_eq!(layout.calculate_crc32(entries), Some(array.calculate_crc32()));
+    /// ```
+    ///
+    /// [`num_entries`]: Self::num_entries
+    #[cfg(feature = "bytemuck")]
+    #[must_use]
+    pub fn calculate_crc32<I>(&self, entries: I) -> Option<Crc32>
+    where
+        I: IntoIterator<Item = GptPartitionEntry>,
+    {
+        const ZEROES: [u8; 128] = [0; 128];
+
+        let padding_size =
+            self.entry_size.to_usize()? - mem::size_of::<GptPartitionEntry>();
+        let crc = crc::Crc::<u32>::new(&Crc32::ALGORITHM);
+        let mut digest = crc.digest();
+        let mut entries = entries.into_iter();
+        for _ in 0..self.num_entries {
+            let entry = entries.next().unwrap_or_default();
+            digest.update(bytes_of(&entry));
+
+            // Entries larger than `GptPartitionEntry` are zero padded.
+            let mut padding_left = padding_size;
+            while padding_left > 0 {
+                let n = padding_left.min(ZEROES.len());
+                digest.update(&ZEROES[..n]);
+                padding_left -= n;
+            }
+        }
+        if entries.next().is_some() {
+            return None;
+        }
+        Some(Crc32(U32Le(digest.finalize().to_le_bytes())))
+    }
 }
 
 impl Display for GptPartitionEntryArrayLayout {
@@ -145,8 +214,84 @@ impl Display for GptPartitionEntryArrayError {
     }
 }
 
+/// Zero-based index of an entry in a partition entry array.
+///
+/// Partitioning tools such as `sgdisk` and `parted`, as well as Linux
+/// device names like `/dev/sda1`, number partitions starting from one.
+/// This type makes the distinction explicit: use [`new`]/[`to_u32`] for
+/// the zero-based index into the array, and
+/// [`from_display_number`]/[`to_display_number`] for the one-based
+/// number shown to users. The [`Display`] impl shows the one-based
+/// number.
+///
+/// [`new`]: Self::new
+/// [`to_u32`]: Self::to_u32
+/// [`from_display_number`]: Self::from_display_number
+/// [`to_display_number`]: Self::to_display_number
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_types::PartitionIndex;
+///
+/// let index = PartitionIndex::from_display_number(1).unwrap();
+/// assert_eq!(index, PartitionIndex::new(0));
+/// assert_eq!(index.to_u32(), 0);
+/// assert_eq!(index.to_display_number(), 1);
+/// assert_eq!(index.to_string(), "1");
+///
+/// assert!(PartitionIndex::from_display_number(0).is_none());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PartitionIndex(u32);
+
+impl PartitionIndex {
+    /// Create a `PartitionIndex` from a zero-based index.
+    #[must_use]
+    pub const fn new(index: u32) -> Self {
+        Self(index)
+    }
+
+    /// Create a `PartitionIndex` from a one-based partition number, as
+    /// shown to users. Returns `None` if `number` is zero or too large
+    /// to be an index into a partition entry array.
+    #[must_use]
+    pub fn from_display_number(number: u64) -> Option<Self> {
+        let index = number.checked_sub(1)?;
+        Some(Self(u32::try_from(index).ok()?))
+    }
+
+    /// Get the zero-based index.
+    #[must_use]
+    pub const fn to_u32(self) -> u32 {
+        self.0
+    }
+
+    /// Get the one-based partition number, as shown to users.
+    #[must_use]
+    pub fn to_display_number(self) -> u64 {
+        u64::from(self.0) + 1
+    }
+}
+
+impl From<PartitionIndex> for u32 {
+    fn from(index: PartitionIndex) -> Self {
+        index.to_u32()
+    }
+}
+
+impl Display for PartitionIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_display_number())
+    }
+}
+
 /// Storage for a GPT partition entry array.
-#[allow(missing_debug_implementations)]
+///
+/// The [`Debug`] impl shows the layout and, if the `bytemuck` feature is
+/// enabled, the number of used entries. With the `bytemuck` feature, the
+/// [`Display`] impl lists each used entry on its own line, prefixed with
+/// its one-based [`PartitionIndex`].
 pub struct GptPartitionEntryArray<'a> {
     layout: GptPartitionEntryArrayLayout,
     num_bytes_exact: usize,
@@ -240,6 +385,111 @@ impl<'a> GptPartitionEntryArray<'a> {
         Some(from_bytes_mut(&mut self.storage[range]))
     }
 
+    /// Get the size in bytes of each entry's storage. This is larger
+    /// than a [`GptPartitionEntry`] if the entry size is over 128 bytes.
+    #[cfg(feature = "bytemuck")]
+    fn entry_chunk_size(&self) -> usize {
+        // The array's size in bytes was checked in `new` to fit in a
+        // `usize`, so the size of one entry also fits unless there are
+        // zero entries. In that case no chunks are produced anyway.
+        self.layout.entry_size.to_usize().unwrap_or(usize::MAX)
+    }
+
+    /// Iterate over all entries in the array, including unused ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{
+    ///     BlockSize, GptPartitionEntryArray, GptPartitionEntryArrayLayout,
+    ///     GptPartitionEntrySize, Lba,
+    /// };
+    ///
+    /// let layout = GptPartitionEntryArrayLayout {
+    ///     start_lba: Lba(2),
+    ///     entry_size: GptPartitionEntrySize::new(128).unwrap(),
+    ///     num_entries: 4,
+    /// };
+    /// let mut storage = [0; 512];
+    /// let mut array =
+    ///     GptPartitionEntryArray::new(layout, BlockSize::BS_512, &mut storage)
+    ///         .unwrap();
+    ///
+    /// for entry in array.iter_mut() {
+    ///     entry.attributes.update_required_partition(true);
+    /// }
+    /// assert!(array.iter().all(|e| e.attributes.required_partition()));
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    pub fn iter(&self) -> impl Iterator<Item = &GptPartitionEntry> + '_ {
+        let entry_size = self.entry_chunk_size();
+        self.storage[..self.num_bytes_exact]
+            .chunks_exact(entry_size)
+            .map(|chunk| {
+                from_bytes(&chunk[..mem::size_of::<GptPartitionEntry>()])
+            })
+    }
+
+    /// Mutably iterate over all entries in the array, including unused
+    /// ones. See [`iter`] for an example.
+    ///
+    /// [`iter`]: Self::iter
+    #[cfg(feature = "bytemuck")]
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut GptPartitionEntry> + '_ {
+        let entry_size = self.entry_chunk_size();
+        self.storage[..self.num_bytes_exact]
+            .chunks_exact_mut(entry_size)
+            .map(|chunk| {
+                from_bytes_mut(
+                    &mut chunk[..mem::size_of::<GptPartitionEntry>()],
+                )
+            })
+    }
+
+    /// Get a partition entry reference by [`PartitionIndex`].
+    #[cfg(feature = "bytemuck")]
+    #[must_use]
+    pub fn get(&self, index: PartitionIndex) -> Option<&GptPartitionEntry> {
+        self.get_partition_entry(index.to_u32())
+    }
+
+    /// Get a mutable partition entry reference by [`PartitionIndex`].
+    #[cfg(feature = "bytemuck")]
+    #[must_use]
+    pub fn get_mut(
+        &mut self,
+        index: PartitionIndex,
+    ) -> Option<&mut GptPartitionEntry> {
+        self.get_partition_entry_mut(index.to_u32())
+    }
+
+    /// Find the used entry whose [`unique_partition_guid`] matches
+    /// `partuuid`, as it would appear in a `root=PARTUUID=...` kernel
+    /// parameter. The comparison ignores case. Returns `None` if no
+    /// entry matches or `partuuid` is not a valid GUID.
+    ///
+    /// [`unique_partition_guid`]: GptPartitionEntry::unique_partition_guid
+    #[allow(clippy::missing_panics_doc)]
+    #[cfg(feature = "bytemuck")]
+    #[must_use]
+    pub fn find_by_partuuid(
+        &self,
+        partuuid: &str,
+    ) -> Option<(PartitionIndex, &GptPartitionEntry)> {
+        let guid = parse_partuuid(partuuid)?;
+        self.iter()
+            .enumerate()
+            .find(|(_, entry)| {
+                entry.is_used() && { entry.unique_partition_guid } == guid
+            })
+            // OK to unwrap: the number of entries is a `u32`.
+            .map(|(i, entry)| {
+                (PartitionIndex::new(u32::try_from(i).unwrap()), entry)
+            })
+    }
+
     /// Calculate the CRC32 checksum for the partition entry array. The
     /// return value can then be set in the
     /// [`GptHeader::partition_entry_array_crc32`] field.
@@ -247,9 +497,31 @@ impl<'a> GptPartitionEntryArray<'a> {
     /// [`GptHeader::partition_entry_array_crc32`]: crate::GptHeader::partition_entry_array_crc32
     #[must_use]
     pub fn calculate_crc32(&self) -> Crc32 {
-        let crc = crc::Crc::<u32>::new(&Crc32::ALGORITHM);
-        let mut digest = crc.digest();
-        digest.update(&self.storage[..self.num_bytes_exact]);
-        Crc32(U32Le(digest.finalize().to_le_bytes()))
+        Crc32::of(&self.storage[..self.num_bytes_exact])
+    }
+}
+
+impl Debug for GptPartitionEntryArray<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("GptPartitionEntryArray");
+        s.field("layout", &self.layout);
+        #[cfg(feature = "bytemuck")]
+        s.field(
+            "num_used_entries",
+            &self.iter().filter(|e| e.is_used()).count(),
+        );
+        s.finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl Display for GptPartitionEntryArray<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (entry, index) in self.iter().zip(0..) {
+            if entry.is_used() {
+                writeln!(f, "{}: {}", PartitionIndex::new(index), entry)?;
+            }
+        }
+        Ok(())
     }
 }
@@ -13,12 +13,26 @@ use core::fmt::{self, Display, Formatter};
 use core::num::NonZeroU32;
 use core::str::FromStr;
 
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+
 #[cfg(feature = "bytemuck")]
 use bytemuck::{Pod, Zeroable};
 
 /// Unique ID representing the type of a partition.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(transparent)
+)]
 #[repr(transparent)]
 pub struct GptPartitionType(pub Guid);
 
@@ -54,8 +68,143 @@ impl GptPartitionType {
     pub const CHROME_OS_ROOT_FS: Self =
         Self(guid!("3cb8e202-3b7e-47dd-8a3c-7ff2a13cfcec"));
 
-    // TODO: there are many more "known" partition types for which we
-    // could add constants.
+    /// Microsoft reserved partition (MSR).
+    pub const MICROSOFT_RESERVED: Self =
+        Self(guid!("e3c9e316-0b5c-4db8-817d-f92df00215ae"));
+
+    /// Windows recovery environment partition.
+    pub const WINDOWS_RECOVERY: Self =
+        Self(guid!("de94bba4-06d1-4d40-a16a-bfd50179d6ac"));
+
+    /// BIOS boot partition, used by GRUB on legacy BIOS systems to hold
+    /// its core image.
+    pub const BIOS_BOOT: Self =
+        Self(guid!("21686148-6449-6e6f-744e-656564454649"));
+
+    /// The partition type constants defined on this type, other than
+    /// [`UNUSED`], along with the name of each constant.
+    ///
+    /// The architecture-specific types of the Discoverable Partitions
+    /// Specification, returned by [`root_for_arch`] and
+    /// [`usr_for_arch`], are not constants and are not included.
+    ///
+    /// This can be used to list the supported types in a user
+    /// interface. See also [`from_name`] and [`name`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::GptPartitionType;
+    ///
+    /// for (name, partition_type) in GptPartitionType::KNOWN_TYPES {
+    ///     println!("{name}: {partition_type}");
+    /// }
+    /// ```
+    ///
+    /// [`UNUSED`]: Self::UNUSED
+    /// [`from_name`]: Self::from_name
+    /// [`name`]: Self::name
+    /// [`root_for_arch`]: Self::root_for_arch
+    /// [`usr_for_arch`]: Self::usr_for_arch
+    pub const KNOWN_TYPES: &'static [(&'static str, Self)] = &[
+        ("EFI_SYSTEM", Self::EFI_SYSTEM),
+        ("LEGACY_MBR", Self::LEGACY_MBR),
+        ("BASIC_DATA", Self::BASIC_DATA),
+        ("CHROME_OS_KERNEL", Self::CHROME_OS_KERNEL),
+        ("CHROME_OS_ROOT_FS", Self::CHROME_OS_ROOT_FS),
+        ("MICROSOFT_RESERVED", Self::MICROSOFT_RESERVED),
+        ("WINDOWS_RECOVERY", Self::WINDOWS_RECOVERY),
+        ("BIOS_BOOT", Self::BIOS_BOOT),
+    ];
+
+    /// Look up a type in [`KNOWN_TYPES`] by name. The comparison is
+    /// ASCII case-insensitive, and `-` is treated the same as `_`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::GptPartitionType;
+    ///
+    /// assert_eq!(
+    ///     GptPartitionType::from_name("efi-system"),
+    ///     Some(GptPartitionType::EFI_SYSTEM)
+    /// );
+    /// assert_eq!(GptPartitionType::from_name("unknown"), None);
+    /// ```
+    ///
+    /// [`KNOWN_TYPES`]: Self::KNOWN_TYPES
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let normalize = |c: u8| {
+            if c == b'-' {
+                b'_'
+            } else {
+                c.to_ascii_uppercase()
+            }
+        };
+        Self::KNOWN_TYPES
+            .iter()
+            .find(|(known, _)| {
+                known.len() == name.len()
+                    && known
+                        .bytes()
+                        .zip(name.bytes())
+                        .all(|(a, b)| a == normalize(b))
+            })
+            .map(|(_, partition_type)| *partition_type)
+    }
+
+    /// Get the name of this type in [`KNOWN_TYPES`], or `None` if it is
+    /// not a known type.
+    ///
+    /// [`KNOWN_TYPES`]: Self::KNOWN_TYPES
+    #[must_use]
+    pub fn name(self) -> Option<&'static str> {
+        Self::KNOWN_TYPES
+            .iter()
+            .find(|(_, partition_type)| *partition_type == self)
+            .map(|(name, _)| *name)
+    }
+
+    /// Create a `GptPartitionType` from a [`Guid`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{guid, GptPartitionType};
+    ///
+    /// const LINUX_FS: GptPartitionType =
+    ///     GptPartitionType::new(guid!("0fc63daf-8483-4772-8e79-3d69d8477de4"));
+    /// assert_eq!(LINUX_FS, guid!("0fc63daf-8483-4772-8e79-3d69d8477de4"));
+    /// ```
+    #[must_use]
+    pub const fn new(guid: Guid) -> Self {
+        Self(guid)
+    }
+}
+
+impl From<Guid> for GptPartitionType {
+    fn from(guid: Guid) -> Self {
+        Self(guid)
+    }
+}
+
+impl From<GptPartitionType> for Guid {
+    fn from(partition_type: GptPartitionType) -> Self {
+        partition_type.0
+    }
+}
+
+impl PartialEq<Guid> for GptPartitionType {
+    fn eq(&self, other: &Guid) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<GptPartitionType> for Guid {
+    fn eq(&self, other: &GptPartitionType) -> bool {
+        *self == other.0
+    }
 }
 
 impl Display for GptPartitionType {
@@ -80,6 +229,12 @@ impl FromStr for GptPartitionType {
 /// Partition attribute bits.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct GptPartitionAttributes(pub U64Le);
 
@@ -97,15 +252,16 @@ impl GptPartitionAttributes {
     /// by UEFI boot loaders.
     pub const LEGACY_BIOS_BOOTABLE_BIT: u8 = 2;
 
-    fn get_bit(self, bit: u8) -> bool {
-        self.0 .0[0] & (1 << bit) != 0
+    pub(crate) fn get_bit(self, bit: u8) -> bool {
+        self.0 .0[usize::from(bit / 8)] & (1 << (bit % 8)) != 0
     }
 
-    fn set_bit(&mut self, bit: u8, set: bool) {
+    pub(crate) fn set_bit(&mut self, bit: u8, set: bool) {
+        let byte = &mut self.0 .0[usize::from(bit / 8)];
         if set {
-            self.0 .0[0] |= 1 << bit;
+            *byte |= 1 << (bit % 8);
         } else {
-            self.0 .0[0] &= !(1 << bit);
+            *byte &= !(1 << (bit % 8));
         }
     }
 
@@ -171,6 +327,47 @@ impl GptPartitionAttributes {
     }
 }
 
+impl GptPartitionAttributes {
+    /// Get the attributes conventionally set on a new partition of type
+    /// `partition_type`:
+    ///
+    /// * [`GptPartitionType::WINDOWS_RECOVERY`] has the
+    ///   [`REQUIRED_PARTITION_BIT`] and type-specific bit 63, which tells
+    ///   Windows not to assign a drive letter
+    ///   (`0x8000000000000001`). This is the value used by Windows setup.
+    /// * [`GptPartitionType::CHROME_OS_KERNEL`] has no attributes. The
+    ///   kernel's priority, tries, and successful bits are all zero, so
+    ///   the firmware will not try to boot it until an installer or
+    ///   updater marks it bootable.
+    /// * All other types, including [`GptPartitionType::EFI_SYSTEM`] and
+    ///   [`GptPartitionType::MICROSOFT_RESERVED`], have no
+    ///   attributes. An EFI system partition can be marked required
+    ///   with [`update_required_partition`] if the platform needs it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{GptPartitionAttributes, GptPartitionType};
+    ///
+    /// let recovery = GptPartitionType::WINDOWS_RECOVERY;
+    /// let attrs = GptPartitionAttributes::conventional_for(recovery);
+    /// assert!(attrs.required_partition());
+    /// assert_eq!(attrs.0.to_u64(), 0x8000_0000_0000_0001);
+    /// ```
+    ///
+    /// [`REQUIRED_PARTITION_BIT`]: Self::REQUIRED_PARTITION_BIT
+    /// [`update_required_partition`]: Self::update_required_partition
+    #[must_use]
+    pub fn conventional_for(partition_type: GptPartitionType) -> Self {
+        let mut attrs = Self::default();
+        if partition_type == GptPartitionType::WINDOWS_RECOVERY {
+            attrs.update_required_partition(true);
+            attrs.update_type_specific_attributes(U16Le::from_u16(0x8000));
+        }
+        attrs
+    }
+}
+
 impl Display for GptPartitionAttributes {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut first = true;
@@ -279,6 +476,12 @@ impl Display for GptPartitionNameSetCharError {
 /// let partition_name: GptPartitionName = "hacktheplanet".parse().unwrap();
 /// ```
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(transparent)]
 pub struct GptPartitionName(pub [u8; 72]);
 
@@ -309,6 +512,145 @@ impl GptPartitionName {
         }
     }
 
+    /// Check if the name is equal to `s`, ignoring case. Like the
+    /// [`PartialEq<str>`] implementation, the comparison stops at the
+    /// first null terminator in the name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::GptPartitionName;
+    ///
+    /// let name: GptPartitionName = "EFI System".parse().unwrap();
+    /// assert!(name.matches_ignore_case("efi system"));
+    /// assert!(!name.matches_ignore_case("efi"));
+    /// ```
+    #[must_use]
+    pub fn matches_ignore_case(&self, s: &str) -> bool {
+        self.eq_by(s, |a, b| a.to_lowercase().eq(b.to_lowercase()))
+    }
+
+    /// Create a name from a UTF-8 string.
+    ///
+    /// The UEFI Specification does not require a null terminator if the
+    /// name uses all 36 characters. If `require_null_terminator` is
+    /// true, the name is limited to 35 characters so that it is always
+    /// null terminated. This matches the behavior of [`from_str`] in
+    /// older versions of this crate, and may be needed for
+    /// compatibility with tools that expect the terminator.
+    ///
+    /// [`from_str`]: Self::from_str
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{GptPartitionName, GptPartitionNameFromStrError};
+    ///
+    /// let s = "abcdefghijklmnopqrstuvwxyz0123456789";
+    /// assert_eq!(s.len(), 36);
+    ///
+    /// assert!(GptPartitionName::from_str_with_options(s, false).is_ok());
+    /// assert_eq!(
+    ///     GptPartitionName::from_str_with_options(s, true),
+    ///     Err(GptPartitionNameFromStrError::Length)
+    /// );
+    /// ```
+    pub fn from_str_with_options(
+        s: &str,
+        require_null_terminator: bool,
+    ) -> Result<Self, GptPartitionNameFromStrError> {
+        let mut name = Self::default();
+
+        let max_index = if require_null_terminator {
+            name.0.len() - 2
+        } else {
+            name.0.len()
+        };
+
+        let mut index = 0;
+        ucs2::encode_with(s, |c| {
+            if index >= max_index {
+                Err(ucs2::Error::BufferOverflow)
+            } else {
+                name.0[index..index + 2].copy_from_slice(&c.to_le_bytes());
+                index += 2;
+                Ok(())
+            }
+        })?;
+        Ok(name)
+    }
+
+    /// Create a name from UCS-2 code units. No validation of the code
+    /// units is done, so this can be used to round-trip names that
+    /// contain invalid characters.
+    ///
+    /// `units` can contain at most 36 code units. A null terminator is
+    /// only added if there are fewer than that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::GptPartitionName;
+    ///
+    /// let units: Vec<u16> = "EFI".encode_utf16().collect();
+    /// let name = GptPartitionName::from_code_units(&units).unwrap();
+    /// assert_eq!(name, "EFI");
+    /// assert!(name.code_units().eq(units));
+    /// ```
+    pub fn from_code_units(
+        units: &[u16],
+    ) -> Result<Self, GptPartitionNameFromStrError> {
+        let mut name = Self::default();
+
+        if units.len() > name.0.len() / 2 {
+            return Err(GptPartitionNameFromStrError::Length);
+        }
+
+        for (dst, unit) in name.0.chunks_exact_mut(2).zip(units) {
+            dst.copy_from_slice(&unit.to_le_bytes());
+        }
+        Ok(name)
+    }
+
+    /// Get an iterator over the UCS-2 code units in the name. Iteration
+    /// ends when either the end of the array or a null terminator is
+    /// reached. The null terminator is not included in the iteration
+    /// output. Unlike [`chars`], no decoding is done, so invalid
+    /// characters are returned as-is.
+    ///
+    /// [`chars`]: Self::chars
+    pub fn code_units(&self) -> impl Iterator<Item = u16> + '_ {
+        self.0
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|c| *c != 0)
+    }
+
+    /// Compare the name to `s` character by character with `eq`. Code
+    /// units that aren't valid characters (unpaired surrogates) never
+    /// match.
+    fn eq_by<F>(&self, s: &str, mut eq: F) -> bool
+    where
+        F: FnMut(char, char) -> bool,
+    {
+        let mut units = self.code_units();
+        let mut chars = s.chars();
+        loop {
+            match (units.next(), chars.next()) {
+                (None, None) => return true,
+                (Some(unit), Some(c2)) => {
+                    let Ok(c1) = char::try_from(u32::from(unit)) else {
+                        return false;
+                    };
+                    if !eq(c1, c2) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+    }
+
     /// Set a UCS-2 character. The `index` is by UCS-2 character rather
     /// than byte (e.g. index 3 indicates byte offset 6). This is valid
     /// because UCS-2 is a fixed-width encoding.
@@ -318,7 +660,7 @@ impl GptPartitionName {
         c: char,
     ) -> Result<(), GptPartitionNameSetCharError> {
         // Ensure the index is valid.
-        if index > self.0.len() / 2 {
+        if index >= self.0.len() / 2 {
             return Err(GptPartitionNameSetCharError::Index);
         }
 
@@ -340,6 +682,23 @@ impl Display for GptPartitionName {
     }
 }
 
+/// Compare the name to a string. The comparison stops at the first null
+/// terminator in the name.
+///
+/// This avoids allocating a `String` with the [`Display`] implementation
+/// just to compare names.
+impl PartialEq<str> for GptPartitionName {
+    fn eq(&self, other: &str) -> bool {
+        self.eq_by(other, |a, b| a == b)
+    }
+}
+
+impl PartialEq<&str> for GptPartitionName {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
 impl Default for GptPartitionName {
     fn default() -> Self {
         Self([0; 72])
@@ -382,24 +741,14 @@ impl From<ucs2::Error> for GptPartitionNameFromStrError {
 impl FromStr for GptPartitionName {
     type Err = GptPartitionNameFromStrError;
 
+    /// Create a name from a UTF-8 string. The name can be up to 36
+    /// characters long; a null terminator is only added if the name is
+    /// shorter than that.
+    ///
+    /// To leave room for a null terminator, as older versions of this
+    /// crate did, use [`GptPartitionName::from_str_with_options`].
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut name = Self::default();
-
-        // Leave room for null terminator.
-        let max_index = name.0.len() - 2 - 1;
-
-        let mut index = 0;
-        ucs2::encode_with(s, |c| {
-            if index >= max_index {
-                Err(ucs2::Error::BufferOverflow)
-            } else {
-                name.0[index] = u8::try_from(c & 0xff).unwrap();
-                name.0[index + 1] = u8::try_from((c & 0xff00) >> 8).unwrap();
-                index += 2;
-                Ok(())
-            }
-        })?;
-        Ok(name)
+        Self::from_str_with_options(s, false)
     }
 }
 
@@ -430,6 +779,10 @@ pub struct GptPartitionEntry {
     pub name: GptPartitionName,
 }
 
+// The derive macros can't be used because the struct is packed.
+#[cfg(feature = "rkyv")]
+impl_rkyv_archive_as_self!(GptPartitionEntry);
+
 impl GptPartitionEntry {
     /// Get the range of blocks covered by this partition. Returns
     /// `None` if the `ending_lba` is less than the `starting_lba`.
@@ -448,6 +801,73 @@ impl GptPartitionEntry {
         let partition_type_guid = self.partition_type_guid;
         partition_type_guid != GptPartitionType::UNUSED
     }
+
+    /// Get a copy of the entry with [`attributes`] set to the
+    /// conventional value for its [`partition_type_guid`]. See
+    /// [`GptPartitionAttributes::conventional_for`].
+    ///
+    /// ```
+    /// use gpt_disk_types::{GptPartitionEntry, GptPartitionType};
+    ///
+    /// let entry = GptPartitionEntry {
+    ///     partition_type_guid: GptPartitionType::WINDOWS_RECOVERY,
+    ///     ..Default::default()
+    /// }
+    /// .with_conventional_attributes();
+    /// assert!({ entry.attributes }.required_partition());
+    /// ```
+    ///
+    /// [`attributes`]: Self::attributes
+    /// [`partition_type_guid`]: Self::partition_type_guid
+    #[must_use]
+    pub fn with_conventional_attributes(mut self) -> Self {
+        self.attributes =
+            GptPartitionAttributes::conventional_for(self.partition_type_guid);
+        self
+    }
+
+    /// Format the [`unique_partition_guid`] the way Linux expects it in
+    /// a `root=PARTUUID=...` kernel parameter or an fstab entry: as a
+    /// lowercase hyphenated string.
+    ///
+    /// ```
+    /// use gpt_disk_types::{guid, GptPartitionEntry};
+    ///
+    /// let entry = GptPartitionEntry {
+    ///     unique_partition_guid: guid!("37C75FFD-8932-467A-9C56-8CF1F0456B12"),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     entry.partuuid_string(),
+    ///     "37c75ffd-8932-467a-9c56-8cf1f0456b12"
+    /// );
+    /// ```
+    ///
+    /// [`unique_partition_guid`]: Self::unique_partition_guid
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn partuuid_string(&self) -> String {
+        let guid = self.unique_partition_guid;
+        guid.to_string()
+    }
+
+    /// Format the [`name`] for use as a `PARTLABEL`. Characters that
+    /// are not valid UCS-2 are replaced as described in
+    /// [`GptPartitionName::chars`].
+    ///
+    /// [`name`]: Self::name
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn partlabel_string(&self) -> String {
+        self.name.to_string()
+    }
+}
+
+/// Parse a `PARTUUID` string, as produced by
+/// [`GptPartitionEntry::partuuid_string`]. Case is ignored. Returns
+/// `None` if `partuuid` is not a valid GUID.
+pub(crate) fn parse_partuuid(partuuid: &str) -> Option<Guid> {
+    partuuid.parse().ok()
 }
 
 impl Display for GptPartitionEntry {
@@ -0,0 +1,1451 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::partition_entry::parse_partuuid;
+use crate::{
+    BlockSize, GptDiskLayout, GptPartitionAttributes, GptPartitionEntry,
+    GptPartitionName, GptPartitionType, Guid, Lba, LbaLe, LbaRangeInclusive,
+    PartitionIndex,
+};
+use core::fmt::{self, Display, Formatter};
+use core::hash::{Hash, Hasher};
+
+#[cfg(feature = "bytemuck")]
+use crate::{GptHeader, GptPartitionEntryArray};
+
+/// Error type for [`GptPartitionTable`].
+///
+/// If the `std` feature is enabled, this type implements the [`Error`]
+/// trait.
+///
+/// [`Error`]: std::error::Error
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum GptPartitionTableError {
+    /// The table's capacity is larger than the number of entries in the
+    /// layout's partition entry array.
+    CapacityTooLarge,
+
+    /// Every entry in the table is already in use.
+    Full,
+
+    /// The entry being added or modified is not in use.
+    UnusedEntry,
+
+    /// The partition's `ending_lba` is less than its `starting_lba`.
+    InvalidLbaRange {
+        /// Index of the invalid entry.
+        index: PartitionIndex,
+    },
+
+    /// The partition is not entirely within the layout's usable range.
+    OutOfBounds {
+        /// Index of the invalid entry.
+        index: PartitionIndex,
+    },
+
+    /// The partition overlaps another partition.
+    Overlap {
+        /// Index of the invalid entry.
+        index: PartitionIndex,
+
+        /// Index of the partition it overlaps.
+        other: PartitionIndex,
+    },
+
+    /// The partition entry array passed to
+    /// [`GptPartitionTable::write_partition_entry_array`] has fewer
+    /// entries than the table's capacity.
+    ArrayTooSmall,
+
+    /// There is no free range of blocks large enough for a partition in
+    /// the layout passed to [`GptPartitionTable::apply_layout`].
+    NoSpace,
+}
+
+impl Display for GptPartitionTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CapacityTooLarge => f.write_str(
+                "table capacity is larger than the partition entry array",
+            ),
+            Self::Full => f.write_str("partition table is full"),
+            Self::UnusedEntry => f.write_str("partition entry is not in use"),
+            Self::InvalidLbaRange { index } => {
+                write!(f, "partition {index} has an invalid LBA range")
+            }
+            Self::OutOfBounds { index } => {
+                write!(f, "partition {index} is outside the usable range")
+            }
+            Self::Overlap { index, other } => {
+                write!(f, "partition {index} overlaps partition {other}")
+            }
+            Self::ArrayTooSmall => f.write_str(
+                "partition entry array is smaller than the table capacity",
+            ),
+            Self::NoSpace => f.write_str("not enough free space for partition"),
+        }
+    }
+}
+
+/// Description of a partition in a desired layout. See
+/// [`GptPartitionTable::apply_layout`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct GptPartitionDescription {
+    /// Type of the partition.
+    pub partition_type: GptPartitionType,
+
+    /// Unique GUID of the partition. If this is not zero, existing
+    /// partitions are matched by this GUID.
+    pub unique_partition_guid: Guid,
+
+    /// Name of the partition. If [`unique_partition_guid`] is zero,
+    /// existing partitions are matched by type and name.
+    ///
+    /// [`unique_partition_guid`]: Self::unique_partition_guid
+    pub name: GptPartitionName,
+
+    /// Attributes of the partition. Only used when creating the
+    /// partition.
+    pub attributes: GptPartitionAttributes,
+
+    /// Size of the partition in blocks.
+    pub num_blocks: u64,
+}
+
+impl GptPartitionDescription {
+    /// Describe an EFI system partition of `num_blocks` blocks.
+    ///
+    /// The partition has type [`GptPartitionType::EFI_SYSTEM`], the
+    /// name "EFI System Partition", and the attributes returned by
+    /// [`GptPartitionAttributes::conventional_for`].
+    ///
+    /// The size is checked against what firmware commonly expects, and
+    /// an [`EspSizeWarning`] is returned along with the description if
+    /// it is too small. The description is usable either way; whether
+    /// to proceed is up to the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{
+    ///     guid, BlockSize, EspSizeWarning, GptPartitionDescription,
+    ///     GptPartitionType,
+    /// };
+    ///
+    /// let guid = guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12");
+    ///
+    /// // 512 MiB.
+    /// let (esp, warning) =
+    ///     GptPartitionDescription::esp(guid, 1024 * 1024, BlockSize::BS_512);
+    /// assert_eq!(esp.partition_type, GptPartitionType::EFI_SYSTEM);
+    /// assert_eq!(warning, None);
+    ///
+    /// // 16 MiB.
+    /// let (_, warning) =
+    ///     GptPartitionDescription::esp(guid, 32 * 1024, BlockSize::BS_512);
+    /// assert_eq!(warning, Some(EspSizeWarning::TooSmallForFat32));
+    /// ```
+    #[allow(clippy::missing_panics_doc)]
+    #[must_use]
+    pub fn esp(
+        unique_partition_guid: Guid,
+        num_blocks: u64,
+        block_size: BlockSize,
+    ) -> (Self, Option<EspSizeWarning>) {
+        let partition_type = GptPartitionType::EFI_SYSTEM;
+        let desc = Self {
+            partition_type,
+            unique_partition_guid,
+            // OK to unwrap: the name is short enough.
+            name: "EFI System Partition".parse().unwrap(),
+            attributes: GptPartitionAttributes::conventional_for(
+                partition_type,
+            ),
+            num_blocks,
+        };
+        (desc, EspSizeWarning::check(num_blocks, block_size))
+    }
+
+    /// Check if `entry` is the partition described by `self`.
+    fn matches(&self, entry: &GptPartitionEntry) -> bool {
+        let (unique_partition_guid, partition_type, name) = (
+            entry.unique_partition_guid,
+            entry.partition_type_guid,
+            entry.name,
+        );
+        if self.unique_partition_guid.is_zero() {
+            partition_type == self.partition_type && name == self.name
+        } else {
+            unique_partition_guid == self.unique_partition_guid
+        }
+    }
+}
+
+/// Warning about the size of an EFI system partition created with
+/// [`GptPartitionDescription::esp`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum EspSizeWarning {
+    /// The partition is too small to hold a FAT32 filesystem, which
+    /// needs at least 65525 clusters. Some firmware only reads FAT32
+    /// EFI system partitions.
+    TooSmallForFat32,
+
+    /// The partition can hold a FAT32 filesystem, but is smaller than
+    /// the commonly recommended minimum of 100 MiB (260 MiB on disks
+    /// with 4096-byte blocks).
+    BelowRecommended,
+}
+
+impl Display for EspSizeWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooSmallForFat32 => {
+                f.write_str("EFI system partition is too small for FAT32")
+            }
+            Self::BelowRecommended => f.write_str(
+                "EFI system partition is smaller than the recommended size",
+            ),
+        }
+    }
+}
+
+impl EspSizeWarning {
+    /// Minimum number of clusters in a FAT32 filesystem.
+    const FAT32_MIN_CLUSTERS: u64 = 65525;
+
+    /// Number of reserved sectors conventionally used by FAT32.
+    const FAT32_RESERVED_SECTORS: u64 = 32;
+
+    /// Get the warning, if any, for an EFI system partition of
+    /// `num_blocks` blocks.
+    fn check(num_blocks: u64, block_size: BlockSize) -> Option<Self> {
+        let bs = block_size.to_u64();
+
+        // Smallest FAT32 filesystem: one block per cluster, the
+        // reserved sectors, and two FATs with four bytes per cluster
+        // (plus two reserved FAT entries).
+        let fat_bytes = (Self::FAT32_MIN_CLUSTERS + 2) * 4;
+        let fat_blocks = (fat_bytes + bs - 1) / bs;
+        let fat32_min_blocks = Self::FAT32_MIN_CLUSTERS
+            + Self::FAT32_RESERVED_SECTORS
+            + 2 * fat_blocks;
+
+        let recommended_mib = if bs >= 4096 { 260 } else { 100 };
+        let recommended_blocks = (recommended_mib * 1024 * 1024) / bs;
+
+        if num_blocks < fat32_min_blocks {
+            Some(Self::TooSmallForFat32)
+        } else if num_blocks < recommended_blocks {
+            Some(Self::BelowRecommended)
+        } else {
+            None
+        }
+    }
+}
+
+/// Options for [`GptPartitionTable::apply_layout`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ApplyLayoutPolicy {
+    /// If true, existing partitions that are smaller than described are
+    /// grown in place, if there is free space directly after them.
+    /// Partitions are never shrunk or moved.
+    pub allow_grow: bool,
+
+    /// Alignment in blocks of the first block of new partitions. Zero
+    /// is treated the same as one, meaning no alignment.
+    pub alignment_blocks: u64,
+}
+
+impl Default for ApplyLayoutPolicy {
+    fn default() -> Self {
+        Self {
+            allow_grow: false,
+            alignment_blocks: 1,
+        }
+    }
+}
+
+/// Change made (or not made) to a partition by
+/// [`GptPartitionTable::apply_layout`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum GptLayoutChange {
+    /// An existing partition matched the description and was left
+    /// unchanged.
+    Unchanged(PartitionIndex),
+
+    /// A new partition was created.
+    Created(PartitionIndex),
+
+    /// An existing partition was grown in place.
+    Grown {
+        /// Index of the partition.
+        index: PartitionIndex,
+
+        /// Size of the partition in blocks before it was grown.
+        old_num_blocks: u64,
+    },
+}
+
+/// Difference between a [`GptPartitionTable`] and an earlier
+/// [`GptPartitionTableSnapshot`] of it. See
+/// [`GptPartitionTable::pending_changes`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum GptChange {
+    /// A partition was created in a previously unused entry, or an
+    /// entry was replaced with a partition that has a different
+    /// [`unique_partition_guid`].
+    ///
+    /// [`unique_partition_guid`]: GptPartitionEntry::unique_partition_guid
+    CreatePartition(PartitionIndex),
+
+    /// A partition was deleted, or replaced with a partition that has a
+    /// different [`unique_partition_guid`].
+    ///
+    /// [`unique_partition_guid`]: GptPartitionEntry::unique_partition_guid
+    DeletePartition(PartitionIndex),
+
+    /// A partition's starting or ending LBA changed.
+    SetRange(PartitionIndex),
+
+    /// A partition's type changed.
+    SetType(PartitionIndex),
+
+    /// A partition's name changed.
+    SetName(PartitionIndex),
+
+    /// A partition's attributes changed.
+    SetAttributes(PartitionIndex),
+}
+
+impl GptChange {
+    /// Index of the partition entry that changed.
+    #[must_use]
+    pub fn index(self) -> PartitionIndex {
+        match self {
+            Self::CreatePartition(index)
+            | Self::DeletePartition(index)
+            | Self::SetRange(index)
+            | Self::SetType(index)
+            | Self::SetName(index)
+            | Self::SetAttributes(index) => index,
+        }
+    }
+
+    /// Get the changes to a single entry, in the order they are
+    /// reported by [`GptPartitionTable::pending_changes`].
+    fn between(
+        index: PartitionIndex,
+        old: &GptPartitionEntry,
+        new: &GptPartitionEntry,
+    ) -> [Option<Self>; 4] {
+        let same_partition =
+            old.is_used() && new.is_used() && { old.unique_partition_guid }
+                == { new.unique_partition_guid };
+        if !same_partition {
+            return [
+                old.is_used().then_some(Self::DeletePartition(index)),
+                new.is_used().then_some(Self::CreatePartition(index)),
+                None,
+                None,
+            ];
+        }
+
+        let range_changed =
+            { old.starting_lba } != { new.starting_lba } || { old.ending_lba }
+                != { new.ending_lba };
+        [
+            range_changed.then_some(Self::SetRange(index)),
+            ({ old.partition_type_guid } != { new.partition_type_guid })
+                .then_some(Self::SetType(index)),
+            ({ old.name } != { new.name }).then_some(Self::SetName(index)),
+            ({ old.attributes } != { new.attributes })
+                .then_some(Self::SetAttributes(index)),
+        ]
+    }
+}
+
+/// Fixed-capacity partition table that does not require allocation.
+///
+/// The table holds up to `N` partition entries in an array, along with
+/// the [`GptDiskLayout`] and disk GUID. `N` may be smaller than the
+/// number of entries in the layout's partition entry array; entries
+/// past `N` are written as unused. This allows a bootloader to work
+/// with a statically bounded number of partitions while still producing
+/// a standard 128-entry array on disk.
+///
+/// Partitions added with [`add`] are checked against the usable range
+/// and the existing partitions. Entries placed directly with
+/// [`from_entries`] can be checked with [`validate`].
+///
+/// Two tables are equal if they have the same layout, disk GUID, and
+/// entries. Whether they are dirty (see [`is_dirty`]) is not compared.
+///
+/// # Examples
+///
+/// ```
+/// use gpt_disk_types::{
+///     guid, BlockSize, GptDiskLayout, GptPartitionEntry, GptPartitionTable,
+///     GptPartitionTableError, GptPartitionType, LbaLe,
+/// };
+///
+/// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+/// let mut table =
+///     GptPartitionTable::<4>::new(layout, guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870"))
+///         .unwrap();
+///
+/// let entry = GptPartitionEntry {
+///     partition_type_guid: GptPartitionType::EFI_SYSTEM,
+///     unique_partition_guid: guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12"),
+///     starting_lba: LbaLe::from_u64(2048),
+///     ending_lba: LbaLe::from_u64(4095),
+///     ..Default::default()
+/// };
+/// let index = table.add(entry).unwrap();
+/// assert_eq!(index.to_u32(), 0);
+///
+/// let (found, _) = table.find_by_type(GptPartitionType::EFI_SYSTEM).unwrap();
+/// assert_eq!(found, index);
+///
+/// // Overlapping partitions are rejected.
+/// assert!(matches!(
+///     table.add(entry),
+///     Err(GptPartitionTableError::Overlap { .. })
+/// ));
+/// ```
+///
+/// [`add`]: Self::add
+/// [`from_entries`]: Self::from_entries
+/// [`is_dirty`]: Self::is_dirty
+/// [`validate`]: Self::validate
+#[derive(Clone, Copy, Debug)]
+pub struct GptPartitionTable<const N: usize> {
+    layout: GptDiskLayout,
+    disk_guid: Guid,
+    entries: [GptPartitionEntry; N],
+    dirty: bool,
+    // Incremented by `mark_clean`, so that `restore` can tell whether
+    // the table was saved after a snapshot was taken.
+    generation: u32,
+}
+
+// `dirty` and `generation` track changes to the table rather than its
+// contents, so they are left out of comparisons.
+impl<const N: usize> PartialEq for GptPartitionTable<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.layout == other.layout
+            && self.disk_guid == other.disk_guid
+            && self.entries == other.entries
+    }
+}
+
+impl<const N: usize> Eq for GptPartitionTable<N> {}
+
+impl<const N: usize> Hash for GptPartitionTable<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.disk_guid.hash(state);
+        self.entries.hash(state);
+    }
+}
+
+/// Statistics about a [`GptPartitionTable`], returned by
+/// [`GptPartitionTable::summary`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct GptPartitionTableSummary {
+    /// Number of entries in use.
+    pub num_used_entries: usize,
+
+    /// Number of unused entries, up to the table's capacity.
+    pub num_free_entries: usize,
+
+    /// Total size in bytes of the used entries' LBA ranges. Entries
+    /// with an invalid LBA range are not counted.
+    pub allocated_bytes: u64,
+
+    /// Largest range of blocks in the layout's usable range that is
+    /// not part of any partition, or `None` if there are no free
+    /// blocks.
+    pub largest_free_range: Option<LbaRangeInclusive>,
+
+    /// Number of used entries whose starting LBA is not a multiple of
+    /// the alignment passed to [`GptPartitionTable::summary`].
+    pub num_misaligned: usize,
+}
+
+/// Kind of a [`GptDiskRegion`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum GptDiskRegionKind {
+    /// The protective MBR in the first block.
+    Mbr,
+
+    /// The primary GPT header.
+    PrimaryHeader,
+
+    /// The primary partition entry array.
+    PrimaryPartitionEntryArray,
+
+    /// Blocks reserved between a partition entry array and the usable
+    /// range. See [`GptDiskLayout::reserve_leading_bytes`] and
+    /// [`GptDiskLayout::reserve_trailing_bytes`].
+    Reserved,
+
+    /// Blocks in the usable range that are not part of any partition.
+    Free,
+
+    /// The partition at the given index.
+    Partition(PartitionIndex),
+
+    /// The secondary partition entry array.
+    SecondaryPartitionEntryArray,
+
+    /// The secondary GPT header in the last block.
+    SecondaryHeader,
+}
+
+/// Range of blocks on a disk, classified by what it contains. Returned
+/// by [`GptPartitionTable::regions`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct GptDiskRegion {
+    /// What the blocks contain.
+    pub kind: GptDiskRegionKind,
+
+    /// Blocks in the region.
+    pub range: LbaRangeInclusive,
+}
+
+/// Saved state of a [`GptPartitionTable`], created with
+/// [`GptPartitionTable::snapshot`] and applied with
+/// [`GptPartitionTable::restore`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct GptPartitionTableSnapshot<const N: usize>(GptPartitionTable<N>);
+
+impl<const N: usize> GptPartitionTableSnapshot<N> {
+    /// Get all entries in the snapshot, including unused ones.
+    #[must_use]
+    pub fn entries(&self) -> &[GptPartitionEntry; N] {
+        &self.0.entries
+    }
+}
+
+impl<const N: usize> GptPartitionTable<N> {
+    /// Create an empty table.
+    ///
+    /// Returns [`GptPartitionTableError::CapacityTooLarge`] if `N` is
+    /// larger than the number of entries in `layout`.
+    pub fn new(
+        layout: GptDiskLayout,
+        disk_guid: Guid,
+    ) -> Result<Self, GptPartitionTableError> {
+        Self::from_entries(layout, disk_guid, [GptPartitionEntry::default(); N])
+    }
+
+    /// Create a table from existing entries, for example entries read
+    /// from a disk. The entries are not validated; call [`validate`] to
+    /// check them.
+    ///
+    /// Returns [`GptPartitionTableError::CapacityTooLarge`] if `N` is
+    /// larger than the number of entries in `layout`.
+    ///
+    /// [`validate`]: Self::validate
+    pub fn from_entries(
+        layout: GptDiskLayout,
+        disk_guid: Guid,
+        entries: [GptPartitionEntry; N],
+    ) -> Result<Self, GptPartitionTableError> {
+        let num_entries =
+            layout.primary_partition_entry_array_layout().num_entries;
+        if u32::try_from(N).map_or(true, |n| n > num_entries) {
+            return Err(GptPartitionTableError::CapacityTooLarge);
+        }
+
+        Ok(Self {
+            layout,
+            disk_guid,
+            entries,
+            dirty: false,
+            generation: 0,
+        })
+    }
+
+    /// Disk layout of the table.
+    #[must_use]
+    pub fn layout(&self) -> &GptDiskLayout {
+        &self.layout
+    }
+
+    /// GUID of the disk.
+    #[must_use]
+    pub fn disk_guid(&self) -> Guid {
+        self.disk_guid
+    }
+
+    /// Maximum number of entries in the table.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Get all entries in the table, including unused ones.
+    #[must_use]
+    pub fn entries(&self) -> &[GptPartitionEntry; N] {
+        &self.entries
+    }
+
+    /// Create the primary GPT header for the table, with both CRC32
+    /// fields set. Unused entries past the table's capacity are
+    /// included in the partition entry array checksum as zeroes.
+    #[cfg(feature = "bytemuck")]
+    #[must_use]
+    pub fn primary_header(&self) -> GptHeader {
+        self.header_with_crcs(self.layout.primary_header(self.disk_guid))
+    }
+
+    /// Create the secondary GPT header for the table, with both CRC32
+    /// fields set. See [`primary_header`].
+    ///
+    /// [`primary_header`]: Self::primary_header
+    #[cfg(feature = "bytemuck")]
+    #[must_use]
+    pub fn secondary_header(&self) -> GptHeader {
+        self.header_with_crcs(self.layout.secondary_header(self.disk_guid))
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn header_with_crcs(&self, mut header: GptHeader) -> GptHeader {
+        // OK to unwrap: `from_entries` checked that `N` is not larger
+        // than the number of entries in the layout, and the entry size
+        // is at most a `u32`.
+        header.partition_entry_array_crc32 = self
+            .layout
+            .primary_partition_entry_array_layout()
+            .calculate_crc32(self.entries.iter().copied())
+            .unwrap();
+        header.update_header_crc32();
+        header
+    }
+
+    /// Get an entry by [`PartitionIndex`].
+    #[must_use]
+    pub fn get(&self, index: PartitionIndex) -> Option<&GptPartitionEntry> {
+        self.entries.get(usize::try_from(index.to_u32()).ok()?)
+    }
+
+    /// Iterate over the used entries in the table.
+    pub fn iter_used(
+        &self,
+    ) -> impl Iterator<Item = (PartitionIndex, &GptPartitionEntry)> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.is_used())
+            .map(|(i, entry)| (Self::index(i), entry))
+    }
+
+    /// Add a partition in the first unused entry, and return its index.
+    ///
+    /// The partition must be in use, have a valid LBA range within the
+    /// layout's usable range, and must not overlap any existing
+    /// partition.
+    pub fn add(
+        &mut self,
+        entry: GptPartitionEntry,
+    ) -> Result<PartitionIndex, GptPartitionTableError> {
+        if !entry.is_used() {
+            return Err(GptPartitionTableError::UnusedEntry);
+        }
+        let i = self
+            .entries
+            .iter()
+            .position(|entry| !entry.is_used())
+            .ok_or(GptPartitionTableError::Full)?;
+        let index = Self::index(i);
+
+        self.check_entry(index, &entry)?;
+        self.entries[i] = entry;
+        self.dirty = true;
+        Ok(index)
+    }
+
+    /// Add a [`BIOS_BOOT`] partition of `num_blocks` blocks starting at
+    /// the layout's first usable LBA, right after the primary partition
+    /// entry array, and return its index.
+    ///
+    /// This is where GRUB expects to embed its core image when booting
+    /// from a GPT disk on a legacy BIOS system. See
+    /// `Disk::write_partition_image` in the `gpt_disk_io` crate for
+    /// writing the image.
+    ///
+    /// Fails in the same ways as [`add`], for example if another
+    /// partition already uses the start of the usable range.
+    ///
+    /// [`BIOS_BOOT`]: GptPartitionType::BIOS_BOOT
+    /// [`add`]: Self::add
+    #[allow(clippy::missing_panics_doc)]
+    pub fn add_bios_boot_partition(
+        &mut self,
+        unique_partition_guid: Guid,
+        num_blocks: u64,
+    ) -> Result<PartitionIndex, GptPartitionTableError> {
+        let start = self.layout.first_usable_lba().to_u64();
+        let end = match num_blocks.checked_sub(1) {
+            // Empty partition; `add` rejects the inverted range.
+            None => start - 1,
+            Some(n) => start.saturating_add(n),
+        };
+        let entry = GptPartitionEntry {
+            partition_type_guid: GptPartitionType::BIOS_BOOT,
+            unique_partition_guid,
+            starting_lba: Lba(start).into(),
+            ending_lba: Lba(end).into(),
+            attributes: GptPartitionAttributes::default(),
+            // OK to unwrap: the name is short enough.
+            name: "BIOS boot partition".parse().unwrap(),
+        };
+        self.add(entry)
+    }
+
+    /// Remove the partition at `index`, replacing it with an unused
+    /// entry. Returns the removed entry, or `None` if the index is out
+    /// of range or the entry is not in use.
+    pub fn remove(
+        &mut self,
+        index: PartitionIndex,
+    ) -> Option<GptPartitionEntry> {
+        let slot = self
+            .entries
+            .get_mut(usize::try_from(index.to_u32()).ok()?)?;
+        if !slot.is_used() {
+            return None;
+        }
+        self.dirty = true;
+        Some(core::mem::take(slot))
+    }
+
+    /// Change the starting LBA of the partition at `index` to
+    /// `new_start`, keeping its size. Returns the partition's previous
+    /// LBA range.
+    ///
+    /// Only the entry is updated; see `Disk::move_partition` in the
+    /// `gpt_disk_io` crate for moving the partition's data as well.
+    ///
+    /// Returns [`GptPartitionTableError::UnusedEntry`] if the index is
+    /// out of range or the entry is not in use. The new location must be
+    /// within the layout's usable range and must not overlap any other
+    /// partition.
+    pub fn move_partition(
+        &mut self,
+        index: PartitionIndex,
+        new_start: Lba,
+    ) -> Result<LbaRangeInclusive, GptPartitionTableError> {
+        let i = usize::try_from(index.to_u32())
+            .ok()
+            .filter(|&i| i < N && self.entries[i].is_used())
+            .ok_or(GptPartitionTableError::UnusedEntry)?;
+        let entry = self.entries[i];
+        let old_range = entry
+            .lba_range()
+            .ok_or(GptPartitionTableError::InvalidLbaRange { index })?;
+
+        let new_end = new_start
+            .to_u64()
+            .checked_add(old_range.num_blocks() - 1)
+            .ok_or(GptPartitionTableError::OutOfBounds { index })?;
+        let moved = GptPartitionEntry {
+            starting_lba: new_start.into(),
+            ending_lba: Lba(new_end).into(),
+            ..entry
+        };
+        self.check_entry(index, &moved)?;
+
+        if self.entries[i] != moved {
+            self.entries[i] = moved;
+            self.dirty = true;
+        }
+        Ok(old_range)
+    }
+
+    /// Change the name of the partition at `index`.
+    ///
+    /// Returns [`GptPartitionTableError::UnusedEntry`] if the index is
+    /// out of range or the entry is not in use.
+    pub fn set_name(
+        &mut self,
+        index: PartitionIndex,
+        name: GptPartitionName,
+    ) -> Result<(), GptPartitionTableError> {
+        let slot = usize::try_from(index.to_u32())
+            .ok()
+            .and_then(|i| self.entries.get_mut(i))
+            .filter(|entry| entry.is_used())
+            .ok_or(GptPartitionTableError::UnusedEntry)?;
+        if { slot.name } != name {
+            slot.name = name;
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Whether the table has been modified since it was created or
+    /// since the last call to [`mark_clean`].
+    ///
+    /// [`mark_clean`]: Self::mark_clean
+    #[must_use]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Mark the table as unmodified. Call this after the table has been
+    /// written to disk.
+    pub fn mark_clean(&mut self) {
+        self.dirty = false;
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Save the current state of the table so that it can be restored
+    /// later with [`restore`], for example to implement undo in an
+    /// interactive partition editor.
+    ///
+    /// [`restore`]: Self::restore
+    #[must_use]
+    pub fn snapshot(&self) -> GptPartitionTableSnapshot<N> {
+        GptPartitionTableSnapshot(*self)
+    }
+
+    /// Iterate over the differences between `snapshot` and the current
+    /// state of the table, in entry order.
+    ///
+    /// This can be used to show a list of pending operations before
+    /// writing the table to disk, by taking a snapshot when the table is
+    /// read or last written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{
+    ///     guid, BlockSize, GptChange, GptDiskLayout, GptPartitionEntry,
+    ///     GptPartitionTable, GptPartitionType, LbaLe, PartitionIndex,
+    /// };
+    ///
+    /// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    /// let mut table =
+    ///     GptPartitionTable::<4>::new(layout, guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870"))
+    ///         .unwrap();
+    /// let saved = table.snapshot();
+    ///
+    /// let index = table
+    ///     .add(GptPartitionEntry {
+    ///         partition_type_guid: GptPartitionType::EFI_SYSTEM,
+    ///         unique_partition_guid: guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12"),
+    ///         starting_lba: LbaLe::from_u64(2048),
+    ///         ending_lba: LbaLe::from_u64(4095),
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert!(table
+    ///     .pending_changes(&saved)
+    ///     .eq([GptChange::CreatePartition(index)]));
+    /// ```
+    pub fn pending_changes<'a>(
+        &'a self,
+        snapshot: &'a GptPartitionTableSnapshot<N>,
+    ) -> impl Iterator<Item = GptChange> + 'a {
+        snapshot
+            .0
+            .entries
+            .iter()
+            .zip(self.entries.iter())
+            .enumerate()
+            .flat_map(|(i, (old, new))| {
+                GptChange::between(Self::index(i), old, new)
+                    .into_iter()
+                    .flatten()
+            })
+    }
+
+    /// Return the table to the state saved in `snapshot`.
+    ///
+    /// The table is dirty afterwards if it was dirty when the snapshot
+    /// was taken, or if [`mark_clean`] has been called since then.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{
+    ///     guid, BlockSize, GptDiskLayout, GptPartitionEntry, GptPartitionTable,
+    ///     GptPartitionType, LbaLe,
+    /// };
+    ///
+    /// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    /// let mut table =
+    ///     GptPartitionTable::<4>::new(layout, guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870"))
+    ///         .unwrap();
+    /// let snapshot = table.snapshot();
+    ///
+    /// table
+    ///     .add(GptPartitionEntry {
+    ///         partition_type_guid: GptPartitionType::EFI_SYSTEM,
+    ///         unique_partition_guid: guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12"),
+    ///         starting_lba: LbaLe::from_u64(2048),
+    ///         ending_lba: LbaLe::from_u64(4095),
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap();
+    /// assert!(table.is_dirty());
+    ///
+    /// table.restore(&snapshot);
+    /// assert_eq!(table.iter_used().count(), 0);
+    /// assert!(!table.is_dirty());
+    /// ```
+    ///
+    /// [`mark_clean`]: Self::mark_clean
+    pub fn restore(&mut self, snapshot: &GptPartitionTableSnapshot<N>) {
+        let saved = snapshot.0;
+        self.layout = saved.layout;
+        self.disk_guid = saved.disk_guid;
+        self.entries = saved.entries;
+        self.dirty = saved.dirty || saved.generation != self.generation;
+    }
+
+    /// Find the used entry whose [`unique_partition_guid`] is `guid`.
+    ///
+    /// [`unique_partition_guid`]: GptPartitionEntry::unique_partition_guid
+    #[must_use]
+    pub fn find_by_unique_guid(
+        &self,
+        guid: Guid,
+    ) -> Option<(PartitionIndex, &GptPartitionEntry)> {
+        self.iter_used()
+            .find(|(_, entry)| { entry.unique_partition_guid } == guid)
+    }
+
+    /// Find the used entry whose [`unique_partition_guid`] matches
+    /// `partuuid`, as it would appear in a `root=PARTUUID=...` kernel
+    /// parameter. The comparison ignores case. Returns `None` if no
+    /// entry matches or `partuuid` is not a valid GUID.
+    ///
+    /// This behaves the same as
+    /// [`GptPartitionEntryArray::find_by_partuuid`].
+    ///
+    /// [`GptPartitionEntryArray::find_by_partuuid`]: crate::GptPartitionEntryArray::find_by_partuuid
+    /// [`unique_partition_guid`]: GptPartitionEntry::unique_partition_guid
+    #[must_use]
+    pub fn find_by_partuuid(
+        &self,
+        partuuid: &str,
+    ) -> Option<(PartitionIndex, &GptPartitionEntry)> {
+        self.find_by_unique_guid(parse_partuuid(partuuid)?)
+    }
+
+    /// Find the first used entry whose [`partition_type_guid`] is
+    /// `partition_type`.
+    ///
+    /// [`partition_type_guid`]: GptPartitionEntry::partition_type_guid
+    #[must_use]
+    pub fn find_by_type(
+        &self,
+        partition_type: GptPartitionType,
+    ) -> Option<(PartitionIndex, &GptPartitionEntry)> {
+        self.iter_used()
+            .find(|(_, entry)| { entry.partition_type_guid } == partition_type)
+    }
+
+    /// Find the first used entry whose name is `name`.
+    #[must_use]
+    pub fn find_by_name(
+        &self,
+        name: &str,
+    ) -> Option<(PartitionIndex, &GptPartitionEntry)> {
+        self.iter_used().find(|(_, entry)| { entry.name } == name)
+    }
+
+    /// Get statistics about the table, for example for health checks
+    /// across many disks. See [`GptPartitionTableSummary`].
+    ///
+    /// Partitions are checked for alignment to `alignment_blocks`. Zero
+    /// is treated the same as one, meaning no alignment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{
+    ///     guid, BlockSize, GptDiskLayout, GptPartitionEntry, GptPartitionTable,
+    ///     GptPartitionType, LbaLe,
+    /// };
+    ///
+    /// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    /// let mut table =
+    ///     GptPartitionTable::<4>::new(layout, guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870"))
+    ///         .unwrap();
+    /// table
+    ///     .add(GptPartitionEntry {
+    ///         partition_type_guid: GptPartitionType::BASIC_DATA,
+    ///         starting_lba: LbaLe::from_u64(2048),
+    ///         ending_lba: LbaLe::from_u64(4095),
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let summary = table.summary(2048);
+    /// assert_eq!(summary.num_used_entries, 1);
+    /// assert_eq!(summary.allocated_bytes, 1024 * 1024);
+    /// assert_eq!(summary.num_misaligned, 0);
+    /// ```
+    #[must_use]
+    pub fn summary(&self, alignment_blocks: u64) -> GptPartitionTableSummary {
+        let align = alignment_blocks.max(1);
+        let block_size = self.layout.block_size();
+        let mut summary = GptPartitionTableSummary {
+            num_free_entries: N,
+            ..Default::default()
+        };
+
+        for (_, entry) in self.iter_used() {
+            summary.num_used_entries += 1;
+            summary.num_free_entries -= 1;
+            if entry.starting_lba.to_u64() % align != 0 {
+                summary.num_misaligned += 1;
+            }
+            if let Some(bytes) =
+                entry.lba_range().and_then(|r| r.num_bytes(block_size))
+            {
+                summary.allocated_bytes =
+                    summary.allocated_bytes.saturating_add(bytes);
+            }
+        }
+
+        summary.largest_free_range = self.largest_free_range();
+        summary
+    }
+
+    /// Find the largest range of blocks in the usable range that is not
+    /// part of any partition.
+    fn largest_free_range(&self) -> Option<LbaRangeInclusive> {
+        let usable = self.layout.usable_range();
+        let used = || self.iter_used().filter_map(|(_, e)| e.lba_range());
+
+        // A free range starts either at the beginning of the usable
+        // range or directly after a partition.
+        let candidates = core::iter::once(usable.start().to_u64())
+            .chain(used().filter_map(|r| r.end().to_u64().checked_add(1)));
+
+        let mut largest: Option<LbaRangeInclusive> = None;
+        for start in candidates {
+            let in_partition = used().any(|r| {
+                r.start().to_u64() <= start && start <= r.end().to_u64()
+            });
+            if start < usable.start().to_u64()
+                || start > usable.end().to_u64()
+                || in_partition
+            {
+                continue;
+            }
+            // The range ends just before the next partition, or at the
+            // end of the usable range.
+            let end = used()
+                .map(|r| r.start().to_u64())
+                .filter(|&s| s > start)
+                .map(|s| s - 1)
+                .fold(usable.end().to_u64(), u64::min);
+            let range = LbaRangeInclusive::new(Lba(start), Lba(end));
+            if let Some(range) = range {
+                if largest.map_or(true, |l| range.num_blocks() > l.num_blocks())
+                {
+                    largest = Some(range);
+                }
+            }
+        }
+        largest
+    }
+
+    /// Iterate over the whole disk as a sequence of regions in LBA
+    /// order: the MBR, the primary header and partition entry array,
+    /// the partitions and the free ranges between them, and the
+    /// secondary partition entry array and header. Any space reserved
+    /// on either side of the usable range is a
+    /// [`GptDiskRegionKind::Reserved`] region.
+    ///
+    /// The regions are contiguous and together cover every block of the
+    /// layout, which makes them suitable for drawing a map of the
+    /// disk.
+    ///
+    /// If the table is not valid (see [`validate`]), partitions that
+    /// are outside the usable range or that overlap an earlier
+    /// partition are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{
+    ///     guid, BlockSize, GptDiskLayout, GptDiskRegionKind, GptPartitionEntry,
+    ///     GptPartitionTable, GptPartitionType, LbaLe,
+    /// };
+    ///
+    /// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    /// let mut table =
+    ///     GptPartitionTable::<4>::new(layout, guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870"))
+    ///         .unwrap();
+    /// let index = table
+    ///     .add(GptPartitionEntry {
+    ///         partition_type_guid: GptPartitionType::BASIC_DATA,
+    ///         starting_lba: LbaLe::from_u64(2048),
+    ///         ending_lba: LbaLe::from_u64(4095),
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let kinds: Vec<_> = table.regions().map(|r| r.kind).collect();
+    /// assert_eq!(
+    ///     kinds,
+    ///     [
+    ///         GptDiskRegionKind::Mbr,
+    ///         GptDiskRegionKind::PrimaryHeader,
+    ///         GptDiskRegionKind::PrimaryPartitionEntryArray,
+    ///         GptDiskRegionKind::Free,
+    ///         GptDiskRegionKind::Partition(index),
+    ///         GptDiskRegionKind::Free,
+    ///         GptDiskRegionKind::SecondaryPartitionEntryArray,
+    ///         GptDiskRegionKind::SecondaryHeader,
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// [`validate`]: Self::validate
+    #[allow(clippy::missing_panics_doc)]
+    pub fn regions(&self) -> impl Iterator<Item = GptDiskRegion> + '_ {
+        let layout = self.layout;
+        let usable = layout.usable_range();
+        let primary_array_end = 1 + layout.partition_entry_array_num_blocks();
+        let secondary_array_start =
+            layout.secondary_partition_entry_array_layout().start_lba;
+        let secondary_header = layout.secondary_header_lba();
+
+        let mut next_lba = Some(0);
+        core::iter::from_fn(move || {
+            let lba = next_lba?;
+            let (kind, end) = if lba == 0 {
+                (GptDiskRegionKind::Mbr, 0)
+            } else if lba == layout.primary_header_lba().to_u64() {
+                (GptDiskRegionKind::PrimaryHeader, lba)
+            } else if lba <= primary_array_end {
+                (
+                    GptDiskRegionKind::PrimaryPartitionEntryArray,
+                    primary_array_end,
+                )
+            } else if lba < usable.start().to_u64() {
+                (GptDiskRegionKind::Reserved, usable.start().to_u64() - 1)
+            } else if lba <= usable.end().to_u64() {
+                self.usable_region_at(lba)
+            } else if lba < secondary_array_start.to_u64() {
+                (
+                    GptDiskRegionKind::Reserved,
+                    secondary_array_start.to_u64() - 1,
+                )
+            } else if lba < secondary_header.to_u64() {
+                (
+                    GptDiskRegionKind::SecondaryPartitionEntryArray,
+                    secondary_header.to_u64() - 1,
+                )
+            } else {
+                (GptDiskRegionKind::SecondaryHeader, lba)
+            };
+
+            next_lba = if end >= secondary_header.to_u64() {
+                None
+            } else {
+                Some(end + 1)
+            };
+            // OK to unwrap: `end` is never less than `lba`.
+            let range = LbaRangeInclusive::new(Lba(lba), Lba(end)).unwrap();
+            Some(GptDiskRegion { kind, range })
+        })
+    }
+
+    /// Get the kind and last LBA of the region in the usable range that
+    /// starts at `lba`.
+    fn usable_region_at(&self, lba: u64) -> (GptDiskRegionKind, u64) {
+        let usable = self.layout.usable_range();
+        let partitions = || {
+            self.iter_used().filter_map(|(index, entry)| {
+                let range = entry.lba_range()?;
+                let in_bounds = range.start().to_u64()
+                    >= usable.start().to_u64()
+                    && range.end().to_u64() <= usable.end().to_u64();
+                in_bounds.then_some((index, range))
+            })
+        };
+
+        if let Some((index, range)) =
+            partitions().find(|(_, r)| r.start().to_u64() == lba)
+        {
+            return (GptDiskRegionKind::Partition(index), range.end().to_u64());
+        }
+
+        // Free space up to the next partition, or the end of the usable
+        // range.
+        let end = partitions()
+            .map(|(_, r)| r.start().to_u64())
+            .filter(|&start| start > lba)
+            .map(|start| start - 1)
+            .fold(usable.end().to_u64(), u64::min);
+        (GptDiskRegionKind::Free, end)
+    }
+
+    /// Check that every used entry has a valid LBA range within the
+    /// layout's usable range, and that no partitions overlap.
+    ///
+    /// Returns the first problem found.
+    pub fn validate(&self) -> Result<(), GptPartitionTableError> {
+        for (index, entry) in self.iter_used() {
+            self.check_entry(index, entry)?;
+        }
+        Ok(())
+    }
+
+    /// Update the table to match a desired layout, making only the
+    /// changes that are needed, similar to `systemd-repart`.
+    ///
+    /// Each description in `desired` is matched against the used
+    /// entries in the table (see [`GptPartitionDescription`]):
+    ///
+    /// * If a matching partition exists, it is left unchanged, unless
+    ///   it is smaller than described and [`ApplyLayoutPolicy::allow_grow`]
+    ///   is set. In that case it is grown in place if the blocks after
+    ///   it are free, and otherwise left unchanged.
+    /// * If no matching partition exists, one is created in the first
+    ///   free range of blocks that is large enough.
+    ///
+    /// Partitions not described in `desired` are never modified.
+    /// `on_change` is called once for each description, in order, with
+    /// the result.
+    ///
+    /// If an error occurs, the table is left unmodified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{
+    ///     guid, ApplyLayoutPolicy, BlockSize, GptDiskLayout, GptLayoutChange,
+    ///     GptPartitionDescription, GptPartitionTable, GptPartitionType,
+    /// };
+    ///
+    /// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    /// let mut table =
+    ///     GptPartitionTable::<4>::new(layout, guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870"))
+    ///         .unwrap();
+    /// let desired = [GptPartitionDescription {
+    ///     partition_type: GptPartitionType::EFI_SYSTEM,
+    ///     unique_partition_guid: guid!("37c75ffd-8932-467a-9c56-8cf1f0456b12"),
+    ///     num_blocks: 2048,
+    ///     ..Default::default()
+    /// }];
+    /// let policy = ApplyLayoutPolicy {
+    ///     alignment_blocks: 2048,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut changes = Vec::new();
+    /// table.apply_layout(&desired, policy, |c| changes.push(c)).unwrap();
+    /// assert!(matches!(changes[0], GptLayoutChange::Created(_)));
+    ///
+    /// // Applying the same layout again does nothing.
+    /// changes.clear();
+    /// table.apply_layout(&desired, policy, |c| changes.push(c)).unwrap();
+    /// assert!(matches!(changes[0], GptLayoutChange::Unchanged(_)));
+    /// ```
+    pub fn apply_layout<F>(
+        &mut self,
+        desired: &[GptPartitionDescription],
+        policy: ApplyLayoutPolicy,
+        mut on_change: F,
+    ) -> Result<(), GptPartitionTableError>
+    where
+        F: FnMut(GptLayoutChange),
+    {
+        // Make changes to a copy so that `self` is unmodified on error.
+        let mut table = *self;
+        // Entries that have been matched or created, so that two
+        // descriptions can't match the same partition.
+        let mut claimed = [false; N];
+
+        for desc in desired {
+            let found = (0..N).find(|&i| {
+                let entry = &table.entries[i];
+                !claimed[i] && entry.is_used() && desc.matches(entry)
+            });
+
+            let change = if let Some(i) = found {
+                claimed[i] = true;
+                table.grow_entry(i, desc.num_blocks, policy)
+            } else {
+                let i = table.create_entry(desc, policy)?;
+                claimed[i] = true;
+                GptLayoutChange::Created(Self::index(i))
+            };
+            if !matches!(change, GptLayoutChange::Unchanged(_)) {
+                table.dirty = true;
+            }
+            on_change(change);
+        }
+
+        *self = table;
+        Ok(())
+    }
+
+    /// Grow the entry at `i` to `num_blocks` if allowed by `policy` and
+    /// there is room.
+    fn grow_entry(
+        &mut self,
+        i: usize,
+        num_blocks: u64,
+        policy: ApplyLayoutPolicy,
+    ) -> GptLayoutChange {
+        let index = Self::index(i);
+        let entry = self.entries[i];
+        let Some(range) = entry.lba_range() else {
+            return GptLayoutChange::Unchanged(index);
+        };
+        let old_num_blocks = range.num_blocks();
+        if !policy.allow_grow || old_num_blocks >= num_blocks {
+            return GptLayoutChange::Unchanged(index);
+        }
+
+        let mut grown = entry;
+        grown.ending_lba =
+            LbaLe::from_u64(range.start().to_u64() + (num_blocks - 1));
+        if self.check_entry(index, &grown).is_err() {
+            return GptLayoutChange::Unchanged(index);
+        }
+        self.entries[i] = grown;
+        GptLayoutChange::Grown {
+            index,
+            old_num_blocks,
+        }
+    }
+
+    /// Create an entry for `desc` in the first free range that is large
+    /// enough, and return its array index.
+    fn create_entry(
+        &mut self,
+        desc: &GptPartitionDescription,
+        policy: ApplyLayoutPolicy,
+    ) -> Result<usize, GptPartitionTableError> {
+        let i = self
+            .entries
+            .iter()
+            .position(|entry| !entry.is_used())
+            .ok_or(GptPartitionTableError::Full)?;
+        if desc.num_blocks == 0 {
+            return Err(GptPartitionTableError::InvalidLbaRange {
+                index: Self::index(i),
+            });
+        }
+
+        let align = policy.alignment_blocks.max(1);
+        let align_up = |lba: u64| -> Option<u64> {
+            lba.checked_add(align - 1).map(|lba| lba - lba % align)
+        };
+        let usable = self.layout.usable_range();
+
+        let mut start = align_up(usable.start().to_u64());
+        while let Some(s) = start {
+            let Some(end) = s.checked_add(desc.num_blocks - 1) else {
+                break;
+            };
+            if end > usable.end().to_u64() {
+                break;
+            }
+            // Find a used partition that overlaps the candidate range.
+            let overlap = self
+                .iter_used()
+                .filter_map(|(_, entry)| entry.lba_range())
+                .find(|r| r.start().to_u64() <= end && s <= r.end().to_u64());
+            if let Some(r) = overlap {
+                start = r.end().to_u64().checked_add(1).and_then(align_up);
+                continue;
+            }
+
+            self.entries[i] = GptPartitionEntry {
+                partition_type_guid: desc.partition_type,
+                unique_partition_guid: desc.unique_partition_guid,
+                starting_lba: Lba(s).into(),
+                ending_lba: Lba(end).into(),
+                attributes: desc.attributes,
+                name: desc.name,
+            };
+            return Ok(i);
+        }
+        Err(GptPartitionTableError::NoSpace)
+    }
+
+    /// Copy the table's entries into `array`. Entries in `array` past
+    /// the table's capacity are set to unused.
+    ///
+    /// The array's CRC32 can then be used to fill in the headers
+    /// created by [`GptDiskLayout::primary_header`] and
+    /// [`GptDiskLayout::secondary_header`].
+    ///
+    /// Returns [`GptPartitionTableError::ArrayTooSmall`] if `array` has
+    /// fewer than `N` entries.
+    #[cfg(feature = "bytemuck")]
+    pub fn write_partition_entry_array(
+        &self,
+        array: &mut GptPartitionEntryArray,
+    ) -> Result<(), GptPartitionTableError> {
+        if usize::try_from(array.layout().num_entries).map_or(false, |n| n < N)
+        {
+            return Err(GptPartitionTableError::ArrayTooSmall);
+        }
+
+        let mut entries = self.entries.iter();
+        for dst in array.iter_mut() {
+            *dst = entries.next().copied().unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    /// Check `entry`, which is or will be at `index`, against the
+    /// usable range and all other used entries.
+    fn check_entry(
+        &self,
+        index: PartitionIndex,
+        entry: &GptPartitionEntry,
+    ) -> Result<(), GptPartitionTableError> {
+        let range = entry
+            .lba_range()
+            .ok_or(GptPartitionTableError::InvalidLbaRange { index })?;
+        let usable = self.layout.usable_range();
+        if range.start() < usable.start() || range.end() > usable.end() {
+            return Err(GptPartitionTableError::OutOfBounds { index });
+        }
+
+        for (other, other_entry) in self.iter_used() {
+            if other == index {
+                continue;
+            }
+            if let Some(other_range) = other_entry.lba_range() {
+                if range.start() <= other_range.end()
+                    && other_range.start() <= range.end()
+                {
+                    return Err(GptPartitionTableError::Overlap {
+                        index,
+                        other,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert an array index to a [`PartitionIndex`].
+    fn index(i: usize) -> PartitionIndex {
+        // OK to unwrap: the capacity was checked against the number of
+        // entries in the layout, which is a `u32`.
+        PartitionIndex::new(u32::try_from(i).unwrap())
+    }
+}
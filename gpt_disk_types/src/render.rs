@@ -0,0 +1,246 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{GptDiskRegion, GptDiskRegionKind, GptPartitionTable};
+use core::fmt::{self, Write};
+
+/// Characters used for partitions in [`GptPartitionTable::render_text`],
+/// by display number.
+const PARTITION_CHARS: &[u8] = b"123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Fill colors used for partitions in [`GptPartitionTable::render_svg`].
+const PARTITION_COLORS: &[&str] = &[
+    "#4e79a7", "#f28e2b", "#59a14f", "#b07aa1", "#76b7b2", "#edc948",
+];
+
+impl GptDiskRegionKind {
+    /// Character used for the region in a text bar.
+    fn to_char(self) -> char {
+        match self {
+            Self::Mbr => 'M',
+            Self::PrimaryHeader | Self::SecondaryHeader => 'H',
+            Self::PrimaryPartitionEntryArray
+            | Self::SecondaryPartitionEntryArray => 'A',
+            Self::Reserved => 'R',
+            Self::Free => '.',
+            Self::Partition(index) => usize::try_from(index.to_u32())
+                .ok()
+                .and_then(|i| PARTITION_CHARS.get(i))
+                .map_or('#', |c| char::from(*c)),
+        }
+    }
+
+    /// Fill color used for the region in an SVG image.
+    fn to_color(self) -> &'static str {
+        match self {
+            Self::Mbr | Self::PrimaryHeader | Self::SecondaryHeader => {
+                "#555555"
+            }
+            Self::PrimaryPartitionEntryArray
+            | Self::SecondaryPartitionEntryArray => "#999999",
+            Self::Reserved => "#cccccc",
+            Self::Free => "#ffffff",
+            Self::Partition(index) => {
+                let i = usize::try_from(index.to_u32()).unwrap_or(0);
+                PARTITION_COLORS[i % PARTITION_COLORS.len()]
+            }
+        }
+    }
+}
+
+/// Scale `lba` from a disk of `num_blocks` blocks to `width` units.
+fn scale(lba: u64, num_blocks: u64, width: u32) -> u64 {
+    let scaled =
+        u128::from(lba) * u128::from(width) / u128::from(num_blocks.max(1));
+    // OK to unwrap: `lba` is less than or equal to `num_blocks`, so
+    // the result is at most `width`.
+    u64::try_from(scaled).unwrap()
+}
+
+/// Write `c`, escaped if it is special in XML.
+fn write_xml_escaped<W: Write>(out: &mut W, c: char) -> fmt::Result {
+    match c {
+        '&' => out.write_str("&amp;"),
+        '<' => out.write_str("&lt;"),
+        '>' => out.write_str("&gt;"),
+        '"' => out.write_str("&quot;"),
+        _ => out.write_char(c),
+    }
+}
+
+impl<const N: usize> GptPartitionTable<N> {
+    /// Write a proportional text bar of the disk layout to `out`, one
+    /// character per column, `width` columns wide.
+    ///
+    /// Each column shows the region (see [`regions`]) containing the
+    /// block at the start of the column:
+    ///
+    /// * `M`: MBR
+    /// * `H`: primary or secondary header
+    /// * `A`: primary or secondary partition entry array
+    /// * `R`: reserved space (see
+    ///   [`GptDiskLayout::reserve_leading_bytes`])
+    /// * `.`: free space
+    /// * `1`-`9`, `a`-`z`: partition, by display number (see
+    ///   [`PartitionIndex::to_display_number`]), or `#` for partitions
+    ///   past `z`.
+    ///
+    /// Regions smaller than a column may not appear at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{
+    ///     guid, BlockSize, GptDiskLayout, GptPartitionEntry, GptPartitionTable,
+    ///     GptPartitionType, LbaLe,
+    /// };
+    ///
+    /// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    /// let mut table =
+    ///     GptPartitionTable::<4>::new(layout, guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870"))
+    ///         .unwrap();
+    /// table
+    ///     .add(GptPartitionEntry {
+    ///         partition_type_guid: GptPartitionType::BASIC_DATA,
+    ///         starting_lba: LbaLe::from_u64(2048),
+    ///         ending_lba: LbaLe::from_u64(6143),
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let mut bar = String::new();
+    /// table.render_text(16, &mut bar).unwrap();
+    /// assert_eq!(bar, "M...11111111....");
+    /// ```
+    ///
+    /// [`GptDiskLayout::reserve_leading_bytes`]: crate::GptDiskLayout::reserve_leading_bytes
+    /// [`PartitionIndex::to_display_number`]: crate::PartitionIndex::to_display_number
+    /// [`regions`]: Self::regions
+    pub fn render_text<W: Write>(
+        &self,
+        width: u32,
+        out: &mut W,
+    ) -> fmt::Result {
+        let num_blocks = self.layout().num_blocks();
+        let mut regions = self.regions().peekable();
+        for column in 0..width {
+            let lba = column_start_lba(column, width, num_blocks);
+            while regions
+                .peek()
+                .map_or(false, |r| r.range.end().to_u64() < lba)
+            {
+                regions.next();
+            }
+            let c = regions.peek().map_or(' ', |r| r.kind.to_char());
+            out.write_char(c)?;
+        }
+        Ok(())
+    }
+
+    /// Write an SVG image of the disk layout to `out`, `width` by
+    /// `height` pixels.
+    ///
+    /// Each region (see [`regions`]) is drawn as a rectangle with a
+    /// width proportional to its size, and at least one pixel wide so
+    /// that small regions such as the headers remain visible. Each
+    /// rectangle has a `<title>` describing the region and its LBA
+    /// range, which most viewers show as a tooltip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpt_disk_types::{guid, BlockSize, GptDiskLayout, GptPartitionTable};
+    ///
+    /// let layout = GptDiskLayout::new(BlockSize::BS_512, 8192).unwrap();
+    /// let table =
+    ///     GptPartitionTable::<4>::new(layout, guid!("57a7feb6-8cd5-4922-b7bd-c78b0914e870"))
+    ///         .unwrap();
+    ///
+    /// let mut svg = String::new();
+    /// table.render_svg(800, 40, &mut svg).unwrap();
+    /// assert!(svg.starts_with("<svg "));
+    /// assert!(svg.contains("<title>free: 34..=8158</title>"));
+    /// ```
+    ///
+    /// [`regions`]: Self::regions
+    pub fn render_svg<W: Write>(
+        &self,
+        width: u32,
+        height: u32,
+        out: &mut W,
+    ) -> fmt::Result {
+        let num_blocks = self.layout().num_blocks();
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" \
+             height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+        )?;
+        for region in self.regions() {
+            let x = scale(region.range.start().to_u64(), num_blocks, width);
+            let end = scale(region.range.end().to_u64() + 1, num_blocks, width);
+            let w = end.saturating_sub(x).max(1);
+            write!(
+                out,
+                "<rect x=\"{x}\" y=\"0\" width=\"{w}\" height=\"{height}\" \
+                 fill=\"{}\" stroke=\"#000000\"><title>",
+                region.kind.to_color()
+            )?;
+            self.write_region_title(&region, out)?;
+            writeln!(out, "</title></rect>")?;
+        }
+        writeln!(out, "</svg>")
+    }
+
+    /// Write a short description of `region` for an SVG `<title>`.
+    fn write_region_title<W: Write>(
+        &self,
+        region: &GptDiskRegion,
+        out: &mut W,
+    ) -> fmt::Result {
+        match region.kind {
+            GptDiskRegionKind::Mbr => out.write_str("MBR")?,
+            GptDiskRegionKind::PrimaryHeader => {
+                out.write_str("primary header")?;
+            }
+            GptDiskRegionKind::PrimaryPartitionEntryArray => {
+                out.write_str("primary partition entry array")?;
+            }
+            GptDiskRegionKind::Reserved => out.write_str("reserved")?,
+            GptDiskRegionKind::Free => out.write_str("free")?,
+            GptDiskRegionKind::Partition(index) => {
+                write!(out, "partition {index}")?;
+                if let Some(entry) = self.get(index) {
+                    if !entry.name.is_empty() {
+                        out.write_str(" \"")?;
+                        for c in entry.name.chars() {
+                            write_xml_escaped(out, c)?;
+                        }
+                        out.write_str("\"")?;
+                    }
+                }
+            }
+            GptDiskRegionKind::SecondaryPartitionEntryArray => {
+                out.write_str("secondary partition entry array")?;
+            }
+            GptDiskRegionKind::SecondaryHeader => {
+                out.write_str("secondary header")?;
+            }
+        }
+        write!(out, ": {}", region.range)
+    }
+}
+
+/// Get the first LBA shown in `column` of a text bar `width` columns
+/// wide for a disk of `num_blocks` blocks.
+fn column_start_lba(column: u32, width: u32, num_blocks: u64) -> u64 {
+    let lba =
+        u128::from(column) * u128::from(num_blocks) / u128::from(width.max(1));
+    // OK to unwrap: `column` is less than `width`, so the result is
+    // less than `num_blocks`.
+    u64::try_from(lba).unwrap()
+}
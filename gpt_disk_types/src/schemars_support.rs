@@ -0,0 +1,239 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! JSON Schema implementations for the types that implement serde's
+//! traits. Each schema describes the human-readable serialized form
+//! produced by the impls in `serde_support` (or the derived impls), so
+//! that it can be used to validate JSON written by this crate.
+
+use crate::serde_support::ATTRIBUTE_FLAGS;
+use crate::{
+    BlockSize, GptDiskLayout, GptPartitionAttributes, GptPartitionEntry,
+    GptPartitionEntrySize, GptPartitionName, GptPartitionTable,
+    GptPartitionType, Guid,
+};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{
+    ArrayValidation, InstanceType, NumberValidation, ObjectValidation, Schema,
+    SchemaObject, StringValidation, SubschemaValidation,
+};
+use schemars::JsonSchema;
+
+/// Schema for an integer of the given `format` (such as `"uint32"`)
+/// with a minimum value.
+fn integer_schema(format: &str, minimum: f64) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::Integer.into()),
+        format: Some(format.into()),
+        number: Some(Box::new(NumberValidation {
+            minimum: Some(minimum),
+            ..NumberValidation::default()
+        })),
+        ..SchemaObject::default()
+    }
+    .into()
+}
+
+/// Schema for an object with the given `properties`. The first
+/// `num_required` properties are required.
+fn object_schema(
+    properties: Vec<(&str, Schema)>,
+    num_required: usize,
+) -> Schema {
+    let mut object = ObjectValidation::default();
+    for (i, (name, schema)) in properties.into_iter().enumerate() {
+        if i < num_required {
+            object.required.insert(name.into());
+        }
+        object.properties.insert(name.into(), schema);
+    }
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(object)),
+        ..SchemaObject::default()
+    }
+    .into()
+}
+
+/// Serialized as an integer number of bytes.
+impl JsonSchema for BlockSize {
+    fn schema_name() -> String {
+        "BlockSize".into()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        integer_schema("uint32", 512.0)
+    }
+}
+
+/// Serialized as an integer number of bytes.
+impl JsonSchema for GptPartitionEntrySize {
+    fn schema_name() -> String {
+        "GptPartitionEntrySize".into()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        integer_schema("uint32", 128.0)
+    }
+}
+
+/// Serialized as a list of flag names, followed by a hex string for any
+/// remaining bits.
+impl JsonSchema for GptPartitionAttributes {
+    fn schema_name() -> String {
+        "GptPartitionAttributes".into()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        let flag_names = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(
+                ATTRIBUTE_FLAGS
+                    .iter()
+                    .map(|(name, _)| serde_json::Value::from(*name))
+                    .collect(),
+            ),
+            ..SchemaObject::default()
+        };
+        let hex_bits = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some("^0[xX][0-9a-fA-F]+$".into()),
+                ..StringValidation::default()
+            })),
+            ..SchemaObject::default()
+        };
+        SchemaObject {
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                items: Some(
+                    Schema::from(SchemaObject {
+                        subschemas: Some(Box::new(SubschemaValidation {
+                            any_of: Some(vec![
+                                flag_names.into(),
+                                hex_bits.into(),
+                            ]),
+                            ..SubschemaValidation::default()
+                        })),
+                        ..SchemaObject::default()
+                    })
+                    .into(),
+                ),
+                ..ArrayValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
+/// Serialized as a string, with `\u{XXXX}` escapes for code units that
+/// aren't valid characters.
+impl JsonSchema for GptPartitionName {
+    fn schema_name() -> String {
+        "GptPartitionName".into()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
+/// Serialized transparently as a [`Guid`].
+impl JsonSchema for GptPartitionType {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        Guid::schema_name()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        gen.subschema_for::<Guid>()
+    }
+}
+
+impl JsonSchema for GptPartitionEntry {
+    fn schema_name() -> String {
+        "GptPartitionEntry".into()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        object_schema(
+            vec![
+                (
+                    "partition_type_guid",
+                    gen.subschema_for::<GptPartitionType>(),
+                ),
+                ("unique_partition_guid", gen.subschema_for::<Guid>()),
+                ("starting_lba", gen.subschema_for::<u64>()),
+                ("ending_lba", gen.subschema_for::<u64>()),
+                ("attributes", gen.subschema_for::<GptPartitionAttributes>()),
+                ("name", gen.subschema_for::<GptPartitionName>()),
+            ],
+            6,
+        )
+    }
+}
+
+/// Only the inputs to [`GptDiskLayout::new_with_options`] and the
+/// reserved regions are serialized. The last three fields are optional.
+impl JsonSchema for GptDiskLayout {
+    fn schema_name() -> String {
+        "GptDiskLayout".into()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        object_schema(
+            vec![
+                ("block_size", gen.subschema_for::<BlockSize>()),
+                ("num_blocks", gen.subschema_for::<u64>()),
+                ("entry_size", gen.subschema_for::<GptPartitionEntrySize>()),
+                ("num_entries", gen.subschema_for::<u32>()),
+                ("reserve_min_array_size", gen.subschema_for::<bool>()),
+                ("reserved_leading_blocks", gen.subschema_for::<u64>()),
+                ("reserved_trailing_blocks", gen.subschema_for::<u64>()),
+            ],
+            4,
+        )
+    }
+}
+
+/// Serialized as the layout, disk GUID, and exactly `N` entries.
+impl<const N: usize> JsonSchema for GptPartitionTable<N> {
+    fn schema_name() -> String {
+        format!("GptPartitionTable_for_{N}")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let num_entries = u32::try_from(N).ok();
+        let entries = SchemaObject {
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                items: Some(gen.subschema_for::<GptPartitionEntry>().into()),
+                max_items: num_entries,
+                min_items: num_entries,
+                ..ArrayValidation::default()
+            })),
+            ..SchemaObject::default()
+        };
+        object_schema(
+            vec![
+                ("layout", gen.subschema_for::<GptDiskLayout>()),
+                ("disk_guid", gen.subschema_for::<Guid>()),
+                ("entries", entries.into()),
+            ],
+            3,
+        )
+    }
+}
@@ -0,0 +1,502 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serde implementations for types that can't use the derive macros
+//! directly, either because they are packed, contain arrays larger
+//! than serde supports, have invariants to check on deserialization,
+//! or use a more readable format than their raw bytes.
+
+use crate::{
+    BlockSize, GptDiskLayout, GptPartitionAttributes, GptPartitionEntry,
+    GptPartitionEntrySize, GptPartitionName, GptPartitionNameFromStrError,
+    GptPartitionTable, GptPartitionType, Guid, LbaLe, U64Le,
+};
+use core::fmt::{self, Display, Formatter, Write};
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeSeq, Serializer};
+use serde::{Deserialize, Serialize};
+
+/// Attribute bits serialized by name. The other bits, including the
+/// type-specific bits, are serialized as a hex value.
+pub(crate) const ATTRIBUTE_FLAGS: [(&str, u8); 3] = [
+    (
+        "required_partition",
+        GptPartitionAttributes::REQUIRED_PARTITION_BIT,
+    ),
+    (
+        "no_block_io_protocol",
+        GptPartitionAttributes::NO_BLOCK_IO_PROTOCOL_BIT,
+    ),
+    (
+        "legacy_bios_bootable",
+        GptPartitionAttributes::LEGACY_BIOS_BOOTABLE_BIT,
+    ),
+];
+
+// In human-readable formats, attributes are serialized as a list of
+// flag names, followed by a hex string such as `"0x8000000000000000"`
+// for any remaining bits. Either that form or a plain integer is
+// accepted when deserializing. Other formats use a plain integer.
+impl Serialize for GptPartitionAttributes {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut bits = self.0.to_u64();
+        if !serializer.is_human_readable() {
+            return serializer.serialize_u64(bits);
+        }
+
+        let mut seq = serializer.serialize_seq(None)?;
+        for (name, bit) in ATTRIBUTE_FLAGS {
+            if self.get_bit(bit) {
+                seq.serialize_element(name)?;
+                bits &= !(1 << bit);
+            }
+        }
+        if bits != 0 {
+            seq.serialize_element(&format_args!("{bits:#x}"))?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for GptPartitionAttributes {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct AttributesVisitor;
+
+        impl<'de> Visitor<'de> for AttributesVisitor {
+            type Value = GptPartitionAttributes;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("an integer or a list of attribute flags")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(GptPartitionAttributes(U64Le::from_u64(v)))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut bits = 0;
+                while let Some(AttributeFlag(flag)) = seq.next_element()? {
+                    bits |= flag;
+                }
+                Ok(GptPartitionAttributes(U64Le::from_u64(bits)))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(AttributesVisitor)
+        } else {
+            deserializer.deserialize_u64(AttributesVisitor)
+        }
+    }
+}
+
+/// Bits set by one element of a serialized [`GptPartitionAttributes`]
+/// list: either a flag name or a hex value.
+struct AttributeFlag(u64);
+
+impl<'de> Deserialize<'de> for AttributeFlag {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct AttributeFlagVisitor;
+
+        impl Visitor<'_> for AttributeFlagVisitor {
+            type Value = AttributeFlag;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("an attribute flag name or a hex value")
+            }
+
+            fn visit_str<E: de::Error>(
+                self,
+                v: &str,
+            ) -> Result<Self::Value, E> {
+                if let Some((_, bit)) =
+                    ATTRIBUTE_FLAGS.iter().find(|(name, _)| *name == v)
+                {
+                    return Ok(AttributeFlag(1 << bit));
+                }
+                v.strip_prefix("0x")
+                    .or_else(|| v.strip_prefix("0X"))
+                    .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+                    .map(AttributeFlag)
+                    .ok_or_else(|| {
+                        E::invalid_value(de::Unexpected::Str(v), &self)
+                    })
+            }
+        }
+
+        deserializer.deserialize_str(AttributeFlagVisitor)
+    }
+}
+
+/// [`GptPartitionName`] formatted for serialization as a string.
+///
+/// Code units that aren't valid characters (unpaired surrogates) are
+/// written as `\u{XXXX}` escapes so that they survive a round trip, and
+/// backslashes are written as `\\`. Anything after the first null
+/// terminator is dropped.
+struct EscapedName<'a>(&'a GptPartitionName);
+
+impl Display for EscapedName<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for unit in self.0.code_units() {
+            match char::try_from(u32::from(unit)) {
+                Ok('\\') => f.write_str("\\\\")?,
+                Ok(c) => f.write_char(c)?,
+                Err(_) => write!(f, "\\u{{{unit:04x}}}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse a name written by [`EscapedName`].
+///
+/// Apart from the escapes, the same checks as [`GptPartitionName::from_str`]
+/// are done: every character must be representable in UCS-2, and there
+/// can be at most 36 of them.
+fn parse_escaped_name<E: de::Error>(s: &str) -> Result<GptPartitionName, E> {
+    let invalid_escape = || {
+        E::invalid_value(
+            de::Unexpected::Str(s),
+            &"a partition name with valid escapes",
+        )
+    };
+
+    let mut units = [0u16; 36];
+    let mut len = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        let unit = if c == '\\' {
+            match chars.next() {
+                Some('\\') => u16::from(b'\\'),
+                Some('u') => {
+                    let rest = chars.as_str();
+                    let (hex, tail) = rest
+                        .strip_prefix('{')
+                        .and_then(|rest| rest.split_once('}'))
+                        .ok_or_else(invalid_escape)?;
+                    chars = tail.chars();
+                    u16::from_str_radix(hex, 16)
+                        .ok()
+                        .filter(|unit| *unit != 0)
+                        .ok_or_else(invalid_escape)?
+                }
+                _ => return Err(invalid_escape()),
+            }
+        } else {
+            u16::try_from(u32::from(c)).map_err(|_| {
+                E::custom(GptPartitionNameFromStrError::InvalidChar)
+            })?
+        };
+        *units
+            .get_mut(len)
+            .ok_or_else(|| E::custom(GptPartitionNameFromStrError::Length))? =
+            unit;
+        len += 1;
+    }
+    GptPartitionName::from_code_units(&units[..len]).map_err(E::custom)
+}
+
+// In human-readable formats, names are serialized as strings (see
+// `EscapedName`). Other formats use the raw 72 bytes.
+impl Serialize for GptPartitionName {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&EscapedName(self))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GptPartitionName {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct NameVisitor;
+
+        impl<'de> Visitor<'de> for NameVisitor {
+            type Value = GptPartitionName;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("a partition name or 72 bytes")
+            }
+
+            fn visit_str<E: de::Error>(
+                self,
+                v: &str,
+            ) -> Result<Self::Value, E> {
+                parse_escaped_name(v)
+            }
+
+            fn visit_bytes<E: de::Error>(
+                self,
+                v: &[u8],
+            ) -> Result<Self::Value, E> {
+                v.try_into()
+                    .map(GptPartitionName)
+                    .map_err(|_| E::invalid_length(v.len(), &self))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                deserialize_array(seq, &self).map(GptPartitionName)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(NameVisitor)
+        } else {
+            deserializer.deserialize_bytes(NameVisitor)
+        }
+    }
+}
+
+/// Serialized form of [`GptPartitionEntry`]. The entry itself is
+/// packed, so the derive macros can't be used on it.
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "GptPartitionEntry")]
+struct GptPartitionEntryRepr {
+    partition_type_guid: GptPartitionType,
+    unique_partition_guid: Guid,
+    starting_lba: u64,
+    ending_lba: u64,
+    attributes: GptPartitionAttributes,
+    name: GptPartitionName,
+}
+
+impl Serialize for GptPartitionEntry {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        GptPartitionEntryRepr {
+            partition_type_guid: self.partition_type_guid,
+            unique_partition_guid: self.unique_partition_guid,
+            starting_lba: self.starting_lba.to_u64(),
+            ending_lba: self.ending_lba.to_u64(),
+            attributes: self.attributes,
+            name: self.name,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GptPartitionEntry {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let repr = GptPartitionEntryRepr::deserialize(deserializer)?;
+        Ok(Self {
+            partition_type_guid: repr.partition_type_guid,
+            unique_partition_guid: repr.unique_partition_guid,
+            starting_lba: LbaLe::from_u64(repr.starting_lba),
+            ending_lba: LbaLe::from_u64(repr.ending_lba),
+            attributes: repr.attributes,
+            name: repr.name,
+        })
+    }
+}
+
+impl Serialize for GptPartitionEntrySize {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.to_u32())
+    }
+}
+
+impl<'de> Deserialize<'de> for GptPartitionEntrySize {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Self::new(u32::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// Serialized form of [`GptDiskLayout`]. Only the inputs to
+/// [`GptDiskLayout::new_with_options`] and the reserved regions are
+/// stored; everything else is recalculated on deserialization.
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "GptDiskLayout")]
+struct GptDiskLayoutRepr {
+    block_size: BlockSize,
+    num_blocks: u64,
+    entry_size: GptPartitionEntrySize,
+    num_entries: u32,
+    #[serde(default = "default_reserve_min_array_size")]
+    reserve_min_array_size: bool,
+    #[serde(default)]
+    reserved_leading_blocks: u64,
+    #[serde(default)]
+    reserved_trailing_blocks: u64,
+}
+
+fn default_reserve_min_array_size() -> bool {
+    true
+}
+
+impl Serialize for GptDiskLayout {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let array_layout = self.primary_partition_entry_array_layout();
+        let spec_array_num_blocks = GptDiskLayout::new_with_entries(
+            self.block_size(),
+            self.num_blocks(),
+            array_layout.entry_size,
+            array_layout.num_entries,
+        )
+        .map(|layout| layout.partition_entry_array_num_blocks());
+        GptDiskLayoutRepr {
+            block_size: self.block_size(),
+            num_blocks: self.num_blocks(),
+            entry_size: array_layout.entry_size,
+            num_entries: array_layout.num_entries,
+            reserve_min_array_size: spec_array_num_blocks
+                == Ok(self.partition_entry_array_num_blocks()),
+            reserved_leading_blocks: self.reserved_leading_blocks(),
+            reserved_trailing_blocks: self.reserved_trailing_blocks(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GptDiskLayout {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let repr = GptDiskLayoutRepr::deserialize(deserializer)?;
+        Self::new_with_options(
+            repr.block_size,
+            repr.num_blocks,
+            repr.entry_size,
+            repr.num_entries,
+            repr.reserve_min_array_size,
+        )
+        .and_then(|layout| {
+            layout.with_reserved_blocks(
+                repr.reserved_leading_blocks,
+                repr.reserved_trailing_blocks,
+            )
+        })
+        .map_err(de::Error::custom)
+    }
+}
+
+/// Serialized form of [`GptPartitionTable`].
+#[derive(Serialize)]
+#[serde(rename = "GptPartitionTable")]
+struct GptPartitionTableRef<'a> {
+    layout: &'a GptDiskLayout,
+    disk_guid: Guid,
+    entries: &'a [GptPartitionEntry],
+}
+
+/// Deserialized form of [`GptPartitionTable`].
+#[derive(Deserialize)]
+#[serde(rename = "GptPartitionTable")]
+struct GptPartitionTableRepr<const N: usize> {
+    layout: GptDiskLayout,
+    disk_guid: Guid,
+    entries: EntryArray<N>,
+}
+
+/// Array of exactly `N` entries. Serde only implements `Deserialize`
+/// for arrays of up to 32 elements.
+struct EntryArray<const N: usize>([GptPartitionEntry; N]);
+
+impl<'de, const N: usize> Deserialize<'de> for EntryArray<N> {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct EntryArrayVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for EntryArrayVisitor<N> {
+            type Value = EntryArray<N>;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                write!(f, "a sequence of {N} partition entries")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                deserialize_array(seq, &self).map(EntryArray)
+            }
+        }
+
+        deserializer.deserialize_seq(EntryArrayVisitor::<N>)
+    }
+}
+
+/// Deserialize a sequence of exactly `N` elements into an array.
+fn deserialize_array<'de, A, T, const N: usize>(
+    mut seq: A,
+    expected: &dyn de::Expected,
+) -> Result<[T; N], A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Copy + Default + Deserialize<'de>,
+{
+    let mut array = [T::default(); N];
+    for (i, dst) in array.iter_mut().enumerate() {
+        *dst = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(i, expected))?;
+    }
+    if seq.next_element::<de::IgnoredAny>()?.is_some() {
+        return Err(de::Error::invalid_length(N + 1, expected));
+    }
+    Ok(array)
+}
+
+// The table's layout, disk GUID, and all `N` entries (including unused
+// ones) are serialized. The dirty flag is not; a deserialized table is
+// clean.
+impl<const N: usize> Serialize for GptPartitionTable<N> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        GptPartitionTableRef {
+            layout: self.layout(),
+            disk_guid: self.disk_guid(),
+            entries: self.entries(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for GptPartitionTable<N> {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let repr = GptPartitionTableRepr::<N>::deserialize(deserializer)?;
+        Self::from_entries(repr.layout, repr.disk_guid, repr.entries.0)
+            .map_err(de::Error::custom)
+    }
+}
@@ -7,11 +7,17 @@
 // except according to those terms.
 
 use crate::{
-    GptPartitionEntryArrayError, GptPartitionEntrySizeError,
+    BlockSizeError, GptDiskLayoutError, GptPartitionEntryArrayError,
+    GptPartitionEntryArrayLocationError, GptPartitionEntrySizeError,
     GptPartitionNameFromStrError, GptPartitionNameSetCharError,
+    GptPartitionTableError,
 };
 use std::error::Error;
 
+impl Error for BlockSizeError {}
+
+impl Error for GptDiskLayoutError {}
+
 impl Error for GptPartitionNameFromStrError {}
 
 impl Error for GptPartitionNameSetCharError {}
@@ -19,3 +25,7 @@ impl Error for GptPartitionNameSetCharError {}
 impl Error for GptPartitionEntrySizeError {}
 
 impl Error for GptPartitionEntryArrayError {}
+
+impl Error for GptPartitionEntryArrayLocationError {}
+
+impl Error for GptPartitionTableError {}
@@ -0,0 +1,203 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{Guid, GuidFromStrError};
+use core::fmt::{self, Display, Formatter, LowerHex, UpperHex};
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+use {
+    serde::de::{self, Visitor},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+};
+
+/// Letter case of the hex digits in a GUID string.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum GuidCase {
+    /// Lower case, e.g. `01234567-89ab-cdef-0123-456789abcdef`.
+    #[default]
+    Lower,
+
+    /// Upper case, e.g. `01234567-89AB-CDEF-0123-456789ABCDEF`.
+    Upper,
+}
+
+impl GuidCase {
+    /// Detect the case of a GUID string. Returns [`GuidCase::Upper`] if
+    /// the string contains at least one upper-case letter and no
+    /// lower-case letters, otherwise [`GuidCase::Lower`].
+    ///
+    /// A string with mixed case, or with no letters at all, is treated
+    /// as lower case.
+    #[must_use]
+    pub const fn detect(s: &str) -> Self {
+        let s = s.as_bytes();
+        let mut has_upper = false;
+        let mut i = 0;
+        while i < s.len() {
+            if s[i].is_ascii_lowercase() {
+                return Self::Lower;
+            }
+            if s[i].is_ascii_uppercase() {
+                has_upper = true;
+            }
+            i += 1;
+        }
+        if has_upper {
+            Self::Upper
+        } else {
+            Self::Lower
+        }
+    }
+}
+
+/// [`Guid`] that remembers the letter case of the string it was parsed
+/// from.
+///
+/// [`Guid`] always formats as lower case. Tools that rewrite existing
+/// files, such as configs that use upper-case GUIDs, can parse into a
+/// `CasedGuid` instead so that unchanged GUIDs are written back exactly
+/// as they were read.
+///
+/// Comparisons include the case; compare [`CasedGuid::guid`] to ignore
+/// it.
+///
+/// If the `serde` feature is enabled, `CasedGuid` is serialized as a
+/// string in its original case.
+///
+/// # Examples
+///
+/// ```
+/// use uguid::{guid, CasedGuid, GuidCase};
+///
+/// let s = "01234567-89AB-CDEF-0123-456789ABCDEF";
+/// let cased: CasedGuid = s.parse().unwrap();
+/// assert_eq!(cased.guid(), guid!("01234567-89ab-cdef-0123-456789abcdef"));
+/// assert_eq!(cased.case(), GuidCase::Upper);
+/// assert_eq!(cased.to_string(), s);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct CasedGuid {
+    guid: Guid,
+    case: GuidCase,
+}
+
+impl CasedGuid {
+    /// Create a `CasedGuid` that formats `guid` in `case`.
+    #[must_use]
+    pub const fn new(guid: Guid, case: GuidCase) -> Self {
+        Self { guid, case }
+    }
+
+    /// Parse a GUID string, remembering its case. See
+    /// [`Guid::try_parse`] and [`GuidCase::detect`].
+    pub const fn try_parse(s: &str) -> Result<Self, GuidFromStrError> {
+        let guid = mtry!(Guid::try_parse(s));
+        Ok(Self::new(guid, GuidCase::detect(s)))
+    }
+
+    /// Get the GUID.
+    #[must_use]
+    pub const fn guid(self) -> Guid {
+        self.guid
+    }
+
+    /// Get the letter case used for formatting.
+    #[must_use]
+    pub const fn case(self) -> GuidCase {
+        self.case
+    }
+
+    /// Convert to a hex ASCII string in the original case.
+    #[must_use]
+    pub const fn to_ascii_hex(self) -> [u8; 36] {
+        match self.case {
+            GuidCase::Lower => self.guid.to_ascii_hex_lower(),
+            GuidCase::Upper => self.guid.to_ascii_hex_upper(),
+        }
+    }
+}
+
+impl From<Guid> for CasedGuid {
+    /// Create a lower-case `CasedGuid`.
+    fn from(guid: Guid) -> Self {
+        Self::new(guid, GuidCase::Lower)
+    }
+}
+
+impl From<CasedGuid> for Guid {
+    fn from(cased: CasedGuid) -> Self {
+        cased.guid
+    }
+}
+
+/// Format the GUID in its original case.
+///
+/// Width, fill, and alignment flags are supported.
+impl Display for CasedGuid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.case {
+            GuidCase::Lower => LowerHex::fmt(&self.guid, f),
+            GuidCase::Upper => UpperHex::fmt(&self.guid, f),
+        }
+    }
+}
+
+impl FromStr for CasedGuid {
+    type Err = GuidFromStrError;
+
+    /// Parse a GUID string, remembering its case. See
+    /// [`CasedGuid::try_parse`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_parse(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for CasedGuid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let ascii = self.to_ascii_hex();
+        // OK to unwrap since the ascii output is valid utf-8.
+        let s = core::str::from_utf8(&ascii).unwrap();
+        serializer.serialize_str(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct CasedDeserializerVisitor;
+
+#[cfg(feature = "serde")]
+impl Visitor<'_> for CasedDeserializerVisitor {
+    type Value = CasedGuid;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(
+            "a string in the format \"xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx\"",
+        )
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        CasedGuid::try_parse(value).map_err(E::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for CasedGuid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(CasedDeserializerVisitor)
+    }
+}
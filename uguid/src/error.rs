@@ -6,6 +6,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use crate::util::{byte_to_ascii_hex_lower, ConstStrBuf};
 use core::fmt::{self, Display, Formatter};
 
 /// Error type for [`Guid::try_parse`] and [`Guid::from_str`].
@@ -16,40 +17,79 @@ use core::fmt::{self, Display, Formatter};
 /// [`Error`]: std::error::Error
 /// [`Guid::from_str`]: core::str::FromStr::from_str
 /// [`Guid::try_parse`]: crate::Guid::try_parse
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum GuidFromStrError {
     /// Input has the wrong length, expected 36 bytes.
+    #[default]
     Length,
 
     /// Input is missing a separator (`-`) at this byte index.
     Separator(u8),
 
-    /// Input contains invalid ASCII hex at this byte index.
-    Hex(u8),
+    /// Input contains invalid ASCII hex. The first field is the byte
+    /// index of the invalid character, and the second field is the
+    /// invalid byte. If the input contains a non-ASCII character, the
+    /// byte is the first byte of its UTF-8 encoding.
+    ///
+    /// In uguid 2.x, this variant only held an index, and that index
+    /// pointed at the start of the two-character hex pair containing
+    /// the invalid character rather than at the character itself.
+    Hex(u8, u8),
 }
 
-impl Default for GuidFromStrError {
-    fn default() -> Self {
-        Self::Length
+impl GuidFromStrError {
+    /// Get the invalid byte of a [`Hex`] error. Returns `None` for
+    /// other errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use uguid::{Guid, GuidFromStrError};
+    ///
+    /// let err = "01234567-89ab-cdef-0123-456789abcdeX"
+    ///     .parse::<Guid>()
+    ///     .unwrap_err();
+    /// assert_eq!(err, GuidFromStrError::Hex(35, b'X'));
+    /// assert_eq!(err.invalid_byte(), Some(b'X'));
+    /// ```
+    ///
+    /// [`Hex`]: Self::Hex
+    #[must_use]
+    pub const fn invalid_byte(self) -> Option<u8> {
+        match self {
+            Self::Hex(_, byte) => Some(byte),
+            _ => None,
+        }
     }
-}
 
-impl Display for GuidFromStrError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    /// Append the error message to `msg`. This is shared by the
+    /// `Display` impl and `Guid::parse_or_panic`, the latter of which
+    /// can't use `fmt` since it's a const function.
+    pub(crate) const fn write_message(self, msg: ConstStrBuf) -> ConstStrBuf {
         match self {
             Self::Length => {
-                f.write_str("GUID string has wrong length (expected 36 bytes)")
+                msg.push_str("GUID string has wrong length (expected 36 bytes)")
             }
-            Self::Separator(index) => write!(
-                f,
-                "GUID string is missing a separator (`-`) at index {index}",
-            ),
-            Self::Hex(index) => {
-                write!(
-                    f,
-                    "GUID string contains invalid ASCII hex at index {index}",
-                )
+            Self::Separator(index) => msg
+                .push_str("GUID string is missing a separator (`-`) at index ")
+                .push_decimal(index),
+            Self::Hex(index, byte) => {
+                let msg =
+                    msg.push_str("GUID string contains invalid ASCII hex ");
+                let msg = if byte.is_ascii_graphic() {
+                    msg.push_str("character '").push_ascii(byte).push_str("'")
+                } else {
+                    let (h, l) = byte_to_ascii_hex_lower(byte);
+                    msg.push_str("byte 0x").push_ascii(h).push_ascii(l)
+                };
+                msg.push_str(" at index ").push_decimal(index)
             }
         }
     }
 }
+
+impl Display for GuidFromStrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.write_message(ConstStrBuf::new()).as_str())
+    }
+}
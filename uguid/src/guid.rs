@@ -6,20 +6,38 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::util::{byte_to_ascii_hex_lower, parse_byte_from_ascii_str_at};
+use crate::util::{
+    byte_to_ascii_hex_lower, parse_byte_from_ascii_str_at, ConstStrBuf,
+};
 use crate::GuidFromStrError;
-use core::fmt::{self, Display, Formatter};
+use core::fmt::{self, Display, Formatter, LowerHex, UpperHex};
 use core::str::{self, FromStr};
 
 #[cfg(feature = "serde")]
 use {
-    serde::de::{self, Visitor},
+    serde::de::{self, SeqAccess, Visitor},
     serde::{Deserialize, Deserializer, Serialize, Serializer},
 };
 
+#[cfg(feature = "borsh")]
+use borsh::{io, BorshDeserialize, BorshSerialize};
+
 #[cfg(feature = "bytemuck")]
 use bytemuck::{Pod, Zeroable};
 
+#[cfg(feature = "rand")]
+use rand::{
+    distributions::{Distribution, Standard},
+    Rng,
+};
+
+#[cfg(feature = "schemars")]
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject, StringValidation},
+    JsonSchema,
+};
+
 /// Globally-unique identifier.
 ///
 /// The format is defined in [RFC 4122]. However, unlike "normal" UUIDs
@@ -31,11 +49,33 @@ use bytemuck::{Pod, Zeroable};
 /// type should be 8-byte aligned, but most C implementations have
 /// 4-byte alignment, so we do the same here for compatibility.
 ///
+/// If a higher alignment is required, for example to match a C ABI that
+/// requires 16-byte aligned GUIDs, wrap `Guid` in a struct with the
+/// desired alignment:
+///
+/// ```
+/// use uguid::{guid, Guid};
+///
+/// #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// #[repr(C, align(16))]
+/// struct Guid16(Guid);
+///
+/// let guids = [Guid16(guid!("01234567-89ab-cdef-0123-456789abcdef")); 2];
+/// assert_eq!(core::mem::align_of_val(&guids), 16);
+/// assert_eq!(core::mem::size_of_val(&guids), 32);
+/// ```
+///
 /// [Appendix A]: https://uefi.org/specs/UEFI/2.10/Apx_A_GUID_and_Time_Formats.html
 /// [RFC 4122]: https://datatracker.ietf.org/doc/html/rfc4122
 /// [`uuid`]: https://docs.rs/uuid/latest/uuid
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "bytemuck", derive(Pod, Zeroable))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize),
+    archive(compare(PartialEq)),
+    archive_attr(derive(Debug))
+)]
 #[repr(C)]
 pub struct Guid {
     // Use `u32` rather than `[u8; 4]` here so that the natural
@@ -237,6 +277,150 @@ impl Guid {
         (self.time_high_and_version[1] & 0b1111_0000) >> 4
     }
 
+    /// Get a copy of the GUID with the variant bits set to `variant`.
+    /// All other bits are unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uguid::{guid, Variant};
+    ///
+    /// let guid = guid!("308bbc16-a308-47e8-c977-5e5646c5291f");
+    /// assert_eq!(guid.variant(), Variant::ReservedMicrosoft);
+    /// let guid = guid.with_variant(Variant::Rfc4122);
+    /// assert_eq!(guid, guid!("308bbc16-a308-47e8-8977-5e5646c5291f"));
+    /// ```
+    #[must_use]
+    pub const fn with_variant(mut self, variant: Variant) -> Self {
+        let b = self.clock_seq_high_and_reserved;
+        self.clock_seq_high_and_reserved = match variant {
+            // 0xx
+            Variant::ReservedNcs => b & 0b0111_1111,
+            // 10x
+            Variant::Rfc4122 => (b & 0b0011_1111) | 0b1000_0000,
+            // 110
+            Variant::ReservedMicrosoft => (b & 0b0001_1111) | 0b1100_0000,
+            // 111
+            Variant::ReservedFuture => b | 0b1110_0000,
+        };
+        self
+    }
+
+    /// Get a copy of the GUID with the version bits set to `version`.
+    /// All other bits are unchanged.
+    ///
+    /// The version is only meaningful for the [`Variant::Rfc4122`]
+    /// variant; see [`with_variant`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` does not fit in four bits (greater than 15).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uguid::{guid, Variant};
+    ///
+    /// // Normalize a hash into a version 5 GUID.
+    /// let guid = guid!("01234567-89ab-cdef-0123-456789abcdef")
+    ///     .with_variant(Variant::Rfc4122)
+    ///     .with_version(5);
+    /// assert_eq!(guid, guid!("01234567-89ab-5def-8123-456789abcdef"));
+    /// ```
+    ///
+    /// [`with_variant`]: Self::with_variant
+    #[must_use]
+    pub const fn with_version(mut self, version: u8) -> Self {
+        assert!(version <= 0b1111, "GUID version must be less than 16");
+        self.time_high_and_version[1] =
+            (self.time_high_and_version[1] & 0b0000_1111) | (version << 4);
+        self
+    }
+
+    /// True if the GUID has the [`Variant::Rfc4122`] variant and
+    /// version `version`, false otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uguid::guid;
+    ///
+    /// let guid = guid!("308bbc16-a308-47e8-8977-5e5646c5291f");
+    /// assert!(guid.is_rfc4122_version(4));
+    /// assert!(!guid.is_rfc4122_version(1));
+    /// ```
+    #[must_use]
+    pub const fn is_rfc4122_version(self, version: u8) -> bool {
+        matches!(self.variant(), Variant::Rfc4122) && self.version() == version
+    }
+
+    /// True if the GUID is a random (version 4) [`Variant::Rfc4122`]
+    /// GUID, such as those created by [`from_random_bytes`], false
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uguid::guid;
+    ///
+    /// assert!(guid!("308bbc16-a308-47e8-8977-5e5646c5291f").is_rfc4122_v4());
+    /// assert!(!guid!("00000000-0000-0000-0000-000000000000").is_rfc4122_v4());
+    /// ```
+    ///
+    /// [`from_random_bytes`]: Self::from_random_bytes
+    #[must_use]
+    pub const fn is_rfc4122_v4(self) -> bool {
+        self.is_rfc4122_version(4)
+    }
+
+    /// Get the timestamp embedded in a time-based GUID.
+    ///
+    /// Returns `None` unless the GUID has the [`Variant::Rfc4122`]
+    /// variant and is version 1 or version 7.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uguid::{guid, GuidTimestamp};
+    ///
+    /// let guid = guid!("017f22e2-79b0-7cc3-98c4-dc0c0c07398f");
+    /// assert_eq!(
+    ///     guid.timestamp(),
+    ///     Some(GuidTimestamp::UnixMillis(0x017f_22e2_79b0))
+    /// );
+    ///
+    /// // Random GUIDs do not have a timestamp.
+    /// assert_eq!(guid!("308bbc16-a308-47e8-8977-5e5646c5291f").timestamp(), None);
+    /// ```
+    #[must_use]
+    pub const fn timestamp(self) -> Option<GuidTimestamp> {
+        if !matches!(self.variant(), Variant::Rfc4122) {
+            return None;
+        }
+
+        // Fields are stored little endian; the timestamp is assembled
+        // from the big-endian field values.
+        let low = self.time_low();
+        let mid = self.time_mid;
+        let high = self.time_high_and_version;
+        match self.version() {
+            1 => Some(GuidTimestamp::Gregorian(u64::from_be_bytes([
+                high[1] & 0b0000_1111,
+                high[0],
+                mid[1],
+                mid[0],
+                low[3],
+                low[2],
+                low[1],
+                low[0],
+            ]))),
+            7 => Some(GuidTimestamp::UnixMillis(u64::from_be_bytes([
+                0, 0, low[3], low[2], low[1], low[0], mid[1], mid[0],
+            ]))),
+            _ => None,
+        }
+    }
+
     /// Parse a GUID from a string.
     ///
     /// This is functionally the same as [`Self::from_str`], but is
@@ -298,22 +482,61 @@ impl Guid {
     /// above. In particular, it will panic if the input is not exactly
     /// 36 bytes long, or if the input does not have separators at the
     /// expected positions, or if any of the remaining characters are
-    /// not valid hex digits.
+    /// not valid hex digits. The panic message includes the input
+    /// string, truncated if it is very long.
     #[must_use]
     #[track_caller]
     pub const fn parse_or_panic(s: &str) -> Self {
         match Self::try_parse(s) {
             Ok(g) => g,
-            Err(GuidFromStrError::Length) => {
-                panic!("GUID string has wrong length (expected 36 bytes)");
-            }
-            Err(GuidFromStrError::Separator(_)) => {
-                panic!("GUID string is missing one or more separators (`-`)");
+            Err(err) => {
+                let msg = err
+                    .write_message(ConstStrBuf::new())
+                    .push_str(": \"")
+                    .push_str_truncated(s, 64)
+                    .push_str("\"");
+                panic!("{}", msg.as_str());
             }
-            Err(GuidFromStrError::Hex(_)) => {
-                panic!("GUID string contains one or more invalid characters");
+        }
+    }
+
+    /// Check whether `s` is the string form of this GUID, ignoring
+    /// ASCII case.
+    ///
+    /// This is the same as parsing `s` with [`try_parse`] and comparing
+    /// the result, except that invalid strings return `false` rather
+    /// than an error. The comparison does not allocate and does not
+    /// depend on the locale, so it is suitable for matching a
+    /// user-supplied string against many GUIDs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use uguid::guid;
+    ///
+    /// let guid = guid!("01234567-89ab-cdef-0123-456789abcdef");
+    /// assert!(guid.eq_ignore_case_str("01234567-89AB-cdef-0123-456789ABCDEF"));
+    /// assert!(!guid.eq_ignore_case_str("01234567-89ab-cdef-0123-456789abcdee"));
+    /// assert!(!guid.eq_ignore_case_str("0123456789abcdef0123456789abcdef"));
+    /// ```
+    ///
+    /// [`try_parse`]: Self::try_parse
+    #[must_use]
+    pub const fn eq_ignore_case_str(self, s: &str) -> bool {
+        let s = s.as_bytes();
+        if s.len() != 36 {
+            return false;
+        }
+
+        let expected = self.to_ascii_hex_lower();
+        let mut i = 0;
+        while i < expected.len() {
+            if s[i].to_ascii_lowercase() != expected[i] {
+                return false;
             }
+            i += 1;
         }
+        true
     }
 
     /// Create a GUID from a 16-byte array. No changes to byte order are made.
@@ -387,6 +610,20 @@ impl Guid {
         (buf[34], buf[35]) = byte_to_ascii_hex_lower(bytes[15]);
         buf
     }
+
+    /// Convert to an upper-case hex ASCII string.
+    ///
+    /// The output is in "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX" format.
+    #[must_use]
+    pub const fn to_ascii_hex_upper(self) -> [u8; 36] {
+        let mut buf = self.to_ascii_hex_lower();
+        let mut i = 0;
+        while i < buf.len() {
+            buf[i] = buf[i].to_ascii_uppercase();
+            i += 1;
+        }
+        buf
+    }
 }
 
 impl Default for Guid {
@@ -395,12 +632,35 @@ impl Default for Guid {
     }
 }
 
+/// Format the GUID in lower-case hex. This is the same as [`LowerHex`].
+///
+/// Width, fill, and alignment flags are supported, so `{:>40}` pads the
+/// GUID to 40 characters.
 impl Display for Guid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        LowerHex::fmt(self, f)
+    }
+}
+
+/// Format the GUID in lower-case hex, e.g.
+/// `01234567-89ab-cdef-0123-456789abcdef`.
+impl LowerHex for Guid {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let ascii = self.to_ascii_hex_lower();
         // OK to unwrap since the ascii output is valid utf-8.
         let s = str::from_utf8(&ascii).unwrap();
-        f.write_str(s)
+        f.pad(s)
+    }
+}
+
+/// Format the GUID in upper-case hex, e.g.
+/// `01234567-89AB-CDEF-0123-456789ABCDEF`.
+impl UpperHex for Guid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let ascii = self.to_ascii_hex_upper();
+        // OK to unwrap since the ascii output is valid utf-8.
+        let s = str::from_utf8(&ascii).unwrap();
+        f.pad(s)
     }
 }
 
@@ -423,10 +683,14 @@ impl Serialize for Guid {
     where
         S: Serializer,
     {
-        let ascii = self.to_ascii_hex_lower();
-        // OK to unwrap since the ascii output is valid utf-8.
-        let s = str::from_utf8(&ascii).unwrap();
-        serializer.serialize_str(s)
+        if serializer.is_human_readable() {
+            let ascii = self.to_ascii_hex_lower();
+            // OK to unwrap since the ascii output is valid utf-8.
+            let s = str::from_utf8(&ascii).unwrap();
+            serializer.serialize_str(s)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes())
+        }
     }
 }
 
@@ -451,13 +715,122 @@ impl Visitor<'_> for DeserializerVisitor {
     }
 }
 
+/// Visitor for non-human-readable formats.
+#[cfg(feature = "serde")]
+struct BytesDeserializerVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for BytesDeserializerVisitor {
+    type Value = Guid;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("16 bytes")
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if let Ok(bytes) = <[u8; 16]>::try_from(value) {
+            return Ok(Guid::from_bytes(bytes));
+        }
+
+        // Data serialized by older versions of this crate uses the
+        // string form in all formats, and some formats encode strings
+        // the same way as bytes.
+        if value.len() == 36 {
+            if let Ok(s) = str::from_utf8(value) {
+                return self.visit_str(s);
+            }
+        }
+
+        Err(E::invalid_length(value.len(), &self))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        DeserializerVisitor.visit_str(value)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = [0; 16];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(17, &self));
+        }
+        Ok(Guid::from_bytes(bytes))
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Guid {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_str(DeserializerVisitor)
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(DeserializerVisitor)
+        } else {
+            deserializer.deserialize_bytes(BytesDeserializerVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshSerialize for Guid {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshDeserialize for Guid {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        <[u8; 16]>::deserialize_reader(reader).map(Self::from_bytes)
+    }
+}
+
+/// Generate a random version 4 GUID with [`Guid::from_random_bytes`].
+///
+/// This allows generating a GUID with `rng.gen::<Guid>()`.
+#[cfg(feature = "rand")]
+impl Distribution<Guid> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Guid {
+        Guid::from_random_bytes(rng.gen())
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for Guid {
+    fn schema_name() -> String {
+        "Guid".into()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("uuid".into()),
+            string: Some(Box::new(StringValidation {
+                max_length: Some(36),
+                min_length: Some(36),
+                pattern: Some(
+                    "^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-\
+                     [0-9a-fA-F]{4}-[0-9a-fA-F]{12}$"
+                        .into(),
+                ),
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
     }
 }
 
@@ -478,3 +851,43 @@ pub enum Variant {
     /// Reserved for future use.
     ReservedFuture,
 }
+
+/// Timestamp embedded in a time-based GUID. See [`Guid::timestamp`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum GuidTimestamp {
+    /// Timestamp of a version 1 GUID: a 60-bit count of 100-nanosecond
+    /// intervals since 00:00:00.00, 15 October 1582 (the start of the
+    /// Gregorian calendar).
+    Gregorian(u64),
+
+    /// Timestamp of a version 7 GUID: a 48-bit count of milliseconds
+    /// since the Unix epoch.
+    UnixMillis(u64),
+}
+
+impl GuidTimestamp {
+    /// Number of 100-nanosecond intervals between the start of the
+    /// Gregorian calendar and the Unix epoch.
+    const GREGORIAN_UNIX_OFFSET: u64 = 0x01b2_1dd2_1381_4000;
+
+    /// Convert to milliseconds since the Unix epoch.
+    ///
+    /// [`Gregorian`] timestamps are truncated to millisecond
+    /// precision. Returns `None` if the timestamp is before the Unix
+    /// epoch.
+    ///
+    /// [`Gregorian`]: Self::Gregorian
+    #[must_use]
+    pub const fn to_unix_millis(self) -> Option<u64> {
+        match self {
+            Self::Gregorian(t) => {
+                if let Some(t) = t.checked_sub(Self::GREGORIAN_UNIX_OFFSET) {
+                    Some(t / 10_000)
+                } else {
+                    None
+                }
+            }
+            Self::UnixMillis(t) => Some(t),
+        }
+    }
+}
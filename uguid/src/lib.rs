@@ -21,8 +21,18 @@
 //!
 //! No features are enabled by default.
 //!
+//! * `borsh`: Implements borsh's `BorshSerialize` and `BorshDeserialize`
+//!   traits for `Guid`. The GUID is encoded as 16 bytes.
 //! * `bytemuck`: Implements bytemuck's `Pod` and `Zeroable` traits for `Guid`.
+//! * `rand`: Implements rand's `Distribution<Guid>` for `Standard`, so
+//!   that random version 4 GUIDs can be generated with `rng.gen()`.
+//! * `rkyv`: Implements rkyv's `Archive`, `Serialize`, and `Deserialize`
+//!   traits for `Guid`.
+//! * `schemars`: Implements schemars's `JsonSchema` trait for `Guid`. The
+//!   schema describes the string form of the GUID. Implies `std`.
 //! * `serde`: Implements serde's `Serialize` and `Deserialize` traits for `Guid`.
+//!   Human-readable formats such as JSON use the string form of the GUID;
+//!   other formats use the 16-byte form.
 //! * `std`: Provides `std::error::Error` implementation for the error type.
 //!
 //! # Examples
@@ -109,12 +119,14 @@ macro_rules! mtry {
     };
 }
 
+mod cased;
 mod error;
 mod guid;
 mod util;
 
+pub use cased::{CasedGuid, GuidCase};
 pub use error::GuidFromStrError;
-pub use guid::{Guid, Variant};
+pub use guid::{Guid, GuidTimestamp, Variant};
 
 #[cfg(feature = "std")]
 impl std::error::Error for GuidFromStrError {}
@@ -78,7 +78,120 @@ pub(crate) const fn parse_byte_from_ascii_str_at(
     {
         Ok(byte)
     } else {
-        Err(GuidFromStrError::Hex(start))
+        // Report the first invalid character of the pair.
+        let index = if parse_byte_from_ascii_char(s[start_usize]).is_none() {
+            start
+        } else {
+            start + 1
+        };
+        Err(GuidFromStrError::Hex(index, s[index as usize]))
+    }
+}
+
+/// Fixed-capacity string buffer that can be built in a const
+/// context. This is used to create panic messages that include runtime
+/// values, which `panic!` does not support in const functions.
+pub(crate) struct ConstStrBuf {
+    buf: [u8; Self::CAPACITY],
+    len: usize,
+}
+
+impl ConstStrBuf {
+    const CAPACITY: usize = 160;
+
+    pub(crate) const fn new() -> Self {
+        Self {
+            buf: [0; Self::CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Append `s`, truncated to at most `max_len` bytes. If `s` is
+    /// truncated, `...` is appended. Truncation happens on a character
+    /// boundary, and also occurs if the buffer is full.
+    pub(crate) const fn push_str_truncated(
+        mut self,
+        s: &str,
+        max_len: usize,
+    ) -> Self {
+        let s = s.as_bytes();
+        let mut i = 0;
+        while i < s.len() {
+            let char_len = utf8_char_len(s[i]);
+            if i + char_len > max_len
+                || self.len + char_len > Self::CAPACITY - 3
+            {
+                return self.push_ascii(b'.').push_ascii(b'.').push_ascii(b'.');
+            }
+            let mut j = 0;
+            while j < char_len {
+                self.buf[self.len] = s[i + j];
+                self.len += 1;
+                j += 1;
+            }
+            i += char_len;
+        }
+        self
+    }
+
+    /// Append `s`, truncated if the buffer is full.
+    pub(crate) const fn push_str(self, s: &str) -> Self {
+        self.push_str_truncated(s, usize::MAX)
+    }
+
+    /// Append a single ASCII byte. Has no effect if the buffer is full.
+    pub(crate) const fn push_ascii(mut self, byte: u8) -> Self {
+        if byte.is_ascii() && self.len < Self::CAPACITY {
+            self.buf[self.len] = byte;
+            self.len += 1;
+        }
+        self
+    }
+
+    /// Append `n` in decimal.
+    pub(crate) const fn push_decimal(self, n: u8) -> Self {
+        let mut this = self;
+        if n >= 100 {
+            this = this.push_ascii(b'0' + n / 100);
+        }
+        if n >= 10 {
+            this = this.push_ascii(b'0' + n / 10 % 10);
+        }
+        this.push_ascii(b'0' + n % 10)
+    }
+
+    /// Get the contents of the buffer.
+    pub(crate) const fn as_str(&self) -> &str {
+        // Slice indexing is not available in const functions, so trim
+        // the unused part of the buffer with a slice pattern.
+        let mut bytes: &[u8] = &self.buf;
+        let mut unused = Self::CAPACITY - self.len;
+        while unused > 0 {
+            if let [rest @ .., _] = bytes {
+                bytes = rest;
+            }
+            unused -= 1;
+        }
+
+        match core::str::from_utf8(bytes) {
+            Ok(s) => s,
+            // Unreachable: only whole characters are appended.
+            Err(_) => "",
+        }
+    }
+}
+
+/// Get the length in bytes of the UTF-8 character that starts with
+/// `lead`.
+const fn utf8_char_len(lead: u8) -> usize {
+    if lead < 0x80 {
+        1
+    } else if lead < 0xe0 {
+        2
+    } else if lead < 0xf0 {
+        3
+    } else {
+        4
     }
 }
 
@@ -100,4 +213,27 @@ mod tests {
         assert_eq!(parse_byte_from_ascii_char_pair(b'g', b'a'), None);
         assert_eq!(parse_byte_from_ascii_char_pair(b'a', b'g'), None);
     }
+
+    #[test]
+    fn test_const_str_buf() {
+        let buf = ConstStrBuf::new().push_str("abc").push_decimal(0);
+        assert_eq!(buf.as_str(), "abc0");
+
+        let buf = ConstStrBuf::new().push_decimal(42).push_decimal(255);
+        assert_eq!(buf.as_str(), "42255");
+
+        let buf = ConstStrBuf::new().push_str_truncated("abcdef", 3);
+        assert_eq!(buf.as_str(), "abc...");
+
+        // Truncation does not split a multi-byte character.
+        let buf = ConstStrBuf::new().push_str_truncated("aé", 2);
+        assert_eq!(buf.as_str(), "a...");
+
+        // Input that doesn't fit in the buffer is truncated.
+        let long = [b'x'; 1000];
+        let long = core::str::from_utf8(&long).unwrap();
+        let buf = ConstStrBuf::new().push_str(long);
+        assert_eq!(buf.as_str().len(), ConstStrBuf::CAPACITY);
+        assert!(buf.as_str().ends_with("..."));
+    }
 }
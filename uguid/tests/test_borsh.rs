@@ -0,0 +1,25 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "borsh")]
+
+use uguid::{guid, Guid};
+
+#[test]
+fn test_borsh() {
+    let guid = guid!("01234567-89ab-cdef-0123-456789abcdef");
+
+    let bytes = borsh::to_vec(&guid).unwrap();
+    assert_eq!(bytes, guid.to_bytes());
+    assert_eq!(borsh::from_slice::<Guid>(&bytes).unwrap(), guid);
+
+    // Too short.
+    assert!(borsh::from_slice::<Guid>(&bytes[..15]).is_err());
+    // Too long.
+    assert!(borsh::from_slice::<Guid>(&[0; 17]).is_err());
+}
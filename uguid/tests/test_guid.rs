@@ -7,7 +7,9 @@
 // except according to those terms.
 
 use core::mem;
-use uguid::{guid, Guid, GuidFromStrError, Variant};
+use uguid::{
+    guid, CasedGuid, Guid, GuidCase, GuidFromStrError, GuidTimestamp, Variant,
+};
 
 #[test]
 fn test_guid() {
@@ -52,6 +54,27 @@ fn test_guid() {
         *b"01234567-89ab-cdef-0123-456789abcdef"
     );
     assert_eq!(guid.to_string(), "01234567-89ab-cdef-0123-456789abcdef");
+    assert_eq!(
+        guid.to_ascii_hex_upper(),
+        *b"01234567-89AB-CDEF-0123-456789ABCDEF"
+    );
+    assert_eq!(format!("{guid:x}"), "01234567-89ab-cdef-0123-456789abcdef");
+    assert_eq!(format!("{guid:X}"), "01234567-89AB-CDEF-0123-456789ABCDEF");
+
+    // Width, fill, and alignment.
+    assert_eq!(
+        format!("{guid:>40}"),
+        "    01234567-89ab-cdef-0123-456789abcdef"
+    );
+    assert_eq!(
+        format!("[{guid:<38}]"),
+        "[01234567-89ab-cdef-0123-456789abcdef  ]"
+    );
+    assert_eq!(
+        format!("{guid:*^40X}"),
+        "**01234567-89AB-CDEF-0123-456789ABCDEF**"
+    );
+    assert_eq!(format!("{guid:10}"), guid.to_string());
 
     // Parsing.
     assert_eq!(
@@ -92,23 +115,37 @@ fn test_parse_or_panic_success() {
 }
 
 #[test]
-#[should_panic]
+#[should_panic(
+    expected = "GUID string has wrong length (expected 36 bytes): \"01234567-89ab-cdef-0123-456789abcdef0\""
+)]
 fn test_parse_or_panic_len() {
     let _g = Guid::parse_or_panic("01234567-89ab-cdef-0123-456789abcdef0");
 }
 
 #[test]
-#[should_panic]
+#[should_panic(
+    expected = "GUID string is missing a separator (`-`) at index 8: \"01234567089ab-cdef-0123-456789abcdef\""
+)]
 fn test_parse_or_panic_sep() {
     let _g = Guid::parse_or_panic("01234567089ab-cdef-0123-456789abcdef");
 }
 
 #[test]
-#[should_panic]
+#[should_panic(
+    expected = "GUID string contains invalid ASCII hex character 'g' at index 0: \"g1234567-89ab-cdef-0123-456789abcdef\""
+)]
 fn test_parse_or_panic_hex() {
     let _g = Guid::parse_or_panic("g1234567-89ab-cdef-0123-456789abcdef");
 }
 
+#[test]
+#[should_panic(
+    expected = "GUID string has wrong length (expected 36 bytes): \"0123456789012345678901234567890123456789012345678901234567890123...\""
+)]
+fn test_parse_or_panic_long_input() {
+    let _g = Guid::parse_or_panic(&"0123456789".repeat(10));
+}
+
 #[test]
 fn test_guid_error() {
     // Wrong length.
@@ -128,7 +165,15 @@ fn test_guid_error() {
 
     // Invalid hex.
     let s = "g1234567-89ab-cdef-0123-456789abcdef";
-    assert_eq!(s.parse::<Guid>(), Err(GuidFromStrError::Hex(0)));
+    assert_eq!(s.parse::<Guid>(), Err(GuidFromStrError::Hex(0, b'g')));
+    let s = "01234567-89ab-cdef-0123-456789abcdeX";
+    assert_eq!(s.parse::<Guid>(), Err(GuidFromStrError::Hex(35, b'X')));
+    let s = "01234567-89ab-cdef-0123-456789abcdé";
+    assert_eq!(s.parse::<Guid>(), Err(GuidFromStrError::Hex(34, 0xc3)));
+
+    assert_eq!(GuidFromStrError::Hex(34, 0xc3).invalid_byte(), Some(0xc3));
+    assert_eq!(GuidFromStrError::Length.invalid_byte(), None);
+    assert_eq!(GuidFromStrError::Separator(8).invalid_byte(), None);
 
     assert_eq!(
         GuidFromStrError::Length.to_string(),
@@ -139,8 +184,36 @@ fn test_guid_error() {
         "GUID string is missing a separator (`-`) at index 8"
     );
     assert_eq!(
-        GuidFromStrError::Hex(10).to_string(),
-        "GUID string contains invalid ASCII hex at index 10"
+        GuidFromStrError::Hex(10, b'g').to_string(),
+        "GUID string contains invalid ASCII hex character 'g' at index 10"
+    );
+    assert_eq!(
+        GuidFromStrError::Hex(34, 0xc3).to_string(),
+        "GUID string contains invalid ASCII hex byte 0xc3 at index 34"
+    );
+}
+
+#[test]
+fn test_guid_eq_ignore_case_str() {
+    let guid = guid!("01234567-89ab-cdef-0123-456789abcdef");
+    assert!(guid.eq_ignore_case_str("01234567-89ab-cdef-0123-456789abcdef"));
+    assert!(guid.eq_ignore_case_str("01234567-89AB-CDEF-0123-456789ABCDEF"));
+    assert!(guid.eq_ignore_case_str("01234567-89aB-CdEf-0123-456789AbCdEf"));
+
+    // Different GUID.
+    assert!(!guid.eq_ignore_case_str("01234567-89ab-cdef-0123-456789abcdee"));
+    // Wrong length.
+    assert!(!guid.eq_ignore_case_str(""));
+    assert!(!guid.eq_ignore_case_str("01234567-89ab-cdef-0123-456789abcdef0"));
+    // Wrong separator.
+    assert!(!guid.eq_ignore_case_str("01234567_89ab-cdef-0123-456789abcdef"));
+    // Not hex.
+    assert!(!guid.eq_ignore_case_str("01234567-89ab-cdef-0123-456789abcdeg"));
+    // Multi-byte characters.
+    assert!(!guid.eq_ignore_case_str("01234567-89ab-cdef-0123-456789abcdé"));
+
+    assert!(
+        Guid::ZERO.eq_ignore_case_str("00000000-0000-0000-0000-000000000000")
     );
 }
 
@@ -178,6 +251,136 @@ fn test_guid_is_zero() {
     assert!(!guid!("308bbc16-a308-47e8-8977-5e5646c5291f").is_zero());
 }
 
+#[test]
+fn test_cased_guid() {
+    const GUID: Guid = guid!("01234567-89ab-cdef-0123-456789abcdef");
+    let lower = "01234567-89ab-cdef-0123-456789abcdef";
+    let upper = "01234567-89AB-CDEF-0123-456789ABCDEF";
+
+    assert_eq!(GuidCase::detect(lower), GuidCase::Lower);
+    assert_eq!(GuidCase::detect(upper), GuidCase::Upper);
+    // Mixed case and no letters are treated as lower case.
+    assert_eq!(
+        GuidCase::detect("01234567-89Ab-cdef-0123-456789abcdef"),
+        GuidCase::Lower
+    );
+    assert_eq!(
+        GuidCase::detect("01234567-8900-0000-0123-456789000000"),
+        GuidCase::Lower
+    );
+
+    // Round trip.
+    let cased: CasedGuid = upper.parse().unwrap();
+    assert_eq!(cased.guid(), GUID);
+    assert_eq!(cased.case(), GuidCase::Upper);
+    assert_eq!(cased.to_string(), upper);
+    assert_eq!(&cased.to_ascii_hex(), upper.as_bytes());
+    let cased: CasedGuid = lower.parse().unwrap();
+    assert_eq!(cased.case(), GuidCase::Lower);
+    assert_eq!(cased.to_string(), lower);
+
+    // Formatting flags are respected.
+    let cased = CasedGuid::new(GUID, GuidCase::Upper);
+    assert_eq!(format!("{cased:>38}"), format!("  {upper}"));
+
+    // Conversions.
+    assert_eq!(CasedGuid::from(GUID).case(), GuidCase::Lower);
+    assert_eq!(Guid::from(cased), GUID);
+
+    // Errors are the same as `Guid`.
+    assert_eq!("1234".parse::<CasedGuid>(), Err(GuidFromStrError::Length));
+}
+
+#[test]
+fn test_guid_with_variant() {
+    let zero = Guid::ZERO;
+    let ones = Guid::from_bytes([0xff; 16]);
+    for variant in [
+        Variant::ReservedNcs,
+        Variant::Rfc4122,
+        Variant::ReservedMicrosoft,
+        Variant::ReservedFuture,
+    ] {
+        assert_eq!(zero.with_variant(variant).variant(), variant);
+        assert_eq!(ones.with_variant(variant).variant(), variant);
+    }
+
+    // Only the variant bits are changed.
+    assert_eq!(
+        ones.with_variant(Variant::Rfc4122),
+        guid!("ffffffff-ffff-ffff-bfff-ffffffffffff")
+    );
+    assert_eq!(
+        zero.with_variant(Variant::ReservedFuture),
+        guid!("00000000-0000-0000-e000-000000000000")
+    );
+}
+
+#[test]
+fn test_guid_with_version() {
+    let ones = Guid::from_bytes([0xff; 16]);
+    assert_eq!(
+        ones.with_version(4),
+        guid!("ffffffff-ffff-4fff-ffff-ffffffffffff")
+    );
+    for version in 0..16 {
+        assert_eq!(Guid::ZERO.with_version(version).version(), version);
+    }
+
+    let guid = ones.with_variant(Variant::Rfc4122).with_version(4);
+    assert!(guid.is_rfc4122_v4());
+    assert!(guid.is_rfc4122_version(4));
+    assert!(!guid.is_rfc4122_version(5));
+    assert!(!ones.with_version(4).is_rfc4122_v4());
+    assert!(Guid::from_random_bytes([0; 16]).is_rfc4122_v4());
+}
+
+#[test]
+#[should_panic]
+fn test_guid_with_version_too_large() {
+    let _ = Guid::ZERO.with_version(16);
+}
+
+#[test]
+fn test_guid_timestamp() {
+    // Version 1, from RFC 9562 appendix A.1: Tuesday, February 22, 2022
+    // 2:22:22.000000 PM GMT-05:00.
+    let guid = guid!("c232ab00-9414-11ec-b3c8-9f6bdeced846");
+    assert_eq!(
+        guid.timestamp(),
+        Some(GuidTimestamp::Gregorian(0x1ec_9414_c232_ab00))
+    );
+    assert_eq!(
+        guid.timestamp().unwrap().to_unix_millis(),
+        Some(1_645_557_742_000)
+    );
+
+    // Version 7, from RFC 9562 appendix A.6: same time as above.
+    let guid = guid!("017f22e2-79b0-7cc3-98c4-dc0c0c07398f");
+    assert_eq!(
+        guid.timestamp(),
+        Some(GuidTimestamp::UnixMillis(1_645_557_742_000))
+    );
+    assert_eq!(
+        guid.timestamp().unwrap().to_unix_millis(),
+        Some(1_645_557_742_000)
+    );
+
+    // Version 1 timestamp before the Unix epoch.
+    assert_eq!(GuidTimestamp::Gregorian(0).to_unix_millis(), None);
+
+    // Other versions and variants have no timestamp.
+    assert_eq!(
+        guid!("308bbc16-a308-47e8-8977-5e5646c5291f").timestamp(),
+        None
+    );
+    assert_eq!(
+        guid!("c232ab00-9414-11ec-c3c8-9f6bdeced846").timestamp(),
+        None
+    );
+    assert_eq!(Guid::ZERO.timestamp(), None);
+}
+
 /// Inner module that only imports the `guid!` macro.
 mod inner {
     use uguid::guid;
@@ -0,0 +1,25 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "rand")]
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use uguid::{Guid, Variant};
+
+#[test]
+fn test_rand() {
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    let guid1: Guid = rng.gen();
+    assert_eq!(guid1.variant(), Variant::Rfc4122);
+    assert_eq!(guid1.version(), 4);
+
+    let guid2: Guid = rng.gen();
+    assert_ne!(guid1, guid2);
+}
@@ -0,0 +1,26 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "rkyv")]
+
+use rkyv::Deserialize;
+use uguid::{guid, Guid};
+
+#[test]
+fn test_rkyv() {
+    let guid = guid!("01234567-89ab-cdef-0123-456789abcdef");
+
+    let bytes = rkyv::to_bytes::<_, 256>(&guid).unwrap();
+    // SAFETY: `bytes` was just created by serializing a `Guid`.
+    let archived = unsafe { rkyv::archived_root::<Guid>(&bytes) };
+    assert_eq!(*archived, guid);
+
+    let deserialized: Guid =
+        archived.deserialize(&mut rkyv::Infallible).unwrap();
+    assert_eq!(deserialized, guid);
+}
@@ -0,0 +1,29 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "schemars")]
+
+use serde_json::json;
+use uguid::Guid;
+
+#[test]
+fn test_schemars() {
+    let schema = schemars::schema_for!(Guid);
+    assert_eq!(
+        serde_json::to_value(&schema).unwrap(),
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Guid",
+            "type": "string",
+            "format": "uuid",
+            "maxLength": 36,
+            "minLength": 36,
+            "pattern": "^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+        })
+    );
+}
@@ -8,24 +8,78 @@
 
 #![cfg(feature = "serde")]
 
-use serde_test::Token;
-use uguid::{guid, Guid};
+use serde_test::{Compact, Configure, Readable, Token};
+use uguid::{guid, CasedGuid, Guid, GuidCase};
 
 #[test]
 fn test_serde() {
     let guid = guid!("01234567-89ab-cdef-0123-456789abcdef");
 
     serde_test::assert_tokens(
-        &guid,
+        &guid.readable(),
         &[Token::Str("01234567-89ab-cdef-0123-456789abcdef")],
     );
 
-    serde_test::assert_de_tokens_error::<Guid>(
+    serde_test::assert_de_tokens_error::<Readable<Guid>>(
         &[Token::Str("1234")],
         "GUID string has wrong length (expected 36 bytes)",
     );
 
-    serde_test::assert_de_tokens_error::<Guid>(
+    serde_test::assert_de_tokens_error::<Readable<Guid>>(
         &[Token::U64(1234)],
         "invalid type: integer `1234`, expected a string in the format \"xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx\"");
 }
+
+#[test]
+fn test_serde_compact() {
+    const GUID: Guid = guid!("01234567-89ab-cdef-0123-456789abcdef");
+    const BYTES: [u8; 16] = GUID.to_bytes();
+    let guid = GUID;
+
+    serde_test::assert_tokens(&guid.compact(), &[Token::Bytes(&BYTES)]);
+
+    // Sequences of bytes are accepted.
+    let mut tokens = vec![Token::Seq { len: Some(16) }];
+    tokens.extend(BYTES.iter().map(|b| Token::U8(*b)));
+    tokens.push(Token::SeqEnd);
+    serde_test::assert_de_tokens(&guid.compact(), &tokens);
+
+    // The string form is accepted for compatibility.
+    serde_test::assert_de_tokens(
+        &guid.compact(),
+        &[Token::Str("01234567-89ab-cdef-0123-456789abcdef")],
+    );
+    serde_test::assert_de_tokens(
+        &guid.compact(),
+        &[Token::Bytes(b"01234567-89ab-cdef-0123-456789abcdef")],
+    );
+
+    serde_test::assert_de_tokens_error::<Compact<Guid>>(
+        &[Token::Bytes(&[0; 15])],
+        "invalid length 15, expected 16 bytes",
+    );
+    serde_test::assert_de_tokens_error::<Compact<Guid>>(
+        &[Token::Bytes(&[0xff; 17])],
+        "invalid length 17, expected 16 bytes",
+    );
+}
+
+#[test]
+fn test_serde_cased() {
+    const GUID: Guid = guid!("01234567-89ab-cdef-0123-456789abcdef");
+
+    // The original case is used in both readable and compact formats.
+    for (case, s) in [
+        (GuidCase::Lower, "01234567-89ab-cdef-0123-456789abcdef"),
+        (GuidCase::Upper, "01234567-89AB-CDEF-0123-456789ABCDEF"),
+    ] {
+        let cased = CasedGuid::new(GUID, case);
+        serde_test::assert_tokens(&cased.readable(), &[Token::Str(s)]);
+        serde_test::assert_tokens(&cased.compact(), &[Token::Str(s)]);
+    }
+
+    serde_test::assert_de_tokens_error::<CasedGuid>(
+        &[Token::Str("1234")],
+        "GUID string has wrong length (expected 36 bytes)",
+    );
+}
@@ -14,8 +14,13 @@ use std::env;
 use std::process::{exit, Command};
 use util::run_cmd;
 
-const FEAT_OPTIONS: [bool; 2] = [false, true];
+const FEAT_ALLOC: &str = "alloc";
+const FEAT_BORSH: &str = "borsh";
 const FEAT_BYTEMUCK: &str = "bytemuck";
+const FEAT_RAND: &str = "rand";
+const FEAT_RENDER: &str = "render";
+const FEAT_RKYV: &str = "rkyv";
+const FEAT_SCHEMARS: &str = "schemars";
 const FEAT_SERDE: &str = "serde";
 const FEAT_STD: &str = "std";
 
@@ -59,39 +64,62 @@ fn test_package(package: Package, features: &[&str]) {
 }
 
 fn test_uguid() {
-    for feat_bytemuck in FEAT_OPTIONS {
-        for feat_serde in FEAT_OPTIONS {
-            for feat_std in FEAT_OPTIONS {
-                let mut features = Vec::new();
-                if feat_bytemuck {
-                    features.push(FEAT_BYTEMUCK);
-                }
-                if feat_serde {
-                    features.push(FEAT_SERDE);
-                }
-                if feat_std {
-                    features.push(FEAT_STD);
-                }
+    let feature_lists = [
+        vec![],
+        vec![FEAT_BORSH],
+        vec![FEAT_BYTEMUCK],
+        vec![FEAT_RAND],
+        vec![FEAT_RKYV],
+        // schemars implicitly enables std.
+        vec![FEAT_SCHEMARS],
+        vec![FEAT_SCHEMARS, FEAT_SERDE],
+        vec![FEAT_SERDE],
+        vec![FEAT_SERDE, FEAT_STD],
+        vec![FEAT_STD],
+        vec![
+            FEAT_BORSH,
+            FEAT_BYTEMUCK,
+            FEAT_RAND,
+            FEAT_RKYV,
+            FEAT_SCHEMARS,
+            FEAT_SERDE,
+            FEAT_STD,
+        ],
+    ];
 
-                test_package(Package::Uguid, &features);
-            }
-        }
+    for features in feature_lists {
+        test_package(Package::Uguid, &features);
     }
 }
 
 fn test_gpt_disk_types() {
-    for feat_bytemuck in FEAT_OPTIONS {
-        for feat_std in FEAT_OPTIONS {
-            let mut features = Vec::new();
-            if feat_bytemuck {
-                features.push(FEAT_BYTEMUCK);
-            }
-            if feat_std {
-                features.push(FEAT_STD);
-            }
+    let feature_lists = [
+        vec![],
+        vec![FEAT_ALLOC],
+        vec![FEAT_BYTEMUCK],
+        // Some header functions require both alloc and bytemuck.
+        vec![FEAT_ALLOC, FEAT_BYTEMUCK],
+        vec![FEAT_RENDER],
+        vec![FEAT_RKYV],
+        // schemars implicitly enables serde and std.
+        vec![FEAT_SCHEMARS],
+        vec![FEAT_SERDE],
+        vec![FEAT_SERDE, FEAT_STD],
+        // std implicitly enables alloc.
+        vec![FEAT_STD],
+        vec![
+            FEAT_ALLOC,
+            FEAT_BYTEMUCK,
+            FEAT_RENDER,
+            FEAT_RKYV,
+            FEAT_SCHEMARS,
+            FEAT_SERDE,
+            FEAT_STD,
+        ],
+    ];
 
-            test_package(Package::GptDiskTypes, &features);
-        }
+    for features in feature_lists {
+        test_package(Package::GptDiskTypes, &features);
     }
 }
 
@@ -101,6 +129,14 @@ fn test_gpt_disk_io() {
         vec!["alloc"],
         // std implicitly enabled alloc, so no need for a separate alloc+std.
         vec!["std"],
+        vec!["devices"],
+        vec!["io_uring"],
+        vec!["log"],
+        vec!["qcow2"],
+        vec!["rayon"],
+        vec!["ureq"],
+        vec!["zstd"],
+        vec!["test_support"],
     ];
 
     for features in feature_lists {